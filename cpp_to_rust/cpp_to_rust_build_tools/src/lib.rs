@@ -163,7 +163,11 @@ impl Config {
     }
 
     for name in cpp_build_config_data.linked_libs() {
-      println!("cargo:rustc-link-lib={}", name);
+      match cpp_build_config_data.linked_lib_type(name) {
+        Some(CppLibraryType::Shared) => println!("cargo:rustc-link-lib=dylib={}", name),
+        Some(CppLibraryType::Static) => println!("cargo:rustc-link-lib=static={}", name),
+        None => println!("cargo:rustc-link-lib={}", name),
+      }
     }
     if ::common::target::current_env() != ::common::target::Env::Msvc {
       // TODO: make it configurable
@@ -42,6 +42,37 @@ pub enum CppLibraryType {
   Static,
 }
 
+/// Kind of a library linked using a `#[link]` attribute
+/// of the generated crate (see
+/// `cpp_to_rust_generator::config::Config::add_link_library`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub enum LinkKind {
+  Dylib,
+  Static,
+  Framework,
+}
+
+impl LinkKind {
+  /// Returns value of the `kind` parameter of the `#[link]` attribute.
+  pub fn name(&self) -> &'static str {
+    match *self {
+      LinkKind::Dylib => "dylib",
+      LinkKind::Static => "static",
+      LinkKind::Framework => "framework",
+    }
+  }
+}
+
+impl From<CppLibraryType> for LinkKind {
+  fn from(t: CppLibraryType) -> LinkKind {
+    match t {
+      CppLibraryType::Shared => LinkKind::Dylib,
+      CppLibraryType::Static => LinkKind::Static,
+    }
+  }
+}
+
 /// Platform-specific information
 /// required to build the C++ wrapper library.
 /// This type contains one configuration item of `CppBuildConfig`.
@@ -49,6 +80,9 @@ pub enum CppLibraryType {
 #[derive(Serialize, Deserialize)]
 pub struct CppBuildConfigData {
   linked_libs: Vec<String>,
+  // missing in build configs serialized by older versions
+  #[serde(default)]
+  linked_lib_types: Vec<(String, CppLibraryType)>,
   linked_frameworks: Vec<String>,
   compiler_flags: Vec<String>,
   library_type: Option<CppLibraryType>,
@@ -65,6 +99,16 @@ impl CppBuildConfigData {
     self.linked_libs.push(lib.into());
   }
 
+  /// Adds a library for linking and requests linking it as
+  /// a shared or static library. The type is passed to `cargo`
+  /// as the `kind` of the library. If the type is not specified
+  /// (see `CppBuildConfigData::add_linked_lib`), `cargo` selects it.
+  pub fn add_linked_lib_with_type<P: Into<String>>(&mut self, lib: P, lib_type: CppLibraryType) {
+    let lib = lib.into();
+    self.linked_lib_types.push((lib.clone(), lib_type));
+    self.linked_libs.push(lib);
+  }
+
   /// Adds a framework for linking (OS X specific). Used as `-f` option to the linker.
   pub fn add_linked_framework<P: Into<String>>(&mut self, lib: P) {
     self.linked_frameworks.push(lib.into());
@@ -96,6 +140,16 @@ impl CppBuildConfigData {
     &self.linked_libs
   }
 
+  /// Returns type of linked library `lib` if it was specified using
+  /// `CppBuildConfigData::add_linked_lib_with_type`.
+  pub fn linked_lib_type(&self, lib: &str) -> Option<CppLibraryType> {
+    self
+      .linked_lib_types
+      .iter()
+      .find(|&&(ref name, _)| name == lib)
+      .map(|&(_, lib_type)| lib_type)
+  }
+
   /// Returns names of linked frameworks.
   pub fn linked_frameworks(&self) -> &[String] {
     &self.linked_frameworks
@@ -113,6 +167,15 @@ impl CppBuildConfigData {
 
   fn add_from(&mut self, other: &CppBuildConfigData) -> Result<()> {
     self.linked_libs.append(&mut other.linked_libs.clone());
+    for &(ref lib, lib_type) in &other.linked_lib_types {
+      if let Some(old_type) = self.linked_lib_type(lib) {
+        if old_type != lib_type {
+          return Err(format!("conflicting library types specified for {}", lib).into());
+        }
+      } else {
+        self.linked_lib_types.push((lib.clone(), lib_type));
+      }
+    }
     self
       .linked_frameworks
       .append(&mut other.linked_frameworks.clone());
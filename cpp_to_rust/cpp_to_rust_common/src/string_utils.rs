@@ -83,6 +83,9 @@ pub trait CaseOperations {
   fn to_class_case(self) -> String;
   /// Convert to snake-case string ("word_word_word")
   fn to_snake_case(self) -> String;
+  /// Convert to snake-case string, keeping each of `acronyms`
+  /// as a single word ("parseURL" with "URL" becomes "parse_url")
+  fn to_snake_case_with_acronyms(self, acronyms: &[String]) -> String;
   /// Convert to upper-case string ("WORD_WORD_WORD")
   fn to_upper_case_words(self) -> String;
 }
@@ -112,7 +115,9 @@ fn ends_with_digit<S: AsRef<str>>(s: S) -> bool {
   }
 }
 
-fn iterator_to_snake_case<S: AsRef<str>, T: Iterator<Item = S>>(it: T) -> String {
+fn iterator_to_snake_case<S: AsRef<str>, T: Iterator<Item = S>>(it: T,
+                                                                 acronyms: &[String])
+                                                                 -> String {
   let mut parts: Vec<_> = it.map(|x| x.as_ref().to_lowercase()).collect();
   replace_all_sub_vecs(&mut parts, vec!["na", "n"]);
   replace_all_sub_vecs(&mut parts, vec!["open", "g", "l"]);
@@ -120,6 +125,17 @@ fn iterator_to_snake_case<S: AsRef<str>, T: Iterator<Item = S>>(it: T) -> String
   replace_all_sub_vecs(&mut parts, vec!["2", "d"]);
   replace_all_sub_vecs(&mut parts, vec!["3", "d"]);
   replace_all_sub_vecs(&mut parts, vec!["4", "d"]);
+  let mut needles: Vec<Vec<String>> = acronyms
+    .iter()
+    .map(|acronym| WordIterator::new(acronym).map(|x| x.to_lowercase()).collect())
+    .collect();
+  // longer acronyms go first, so that "RGBA" is not split by "RGB"
+  needles.sort_by(|a, b| b.len().cmp(&a.len()));
+  for needle in &needles {
+    if needle.len() > 1 {
+      replace_all_sub_vecs(&mut parts, needle.iter().map(|x| x.as_str()).collect());
+    }
+  }
   let mut str = String::new();
   for (i, part) in parts.into_iter().enumerate() {
     if part.is_empty() {
@@ -163,7 +179,10 @@ impl<'a> CaseOperations for &'a str {
     iterator_to_class_case(WordIterator::new(self))
   }
   fn to_snake_case(self) -> String {
-    iterator_to_snake_case(WordIterator::new(self))
+    iterator_to_snake_case(WordIterator::new(self), &[])
+  }
+  fn to_snake_case_with_acronyms(self, acronyms: &[String]) -> String {
+    iterator_to_snake_case(WordIterator::new(self), acronyms)
   }
   fn to_upper_case_words(self) -> String {
     iterator_to_upper_case_words(WordIterator::new(self))
@@ -175,7 +194,10 @@ impl<'a> CaseOperations for Vec<&'a str> {
     iterator_to_class_case(self.into_iter())
   }
   fn to_snake_case(self) -> String {
-    iterator_to_snake_case(self.into_iter())
+    iterator_to_snake_case(self.into_iter(), &[])
+  }
+  fn to_snake_case_with_acronyms(self, acronyms: &[String]) -> String {
+    iterator_to_snake_case(self.into_iter(), acronyms)
   }
   fn to_upper_case_words(self) -> String {
     iterator_to_upper_case_words(self.into_iter())
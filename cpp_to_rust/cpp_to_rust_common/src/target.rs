@@ -1,5 +1,7 @@
 //! Types for expressing properties of different target platforms and platform-based conditions
 
+use errors::Result;
+
 /// CPU architecture, as reported by `target_arch`.
 #[allow(non_camel_case_types)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -289,4 +291,194 @@ impl Condition {
   pub fn negate(&self) -> Condition {
     Condition::Not(Box::new(self.clone()))
   }
+  /// Returns a configuration predicate that can be used
+  /// in `#[cfg(...)]` attributes of the generated code and is true
+  /// on the same targets as `self`.
+  pub fn to_cfg(&self) -> String {
+    use target::Condition::*;
+    let list = |conditions: &[Condition]| -> String {
+      conditions
+        .iter()
+        .map(|c| c.to_cfg())
+        .collect::<Vec<_>>()
+        .join(", ")
+    };
+    match *self {
+      Arch(ref arch) => format!("target_arch = \"{}\"", arch.cfg_value()),
+      OS(ref os) => format!("target_os = \"{}\"", os.cfg_value()),
+      Family(ref family) => format!("target_family = \"{}\"", family.cfg_value()),
+      Env(ref env) => format!("target_env = \"{}\"", env.cfg_value()),
+      PointerWidth(ref pointer_width) => {
+        format!("target_pointer_width = \"{}\"", pointer_width.cfg_value())
+      }
+      Endian(ref endian) => format!("target_endian = \"{}\"", endian.cfg_value()),
+      And(ref conditions) => format!("all({})", list(conditions)),
+      Or(ref conditions) => format!("any({})", list(conditions)),
+      Not(ref condition) => format!("not({})", condition.to_cfg()),
+      True => "all()".to_string(),
+      False => "any()".to_string(),
+    }
+  }
+  /// Returns a C++ preprocessor expression that can be used
+  /// in `#if` directives of the generated C++ code and is true
+  /// on the same targets as `self`. Returns an error if some of the
+  /// nested conditions can't be detected by the preprocessor.
+  pub fn to_cpp_condition(&self) -> Result<String> {
+    use target::Condition::*;
+    let list = |conditions: &[Condition], separator: &str, empty: &str| -> Result<String> {
+      if conditions.is_empty() {
+        return Ok(empty.to_string());
+      }
+      let items: Vec<_> = conditions
+        .iter()
+        .map(|c| c.to_cpp_condition().map(|c| format!("({})", c)))
+        .collect::<Result<_>>()?;
+      Ok(items.join(separator))
+    };
+    Ok(match *self {
+         Arch(ref arch) => {
+           match *arch {
+               self::Arch::X86 => "defined(__i386__) || defined(_M_IX86)",
+               self::Arch::X86_64 => "defined(__x86_64__) || defined(_M_X64)",
+               self::Arch::Mips => "defined(__mips__) && !defined(__mips64)",
+               self::Arch::PowerPC => "defined(__powerpc__) && !defined(__powerpc64__)",
+               self::Arch::PowerPC64 => "defined(__powerpc64__)",
+               self::Arch::Arm => "defined(__arm__) || defined(_M_ARM)",
+               self::Arch::AArch64 => "defined(__aarch64__) || defined(_M_ARM64)",
+             }
+             .to_string()
+         }
+         OS(ref os) => {
+           match *os {
+               self::OS::Windows => "defined(_WIN32)",
+               self::OS::MacOS => {
+                 "defined(__APPLE__) && !defined(__ENVIRONMENT_IPHONE_OS_VERSION_MIN_REQUIRED__)"
+               }
+               self::OS::IOS => "defined(__ENVIRONMENT_IPHONE_OS_VERSION_MIN_REQUIRED__)",
+               self::OS::Linux => "defined(__linux__) && !defined(__ANDROID__)",
+               self::OS::Android => "defined(__ANDROID__)",
+               self::OS::FreeBSD => "defined(__FreeBSD__)",
+               self::OS::DragonFly => "defined(__DragonFly__)",
+               self::OS::Bitrig => "defined(__Bitrig__)",
+               self::OS::OpenBSD => "defined(__OpenBSD__)",
+               self::OS::NetBSD => "defined(__NetBSD__)",
+             }
+             .to_string()
+         }
+         Family(ref family) => {
+           match *family {
+               self::Family::Windows => "defined(_WIN32)",
+               self::Family::Unix => "!defined(_WIN32)",
+             }
+             .to_string()
+         }
+         Env(ref env) => {
+           match *env {
+             self::Env::Msvc => "defined(_MSC_VER)".to_string(),
+             self::Env::Gnu => "defined(__MINGW32__) || defined(__GLIBC__)".to_string(),
+             self::Env::Musl | self::Env::None => {
+               return Err(format!("target_env = \"{}\" can't be detected by C++ preprocessor",
+                                  env.cfg_value())
+                              .into())
+             }
+           }
+         }
+         PointerWidth(ref pointer_width) => {
+           match *pointer_width {
+               self::PointerWidth::P64 => "defined(_WIN64) || defined(__LP64__)",
+               self::PointerWidth::P32 => "!defined(_WIN64) && !defined(__LP64__)",
+             }
+             .to_string()
+         }
+         Endian(ref endian) => {
+           match *endian {
+               self::Endian::Little => {
+                 "defined(_WIN32) || __BYTE_ORDER__ == __ORDER_LITTLE_ENDIAN__"
+               }
+               self::Endian::Big => "!defined(_WIN32) && __BYTE_ORDER__ == __ORDER_BIG_ENDIAN__",
+             }
+             .to_string()
+         }
+         And(ref conditions) => list(conditions, " && ", "1")?,
+         Or(ref conditions) => list(conditions, " || ", "0")?,
+         Not(ref condition) => format!("!({})", condition.to_cpp_condition()?),
+         True => "1".to_string(),
+         False => "0".to_string(),
+       })
+  }
+}
+
+impl Arch {
+  /// Returns value of `target_arch` for this architecture.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Arch::X86 => "x86",
+      Arch::X86_64 => "x86_64",
+      Arch::Mips => "mips",
+      Arch::PowerPC => "powerpc",
+      Arch::PowerPC64 => "powerpc64",
+      Arch::Arm => "arm",
+      Arch::AArch64 => "aarch64",
+    }
+  }
+}
+
+impl OS {
+  /// Returns value of `target_os` for this operating system.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      OS::Windows => "windows",
+      OS::MacOS => "macos",
+      OS::IOS => "ios",
+      OS::Linux => "linux",
+      OS::Android => "android",
+      OS::FreeBSD => "freebsd",
+      OS::DragonFly => "dragonfly",
+      OS::Bitrig => "bitrig",
+      OS::OpenBSD => "openbsd",
+      OS::NetBSD => "netbsd",
+    }
+  }
+}
+
+impl Family {
+  /// Returns value of `target_family` for this family.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Family::Windows => "windows",
+      Family::Unix => "unix",
+    }
+  }
+}
+
+impl Env {
+  /// Returns value of `target_env` for this environment.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Env::Gnu => "gnu",
+      Env::Msvc => "msvc",
+      Env::Musl => "musl",
+      Env::None => "",
+    }
+  }
+}
+
+impl PointerWidth {
+  /// Returns value of `target_pointer_width` for this pointer width.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      PointerWidth::P64 => "64",
+      PointerWidth::P32 => "32",
+    }
+  }
+}
+
+impl Endian {
+  /// Returns value of `target_endian` for this endianness.
+  pub fn cfg_value(&self) -> &'static str {
+    match *self {
+      Endian::Little => "little",
+      Endian::Big => "big",
+    }
+  }
 }
@@ -0,0 +1,40 @@
+use cpp_build_config::*;
+use target::{Condition, current_target};
+
+#[test]
+fn linked_lib_types() {
+  let mut config = CppBuildConfig::new();
+  {
+    let mut data = CppBuildConfigData::new();
+    data.add_linked_lib("lib1");
+    data.add_linked_lib_with_type("lib2", CppLibraryType::Static);
+    config.add(Condition::True, data);
+  }
+  {
+    let mut data = CppBuildConfigData::new();
+    data.add_linked_lib_with_type("lib3", CppLibraryType::Shared);
+    config.add(Condition::True, data);
+  }
+  {
+    let mut data = CppBuildConfigData::new();
+    data.add_linked_lib_with_type("lib4", CppLibraryType::Shared);
+    config.add(Condition::False, data);
+  }
+  let data = config.eval(&current_target()).unwrap();
+  assert_eq!(data.linked_libs(), &["lib1", "lib2", "lib3"]);
+  assert_eq!(data.linked_lib_type("lib1"), None);
+  assert_eq!(data.linked_lib_type("lib2"), Some(CppLibraryType::Static));
+  assert_eq!(data.linked_lib_type("lib3"), Some(CppLibraryType::Shared));
+  assert_eq!(data.linked_lib_type("lib4"), None);
+}
+
+#[test]
+fn linked_lib_types_conflict() {
+  let mut config = CppBuildConfig::new();
+  for &lib_type in &[CppLibraryType::Static, CppLibraryType::Shared] {
+    let mut data = CppBuildConfigData::new();
+    data.add_linked_lib_with_type("lib1", lib_type);
+    config.add(Condition::True, data);
+  }
+  assert!(config.eval(&current_target()).is_err());
+}
@@ -1,2 +1,3 @@
-
+mod cpp_build_config;
+mod target;
 mod utils;
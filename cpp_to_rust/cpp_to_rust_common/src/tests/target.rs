@@ -0,0 +1,14 @@
+use target::*;
+
+#[test]
+fn cpp_condition() {
+  assert_eq!(Condition::OS(OS::Windows).to_cpp_condition().unwrap(),
+             "defined(_WIN32)");
+  assert_eq!(Condition::And(vec![Condition::Env(Env::Msvc),
+                                 Condition::OS(OS::Linux).negate()])
+                 .to_cpp_condition()
+                 .unwrap(),
+             "(defined(_MSC_VER)) && (!(defined(__linux__) && !defined(__ANDROID__)))");
+  assert_eq!(Condition::Or(Vec::new()).to_cpp_condition().unwrap(), "0");
+  assert!(Condition::Env(Env::Musl).to_cpp_condition().is_err());
+}
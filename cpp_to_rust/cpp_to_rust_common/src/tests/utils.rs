@@ -130,3 +130,20 @@ fn case_operations() {
   assert_eq!(v4.to_snake_case(), "count1");
 
 }
+
+#[test]
+fn case_operations_with_acronyms() {
+  use string_utils::CaseOperations;
+
+  let acronyms = vec!["URL".to_string(), "HTML".to_string(), "RGB".to_string(),
+                      "RGBA".to_string()];
+  assert_eq!("parseURL".to_snake_case(), "parse_u_r_l");
+  assert_eq!("parseURL".to_snake_case_with_acronyms(&acronyms), "parse_url");
+  assert_eq!("toHTML".to_snake_case_with_acronyms(&acronyms), "to_html");
+  assert_eq!("URLInfo".to_snake_case_with_acronyms(&acronyms), "url_info");
+  assert_eq!("fromRGBA".to_snake_case_with_acronyms(&acronyms), "from_rgba");
+  assert_eq!("fromRGB".to_snake_case_with_acronyms(&acronyms), "from_rgb");
+  assert_eq!("isNaN".to_snake_case_with_acronyms(&acronyms), "is_nan");
+  assert_eq!("toUpper".to_snake_case_with_acronyms(&acronyms), "to_upper");
+  assert_eq!(vec!["set", "URL"].to_snake_case_with_acronyms(&acronyms), "set_url");
+}
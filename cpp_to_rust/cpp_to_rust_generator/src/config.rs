@@ -5,7 +5,11 @@ use common::errors::Result;
 use cpp_method::CppMethod;
 use cpp_data::ParserCppData;
 pub use cpp_data::CppTypeAllocationPlace;
-use common::cpp_build_config::CppBuildConfig;
+pub use rust_info::{RustProcessedTypeInfo, RustModule};
+use common::cpp_build_config::{CppBuildConfig, LinkKind};
+use common::target;
+use rust_type::RustName;
+use rust_generator::{default_box_type, default_acronyms};
 use std::collections::HashMap;
 use common;
 
@@ -31,6 +35,50 @@ impl ::std::fmt::Debug for CppDataFilter {
   }
 }
 
+/// Function type used in `Config::add_type_plugin`.
+pub type TypePluginFn = Fn(&RustProcessedTypeInfo) -> Option<String> + Send + Sync;
+
+struct TypePlugin(Box<TypePluginFn>);
+
+impl ::std::fmt::Debug for TypePlugin {
+  fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
+    write!(f, "TypePlugin")
+  }
+}
+
+/// Function type used in `Config::set_module_name_hook`.
+pub type ModuleNameHookFn = Fn(&str) -> Option<String> + Send + Sync;
+
+struct ModuleNameHook(Box<ModuleNameHookFn>);
+
+impl ::std::fmt::Debug for ModuleNameHook {
+  fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
+    write!(f, "ModuleNameHook")
+  }
+}
+
+/// Function type used in `Config::add_doc_hook`.
+pub type DocHookFn = Fn(&str, String) -> String + Send + Sync;
+
+struct DocHook(Box<DocHookFn>);
+
+impl ::std::fmt::Debug for DocHook {
+  fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
+    write!(f, "DocHook")
+  }
+}
+
+/// Function type used in `Config::add_module_tree_hook`.
+pub type ModuleTreeHookFn = Fn(&mut Vec<RustModule>) -> Result<()> + Send + Sync;
+
+struct ModuleTreeHook(Box<ModuleTreeHookFn>);
+
+impl ::std::fmt::Debug for ModuleTreeHook {
+  fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::result::Result<(), ::std::fmt::Error> {
+    write!(f, "ModuleTreeHook")
+  }
+}
+
 /// Information about an extra non-`cpp_to_rust`-based dependency.
 #[derive(Default, Debug, Clone)]
 pub struct CrateDependency {
@@ -63,6 +111,10 @@ pub struct CrateProperties {
   name: String,
   /// Version of the crate (must be in compliance with cargo requirements)
   version: String,
+  /// Authors of the crate
+  authors: Vec<String>,
+  /// License of the crate (SPDX expression)
+  license: String,
   /// Extra properties to be merged with auto generated content of `Cargo.toml`
   custom_fields: common::toml::Table,
   /// Extra dependencies for output `Cargo.toml`
@@ -81,6 +133,8 @@ impl CrateProperties {
     CrateProperties {
       name: name.into(),
       version: version.into(),
+      authors: Vec::new(),
+      license: String::new(),
       custom_fields: Default::default(),
       dependencies: Vec::new(),
       build_dependencies: Vec::new(),
@@ -129,6 +183,21 @@ impl CrateProperties {
     self.remove_default_build_dependencies = true;
   }
 
+  /// Sets authors of the crate for output `Cargo.toml`.
+  /// Empty values are not written.
+  pub fn set_authors<Item, Iter>(&mut self, authors: Iter)
+    where Item: Into<String>,
+          Iter: IntoIterator<Item = Item>
+  {
+    self.authors = authors.into_iter().map(|x| x.into()).collect();
+  }
+
+  /// Sets license of the crate (e.g. `"MIT OR Apache-2.0"`) for output `Cargo.toml`.
+  /// Empty value is not written.
+  pub fn set_license<S: Into<String>>(&mut self, license: S) {
+    self.license = license.into();
+  }
+
   /// Sets custom fields for output `Cargo.toml`. These fields will
   /// be added to auto-generated fields (or replace them in case of a name conflict).
   pub fn set_custom_fields(&mut self, value: common::toml::Table) {
@@ -143,6 +212,14 @@ impl CrateProperties {
   pub fn version(&self) -> &String {
     &self.version
   }
+  /// Authors of the crate
+  pub fn authors(&self) -> &Vec<String> {
+    &self.authors
+  }
+  /// License of the crate
+  pub fn license(&self) -> &String {
+    &self.license
+  }
 
   /// Extra non-`cpp_to_rust`-based dependencies of the crate
   pub fn dependencies(&self) -> &Vec<CrateDependency> {
@@ -206,6 +283,46 @@ impl Default for CacheUsage {
   }
 }
 
+/// Rust edition of the generated crate.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RustEdition {
+  /// Rust 2015. `extern crate` declarations are generated and
+  /// paths within the crate start with `::`.
+  Edition2015,
+  /// Rust 2018. `edition` key is written to `Cargo.toml`,
+  /// `extern crate` declarations are omitted and paths within the crate
+  /// start with `crate::`.
+  Edition2018,
+}
+
+/// Visibility of a generated Rust type.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TypeVisibility {
+  /// The type is declared with `pub`.
+  Public,
+  /// The type is declared with `pub(crate)` and can only be used
+  /// within the generated crate.
+  Crate,
+}
+
+impl Default for RustEdition {
+  fn default() -> RustEdition {
+    RustEdition::Edition2015
+  }
+}
+
+/// A library linked with a `#[link]` attribute placed on
+/// the FFI functions block of the generated crate.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LinkLibrary {
+  /// Name of the library (`name` parameter of the attribute).
+  pub name: String,
+  /// Kind of the library (`kind` parameter of the attribute).
+  pub kind: LinkKind,
+  /// If set, the attribute is only active on targets matching the condition.
+  pub target_filter: Option<target::Condition>,
+}
+
 /// Value of this enum determines how extra logging information
 /// will be used.
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -224,6 +341,316 @@ impl Default for DebugLoggingConfig {
   }
 }
 
+/// Settings of the generated Rust API and crate. They are stored in `Config`
+/// and passed to the Rust generator and the Rust code generator by reference.
+#[derive(Debug)]
+pub struct RustGeneratorSettings {
+  // see `Config` setters documentation for information about these properties
+  type_plugins: Vec<TypePlugin>,
+  module_name_hook: Option<ModuleNameHook>,
+  doc_hooks: Vec<DocHook>,
+  module_tree_hooks: Vec<ModuleTreeHook>,
+  flattened_namespaces: Vec<String>,
+  debug_impl_types: Vec<String>,
+  display_method_names: Vec<String>,
+  string_types: Vec<String>,
+  link_libraries: Vec<LinkLibrary>,
+  write_module_inventories: bool,
+  format_generated_code: bool,
+  rust_edition: RustEdition,
+  generate_layout_tests: bool,
+  inline_wrappers: bool,
+  flatten_single_type_modules: bool,
+  modules_behind_features: bool,
+  debug_size_assertions: bool,
+  errors_module: bool,
+  prune_ffi_functions: bool,
+  pod_types: HashMap<String, bool>,
+  transparent_types: HashMap<String, RustName>,
+  overload_suffixes: HashMap<String, Vec<String>>,
+  raw_trait_impls: HashMap<String, Vec<String>>,
+  iterable_types: Vec<String>,
+  total_order_types: Vec<String>,
+  send_types: Vec<String>,
+  sync_types: Vec<String>,
+  type_visibility: HashMap<String, TypeVisibility>,
+  acronyms: Vec<String>,
+  blocked_enum_values: HashMap<String, Vec<String>>,
+  platform_methods: HashMap<String, target::Condition>,
+  deprecated_methods: HashMap<String, String>,
+  fallible_constructors: HashMap<String, String>,
+  out_parameters: HashMap<String, Vec<String>>,
+  argument_renames: HashMap<String, Vec<(usize, String)>>,
+  bool_getter_is_prefix: bool,
+  bool_getter_is_prefix_exceptions: Vec<String>,
+  wide_string_conversion: bool,
+  merge_const_getter_pairs: bool,
+  target_family: target::Family,
+  box_type: RustName,
+  cast_error_type: Option<RustName>,
+  chainable_setters: bool,
+  chainable_setter_methods: Vec<String>,
+  qstring_return_conversion: bool,
+  stream_operator_traits: bool,
+  string_from_impls: bool,
+  parented_qobject_constructors: bool,
+  cpp_doc_base_url: Option<String>,
+}
+
+impl RustGeneratorSettings {
+  /// Returns values added by `Config::add_flattened_namespace`.
+  pub fn flattened_namespaces(&self) -> &Vec<String> {
+    &self.flattened_namespaces
+  }
+
+  /// Returns values added by `Config::add_type_plugin`.
+  pub fn type_plugins(&self) -> Vec<&Box<TypePluginFn>> {
+    self.type_plugins.iter().map(|x| &x.0).collect()
+  }
+
+  /// Returns value set by `Config::set_module_name_hook`.
+  pub fn module_name_hook(&self) -> Option<&Box<ModuleNameHookFn>> {
+    self.module_name_hook.as_ref().map(|x| &x.0)
+  }
+
+  /// Returns values added by `Config::add_doc_hook`.
+  pub fn doc_hooks(&self) -> Vec<&Box<DocHookFn>> {
+    self.doc_hooks.iter().map(|x| &x.0).collect()
+  }
+
+  /// Returns values added by `Config::add_module_tree_hook`.
+  pub fn module_tree_hooks(&self) -> Vec<&Box<ModuleTreeHookFn>> {
+    self.module_tree_hooks.iter().map(|x| &x.0).collect()
+  }
+
+  /// Returns values added by `Config::add_debug_impl_type`.
+  pub fn debug_impl_types(&self) -> &Vec<String> {
+    &self.debug_impl_types
+  }
+
+  /// Returns values added by `Config::add_display_method_name`.
+  pub fn display_method_names(&self) -> &Vec<String> {
+    &self.display_method_names
+  }
+
+  /// Returns value set by `Config::set_string_types`.
+  pub fn string_types(&self) -> &Vec<String> {
+    &self.string_types
+  }
+
+  /// Returns values added by `Config::add_link_library`.
+  pub fn link_libraries(&self) -> &Vec<LinkLibrary> {
+    &self.link_libraries
+  }
+
+  /// Returns value set by `Config::set_write_module_inventories`.
+  pub fn write_module_inventories(&self) -> bool {
+    self.write_module_inventories
+  }
+
+  /// Returns value set by `Config::set_format_generated_code`.
+  pub fn format_generated_code(&self) -> bool {
+    self.format_generated_code
+  }
+
+  /// Returns value set by `Config::set_rust_edition`.
+  pub fn rust_edition(&self) -> RustEdition {
+    self.rust_edition
+  }
+
+  /// Returns value set by `Config::set_generate_layout_tests`.
+  pub fn generate_layout_tests(&self) -> bool {
+    self.generate_layout_tests
+  }
+
+  /// Returns value set by `Config::set_inline_wrappers`.
+  pub fn inline_wrappers(&self) -> bool {
+    self.inline_wrappers
+  }
+
+  /// Returns value set by `Config::set_flatten_single_type_modules`.
+  pub fn flatten_single_type_modules(&self) -> bool {
+    self.flatten_single_type_modules
+  }
+
+  /// Returns value set by `Config::set_modules_behind_features`.
+  pub fn modules_behind_features(&self) -> bool {
+    self.modules_behind_features
+  }
+
+  /// Returns value set by `Config::set_debug_size_assertions`.
+  pub fn debug_size_assertions(&self) -> bool {
+    self.debug_size_assertions
+  }
+
+  /// Returns value set by `Config::set_errors_module`.
+  pub fn errors_module(&self) -> bool {
+    self.errors_module
+  }
+
+  /// Returns value set by `Config::set_prune_ffi_functions`.
+  pub fn prune_ffi_functions(&self) -> bool {
+    self.prune_ffi_functions
+  }
+
+  /// Returns values added by `Config::set_pod_type`.
+  /// Values indicate whether `PartialEq` should be implemented.
+  pub fn pod_types(&self) -> &HashMap<String, bool> {
+    &self.pod_types
+  }
+
+  /// Returns values added by `Config::set_transparent_type`.
+  pub fn transparent_types(&self) -> &HashMap<String, RustName> {
+    &self.transparent_types
+  }
+
+  /// Returns values set by `Config::set_overload_suffixes`.
+  /// Keys of the hash map are full names of C++ methods.
+  pub fn overload_suffixes(&self) -> &HashMap<String, Vec<String>> {
+    &self.overload_suffixes
+  }
+
+  /// Returns values added by `Config::add_raw_trait_impl`.
+  /// Keys of the hash map are names of C++ types.
+  pub fn raw_trait_impls(&self) -> &HashMap<String, Vec<String>> {
+    &self.raw_trait_impls
+  }
+
+  /// Returns values added by `Config::add_iterable_type`.
+  pub fn iterable_types(&self) -> &Vec<String> {
+    &self.iterable_types
+  }
+
+  /// Returns values added by `Config::mark_total_order`.
+  pub fn total_order_types(&self) -> &Vec<String> {
+    &self.total_order_types
+  }
+
+  /// Returns values added by `Config::mark_send`.
+  pub fn send_types(&self) -> &Vec<String> {
+    &self.send_types
+  }
+
+  /// Returns values added by `Config::mark_sync`.
+  pub fn sync_types(&self) -> &Vec<String> {
+    &self.sync_types
+  }
+
+  /// Returns values set by `Config::set_type_visibility`.
+  pub fn type_visibility(&self) -> &HashMap<String, TypeVisibility> {
+    &self.type_visibility
+  }
+
+  /// Returns values added by `Config::add_acronym`.
+  pub fn acronyms(&self) -> &Vec<String> {
+    &self.acronyms
+  }
+
+  /// Returns values added by `Config::add_cpp_parser_blocked_enum_value`.
+  /// Keys of the hash map are names of C++ enums.
+  pub fn blocked_enum_values(&self) -> &HashMap<String, Vec<String>> {
+    &self.blocked_enum_values
+  }
+
+  /// Returns values added by `Config::add_platform_method`.
+  pub fn platform_methods(&self) -> &HashMap<String, target::Condition> {
+    &self.platform_methods
+  }
+
+  /// Returns values added by `Config::add_deprecated_method`.
+  pub fn deprecated_methods(&self) -> &HashMap<String, String> {
+    &self.deprecated_methods
+  }
+
+  /// Returns values added by `Config::add_fallible_constructor`.
+  /// Keys of the hash map are names of C++ types.
+  pub fn fallible_constructors(&self) -> &HashMap<String, String> {
+    &self.fallible_constructors
+  }
+
+  /// Returns values added by `Config::add_out_parameter`.
+  /// Keys of the hash map are full names of C++ methods.
+  pub fn out_parameters(&self) -> &HashMap<String, Vec<String>> {
+    &self.out_parameters
+  }
+
+  /// Returns values added by `Config::add_argument_rename`.
+  /// Keys of the hash map are full names of C++ methods.
+  pub fn argument_renames(&self) -> &HashMap<String, Vec<(usize, String)>> {
+    &self.argument_renames
+  }
+
+  /// Returns value set by `Config::set_bool_getter_is_prefix`.
+  pub fn bool_getter_is_prefix(&self) -> bool {
+    self.bool_getter_is_prefix
+  }
+
+  /// Returns values added by `Config::add_bool_getter_is_prefix_exception`.
+  pub fn bool_getter_is_prefix_exceptions(&self) -> &Vec<String> {
+    &self.bool_getter_is_prefix_exceptions
+  }
+
+  /// Returns value set by `Config::set_wide_string_conversion`.
+  pub fn wide_string_conversion(&self) -> bool {
+    self.wide_string_conversion
+  }
+
+  /// Returns value set by `Config::set_merge_const_getter_pairs`.
+  pub fn merge_const_getter_pairs(&self) -> bool {
+    self.merge_const_getter_pairs
+  }
+
+  /// Returns value set by `Config::set_target_family`.
+  pub fn target_family(&self) -> target::Family {
+    self.target_family
+  }
+
+  /// Returns value set by `Config::set_box_type`.
+  pub fn box_type(&self) -> &RustName {
+    &self.box_type
+  }
+
+  /// Returns value set by `Config::set_cast_error_type`.
+  pub fn cast_error_type(&self) -> Option<&RustName> {
+    self.cast_error_type.as_ref()
+  }
+
+  /// Returns value set by `Config::set_chainable_setters`.
+  pub fn chainable_setters(&self) -> bool {
+    self.chainable_setters
+  }
+
+  /// Returns values added by `Config::add_chainable_setter`.
+  pub fn chainable_setter_methods(&self) -> &Vec<String> {
+    &self.chainable_setter_methods
+  }
+
+  /// Returns value set by `Config::set_qstring_return_conversion`.
+  pub fn qstring_return_conversion(&self) -> bool {
+    self.qstring_return_conversion
+  }
+
+  /// Returns value set by `Config::set_stream_operator_traits`.
+  pub fn stream_operator_traits(&self) -> bool {
+    self.stream_operator_traits
+  }
+
+  /// Returns value set by `Config::set_string_from_impls`.
+  pub fn string_from_impls(&self) -> bool {
+    self.string_from_impls
+  }
+
+  /// Returns value set by `Config::set_parented_qobject_constructors`.
+  pub fn parented_qobject_constructors(&self) -> bool {
+    self.parented_qobject_constructors
+  }
+
+  /// Returns value set by `Config::set_cpp_doc_base_url`.
+  pub fn cpp_doc_base_url(&self) -> Option<&str> {
+    self.cpp_doc_base_url.as_ref().map(|x| x.as_str())
+  }
+}
+
 /// The starting point of `cpp_to_rust` API.
 /// Create a `Config` object, set its properties,
 /// add custom functions if necessary, and start
@@ -243,15 +670,20 @@ pub struct Config {
   include_directives: Vec<PathBuf>,
   cpp_parser_arguments: Vec<String>,
   cpp_parser_blocked_names: Vec<String>,
+  cpp_parser_allowed_names: Vec<String>,
   cpp_ffi_generator_filters: Vec<CppFfiGeneratorFilter>,
   cpp_data_filters: Vec<CppDataFilter>,
   cpp_filtered_namespaces: Vec<String>,
+  rust_generator_settings: RustGeneratorSettings,
   cpp_build_config: CppBuildConfig, // TODO: add CppBuildPaths when needed
   write_dependencies_local_paths: bool,
   type_allocation_places: HashMap<String, CppTypeAllocationPlace>,
+  protected_constructor_classes: Vec<String>,
+  inline_inherited_methods: Vec<String>,
   debug_logging_config: DebugLoggingConfig,
   quiet_mode: bool,
   write_cache: bool,
+  dry_run: bool,
   cpp_lib_version: Option<String>,
 }
 
@@ -277,16 +709,71 @@ impl Config {
       include_directives: Default::default(),
       cpp_parser_arguments: Default::default(),
       cpp_parser_blocked_names: Default::default(),
+      cpp_parser_allowed_names: Default::default(),
       cpp_ffi_generator_filters: Default::default(),
       cpp_data_filters: Default::default(),
       cpp_filtered_namespaces: Default::default(),
+      rust_generator_settings: RustGeneratorSettings {
+        type_plugins: Default::default(),
+        module_name_hook: None,
+        doc_hooks: Default::default(),
+        module_tree_hooks: Default::default(),
+        flattened_namespaces: Vec::new(),
+        debug_impl_types: Default::default(),
+        display_method_names: Default::default(),
+        string_types: Vec::new(),
+        link_libraries: Vec::new(),
+        pod_types: Default::default(),
+        transparent_types: Default::default(),
+        overload_suffixes: Default::default(),
+        raw_trait_impls: Default::default(),
+        iterable_types: Vec::new(),
+        total_order_types: Vec::new(),
+        send_types: Vec::new(),
+        sync_types: Vec::new(),
+        type_visibility: Default::default(),
+        acronyms: default_acronyms(),
+        blocked_enum_values: Default::default(),
+        platform_methods: Default::default(),
+        deprecated_methods: Default::default(),
+        fallible_constructors: Default::default(),
+        out_parameters: Default::default(),
+        argument_renames: Default::default(),
+        bool_getter_is_prefix: false,
+        bool_getter_is_prefix_exceptions: Vec::new(),
+        wide_string_conversion: false,
+        merge_const_getter_pairs: false,
+        target_family: target::current_family(),
+        box_type: default_box_type(),
+        cast_error_type: None,
+        chainable_setters: false,
+        chainable_setter_methods: Vec::new(),
+        qstring_return_conversion: false,
+        stream_operator_traits: false,
+        string_from_impls: false,
+        parented_qobject_constructors: false,
+        cpp_doc_base_url: None,
+        write_module_inventories: false,
+        format_generated_code: true,
+        rust_edition: RustEdition::default(),
+        generate_layout_tests: false,
+        inline_wrappers: false,
+        flatten_single_type_modules: false,
+        modules_behind_features: false,
+        debug_size_assertions: false,
+        errors_module: false,
+        prune_ffi_functions: false,
+      },
       cpp_build_config: Default::default(),
       type_allocation_places: Default::default(),
+      protected_constructor_classes: Vec::new(),
+      inline_inherited_methods: Vec::new(),
       write_dependencies_local_paths: true,
       cache_usage: CacheUsage::default(),
       debug_logging_config: DebugLoggingConfig::default(),
       quiet_mode: false,
       write_cache: true,
+      dry_run: false,
       cpp_lib_version: None,
     }
   }
@@ -366,6 +853,135 @@ impl Config {
     }
   }
 
+  /// Adds a variant `value_name` of C++ enum `enum_name` that should be
+  /// hidden in the generated Rust enum (e.g. a deprecated or
+  /// platform-specific variant). Blocked variants don't affect
+  /// removal of the common prefix of variant names. If no other variant
+  /// has the same value, the variant is kept with `#[doc(hidden)]` and
+  /// a `_` prefix because C++ code may still return its value.
+  /// At least one variant of the enum must not be blocked.
+  pub fn add_cpp_parser_blocked_enum_value<S1, S2>(&mut self, enum_name: S1, value_name: S2)
+    where S1: Into<String>,
+          S2: Into<String>
+  {
+    self
+      .rust_generator_settings
+      .blocked_enum_values
+      .entry(enum_name.into())
+      .or_insert_with(Vec::new)
+      .push(value_name.into());
+  }
+
+  /// Marks C++ methods with name `name` as only available on targets
+  /// matching `condition`. The C++ wrappers of these methods are put
+  /// under the corresponding `#if`, and their FFI functions and Rust wrappers
+  /// are annotated with `#[cfg(...)]` instead of being available everywhere.
+  /// `name` is matched against the full name of the method
+  /// (e.g. `"QProcess::nativeArguments"`) or its own name
+  /// (e.g. `"nativeArguments"`), similar to `Config::add_cpp_parser_blocked_name`.
+  /// A platform-specific method is only overloaded with methods
+  /// available on the same targets; other overloads receive separate
+  /// Rust methods.
+  pub fn add_platform_method<S: Into<String>>(&mut self, name: S, condition: target::Condition) {
+    self.rust_generator_settings.platform_methods.insert(name.into(), condition);
+  }
+
+  /// Marks C++ methods with name `name` as deprecated. Rust wrappers of
+  /// these methods will be annotated with `#[deprecated(note = "...")]`
+  /// containing `note`, so that users of the crate get a warning.
+  /// `name` is matched in the same way as in `Config::add_platform_method`.
+  /// An overloaded Rust method is only marked as deprecated if all
+  /// C++ methods it wraps are deprecated.
+  pub fn add_deprecated_method<S1, S2>(&mut self, name: S1, note: S2)
+    where S1: Into<String>,
+          S2: Into<String>
+  {
+    self.rust_generator_settings.deprecated_methods.insert(name.into(), note.into());
+  }
+
+  /// Makes constructors of C++ class `type_name` fallible. Instead of returning
+  /// the created object, the generated constructors call its `check_method`
+  /// (a const method without arguments returning `bool`, e.g. `"isValid"`)
+  /// and return `Result<T, cpp_utils::InvalidObjectError>`, where `T` is
+  /// the usual return type of the constructor, and `Err` is returned
+  /// if the check fails. Constructors of other types are not affected.
+  pub fn add_fallible_constructor<S1, S2>(&mut self, type_name: S1, check_method: S2)
+    where S1: Into<String>,
+          S2: Into<String>
+  {
+    self
+      .rust_generator_settings
+      .fallible_constructors
+      .insert(type_name.into(), check_method.into());
+  }
+
+  /// Marks argument `argument_name` of C++ method `method_name`
+  /// (e.g. `"QString::toInt"`, `"ok"`) as an out-parameter. The argument
+  /// must be a non-const pointer or reference to a numeric or `bool` type.
+  /// It is removed from the arguments of the Rust method, and its value
+  /// after the call is returned together with the return value of the method
+  /// as a tuple (e.g. `bool parse(int* outCode)` becomes
+  /// `fn parse(&self) -> (bool, i32)`). Methods with out-parameters
+  /// can't be overloaded and can't have converted return types.
+  pub fn add_out_parameter<S1, S2>(&mut self, method_name: S1, argument_name: S2)
+    where S1: Into<String>,
+          S2: Into<String>
+  {
+    self
+      .rust_generator_settings
+      .out_parameters
+      .entry(method_name.into())
+      .or_insert_with(Vec::new)
+      .push(argument_name.into());
+  }
+
+  /// Sets Rust name `new_name` for the argument with index `argument_index`
+  /// of C++ method `method_name` (e.g. `"QPoint::setX"`, `0`, `"x"`).
+  /// Argument indexes start from 0 and don't include `this` argument.
+  /// By default, names of arguments are taken from the C++ declaration,
+  /// which is not helpful if the declaration uses names like `a` or `arg1`.
+  /// Indexes are checked against the full signature, so a variant with
+  /// omitted default arguments keeps the names of its remaining arguments.
+  /// The method is skipped with an error if it has no argument with this index
+  /// or `new_name` conflicts with the name of another argument.
+  pub fn add_argument_rename<S1, S2>(&mut self,
+                                     method_name: S1,
+                                     argument_index: usize,
+                                     new_name: S2)
+    where S1: Into<String>,
+          S2: Into<String>
+  {
+    self
+      .rust_generator_settings
+      .argument_renames
+      .entry(method_name.into())
+      .or_insert_with(Vec::new)
+      .push((argument_index, new_name.into()));
+  }
+
+  /// Adds a C++ identifier that should be processed by the C++ parser.
+  /// If at least one allowed name is added, only matching entities
+  /// and entities declared inside them (e.g. the methods of an allowed class
+  /// or the contents of an allowed namespace) will be processed.
+  /// Identifier format is the same as in `Config::add_cpp_parser_blocked_name`.
+  ///
+  /// Types used by the allowed methods (argument and return types,
+  /// base classes and field types of the used classes)
+  /// are kept regardless of the allowed names, but only their destructors
+  /// are added to the API. If a name is both allowed and blocked, it's blocked.
+  pub fn add_cpp_parser_allowed_name<P: Into<String>>(&mut self, name: P) {
+    self.cpp_parser_allowed_names.push(name.into());
+  }
+
+  /// Sets the list of allowed names, replacing previously added names.
+  /// See `Config::add_cpp_parser_allowed_name`.
+  pub fn set_cpp_parser_allowlist<Item, Iter>(&mut self, items: Iter)
+    where Item: Into<String>,
+          Iter: IntoIterator<Item = Item>
+  {
+    self.cpp_parser_allowed_names = items.into_iter().map(|x| x.into()).collect();
+  }
+
   /// Adds a command line argument for clang C++ parser.
   ///
   /// Note that this value is not used when building the wrapper library.
@@ -386,7 +1002,6 @@ impl Config {
     }
   }
 
-
   /// Adds path to an include directory.
   /// It's supplied to the C++ parser via `-I` option.
   ///
@@ -407,7 +1022,6 @@ impl Config {
     self.framework_paths.push(path.into());
   }
 
-
   /// Adds path to an include directory or an include file
   /// of the target library.
   /// Any C++ types and methods will be parsed and used only
@@ -424,10 +1038,33 @@ impl Config {
   /// Adds an include directive. Each directive will be added
   /// as `#include <path>` to the input file for the C++ parser.
   /// File name only paths or relative paths should be used in this method.
+  ///
+  /// Directives are emitted in the order they were added.
   pub fn add_include_directive<P: Into<PathBuf>>(&mut self, path: P) {
     self.include_directives.push(path.into());
   }
 
+  /// Adds an include directive that will be emitted before
+  /// all previously added directives. This is useful when a header
+  /// requires another header to be included first.
+  /// Like `Config::add_include_directive`, this method doesn't
+  /// remove repeated directives (see `Config::dedup_include_directives`).
+  pub fn add_include_directive_front<P: Into<PathBuf>>(&mut self, path: P) {
+    self.include_directives.insert(0, path.into());
+  }
+
+  /// Removes repeated include directives, keeping only
+  /// the first occurrence of each path.
+  pub fn dedup_include_directives(&mut self) {
+    let mut directives = Vec::new();
+    for path in self.include_directives.drain(..) {
+      if !directives.contains(&path) {
+        directives.push(path);
+      }
+    }
+    self.include_directives = directives;
+  }
+
   /// Adds a custom function that decides whether a C++ method should be
   /// added to the C++ wrapper library. For each C++ method,
   /// each function will be run once. Filters are executed in the same order they
@@ -448,6 +1085,26 @@ impl Config {
       .push(CppFfiGeneratorFilter(Box::new(f)));
   }
 
+  /// Blocks the overload of C++ method `method_name` with arguments of types
+  /// `argument_types` while keeping other overloads of the method.
+  /// `method_name` can be either the full name of the method
+  /// (e.g. `"QString::arg"`) or its own name (e.g. `"arg"`).
+  /// Argument types are compared with the C++ pseudo-code of the argument types
+  /// of each method (e.g. `"int"`, `"const QString&"`). This is a shortcut for
+  /// a filter added with `Config::add_cpp_ffi_generator_filter`.
+  pub fn add_blocked_overload<S, I>(&mut self, method_name: S, argument_types: I)
+    where S: Into<String>,
+          I: IntoIterator,
+          I::Item: Into<String>
+  {
+    let method_name = method_name.into();
+    let argument_types: Vec<String> = argument_types.into_iter().map(|x| x.into()).collect();
+    self.add_cpp_ffi_generator_filter(move |method| {
+                                        Ok(!method.matches_overload(&method_name,
+                                                                    &argument_types))
+                                      });
+  }
+
   /// Adds a custom function that visits `&mut CppData` and can perform any changes
   /// in the output of the C++ parser. Filters are executed in the same order they
   /// were added. If the function returns `Err`, the processing is terminated.
@@ -457,6 +1114,59 @@ impl Config {
     self.cpp_data_filters.push(CppDataFilter(Box::new(f)));
   }
 
+  /// Adds a custom function that is called for each Rust wrapper type
+  /// after its methods and trait implementations are generated.
+  /// If the function returns `Some(code)`, `code` is appended to the
+  /// module containing the type. Plugins are executed in the same order
+  /// they were added.
+  pub fn add_type_plugin<F>(&mut self, f: F)
+    where F: Fn(&RustProcessedTypeInfo) -> Option<String> + Send + Sync + 'static
+  {
+    self.rust_generator_settings.type_plugins.push(TypePlugin(Box::new(f)));
+  }
+
+  /// Sets a custom function that chooses names of top level Rust modules.
+  /// The function is called with the name of each C++ include file
+  /// (e.g. `"QtGlobal"` or `"qstring.h"`). If it returns `Some(name)`,
+  /// `name` is used as the module name instead of the name derived
+  /// from the include file name. If it returns `None`, the default name is used.
+  ///
+  /// The generator reports an error if a name returned by the function
+  /// conflicts with the module name of another include file.
+  pub fn set_module_name_hook<F>(&mut self, f: F)
+    where F: Fn(&str) -> Option<String> + Send + Sync + 'static
+  {
+    self.rust_generator_settings.module_name_hook = Some(ModuleNameHook(Box::new(f)));
+  }
+
+  /// Adds a custom function that can modify documentation of generated
+  /// types and methods. The function is called with the full Rust name
+  /// of the item (e.g. `"qt_core::string::String"` for a type or
+  /// `"qt_core::string::String::to_upper"` for a method) and its
+  /// automatically generated documentation, and it should return
+  /// the final documentation (e.g. the input with an appended example).
+  /// Hooks are executed in the same order they were added.
+  pub fn add_doc_hook<F>(&mut self, f: F)
+    where F: Fn(&str, String) -> String + Send + Sync + 'static
+  {
+    self.rust_generator_settings.doc_hooks.push(DocHook(Box::new(f)));
+  }
+
+  /// Adds a custom function that can modify the tree of generated Rust modules
+  /// (e.g. change documentation, reorder or remove methods) after all wrappers
+  /// are generated but before any code is written. Hooks are executed in the same
+  /// order they were added. If the function returns `Err`, the processing is terminated.
+  /// Types removed by the hook are reported as skipped
+  /// (see `RustGeneratorOutput::type_manifest`), but types still used
+  /// by other items can't be removed.
+  /// Other items refer to types by their full names, so modules, types and
+  /// free functions can't be renamed or moved to another module.
+  pub fn add_module_tree_hook<F>(&mut self, f: F)
+    where F: Fn(&mut Vec<RustModule>) -> Result<()> + Send + Sync + 'static
+  {
+    self.rust_generator_settings.module_tree_hooks.push(ModuleTreeHook(Box::new(f)));
+  }
+
   /// Adds a namespace to filter out before rust code generation.
   pub fn add_cpp_filtered_namespace<N: Into<String>>(&mut self, namespace: N) {
     self.cpp_filtered_namespaces.push(namespace.into());
@@ -472,9 +1182,67 @@ impl Config {
     }
   }
 
+  /// Adds a C++ namespace (e.g. `"mylib::detail"`) whose members should be
+  /// placed in the module of the parent namespace instead of a nested module.
+  /// Unlike `Config::add_cpp_filtered_namespace`, the fully qualified name
+  /// of the namespace is used, so other namespaces with the same name
+  /// are not affected. Generation fails if a member of the flattened namespace
+  /// gets the same Rust name as a member of another namespace.
+  pub fn add_flattened_namespace<N: Into<String>>(&mut self, namespace: N) {
+    self.rust_generator_settings.flattened_namespaces.push(namespace.into());
+  }
+
+  /// Requests `std::fmt::Debug` implementation for C++ class `type_name`.
+  /// If the class has a `toString()` const method, the implementation
+  /// forwards to its result (which must implement `Debug`). Otherwise,
+  /// it prints the type name and the address of the object.
+  pub fn add_debug_impl_type<S: Into<String>>(&mut self, type_name: S) {
+    self.rust_generator_settings.debug_impl_types.push(type_name.into());
+  }
+
+  /// Requests `std::fmt::Debug` implementation for multiple C++ classes.
+  /// See `Config::add_debug_impl_type`.
+  pub fn add_debug_impl_types<Item, Iter>(&mut self, type_names: Iter)
+    where Item: Into<String>,
+          Iter: IntoIterator<Item = Item>
+  {
+    for type_name in type_names {
+      self.rust_generator_settings.debug_impl_types.push(type_name.into());
+    }
+  }
+
+  /// Adds name of a C++ method used for generating `std::fmt::Display`
+  /// implementations. If a class has a non-static const method with this name
+  /// that doesn't take any arguments and returns a class by value,
+  /// `Display` implementation of the class will forward to its result
+  /// (which must implement `Display`). If multiple methods are available,
+  /// the one that was added first is used.
+  pub fn add_display_method_name<S: Into<String>>(&mut self, name: S) {
+    self.rust_generator_settings.display_method_names.push(name.into());
+  }
+
+  /// Sets C++ names of string classes that are accepted as
+  /// `impl cpp_utils::ToCppRef<T>` in arguments of non-overloaded methods
+  /// passed by value or by const reference. Such arguments accept `&T`,
+  /// `&str` and `String`. For `&str` and `String`, a temporary object is created
+  /// using `From` implementations of the wrapper type, so they must be provided
+  /// (e.g. in the crate template or with `Config::set_string_from_impls`).
+  /// Arguments of overloaded methods still only accept `&T` because generic
+  /// types can't be used in the parameters traits emulating overloading.
+  /// Default value is empty.
+  pub fn set_string_types<Item, Iter>(&mut self, types: Iter)
+    where Item: Into<String>,
+          Iter: IntoIterator<Item = Item>
+  {
+    self.rust_generator_settings.string_types = types.into_iter().map(|x| x.into()).collect();
+  }
 
   /// Overrides automatic selection of type allocation place for `type_name` and uses `place`
   /// instead. See `CppTypeAllocationPlace` for more information.
+  ///
+  /// `type_name` may also be a template instantiation (e.g. `"QList<int>"`).
+  /// Such an override only affects this instantiation and takes precedence over
+  /// the allocation place of the template class. Whitespace in `type_name` is ignored.
   pub fn set_type_allocation_place<S: Into<String>>(&mut self,
                                                     place: CppTypeAllocationPlace,
                                                     type_name: S) {
@@ -495,6 +1263,313 @@ impl Config {
     }
   }
 
+  /// Marks `type_name` as a trivially copyable (POD) type. The generated
+  /// struct will derive `Clone` and `Copy`, so the value is copied
+  /// bytewise without calling C++ copy constructor. If `partial_eq`
+  /// is true, `PartialEq` is implemented by comparing bytes of the values.
+  /// This is only allowed for classes without base classes that contain only
+  /// integer, enum and pointer fields laid out without padding bytes.
+  ///
+  /// `Drop` is not implemented for the struct, so the C++ destructor
+  /// is never called. The generator can't verify that the C++ type is actually
+  /// trivially copyable and trivially destructible, so it's the caller's responsibility.
+  /// The type must be stack-allocated (see `Config::set_type_allocation_place`),
+  /// otherwise the generator will report an error.
+  pub fn set_pod_type<S: Into<String>>(&mut self, type_name: S, partial_eq: bool) {
+    self.rust_generator_settings.pod_types.insert(type_name.into(), partial_eq);
+  }
+
+  /// Marks `type_name` as a type consisting of a single value of
+  /// Rust type `inner_type` (e.g. `libc::c_int`), such as a C++ struct
+  /// with a single field or a strong typedef. The generated struct will be
+  /// declared as `#[repr(transparent)] pub struct T(pub inner_type);`
+  /// instead of an opaque byte buffer, so the value can be accessed
+  /// directly and passed through FFI by value.
+  ///
+  /// The type must be stack-allocated (see `Config::set_type_allocation_place`),
+  /// otherwise the generator will report an error. The generated crate
+  /// fails to compile if size of `inner_type` differs from
+  /// size of the C++ type.
+  pub fn set_transparent_type<S: Into<String>>(&mut self, type_name: S, inner_type: RustName) {
+    self.rust_generator_settings.transparent_types.insert(type_name.into(), inner_type);
+  }
+
+  /// Overrides automatically generated name suffixes of Rust methods
+  /// produced from C++ method `cpp_method_name` (e.g. `"QString::arg"`).
+  /// Overloads that can't be represented by a single Rust method
+  /// are split into groups, and each group receives a suffix.
+  /// `suffixes` are assigned to these groups in order of declaration
+  /// of their first methods in C++ headers. An empty string means no suffix.
+  ///
+  /// The number of suffixes must be equal to the number of groups,
+  /// and the suffixes must be unique, otherwise the generator will report an error.
+  pub fn set_overload_suffixes<S, SI, S2>(&mut self, cpp_method_name: S, suffixes: SI)
+    where S: Into<String>,
+          SI: IntoIterator<Item = S2>,
+          S2: Into<String>
+  {
+    self
+      .rust_generator_settings
+      .overload_suffixes
+      .insert(cpp_method_name.into(),
+              suffixes.into_iter().map(|x| x.into()).collect());
+  }
+
+  /// If `value` is `true`, Rust names of class methods without arguments
+  /// returning `bool` will receive `is_` prefix if the C++ name looks like
+  /// an adjective (e.g. `visible()` becomes `is_visible()`). Names starting
+  /// with `is`, `has`, `can` or `should` are not changed. This is a heuristic,
+  /// so individual methods can be excluded with
+  /// `Config::add_bool_getter_is_prefix_exception`. Default value is `false`.
+  pub fn set_bool_getter_is_prefix(&mut self, value: bool) {
+    self.rust_generator_settings.bool_getter_is_prefix = value;
+  }
+
+  /// Excludes C++ method `cpp_method_name` (e.g. `"QWidget::visible"`)
+  /// from renaming enabled by `Config::set_bool_getter_is_prefix`.
+  pub fn add_bool_getter_is_prefix_exception<S: Into<String>>(&mut self, cpp_method_name: S) {
+    self
+      .rust_generator_settings
+      .bool_getter_is_prefix_exceptions
+      .push(cpp_method_name.into());
+  }
+
+  /// If `value` is `true` and the target family is `Windows`
+  /// (see `Config::set_target_family`), `const wchar_t*` method arguments
+  /// are accepted as `Option<&std::ffi::OsStr>` and `const wchar_t*` return values
+  /// are converted to `Option<std::ffi::OsString>`, assuming that
+  /// the strings are null-terminated UTF-16. `None` corresponds to
+  /// a null pointer. On other targets `wchar_t`
+  /// is not UTF-16, so this setting has no effect. FFI functions always use
+  /// raw pointers. Default value is `false`.
+  pub fn set_wide_string_conversion(&mut self, value: bool) {
+    self.rust_generator_settings.wide_string_conversion = value;
+  }
+
+  /// If `value` is `true`, a const and a non-const overload of a method
+  /// that only differ by constness of `this` and return references
+  /// (e.g. `const T& data() const` and `T& data()`) are always named
+  /// `name` and `name_mut` in Rust, regardless of other overloads
+  /// with the same name. Default value is `false`.
+  pub fn set_merge_const_getter_pairs(&mut self, value: bool) {
+    self.rust_generator_settings.merge_const_getter_pairs = value;
+  }
+
+  /// Sets the target family the generated crate is intended for.
+  /// This value determines representation of platform-dependent types
+  /// such as `wchar_t`. Default value is the family of the platform
+  /// the generator is running on.
+  pub fn set_target_family(&mut self, family: target::Family) {
+    self.rust_generator_settings.target_family = family;
+  }
+
+  /// Sets the generic wrapper type used for owned C++ objects allocated on
+  /// the heap (e.g. objects returned by value from methods of heap-allocated
+  /// classes). `name` must be a full path to a type with exactly one generic
+  /// argument (e.g. `my_utils::MyBox`) that provides the same API as
+  /// `cpp_utils::CppBox` (`new`, `as_ptr` and `as_mut_ptr`).
+  /// Default value is `cpp_utils::CppBox`.
+  pub fn set_box_type(&mut self, name: RustName) {
+    self.rust_generator_settings.box_type = name;
+  }
+
+  /// Sets the error type returned by `dynamic_cast` wrappers.
+  /// By default, `cpp_utils::DynamicCast` is implemented, and its methods
+  /// return `None` if the object is not an instance of the target type.
+  /// If this value is set, `cpp_utils::DynamicCastResult` is implemented
+  /// instead, and its methods return `Err(E::default())` in that case.
+  /// `name` must be a full path to a type (e.g. `my_crate::CastFailed`)
+  /// that implements `Default`.
+  pub fn set_cast_error_type(&mut self, name: RustName) {
+    self.rust_generator_settings.cast_error_type = Some(name);
+  }
+
+  /// If `value` is `true`, non-const methods with names starting with `set`
+  /// and returning a non-const reference to their own class
+  /// (e.g. `Builder& setName(const QString&)` returning `*this`)
+  /// are treated as chainable setters: the Rust wrapper returns
+  /// its `&mut self` argument instead of converting the returned pointer,
+  /// so calls can be chained. Methods with other names can be added with
+  /// `Config::add_chainable_setter`. Default value is `false`.
+  pub fn set_chainable_setters(&mut self, value: bool) {
+    self.rust_generator_settings.chainable_setters = value;
+  }
+
+  /// Treats C++ method `cpp_method_name` (e.g. `"QUrlQuery::addQueryItem"`)
+  /// as a chainable setter if `Config::set_chainable_setters` is enabled,
+  /// even if its name doesn't start with `set`. The method must still
+  /// be non-const and return a non-const reference to its own class.
+  pub fn add_chainable_setter<S: Into<String>>(&mut self, cpp_method_name: S) {
+    self
+      .rust_generator_settings
+      .chainable_setter_methods
+      .push(cpp_method_name.into());
+  }
+
+  /// If `value` is `true`, methods returning `QString` or `const QString&`
+  /// will return `std::string::String` in the Rust API. The returned Qt string
+  /// is converted using `From<&qt_core::string::String>` and deleted afterwards.
+  /// Methods of `QString` itself are not affected. `const QString&` arguments
+  /// also accept `&str` if `QString` is listed in `Config::set_string_types`.
+  /// The FFI functions still return the Qt string and can be used to access it
+  /// directly. Default value is `false`.
+  pub fn set_qstring_return_conversion(&mut self, value: bool) {
+    self.rust_generator_settings.qstring_return_conversion = value;
+  }
+
+  /// Changes how free `operator<<` functions writing to `QDataStream`
+  /// or `QTextStream` (e.g. `operator<<(QDataStream&, const QPoint&)`) are wrapped.
+  /// If `value` is `true`, `std::ops::Shl` is implemented for a mutable reference
+  /// to the stream, so values can be written with `&mut stream << &point`.
+  /// If `value` is `false`, a `write_to(&self, stream)` method is added to
+  /// the written type instead. This is only possible if the type is declared
+  /// in the same module as the function; otherwise the function is wrapped
+  /// as a regular free function. Default value is `false`.
+  pub fn set_stream_operator_traits(&mut self, value: bool) {
+    self.rust_generator_settings.stream_operator_traits = value;
+  }
+
+  /// If `value` is `true`, constructors of `QObject` and its subclasses
+  /// that take a `parent` pointer to a `QObject`-based class return
+  /// `cpp_utils::MaybeOwned<T>` instead of `CppBox<T>`. The object is owned
+  /// by the caller only if the passed parent is null; otherwise it's deleted
+  /// by its parent, and dropping `MaybeOwned` doesn't delete it.
+  /// The owned variant always uses `cpp_utils::CppBox`.
+  /// Default value is `false`.
+  pub fn set_parented_qobject_constructors(&mut self, value: bool) {
+    self.rust_generator_settings.parented_qobject_constructors = value;
+  }
+
+  /// If `value` is `true`, `From<&str>` and `From<String>` are implemented
+  /// for string classes listed in `Config::set_string_types`, so owned strings
+  /// can be created with `QString::from("text")`. The conversion uses the static
+  /// `fromUtf8(const char*, int)` method of the class, and no implementations
+  /// are generated for classes without it. `From` can't be implemented
+  /// for `CppBox`, so only stack-allocated classes are supported.
+  /// The conversion panics if the string length doesn't fit in the length argument.
+  /// The crate template must not provide these implementations.
+  /// Default value is `false`.
+  pub fn set_string_from_impls(&mut self, value: bool) {
+    self.rust_generator_settings.string_from_impls = value;
+  }
+
+  /// Sets base URL of the online C++ documentation
+  /// (e.g. `"https://doc.qt.io/qt-5"`). If set, documentation of each
+  /// generated method contains a link to the documentation of the
+  /// corresponding C++ method. The URL of the page is derived from
+  /// the class name (e.g. `QString` becomes `{url}/qstring.html`),
+  /// and the method name is used as the anchor (e.g. `#arg`).
+  /// Overloaded methods and operators are linked to the class page
+  /// because their anchors can't be derived from the name.
+  /// Free functions are not linked.
+  pub fn set_cpp_doc_base_url<S: Into<String>>(&mut self, url: S) {
+    self.rust_generator_settings.cpp_doc_base_url = Some(url.into());
+  }
+
+  /// Adds Rust `code` (e.g. `"unsafe impl Send for Class1 {}"`) that will be appended
+  /// to the generated module containing the wrapper of C++ type `type_name` after
+  /// all generated `impl` blocks. `type_name` must be a non-template C++ class
+  /// of the processed library, and `code` must refer to the Rust name of its wrapper,
+  /// otherwise the generator will report an error.
+  pub fn add_raw_trait_impl<S1: Into<String>, S2: Into<String>>(&mut self,
+                                                                type_name: S1,
+                                                                code: S2) {
+    self
+      .rust_generator_settings
+      .raw_trait_impls
+      .entry(type_name.into())
+      .or_insert_with(Vec::new)
+      .push(code.into());
+  }
+
+  /// Marks C++ class `cpp_name` as a container that should be iterable in Rust.
+  /// For each marked class that has argument-less `begin() const` and `end() const`
+  /// methods returning an iterator class by value, the generator will add
+  /// an `iter()` method returning an adapter that implements `std::iter::Iterator`.
+  /// The iterator class must have `operator++` (prefix), `operator*` and `operator!=`
+  /// methods, and `operator*` must return a reference or a non-class value.
+  pub fn add_iterable_type<S: Into<String>>(&mut self, cpp_name: S) {
+    self.rust_generator_settings.iterable_types.push(cpp_name.into());
+  }
+
+  /// Marks C++ class `cpp_name` as having a total order. In addition to
+  /// `PartialEq` and `PartialOrd`, the generator will implement `Eq` and `Ord`
+  /// for the marked class, with `Ord::cmp` unwrapping the result of `partial_cmp`.
+  /// `PartialEq` and `PartialOrd` must already be implemented for the class
+  /// (e.g. with `Config::add_raw_trait_impl`), otherwise the generator
+  /// will report an error. The caller is responsible for ensuring that
+  /// `partial_cmp` never returns `None` for values of the class.
+  pub fn mark_total_order<S: Into<String>>(&mut self, cpp_name: S) {
+    self.rust_generator_settings.total_order_types.push(cpp_name.into());
+  }
+
+  /// Marks C++ class `cpp_name` as safe to transfer between threads.
+  /// The generator will add `unsafe impl Send` for the Rust wrapper of
+  /// the marked class. Wrappers are not `Send` by default because
+  /// the generator can't verify thread safety of C++ types, so
+  /// the caller is responsible for ensuring that the C++ class
+  /// doesn't depend on the thread it was created in (e.g. `QObject`
+  /// subclasses usually do). `cpp_name` must be a non-template C++ class
+  /// of the processed library, otherwise the generator will report an error.
+  pub fn mark_send<S: Into<String>>(&mut self, cpp_name: S) {
+    self.rust_generator_settings.send_types.push(cpp_name.into());
+  }
+
+  /// Marks C++ class `cpp_name` as safe to share between threads.
+  /// The generator will add `unsafe impl Sync` for the Rust wrapper of
+  /// the marked class. The caller is responsible for ensuring that
+  /// all methods of the class taking a const `this` pointer
+  /// can be called concurrently. `cpp_name` must be a non-template
+  /// C++ class of the processed library, otherwise the generator
+  /// will report an error.
+  pub fn mark_sync<S: Into<String>>(&mut self, cpp_name: S) {
+    self.rust_generator_settings.sync_types.push(cpp_name.into());
+  }
+
+  /// Sets visibility of the Rust wrapper of C++ type `cpp_name`.
+  /// All types are public by default. A type with `TypeVisibility::Crate`
+  /// is declared with `pub(crate)`, which is useful for internal helper types
+  /// that shouldn't be part of the public API of the crate. The generator
+  /// reports an error if such a type is used in a signature of a public method.
+  /// `cpp_name` must be a non-template C++ type of the processed library.
+  pub fn set_type_visibility<S: Into<String>>(&mut self,
+                                              cpp_name: S,
+                                              visibility: TypeVisibility) {
+    self.rust_generator_settings.type_visibility.insert(cpp_name.into(), visibility);
+  }
+
+  /// Enables wrapping of protected constructors of C++ class `class_name`.
+  /// Protected constructors are normally skipped. This option is intended
+  /// for classes designed for subclassing. The C++ wrapper library calls
+  /// these constructors through a helper class derived from `class_name`
+  /// that doesn't add any fields or virtual methods, and the generated
+  /// Rust constructors are `unsafe`. The caller must ensure that the class
+  /// is ready to be used without a C++ subclass, i.e. that it doesn't rely
+  /// on behavior only a real subclass would provide. Because the objects
+  /// are deleted through a pointer to `class_name`, protected constructors
+  /// are only wrapped if the class has a virtual destructor.
+  pub fn add_protected_constructor_class<S: Into<String>>(&mut self, class_name: S) {
+    self.protected_constructor_classes.push(class_name.into());
+  }
+
+  /// Adds public methods inherited by C++ class `type_name` from its base classes
+  /// to the generated Rust type. By default, base class methods are only
+  /// available after an explicit upcast, which is inconvenient for classes with
+  /// multiple bases. The C++ wrapper library calls these methods through
+  /// a pointer to `type_name`. Methods with the same name as a method declared
+  /// in `type_name` are not added.
+  pub fn set_inline_inherited_methods<S: Into<String>>(&mut self, type_name: S) {
+    self.inline_inherited_methods.push(type_name.into());
+  }
+
+  /// Adds an acronym that should be kept as a single word when converting
+  /// C++ names to snake case. For example, with "URL" added, `parseURL`
+  /// becomes `parse_url` instead of `parse_u_r_l`. Common acronyms
+  /// used in Qt (e.g. "URL", "HTML", "XML", "JSON") are added by default.
+  pub fn add_acronym<S: Into<String>>(&mut self, acronym: S) {
+    self.rust_generator_settings.acronyms.push(acronym.into());
+  }
+
   /// Changes how debug logs are handled. See `DebugLoggingConfig` for more information.
   pub fn set_debug_logging_config(&mut self, config: DebugLoggingConfig) {
     self.debug_logging_config = config;
@@ -519,6 +1594,17 @@ impl Config {
     self.write_cache = write_cache;
   }
 
+  /// Enables dry run mode. In this mode the generator parses the C++ library
+  /// and prepares the Rust API, but doesn't write the C++ wrapper library
+  /// or the crate to the output directory. Instead, a report listing the modules
+  /// that would be generated and the C++ methods that would be skipped
+  /// is saved to `dry_run_report.json` in the cache directory
+  /// (see `dry_run::DryRunReport`). The completion marker is not created.
+  /// Dry run mode is disabled by default.
+  pub fn set_dry_run(&mut self, value: bool) {
+    self.dry_run = value;
+  }
+
   /// Sets `CppBuildConfig` value that will be passed to the build script
   /// of the generated crate.
   pub fn set_cpp_build_config(&mut self, cpp_build_config: CppBuildConfig) {
@@ -531,6 +1617,27 @@ impl Config {
     &mut self.cpp_build_config
   }
 
+  /// Adds a library that will be linked to the generated crate using
+  /// a `#[link(name = "...", kind = "...")]` attribute. If `target_filter` is
+  /// specified, the attribute is wrapped in `#[cfg_attr(...)]` and only applies
+  /// to the matching targets, which allows to use different library names
+  /// or kinds (e.g. frameworks on macOS) on different platforms.
+  /// Libraries linked by the build script according to `CppBuildConfig`
+  /// should not be added here.
+  pub fn add_link_library<S: Into<String>>(&mut self,
+                                           name: S,
+                                           kind: LinkKind,
+                                           target_filter: Option<target::Condition>) {
+    self
+      .rust_generator_settings
+      .link_libraries
+      .push(LinkLibrary {
+              name: name.into(),
+              kind: kind,
+              target_filter: target_filter,
+            });
+  }
+
   pub fn set_cpp_lib_version<S: Into<String>>(&mut self, version: S) {
     self.cpp_lib_version = Some(version.into());
   }
@@ -561,6 +1668,17 @@ impl Config {
     &self.crate_properties
   }
 
+  /// Returns mutable access to crate properties passed to `Config::new`.
+  pub fn crate_properties_mut(&mut self) -> &mut CrateProperties {
+    &mut self.crate_properties
+  }
+
+  /// Returns settings of the generated Rust API and crate
+  /// configured by the setters of this object.
+  pub fn rust_generator_settings(&self) -> &RustGeneratorSettings {
+    &self.rust_generator_settings
+  }
+
   /// Returns path to the output directory passed to `Config::new`.
   pub fn output_dir_path(&self) -> &PathBuf {
     &self.output_dir_path
@@ -587,13 +1705,18 @@ impl Config {
     &self.cpp_parser_blocked_names
   }
 
+  /// Returns names added with `Config::add_cpp_parser_allowed_name`
+  /// and `Config::set_cpp_parser_allowlist`.
+  pub fn cpp_parser_allowed_names(&self) -> &[String] {
+    &self.cpp_parser_allowed_names
+  }
+
   /// Returns names added with `Config::add_cpp_parser_argument`
   /// and similar methods.
   pub fn cpp_parser_arguments(&self) -> &[String] {
     &self.cpp_parser_arguments
   }
 
-
   /// Returns values added by `Config::add_include_path`.
   pub fn include_paths(&self) -> &[PathBuf] {
     &self.include_paths
@@ -609,7 +1732,8 @@ impl Config {
     &self.target_include_paths
   }
 
-  /// Returns values added by `Config::add_include_directive`.
+  /// Returns values added by `Config::add_include_directive`
+  /// and `Config::add_include_directive_front`.
   pub fn include_directives(&self) -> &[PathBuf] {
     &self.include_directives
   }
@@ -647,6 +1771,16 @@ impl Config {
     &self.type_allocation_places
   }
 
+  /// Returns values added by `Config::add_protected_constructor_class`.
+  pub fn protected_constructor_classes(&self) -> &Vec<String> {
+    &self.protected_constructor_classes
+  }
+
+  /// Returns values added by `Config::set_inline_inherited_methods`.
+  pub fn inline_inherited_methods(&self) -> &Vec<String> {
+    &self.inline_inherited_methods
+  }
+
   /// If `value` is `true`, the generated `Cargo.toml` will specify
   /// both versions and local paths of all dependencies. If `value` is `false`,
   /// only version will be specified, so publishing all dependencies would be
@@ -658,6 +1792,86 @@ impl Config {
   pub fn write_dependencies_local_paths(&self) -> bool {
     self.write_dependencies_local_paths
   }
+  /// If `value` is `true`, a JSON file listing public types, methods
+  /// and trait implementations of each generated module will be written
+  /// next to the module's source file. Default value is `false`.
+  pub fn set_write_module_inventories(&mut self, value: bool) {
+    self.rust_generator_settings.write_module_inventories = value;
+  }
+  /// If `value` is `false`, `rustfmt` will not be called on
+  /// the generated source files. This speeds up the generation
+  /// during development. Default value is `true`.
+  pub fn set_format_generated_code(&mut self, value: bool) {
+    self.rust_generator_settings.format_generated_code = value;
+  }
+  /// Sets Rust edition of the generated crate.
+  /// Default value is `RustEdition::Edition2015`.
+  pub fn set_rust_edition(&mut self, edition: RustEdition) {
+    self.rust_generator_settings.rust_edition = edition;
+  }
+  /// If `value` is `true`, each generated module will contain tests checking
+  /// that sizes of stack-allocated structs match sizes of the corresponding
+  /// C++ types. This increases compilation time of the crate's tests.
+  /// Default value is `false`.
+  pub fn set_generate_layout_tests(&mut self, value: bool) {
+    self.rust_generator_settings.generate_layout_tests = value;
+  }
+  /// If `value` is `true`, generated methods that directly call
+  /// a single FFI function will be marked with `#[inline]`, allowing
+  /// them to be inlined across crate boundaries. Methods dispatching
+  /// over multiple overloads through a trait are not affected.
+  /// Default value is `false`.
+  pub fn set_inline_wrappers(&mut self, value: bool) {
+    self.rust_generator_settings.inline_wrappers = value;
+  }
+  /// If `value` is `true`, a module that contains exactly one public type
+  /// and no functions or submodules will have that type re-exported
+  /// in its parent module with `pub use`, so that, for example,
+  /// `crate::point_f::PointF` is also available as `crate::PointF`.
+  /// The module itself is still generated.
+  /// Default value is `false`.
+  pub fn set_flatten_single_type_modules(&mut self, value: bool) {
+    self.rust_generator_settings.flatten_single_type_modules = value;
+  }
+  /// If `value` is `true`, each top level module of the generated crate
+  /// is declared behind a Cargo feature with the same name. FFI functions
+  /// used only by such modules are also disabled along with them.
+  /// If a module refers to items of another module, its feature enables
+  /// the other module's feature. All features are enabled by default,
+  /// so the crate's users should disable default features and select
+  /// the modules they need.
+  /// Default value is `false`.
+  pub fn set_modules_behind_features(&mut self, value: bool) {
+    self.rust_generator_settings.modules_behind_features = value;
+  }
+  /// If `value` is `true`, constructors of stack-allocated types
+  /// check that size of the Rust struct matches size of the C++ type
+  /// with `debug_assert_eq!`, so ABI mismatches are detected at runtime
+  /// in debug builds. See also `Config::set_generate_layout_tests`.
+  /// Default value is `false`.
+  pub fn set_debug_size_assertions(&mut self, value: bool) {
+    self.rust_generator_settings.debug_size_assertions = value;
+  }
+  /// If `value` is `true`, the generated crate contains an `errors` module
+  /// re-exporting error types of `cpp_utils` used by fallible operations
+  /// (`CastError`, `InvalidObjectError` and `UnknownEnumValue`) and a shared
+  /// `ConversionError` type all of them can be converted to, so `?` can be used
+  /// with different fallible operations in the same function. Generated
+  /// implementations refer to the error types through this module.
+  /// The module is only generated if any of the error types is used.
+  /// Default value is `false`.
+  pub fn set_errors_module(&mut self, value: bool) {
+    self.rust_generator_settings.errors_module = value;
+  }
+  /// If `value` is `true`, only FFI functions called by the generated Rust API
+  /// are declared in the `ffi` module. FFI functions of methods that were skipped
+  /// during Rust code generation are omitted, so they don't produce dead code
+  /// warnings. Code added by type plugins, `Config::add_raw_trait_impl` or
+  /// the crate template must not call FFI functions directly if this option is enabled.
+  /// Default value is `false`.
+  pub fn set_prune_ffi_functions(&mut self, value: bool) {
+    self.rust_generator_settings.prune_ffi_functions = value;
+  }
   /// Returns value set by `Config::set_debug_logging_config`.
   pub fn debug_logging_config(&self) -> &DebugLoggingConfig {
     &self.debug_logging_config
@@ -670,6 +1884,10 @@ impl Config {
   pub fn write_cache(&self) -> bool {
     self.write_cache
   }
+  /// Returns value set by `Config::set_dry_run`.
+  pub fn dry_run(&self) -> bool {
+    self.dry_run
+  }
 }
 
 pub use launcher::{is_completed, completed_marker_path, exec};
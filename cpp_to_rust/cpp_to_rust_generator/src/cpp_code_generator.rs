@@ -1,13 +1,16 @@
 use cpp_ffi_data::{QtSlotWrapper, CppIndirectionChange, CppAndFfiMethod, CppFfiArgumentMeaning,
                    CppFfiHeaderData, CppFfiType, CppFieldAccessorType, CppFfiMethodKind};
 use cpp_method::ReturnValueAllocationPlace;
-use cpp_type::{CppTypeIndirection, CppTypeBase, CppType};
+use cpp_type::{CppTypeIndirection, CppTypeBase, CppType, CppTypeClassBase};
+use rust_generator::align_type_name;
 use common::errors::{Result, ChainErr, unexpected};
 use common::file_utils::{PathBufWithAdded, create_dir_all, create_file, path_to_str};
 use common::string_utils::JoinWithSeparator;
 use common::utils::MapIfOk;
 use common::utils::get_command_output;
+use common::target;
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::iter::once;
 use std::process::Command;
@@ -20,17 +23,25 @@ pub struct CppCodeGenerator {
   lib_name_upper: String,
   /// Path to the directory where the library is generated
   lib_path: ::std::path::PathBuf,
+  /// Target conditions of platform-specific methods
+  /// (see `Config::add_platform_method`)
+  platform_methods: HashMap<String, target::Condition>,
 }
 
 impl CppCodeGenerator {
   /// Creates a generator for a library.
   /// lib_name: library name
   /// lib_path: path to the directory where the library is generated
-  pub fn new(lib_name: String, lib_path: ::std::path::PathBuf) -> Self {
+  /// platform_methods: target conditions of platform-specific methods
+  pub fn new(lib_name: String,
+             lib_path: ::std::path::PathBuf,
+             platform_methods: HashMap<String, target::Condition>)
+             -> Self {
     CppCodeGenerator {
       lib_name: lib_name.clone(),
       lib_name_upper: lib_name.to_uppercase(),
       lib_path: lib_path,
+      platform_methods: platform_methods,
     }
   }
 
@@ -81,11 +92,21 @@ impl CppCodeGenerator {
                .enumerate()
                .map_if_ok(|(num, t)| self.convert_type_to_ffi(t, format!("arg{}", num)))?)
       .join(", ");
+    let return_type = wrapper.return_type.original_type.to_cpp_code(None)?;
+    let (maybe_return, default_return) = if wrapper.return_type.original_type.is_void() {
+      ("", String::new())
+    } else {
+      // the value returned if the callback is not set
+      ("return ", format!("\n    return {}();", return_type))
+    };
     Ok(format!(include_str!("../templates/c_lib/qt_slot_wrapper.h"),
                class_name = &wrapper.class_name,
                func_arg = func_type.to_cpp_code(Some("func"))?,
                func_field = func_type.to_cpp_code(Some("m_func"))?,
+               return_type = return_type,
                method_args = method_args,
+               maybe_return = maybe_return,
+               default_return = default_return,
                func_args = func_args))
 
 
@@ -103,7 +124,13 @@ impl CppCodeGenerator {
                    expression)
          }
          CppIndirectionChange::ReferenceToPointer => format!("&{}", expression),
+         CppIndirectionChange::RValueReferenceToPointer => {
+           return Err(unexpected("rvalue references can't be converted to FFI values").into());
+         }
          CppIndirectionChange::QFlagsToUInt => format!("uint({})", expression),
+         CppIndirectionChange::OptionalToPointer => {
+           return Err("std::optional can't be converted to FFI values here".into());
+         }
        })
   }
 
@@ -136,9 +163,29 @@ impl CppCodeGenerator {
       CppIndirectionChange::ReferenceToPointer => {
         result = format!("&{}", result);
       }
+      CppIndirectionChange::RValueReferenceToPointer => {
+        return Err(unexpected("rvalue reference return types are not supported").into());
+      }
       CppIndirectionChange::QFlagsToUInt => {
         result = format!("uint({})", result);
       }
+      CppIndirectionChange::OptionalToPointer => {
+        return Err(unexpected("std::optional is expected to be returned in an output argument")
+                     .into());
+      }
+    }
+    if let Some(arg) = method
+         .c_signature
+         .arguments
+         .iter()
+         .find(|x| {
+                 x.meaning == CppFfiArgumentMeaning::ReturnValue &&
+                 x.argument_type.conversion == CppIndirectionChange::OptionalToPointer
+               }) {
+      result = format!("[&]() {{ auto value = {}; if (value.has_value()) {{ *{} = value.value(); \
+                        }} return value.has_value(); }}()",
+                       result,
+                       arg.name);
     }
 
     if method.allocation_place == ReturnValueAllocationPlace::Stack &&
@@ -170,6 +217,9 @@ impl CppCodeGenerator {
         match c_argument.argument_type.conversion {
           CppIndirectionChange::ValueToPointer |
           CppIndirectionChange::ReferenceToPointer => result = format!("*{}", result),
+          CppIndirectionChange::RValueReferenceToPointer => {
+            result = format!("std::move(*{})", result)
+          }
           CppIndirectionChange::NoChange => {}
           CppIndirectionChange::QFlagsToUInt => {
             let type_text = if cpp_argument.argument_type.indirection == CppTypeIndirection::Ref &&
@@ -183,6 +233,14 @@ impl CppCodeGenerator {
             };
             result = format!("{}({})", type_text, result);
           }
+          CppIndirectionChange::OptionalToPointer => {
+            let mut optional_type = cpp_argument.argument_type.clone();
+            optional_type.is_const = false;
+            optional_type.indirection = CppTypeIndirection::None;
+            result = format!("{arg} ? {t}(*{arg}) : {t}(std::nullopt)",
+                             arg = result,
+                             t = optional_type.to_cpp_code(None)?);
+          }
         }
         filled_arguments.push(result);
       } else {
@@ -192,6 +250,24 @@ impl CppCodeGenerator {
     Ok(filled_arguments.into_iter().join(", "))
   }
 
+  /// Returns name of the helper class used to call
+  /// protected constructors of `class_type`.
+  fn protected_access_class_name(&self, class_type: &CppTypeClassBase) -> Result<String> {
+    Ok(format!("{}_{}_protected_access", self.lib_name, class_type.caption()?))
+  }
+
+  /// Generates a helper class derived from `class_type` that has
+  /// a public constructor forwarding all arguments to a protected
+  /// constructor of `class_type`.
+  fn protected_access_class(&self, class_type: &CppTypeClassBase) -> Result<String> {
+    Ok(format!("class {name} : public {base} {{\npublic:\n  \
+                template<typename... Args>\n  \
+                {name}(Args&&... args) : {base}(std::forward<Args>(args)...) {{}}\n\
+                }};\n\n",
+               name = self.protected_access_class_name(class_type)?,
+               base = class_type.to_cpp_code()?))
+  }
+
   /// Generates code for the value returned by the FFI method.
   #[cfg_attr(feature="clippy", allow(collapsible_if))]
   fn returned_expression(&self, method: &CppAndFfiMethod) -> Result<String> {
@@ -208,7 +284,11 @@ impl CppCodeGenerator {
     } else {
       let mut is_field_accessor = false;
       let result_without_args = if let Some(info) = method.cpp_method.class_info_if_constructor() {
-        let class_type = &info.class_type;
+        let class_code = if method.cpp_method.is_protected_constructor() {
+          self.protected_access_class_name(&info.class_type)?
+        } else {
+          info.class_type.to_cpp_code()?
+        };
         match method.allocation_place {
           ReturnValueAllocationPlace::Stack => {
             if let Some(arg) = method
@@ -216,13 +296,13 @@ impl CppCodeGenerator {
                  .arguments
                  .iter()
                  .find(|x| x.meaning == CppFfiArgumentMeaning::ReturnValue) {
-              format!("new({}) {}", arg.name, class_type.to_cpp_code()?)
+              format!("new({}) {}", arg.name, class_code)
             } else {
               return Err(unexpected(format!("return value argument not found\n{:?}", method))
                            .into());
             }
           }
-          ReturnValueAllocationPlace::Heap => format!("new {}", class_type.to_cpp_code()?),
+          ReturnValueAllocationPlace::Heap => format!("new {}", class_code),
           ReturnValueAllocationPlace::NotApplicable => {
             return Err(unexpected("NotApplicable in constructor").into());
           }
@@ -414,10 +494,52 @@ impl CppCodeGenerator {
       for wrapper in &data.qt_slot_wrappers {
         h_file.write(self.qt_slot_wrapper(wrapper)?)?;
       }
+      let mut protected_access_classes = Vec::new();
+      let mut moves_arguments = false;
+      for method in &data.methods {
+        if let Some(info) = method.cpp_method.class_info_if_constructor() {
+          if method.cpp_method.is_protected_constructor() &&
+             !protected_access_classes.contains(&info.class_type) {
+            protected_access_classes.push(info.class_type.clone());
+          }
+        }
+        if method
+             .c_signature
+             .arguments
+             .iter()
+             .any(|x| x.argument_type.conversion == CppIndirectionChange::RValueReferenceToPointer) {
+          moves_arguments = true;
+        }
+      }
+      if moves_arguments || !protected_access_classes.is_empty() {
+        cpp_file.write("#include <utility>\n\n")?;
+      }
+      for class_type in &protected_access_classes {
+        cpp_file.write(self.protected_access_class(class_type)?)?;
+      }
       h_file.write("extern \"C\" {\n\n")?;
       for method in &data.methods {
+        let condition = match method.cpp_method.platform_condition(&self.platform_methods) {
+          Some(condition) => {
+            Some(condition
+                   .to_cpp_condition()
+                   .chain_err(|| {
+                                format!("unsupported platform condition for {}",
+                                        method.cpp_method.full_name())
+                              })?)
+          }
+          None => None,
+        };
+        if let Some(ref condition) = condition {
+          h_file.write(format!("#if {}\n", condition))?;
+          cpp_file.write(format!("#if {}\n", condition))?;
+        }
         h_file.write(self.function_declaration(method)?)?;
         cpp_file.write(self.function_implementation(method)?)?;
+        if condition.is_some() {
+          h_file.write("#endif\n")?;
+          cpp_file.write("#endif\n")?;
+        }
       }
 
       h_file.write("\n} // extern \"C\"\n\n")?;
@@ -436,19 +558,20 @@ impl CppCodeGenerator {
 }
 
 /// Entry about a Rust struct with a buffer that must have the exact same size
-/// as its corresponding C++ class. This information is required for the C++ program
+/// and alignment as its corresponding C++ class. This information is required for the C++ program
 /// that is launched by the build script to determine type sizes and generate `type_sizes.rs`.
 #[derive(Debug, Clone)]
 pub struct CppTypeSizeRequest {
-  /// C++ code representing the type. Used as argument to `sizeof`.
+  /// C++ code representing the type. Used as argument to `sizeof` and `alignof`.
   pub cpp_code: String,
   /// Name of the constant in `type_sizes.rs`.
   pub size_const_name: String,
 }
 
-/// Generates a C++ program that determines sizes of target C++ types
+/// Generates a C++ program that determines sizes and alignments of target C++ types
 /// on the current platform and outputs the Rust code for `type_sizes.rs` module
-/// to the standard output.
+/// to the standard output. Alignment of each type is represented by an alias
+/// of a type from `cpp_utils::align`.
 pub fn generate_cpp_type_size_requester(requests: &[CppTypeSizeRequest],
                                         include_directives: &[PathBuf])
                                         -> Result<String> {
@@ -461,7 +584,184 @@ pub fn generate_cpp_type_size_requester(requests: &[CppTypeSizeRequest],
     result.push(format!("  std::cout << \"pub const {}: usize = \" << sizeof({}) << \";\\n\";\n",
                         request.size_const_name,
                         request.cpp_code));
+    result.push(format!("  std::cout << \"#[allow(non_camel_case_types)]\\npub type {} = \
+                         ::cpp_utils::align::Align\" << alignof({}) << \";\\n\";\n",
+                        align_type_name(&request.size_const_name),
+                        request.cpp_code));
   }
   result.push("}\n".to_string());
   Ok(result.join(""))
 }
+
+#[test]
+fn qt_slot_wrapper_with_return_type() {
+  use cpp_type::{CppBuiltInNumericType, CppFunctionPointerType, CppTypeRole};
+
+  let numeric_type = |t: CppBuiltInNumericType| {
+    CppType {
+      base: CppTypeBase::BuiltInNumeric(t),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let void_ptr = CppType {
+    base: CppTypeBase::Void,
+    indirection: CppTypeIndirection::Ptr,
+    is_const: false,
+    is_const2: false,
+  };
+  let wrapper = QtSlotWrapper {
+    class_name: "lib1_SlotWrapper_int_returns_bool".to_string(),
+    arguments: vec![numeric_type(CppBuiltInNumericType::Int)
+                      .to_cpp_ffi_type(CppTypeRole::NotReturnType)
+                      .unwrap()],
+    return_type: numeric_type(CppBuiltInNumericType::Bool)
+      .to_cpp_ffi_type(CppTypeRole::ReturnType)
+      .unwrap(),
+    function_type: CppFunctionPointerType {
+      return_type: Box::new(numeric_type(CppBuiltInNumericType::Bool)),
+      arguments: vec![void_ptr, numeric_type(CppBuiltInNumericType::Int)],
+      allows_variadic_arguments: false,
+    },
+    receiver_id: "1custom_slot(int)".to_string(),
+  };
+  let generator = CppCodeGenerator::new("lib1".to_string(), PathBuf::new(), HashMap::new());
+  let code = generator.qt_slot_wrapper(&wrapper).unwrap();
+  assert!(code.contains("bool custom_slot(int arg0) {"));
+  assert!(code.contains("return m_func(m_data, arg0);"));
+  assert!(code.contains("return bool();"));
+}
+
+#[test]
+fn protected_constructor() {
+  use cpp_data::CppVisibility;
+  use cpp_method::CppMethodKind;
+
+  let class_type = CppTypeClassBase {
+    name: "Class1".to_string(),
+    template_arguments: None,
+  };
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "Class1".to_string();
+  cpp_method.return_type = CppType {
+    base: CppTypeBase::Class(class_type.clone()),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let mut membership = ::tests::cpp_method::empty_membership("Class1");
+  membership.kind = CppMethodKind::Constructor;
+  membership.visibility = CppVisibility::Protected;
+  cpp_method.class_membership = Some(membership);
+  assert!(cpp_method.is_protected_constructor());
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::Heap)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::Heap,
+    c_name: "lib1_Class1_Class1".to_string(),
+  };
+  let generator = CppCodeGenerator::new("lib1".to_string(), PathBuf::new(), HashMap::new());
+  assert!(generator
+            .function_implementation(&method)
+            .unwrap()
+            .contains("return new lib1_Class1_protected_access();"));
+  let helper = generator.protected_access_class(&class_type).unwrap();
+  assert!(helper.starts_with("class lib1_Class1_protected_access : public Class1 {\npublic:\n"));
+  assert!(helper.contains("lib1_Class1_protected_access(Args&&... args) : \
+                           Class1(std::forward<Args>(args)...) {}"));
+}
+
+#[test]
+fn rvalue_reference_argument() {
+  use cpp_method::CppMethodArgument;
+
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "take".to_string();
+  cpp_method.class_membership = Some(::tests::cpp_method::empty_membership("Class1"));
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "value".to_string(),
+                              argument_type: CppType {
+                                base: CppTypeBase::Class(CppTypeClassBase {
+                                                           name: "Class2".to_string(),
+                                                           template_arguments: None,
+                                                         }),
+                                indirection: CppTypeIndirection::RValueRef,
+                                is_const: false,
+                                is_const2: false,
+                              },
+                              has_default_value: false,
+                            });
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::NotApplicable)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_name: "lib1_Class1_take".to_string(),
+  };
+  assert_eq!(method.c_signature.arguments[1].argument_type.ffi_type.indirection,
+             CppTypeIndirection::Ptr);
+  let generator = CppCodeGenerator::new("lib1".to_string(), PathBuf::new(), HashMap::new());
+  assert!(generator
+            .function_implementation(&method)
+            .unwrap()
+            .contains("this_ptr->take(std::move(*value))"));
+}
+
+#[test]
+fn platform_method() {
+  use common::file_utils::file_to_string;
+
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "nativeArguments".to_string();
+  cpp_method.class_membership = Some(::tests::cpp_method::empty_membership("Class1"));
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::NotApplicable)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_name: "lib1_Class1_nativeArguments".to_string(),
+  };
+  let dir = ::tests::TempTestDir::new("test_cpp_platform_method");
+  let lib_path = dir.path().to_path_buf();
+  create_dir_all(lib_path.with_added("src")).unwrap();
+  create_dir_all(lib_path.with_added("include")).unwrap();
+  let mut platform_methods = HashMap::new();
+  platform_methods.insert("Class1::nativeArguments".to_string(),
+                          target::Condition::OS(target::OS::Windows));
+  let generator = CppCodeGenerator::new("lib1".to_string(), lib_path.clone(), platform_methods);
+  generator
+    .generate_files(&[CppFfiHeaderData {
+                        include_file_base_name: "Class1".to_string(),
+                        methods: vec![method],
+                        qt_slot_wrappers: Vec::new(),
+                      }])
+    .unwrap();
+  let header = file_to_string(lib_path.with_added("include").with_added("lib1_Class1.h"))
+    .unwrap();
+  assert!(header.contains("#if defined(_WIN32)\nLIB1_EXPORT void lib1_Class1_nativeArguments("));
+  let source = file_to_string(lib_path.with_added("src").with_added("lib1_Class1.cpp")).unwrap();
+  assert!(source.contains("#if defined(_WIN32)\nvoid lib1_Class1_nativeArguments("));
+  assert!(source.contains("}\n\n#endif\n"));
+}
+
+#[test]
+fn type_size_requester() {
+  let code = generate_cpp_type_size_requester(&[CppTypeSizeRequest {
+                                                  cpp_code: "Class1".to_string(),
+                                                  size_const_name: "CLASS1".to_string(),
+                                                }],
+                                              &[PathBuf::from("class1.h")])
+    .unwrap();
+  assert!(code.starts_with("#include <class1.h>\n"));
+  assert!(code.contains("std::cout << \"pub const CLASS1: usize = \" << sizeof(Class1)"));
+  assert!(code.contains("std::cout << \"#[allow(non_camel_case_types)]\\npub type CLASS1_ALIGN = \
+                         ::cpp_utils::align::Align\" << alignof(Class1) << \";\\n\";"));
+}
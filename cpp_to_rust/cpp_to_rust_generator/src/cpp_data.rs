@@ -40,6 +40,22 @@ pub struct CppClassField {
   pub size: Option<usize>,
 }
 
+/// Static data member of a C++ class declaration
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Serialize, Deserialize)]
+pub struct CppClassStaticField {
+  /// Identifier
+  pub name: String,
+  /// Field type
+  pub field_type: CppType,
+  /// Visibility
+  pub visibility: CppVisibility,
+  /// Value of the constant if the member is a const
+  /// integral value initialized with a literal
+  /// in the class declaration.
+  pub value: Option<i64>,
+}
+
 /// A "using" directive inside a class definition,
 /// indicating that the class should inherite a
 /// certain method of a base class.
@@ -80,10 +96,18 @@ pub enum CppTypeKind {
     bases: Vec<CppBaseSpecifier>,
     /// List of class fields
     fields: Vec<CppClassField>,
+    /// List of static data members
+    static_fields: Vec<CppClassStaticField>,
     /// Information about template arguments of this type.
     template_arguments: Option<TemplateArgumentsDeclaration>,
     /// List of using directives, like "using BaseClass::method1;"
     using_directives: Vec<CppClassUsingDirective>,
+    /// Alignment of the type in bytes on the parser's target, if reported
+    /// by the parser. Not available for template classes. The generated crate
+    /// doesn't use this value because it determines alignment on its own target
+    /// (see `generate_cpp_type_size_requester`).
+    #[serde(default)]
+    alignment: Option<usize>,
   },
 }
 
@@ -195,6 +219,13 @@ pub enum CppTypeAllocationPlace {
   Stack,
 }
 
+/// Returns key used in `ProcessedCppData::type_allocation_places` for
+/// a template instantiation named `name` (e.g. `"QList<int>"`).
+/// Whitespace is ignored, so `"QList< int >"` produces the same key.
+pub fn template_instantiation_key(name: &str) -> String {
+  name.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
 /// C++ parser output
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 #[derive(Serialize, Deserialize)]
@@ -205,6 +236,17 @@ pub struct ParserCppData {
   pub methods: Vec<CppMethod>,
 }
 
+/// Argument and return types of a Qt signal
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Serialize, Deserialize)]
+pub struct CppSignalTypes {
+  /// Types of the arguments
+  pub arguments: Vec<CppType>,
+  /// Return type. Return types other than built-in numeric types
+  /// are replaced with `void`.
+  pub return_type: CppType,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 #[derive(Serialize, Deserialize)]
 pub struct ProcessedCppData {
@@ -215,12 +257,14 @@ pub struct ProcessedCppData {
   /// List of found template instantiations. Key is name of
   /// the template class, value is list of instantiations.
   pub template_instantiations: Vec<CppTemplateInstantiations>,
-  /// List of all argument types used by signals,
+  /// List of all argument and return types used by signals,
   /// including variations with omitted arguments,
-  /// but excluding argument types from dependencies.
-  pub signal_argument_types: Vec<Vec<CppType>>,
+  /// but excluding types from dependencies.
+  pub signal_types: Vec<CppSignalTypes>,
   /// List of selected (automatically or in configuration)
   /// type allocation places for all class types.
+  /// Overrides of specific template instantiations are stored
+  /// with keys produced by `template_instantiation_key`.
   pub type_allocation_places: HashMap<String, CppTypeAllocationPlace>,
 }
 
@@ -585,6 +629,23 @@ impl<'a> CppDataWithDeps<'a> {
     Err(format!("no type allocation place information for {}", class_name).into())
   }
 
+  /// Returns selected type allocation place for `class_type`.
+  /// If `class_type` is a template instantiation, an override of this
+  /// specific instantiation takes precedence over the template class's place.
+  pub fn class_type_allocation_place(&self,
+                                     class_type: &CppTypeClassBase)
+                                     -> Result<CppTypeAllocationPlace> {
+    if class_type.template_arguments.is_some() {
+      let key = template_instantiation_key(&class_type.to_cpp_pseudo_code());
+      for data in once(&self.current).chain(self.dependencies.iter().cloned()) {
+        if let Some(r) = data.processed.type_allocation_places.get(&key) {
+          return Ok(r.clone());
+        }
+      }
+    }
+    self.type_allocation_place(&class_type.name)
+  }
+
   /// Search for a `CppTypeData` object in this `CppData` and all dependencies.
   pub fn find_type_info<F>(&self, f: F) -> Option<&CppTypeData>
     where F: Fn(&&CppTypeData) -> bool
@@ -927,6 +988,52 @@ impl<'a> CppDataWithDeps<'a> {
     false
   }
 
+  /// Checks if specified class has a virtual destructor, declared
+  /// in the class itself or in one of its bases.
+  pub fn has_virtual_destructor(&self, class_name: &str) -> bool {
+    for methods in once(&self.current.parser.methods)
+          .chain(self.dependencies.iter().map(|x| &x.parser.methods)) {
+      for method in methods {
+        if let Some(ref info) = method.class_membership {
+          if &info.class_type.name == class_name && method.is_destructor() && info.is_virtual {
+            return true;
+          }
+        }
+      }
+    }
+    for types in self.all_types() {
+      if let Some(info) = types.iter().find(|x| &x.name == class_name) {
+        if let CppTypeKind::Class { ref bases, .. } = info.kind {
+          for base in bases {
+            if let CppTypeBase::Class(CppTypeClassBase { ref name, .. }) = base.base_type.base {
+              if self.has_virtual_destructor(name) {
+                return true;
+              }
+            }
+          }
+        }
+      }
+    }
+    false
+  }
+
+
+  /// Checks if specified class has a public copy constructor.
+  pub fn has_public_copy_constructor(&self, class_name: &str) -> bool {
+    once(&self.current.parser.methods)
+      .chain(self.dependencies.iter().map(|x| &x.parser.methods))
+      .flat_map(|methods| methods.iter())
+      .any(|method| {
+             method.is_copy_constructor() &&
+             method
+               .class_membership
+               .as_ref()
+               .map_or(false, |info| {
+                 &info.class_type.name == class_name &&
+                 info.visibility == CppVisibility::Public
+               })
+           })
+  }
 
   //
   //  /// Returns true if C++ type `name` is polymorphic, i.e. has
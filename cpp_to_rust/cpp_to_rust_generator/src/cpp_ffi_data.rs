@@ -93,9 +93,17 @@ pub enum CppIndirectionChange {
   /// C++ argument is a reference (like QPoint&)
   /// and FFI argument is a pointer (like QPoint*)
   ReferenceToPointer,
+  /// C++ argument is an rvalue reference (like QPoint&&)
+  /// and FFI argument is a pointer (like QPoint*)
+  RValueReferenceToPointer,
   /// C++ argument is QFlags<T>
   /// and FFI argument is uint
   QFlagsToUInt,
+  /// C++ argument is std::optional<T>
+  /// and FFI argument is a pointer to T (null for `std::nullopt`).
+  /// Return values are written to an output argument, and
+  /// the FFI function returns `has_value()`.
+  OptionalToPointer,
 }
 
 /// Information that indicates how an FFI function argument
@@ -374,6 +382,8 @@ pub struct QtSlotWrapper {
   pub class_name: String,
   /// Arguments of the slot.
   pub arguments: Vec<CppFfiType>,
+  /// Return type of the slot.
+  pub return_type: CppFfiType,
   /// The function pointer type accepted by this wrapper
   pub function_type: CppFunctionPointerType,
   /// String identifier passed to `QObject::connect` function to
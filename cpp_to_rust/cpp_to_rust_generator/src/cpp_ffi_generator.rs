@@ -22,6 +22,8 @@ struct CppFfiGenerator<'a> {
   cpp_ffi_lib_name: String,
   /// FFI filters passed to `Config`
   filters: Vec<&'a Box<CppFfiGeneratorFilterFn>>,
+  /// Classes added by `Config::add_protected_constructor_class`
+  protected_constructor_classes: &'a [String],
 }
 
 #[derive(Debug, Clone)]
@@ -47,12 +49,15 @@ impl CppMethodWithKind {
 /// Runs the FFI generator
 pub fn run(cpp_data: &CppDataWithDeps,
            cpp_ffi_lib_name: String,
-           filters: Vec<&Box<CppFfiGeneratorFilterFn>>)
+           filters: Vec<&Box<CppFfiGeneratorFilterFn>>,
+           protected_constructor_classes: &[String],
+           inline_inherited_methods: &[String])
            -> Result<Vec<CppFfiHeaderData>> {
   let generator = CppFfiGenerator {
     cpp_data: cpp_data,
     cpp_ffi_lib_name: cpp_ffi_lib_name,
     filters: filters,
+    protected_constructor_classes: protected_constructor_classes,
   };
 
   let mut c_headers = Vec::new();
@@ -67,6 +72,8 @@ pub fn run(cpp_data: &CppDataWithDeps,
   extra_methods.append(&mut instantiate_templates(&generator.cpp_data)?);
   extra_methods.append(&mut generate_field_accessors(&generator.cpp_data)?);
   extra_methods.append(&mut generate_casts(&generator.cpp_data)?);
+  extra_methods.append(&mut generate_inherited_methods(&generator.cpp_data,
+                                                       inline_inherited_methods));
 
   for include_file in &include_name_list {
     let mut include_file_base_name = include_file.clone();
@@ -264,54 +271,67 @@ fn instantiate_templates(data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind
 
 
 /// Adds fictional getter and setter methods for each known public field of each class.
+/// Static fields with a known constant value are not included because they are
+/// converted to associated constants instead.
 fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind>> {
   // TODO: fix doc generator for field accessors
   log::status("Adding field accessors");
   let mut new_methods = Vec::new();
   for type_info in &cpp_data.current.parser.types {
-    if let CppTypeKind::Class { ref fields, .. } = type_info.kind {
+    if let CppTypeKind::Class {
+             ref fields,
+             ref static_fields,
+             ..
+           } = type_info.kind {
+      let create_method = |field_name: &str,
+                           is_static: bool,
+                           name,
+                           accessor_type,
+                           return_type,
+                           arguments|
+       -> Result<CppMethodWithKind> {
+        Ok(CppMethodWithKind {
+             method: CppMethod {
+               name: name,
+               class_membership: Some(CppMethodClassMembership {
+                                        class_type: type_info.default_class_type()?,
+                                        kind: CppMethodKind::Regular,
+                                        is_virtual: false,
+                                        is_pure_virtual: false,
+                                        is_const: !is_static &&
+                                                  match accessor_type {
+                                                    CppFieldAccessorType::CopyGetter |
+                                                    CppFieldAccessorType::ConstRefGetter => true,
+                                                    CppFieldAccessorType::MutRefGetter |
+                                                    CppFieldAccessorType::Setter => false,
+                                                  },
+                                        is_static: is_static,
+                                        visibility: CppVisibility::Public,
+                                        is_signal: false,
+                                        is_private_signal: false,
+                                        is_slot: false,
+                                      }),
+               operator: None,
+               return_type: return_type,
+               arguments: arguments,
+               allows_variadic_arguments: false,
+               include_file: type_info.include_file.clone(),
+               origin_location: None,
+               template_arguments: None,
+               template_arguments_values: None,
+               declaration_code: None,
+               doc: None,
+               inheritance_chain: Vec::new(),
+               //is_fake_inherited_method: false,
+               is_ffi_whitelisted: false,
+             },
+             kind: CppFfiMethodKind::FieldAccessor {
+               accessor_type: accessor_type,
+               field_name: field_name.to_string(),
+             },
+           })
+      };
       for field in fields {
-        let create_method =
-          |name, accessor_type, return_type, arguments| -> Result<CppMethodWithKind> {
-            Ok(CppMethodWithKind {
-                 method: CppMethod {
-                   name: name,
-                   class_membership: Some(CppMethodClassMembership {
-                                            class_type: type_info.default_class_type()?,
-                                            kind: CppMethodKind::Regular,
-                                            is_virtual: false,
-                                            is_pure_virtual: false,
-                                            is_const: match accessor_type {
-                                              CppFieldAccessorType::CopyGetter |
-                                              CppFieldAccessorType::ConstRefGetter => true,
-                                              CppFieldAccessorType::MutRefGetter |
-                                              CppFieldAccessorType::Setter => false,
-                                            },
-                                            is_static: false,
-                                            visibility: CppVisibility::Public,
-                                            is_signal: false,
-                                            is_slot: false,
-                                          }),
-                   operator: None,
-                   return_type: return_type,
-                   arguments: arguments,
-                   allows_variadic_arguments: false,
-                   include_file: type_info.include_file.clone(),
-                   origin_location: None,
-                   template_arguments: None,
-                   template_arguments_values: None,
-                   declaration_code: None,
-                   doc: None,
-                   inheritance_chain: Vec::new(),
-                   //is_fake_inherited_method: false,
-                   is_ffi_whitelisted: false,
-                 },
-                 kind: CppFfiMethodKind::FieldAccessor {
-                   accessor_type: accessor_type,
-                   field_name: field.name.clone(),
-                 },
-               })
-          };
         if field.visibility == CppVisibility::Public {
           if field.field_type.indirection == CppTypeIndirection::None &&
              field.field_type.base.is_class() {
@@ -322,16 +342,22 @@ fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodW
             let mut type2_mut = field.field_type.clone();
             type2_mut.is_const = false;
             type2_mut.indirection = CppTypeIndirection::Ref;
-            new_methods.push(create_method(field.name.clone(),
+            new_methods.push(create_method(&field.name,
+                                           false,
+                                           field.name.clone(),
                                            CppFieldAccessorType::ConstRefGetter,
                                            type2_const,
                                            Vec::new())?);
-            new_methods.push(create_method(format!("{}_mut", field.name),
+            new_methods.push(create_method(&field.name,
+                                           false,
+                                           format!("{}_mut", field.name),
                                            CppFieldAccessorType::MutRefGetter,
                                            type2_mut,
                                            Vec::new())?);
           } else {
-            new_methods.push(create_method(field.name.clone(),
+            new_methods.push(create_method(&field.name,
+                                           false,
+                                           field.name.clone(),
                                            CppFieldAccessorType::CopyGetter,
                                            field.field_type.clone(),
                                            Vec::new())?);
@@ -341,12 +367,26 @@ fn generate_field_accessors(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodW
             name: "value".to_string(),
             has_default_value: false,
           };
-          new_methods.push(create_method(format!("set_{}", field.name),
+          new_methods.push(create_method(&field.name,
+                                         false,
+                                         format!("set_{}", field.name),
                                          CppFieldAccessorType::Setter,
                                          CppType::void(),
                                          vec![arg])?);
         }
       }
+      for field in static_fields {
+        if field.visibility == CppVisibility::Public && field.value.is_none() {
+          let mut return_type = field.field_type.clone();
+          return_type.is_const = false;
+          new_methods.push(create_method(&field.name,
+                                         true,
+                                         field.name.clone(),
+                                         CppFieldAccessorType::CopyGetter,
+                                         return_type,
+                                         Vec::new())?);
+        }
+      }
     }
   }
   Ok(new_methods)
@@ -468,17 +508,44 @@ fn generate_casts(cpp_data: &CppDataWithDeps) -> Result<Vec<CppMethodWithKind>>
   Ok(new_methods)
 }
 
+/// Returns public methods inherited by classes listed in `type_names`
+/// (see `Config::set_inline_inherited_methods`). The methods are
+/// already assigned to the derived class, and methods hidden by a method
+/// of the derived class are not present in the inherited methods list.
+fn generate_inherited_methods(cpp_data: &CppDataWithDeps,
+                              type_names: &[String])
+                              -> Vec<CppMethodWithKind> {
+  cpp_data
+    .current
+    .processed
+    .inherited_methods
+    .iter()
+    .filter(|method| if let Some(ref info) = method.class_membership {
+              type_names.contains(&info.class_type.name) &&
+              info.kind == CppMethodKind::Regular &&
+              info.visibility == CppVisibility::Public
+            } else {
+              false
+            })
+    .map(|method| {
+           CppMethodWithKind {
+             method: method.clone(),
+             kind: CppFfiMethodKind::Real,
+           }
+         })
+    .collect()
+}
 
 /// Generates the FFI function signature for this method.
 fn method_to_ffi_signature<'a>(method: CppMethodRefWithKind<'a>,
                                cpp_data: &CppDataWithDeps,
                                type_allocation_places_override: Option<CppTypeAllocationPlace>)
                                -> Result<CppMethodWithFfiSignature> {
-  let get_place = |name| -> Result<ReturnValueAllocationPlace> {
+  let get_place = |class_type: &CppTypeClassBase| -> Result<ReturnValueAllocationPlace> {
     let v = if let Some(ref x) = type_allocation_places_override {
       x.clone()
     } else {
-      cpp_data.type_allocation_place(name)?
+      cpp_data.class_type_allocation_place(class_type)?
     };
     Ok(match v {
          CppTypeAllocationPlace::Heap => ReturnValueAllocationPlace::Heap,
@@ -492,13 +559,13 @@ fn method_to_ffi_signature<'a>(method: CppMethodRefWithKind<'a>,
       .class_membership
       .as_ref()
       .expect("class info expected here");
-    get_place(&info.class_type.name)?
+    get_place(&info.class_type)?
   } else if method
               .method
               .return_type
               .needs_allocation_place_variants() {
-    if let CppTypeBase::Class(CppTypeClassBase { ref name, .. }) = method.method.return_type.base {
-      get_place(name)?
+    if let CppTypeBase::Class(ref class_type) = method.method.return_type.base {
+      get_place(class_type)?
     } else {
       return Err(unexpected("class type expected here").into());
     }
@@ -548,9 +615,22 @@ impl<'a> CppFfiGenerator<'a> {
         return Ok(false);
       }
       if membership.visibility == CppVisibility::Protected {
-        return Ok(false);
+        if membership.kind != CppMethodKind::Constructor ||
+           !self.protected_constructor_classes.contains(&class_name) {
+          return Ok(false);
+        }
+        // the object is created as a helper subclass but deleted
+        // through a pointer to `class_name`
+        if !self.cpp_data.has_virtual_destructor(&class_name) {
+          log::llog(log::DebugFfiSkips, || {
+            format!("Skipping protected constructor of class without virtual destructor: {}",
+                    method.short_text())
+          });
+          return Ok(false);
+        }
       }
-      if membership.is_signal {
+      // signals with `QPrivateSignal` argument can't be called outside of the class
+      if membership.is_private_signal {
         return Ok(false);
       }
     }
@@ -702,7 +782,7 @@ impl<'a> CppFfiGenerator<'a> {
     Ok(processed_methods)
   }
 
-  /// Generates slot wrappers for all encountered argument types
+  /// Generates slot wrappers for all encountered argument and return types
   /// (excluding types already handled in the dependencies).
   fn generate_slot_wrappers(&'a self) -> Result<Option<CppFfiHeaderData>> {
     let include_file_name = "slots";
@@ -710,22 +790,33 @@ impl<'a> CppFfiGenerator<'a> {
          .cpp_data
          .current
          .processed
-         .signal_argument_types
+         .signal_types
          .is_empty() {
       return Ok(None);
     }
     let mut qt_slot_wrappers = Vec::new();
     let mut methods = Vec::new();
-    for types in &self.cpp_data.current.processed.signal_argument_types {
+    for signal_types in &self.cpp_data.current.processed.signal_types {
+      let types = &signal_types.arguments;
       let ffi_types = types
         .map_if_ok(|t| t.to_cpp_ffi_type(CppTypeRole::NotReturnType))?;
+      let ffi_return_type = signal_types
+        .return_type
+        .to_cpp_ffi_type(CppTypeRole::ReturnType)?;
       let args_captions = types
         .map_if_ok(|t| t.caption(TypeCaptionStrategy::Full))?;
-      let args_caption = if args_captions.is_empty() {
+      let mut args_caption = if args_captions.is_empty() {
         "no_args".to_string()
       } else {
         args_captions.join("_")
       };
+      if !signal_types.return_type.is_void() {
+        args_caption = format!("{}_returns_{}",
+                               args_caption,
+                               signal_types
+                                 .return_type
+                                 .caption(TypeCaptionStrategy::Full)?);
+      }
       let void_ptr = CppType {
         base: CppTypeBase::Void,
         indirection: CppTypeIndirection::Ptr,
@@ -737,14 +828,15 @@ impl<'a> CppFfiGenerator<'a> {
         .collect();
       let class_name = format!("{}_SlotWrapper_{}", self.cpp_ffi_lib_name, args_caption);
       let function_type = CppFunctionPointerType {
-        return_type: Box::new(CppType::void()),
+        return_type: Box::new(ffi_return_type.ffi_type.clone()),
         arguments: func_arguments,
         allows_variadic_arguments: false,
       };
       let create_function = |kind: CppMethodKind,
                              name: String,
                              is_slot: bool,
-                             arguments: Vec<CppMethodArgument>|
+                             arguments: Vec<CppMethodArgument>,
+                             return_type: CppType|
        -> CppMethodWithKind {
         CppMethodWithKind {
           method: CppMethod {
@@ -760,11 +852,12 @@ impl<'a> CppFfiGenerator<'a> {
                                      is_static: false,
                                      visibility: CppVisibility::Public,
                                      is_signal: false,
+                                     is_private_signal: false,
                                      is_slot: is_slot,
                                      kind: kind,
                                    }),
             operator: None,
-            return_type: CppType::void(),
+            return_type: return_type,
             arguments: arguments,
             allows_variadic_arguments: false,
             include_file: include_file_name.to_string(),
@@ -783,11 +876,13 @@ impl<'a> CppFfiGenerator<'a> {
       methods.push(create_function(CppMethodKind::Constructor,
                                    class_name.clone(),
                                    false,
-                                   vec![]));
+                                   vec![],
+                                   CppType::void()));
       methods.push(create_function(CppMethodKind::Destructor,
                                    format!("~{}", class_name),
                                    false,
-                                   vec![]));
+                                   vec![],
+                                   CppType::void()));
       let method_set_args = vec![CppMethodArgument {
                                    name: "func".to_string(),
                                    argument_type: CppType {
@@ -806,7 +901,8 @@ impl<'a> CppFfiGenerator<'a> {
       methods.push(create_function(CppMethodKind::Regular,
                                    "set".to_string(),
                                    false,
-                                   method_set_args));
+                                   method_set_args,
+                                   CppType::void()));
 
       let method_custom_slot = create_function(CppMethodKind::Regular,
                                                "custom_slot".to_string(),
@@ -821,12 +917,14 @@ impl<'a> CppFfiGenerator<'a> {
                                                           has_default_value: false,
                                                         }
                                                       })
-                                                 .collect());
+                                                 .collect(),
+                                               signal_types.return_type.clone());
       let receiver_id = method_custom_slot.method.receiver_id()?;
       methods.push(method_custom_slot);
       qt_slot_wrappers.push(QtSlotWrapper {
                               class_name: class_name.clone(),
                               arguments: ffi_types,
+                              return_type: ffi_return_type,
                               function_type: function_type.clone(),
                               receiver_id: receiver_id,
                             });
@@ -2,11 +2,15 @@
 
 
 use cpp_data::{CppVisibility, CppOriginLocation, TemplateArgumentsDeclaration, CppBaseSpecifier};
-use cpp_ffi_data::{CppFfiType, CppFfiMethodSignature, CppFfiMethodArgument, CppFfiArgumentMeaning};
-use cpp_type::{CppType, CppTypeIndirection, CppTypeRole, CppTypeBase, CppTypeClassBase};
+use cpp_ffi_data::{CppFfiType, CppFfiMethodSignature, CppFfiMethodArgument, CppFfiArgumentMeaning,
+                   CppIndirectionChange};
+use cpp_type::{CppType, CppTypeIndirection, CppTypeRole, CppTypeBase, CppTypeClassBase,
+               CppBuiltInNumericType};
 use common::errors::{Result, unexpected};
 use common::string_utils::JoinWithSeparator;
 use common::utils::MapIfOk;
+use common::target;
+use std::collections::HashMap;
 
 pub use cpp_operator::{CppOperator, CppOperatorInfo};
 
@@ -80,6 +84,9 @@ pub struct CppMethodClassMembership {
   pub visibility: CppVisibility,
   /// True if the method is a Qt signal
   pub is_signal: bool,
+  /// True if the method is a Qt signal that can only be emitted
+  /// by its class (i.e. it has a `QPrivateSignal` argument)
+  pub is_private_signal: bool,
   /// True if the method is a Qt slot
   pub is_slot: bool,
 
@@ -223,10 +230,27 @@ impl CppMethod {
     true
   }
 
+  /// Checks if this method is named `name` (either the full name,
+  /// e.g. `"QString::arg"`, or its own name, e.g. `"arg"`) and has
+  /// arguments of types `argument_types` in C++ pseudo-code
+  /// (as returned by `CppType::to_cpp_pseudo_code`, e.g. `"const QString&"`).
+  pub fn matches_overload(&self, name: &str, argument_types: &[String]) -> bool {
+    if self.name != name && self.full_name() != name {
+      return false;
+    }
+    self.arguments.len() == argument_types.len() &&
+    self
+      .arguments
+      .iter()
+      .zip(argument_types.iter())
+      .all(|(arg, type_code)| &arg.argument_type.to_cpp_pseudo_code() == type_code)
+  }
+
   /// Creates FFI method signature for this method:
   /// - converts all types to FFI types;
   /// - adds "this" argument explicitly if present;
-  /// - adds "output" argument for return value if `allocation_place` is `Stack`.
+  /// - adds "output" argument for return value if `allocation_place` is `Stack`
+  ///   or the return type is `std::optional`.
   pub fn c_signature(&self,
                      allocation_place: ReturnValueAllocationPlace)
                      -> Result<CppFfiMethodSignature> {
@@ -295,6 +319,22 @@ impl CppMethod {
                          .into());
         }
       }
+    } else if c_type.conversion == CppIndirectionChange::OptionalToPointer {
+      // the contained value is written to the output argument
+      // and the FFI function returns whether the value is present
+      r.arguments
+        .push(CppFfiMethodArgument {
+                name: "output".to_string(),
+                argument_type: c_type,
+                meaning: CppFfiArgumentMeaning::ReturnValue,
+              });
+      r.return_type = CppType {
+          base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+          is_const: false,
+          is_const2: false,
+          indirection: CppTypeIndirection::None,
+        }
+        .to_cpp_ffi_type(CppTypeRole::ReturnType)?;
     } else {
       r.return_type = c_type;
     }
@@ -315,6 +355,16 @@ impl CppMethod {
     }
   }
 
+  /// Returns the target condition set for this method with
+  /// `Config::add_platform_method`, or `None` if the method is not platform-specific.
+  pub fn platform_condition<'a>(&self,
+                                platform_methods: &'a HashMap<String, target::Condition>)
+                                -> Option<&'a target::Condition> {
+    platform_methods
+      .get(&self.full_name())
+      .or_else(|| platform_methods.get(&self.name))
+  }
+
   /// Returns the identifier this method would be presented with
   /// in Qt documentation.
   pub fn doc_id(&self) -> String {
@@ -439,6 +489,64 @@ impl CppMethod {
     }
   }
 
+  /// Returns true if this method is a protected constructor.
+  pub fn is_protected_constructor(&self) -> bool {
+    match self.class_info_if_constructor() {
+      Some(info) => info.visibility == CppVisibility::Protected,
+      None => false,
+    }
+  }
+
+  /// Returns true if this method is a copy constructor,
+  /// i.e. a constructor taking a single const reference
+  /// to its own class.
+  pub fn is_copy_constructor(&self) -> bool {
+    if let Some(info) = self.class_info_if_constructor() {
+      self.arguments.len() == 1 &&
+      self.arguments[0].argument_type ==
+      CppType {
+        base: CppTypeBase::Class(info.class_type.clone()),
+        is_const: true,
+        is_const2: false,
+        indirection: CppTypeIndirection::Ref,
+      }
+    } else {
+      false
+    }
+  }
+
+  /// Returns true if this method is a setter (its name starts with `set`
+  /// followed by an uppercase letter, e.g. `setName`) that
+  /// `returns_self_reference`, which usually means it returns `*this`
+  /// to allow chaining.
+  pub fn is_chainable_setter(&self) -> bool {
+    self.name.starts_with("set") &&
+    self.name[3..].chars().next().map_or(false, |c| c.is_uppercase()) &&
+    self.returns_self_reference()
+  }
+
+  /// Returns true if this method is a non-const, non-static method
+  /// taking at least one argument and returning a non-const reference
+  /// to its own class (e.g. `Builder& setName(const QString& name)`).
+  /// Operators are not considered.
+  pub fn returns_self_reference(&self) -> bool {
+    if self.operator.is_some() || self.arguments.is_empty() {
+      return false;
+    }
+    if let Some(ref info) = self.class_membership {
+      info.kind == CppMethodKind::Regular && !info.is_const && !info.is_static &&
+      self.return_type ==
+      CppType {
+        base: CppTypeBase::Class(info.class_type.clone()),
+        is_const: false,
+        is_const2: false,
+        indirection: CppTypeIndirection::Ref,
+      }
+    } else {
+      false
+    }
+  }
+
   /// A convenience method. Returns `class_membership` if
   /// the method is a constructor, and `None` otherwise.
   pub fn class_info_if_constructor(&self) -> Option<&CppMethodClassMembership> {
@@ -479,6 +587,34 @@ impl CppMethod {
 
 
 
+  /// Returns true if this method is a conversion operator
+  /// that is not declared `explicit`. Returns false if
+  /// the declaration code of the method is not available.
+  pub fn is_implicit_conversion_operator(&self) -> bool {
+    if let Some(CppOperator::Conversion(..)) = self.operator {
+      if let Some(ref code) = self.declaration_code {
+        return !code
+                  .split(|c: char| !c.is_alphanumeric() && c != '_')
+                  .any(|word| word == "explicit");
+      }
+    }
+    false
+  }
+
+  /// Returns true if this method is a conversion operator
+  /// declared `explicit`. Returns false if
+  /// the declaration code of the method is not available.
+  pub fn is_explicit_conversion_operator(&self) -> bool {
+    if let Some(CppOperator::Conversion(..)) = self.operator {
+      if let Some(ref code) = self.declaration_code {
+        return code
+                 .split(|c: char| !c.is_alphanumeric() && c != '_')
+                 .any(|word| word == "explicit");
+      }
+    }
+    false
+  }
+
   #[allow(dead_code)]
   /// Returns true if this method is an operator.
   pub fn is_operator(&self) -> bool {
@@ -1,6 +1,6 @@
 use cpp_data::{ParserCppData, CppData, CppTypeData, CppTypeKind, CppClassField, CppEnumValue,
                CppOriginLocation, CppVisibility, CppClassUsingDirective, CppBaseSpecifier,
-               TemplateArgumentsDeclaration};
+               TemplateArgumentsDeclaration, CppClassStaticField};
 use cpp_method::{CppMethod, CppMethodArgument, CppMethodKind, CppMethodClassMembership};
 use cpp_operator::CppOperator;
 use cpp_type::{CppType, CppTypeBase, CppBuiltInNumericType, CppTypeIndirection,
@@ -11,6 +11,7 @@ use common::file_utils::{remove_file, open_file, create_file, path_to_str, os_st
 use common::string_utils::JoinWithSeparator;
 use common::log;
 
+use std::collections::HashSet;
 use std::io::{BufRead, BufReader};
 use std::path::{Path, PathBuf};
 
@@ -141,6 +142,41 @@ fn get_full_name(entity: Entity) -> Result<String> {
   }
 }
 
+/// Parses an integer or boolean literal from a list of token spellings,
+/// e.g. `["-", "0x10", "u"]`. Returns `None` if the tokens contain
+/// anything else (such as a more complex expression).
+fn parse_integer_literal(tokens: &[String]) -> Option<i64> {
+  let (is_negative, tokens) = match tokens.split_first() {
+    Some((first, rest)) if first == "-" => (true, rest),
+    _ => (false, tokens),
+  };
+  if tokens.len() != 1 {
+    return None;
+  }
+  let literal = tokens[0].trim_right_matches(|c| c == 'u' || c == 'U' || c == 'l' || c == 'L');
+  let value = if literal == "true" {
+    1
+  } else if literal == "false" {
+    0
+  } else if literal.starts_with("0x") || literal.starts_with("0X") {
+    match i64::from_str_radix(&literal[2..], 16) {
+      Ok(value) => value,
+      Err(_) => return None,
+    }
+  } else if literal.len() > 1 && literal.starts_with('0') {
+    match i64::from_str_radix(&literal[1..], 8) {
+      Ok(value) => value,
+      Err(_) => return None,
+    }
+  } else {
+    match literal.parse::<i64>() {
+      Ok(value) => value,
+      Err(_) => return None,
+    }
+  };
+  Some(if is_negative { -value } else { value })
+}
+
 /// C++ parser configuration
 #[derive(Clone, Debug)]
 pub struct CppParserConfig {
@@ -161,6 +197,9 @@ pub struct CppParserConfig {
   /// List of names that should be excluded from the processing.
   /// See `Config::add_cpp_parser_blocked_name` for more details.
   pub name_blacklist: Vec<String>,
+  /// List of names that should be processed. If empty, all names are processed.
+  /// See `Config::add_cpp_parser_allowed_name` for more details.
+  pub name_allowlist: Vec<String>,
 }
 
 #[cfg(test)]
@@ -182,6 +221,20 @@ fn init_clang() -> Result<Clang> {
 }
 
 
+/// Returns content of the C++ file passed to the parser.
+/// Include directives are added in the order specified in `config`,
+/// followed by `cpp_code` (if any).
+pub fn parser_input_code(config: &CppParserConfig, cpp_code: Option<String>) -> Result<String> {
+  let mut code = String::new();
+  for directive in &config.include_directives {
+    code.push_str(&format!("#include \"{}\"\n", path_to_str(directive)?));
+  }
+  if let Some(cpp_code) = cpp_code {
+    code.push_str(&cpp_code);
+  }
+  Ok(code)
+}
+
 /// Runs `clang` parser with `config`.
 /// If `cpp_code` is specified, it's written to the C++ file before parsing it.
 /// If successful, calls `f` and passes the topmost entity (the translation unit)
@@ -195,13 +248,7 @@ fn run_clang<R, F: Fn(Entity) -> Result<R>>(config: &CppParserConfig,
   let index = Index::new(&clang, false, false);
   {
     let mut tmp_file = create_file(&config.tmp_cpp_path)?;
-    for directive in &config.include_directives {
-      tmp_file
-        .write(format!("#include \"{}\"\n", path_to_str(directive)?))?;
-    }
-    if let Some(cpp_code) = cpp_code {
-      tmp_file.write(cpp_code)?;
-    }
+    tmp_file.write(parser_input_code(config, cpp_code)?)?;
   }
   let mut args = vec!["-Xclang".to_string(),
                       "-detailed-preprocessing-record".to_string()];
@@ -278,6 +325,7 @@ pub fn run(config: CppParserConfig, dependencies_data: &[&CppData]) -> Result<Pa
     let methods = parser.parse_methods(translation_unit);
     Ok((parser, methods))
   })?;
+  let methods = parser.apply_allowlist(methods);
   log::status("Checking data integrity");
   let (good_methods, good_types) = parser.check_integrity(methods);
   parser.types = good_types;
@@ -288,6 +336,38 @@ pub fn run(config: CppParserConfig, dependencies_data: &[&CppData]) -> Result<Pa
      })
 }
 
+/// Adds names of all classes and enums used in `type_base`
+/// (including template arguments) to `names`.
+fn add_used_type_names(type_base: &CppTypeBase, names: &mut HashSet<String>) {
+  match *type_base {
+    CppTypeBase::Enum { ref name } => {
+      names.insert(name.clone());
+    }
+    CppTypeBase::Class(CppTypeClassBase {
+                         ref name,
+                         ref template_arguments,
+                       }) => {
+      names.insert(name.clone());
+      if let Some(ref args) = *template_arguments {
+        for arg in args {
+          add_used_type_names(&arg.base, names);
+        }
+      }
+    }
+    CppTypeBase::FunctionPointer(CppFunctionPointerType {
+                                   ref return_type,
+                                   ref arguments,
+                                   ..
+                                 }) => {
+      add_used_type_names(&return_type.base, names);
+      for arg in arguments {
+        add_used_type_names(&arg.base, names);
+      }
+    }
+    _ => {}
+  }
+}
+
 impl<'a> CppParser<'a> {
   /// Search for a C++ type information in the types found by the parser
   /// and in types of the dependencies.
@@ -477,11 +557,15 @@ impl<'a> CppParser<'a> {
             new_indirection = CppTypeIndirection::None;
           }
         }
-        let new_is_const2 = if new_indirection == CppTypeIndirection::PtrPtr {
-          remaining_name.trim().ends_with(" const")
-        } else {
-          false
+        let is_const_qualified = remaining_name.trim().ends_with(" const");
+        let new_is_const2 = match new_indirection {
+          CppTypeIndirection::PtrPtr => is_const_qualified,
+          CppTypeIndirection::PtrPtrPtr { .. } => subtype.is_const2,
+          _ => false,
         };
+        if let CppTypeIndirection::PtrPtrPtr { ref mut is_const3 } = new_indirection {
+          *is_const3 = is_const_qualified;
+        }
         return Ok(CppType {
                     base: subtype.base,
                     is_const: subtype.is_const,
@@ -747,11 +831,14 @@ impl<'a> CppParser<'a> {
                     new_indirection = CppTypeIndirection::None;
                   }
                 }
-                let new_is_const2 = if new_indirection == CppTypeIndirection::PtrPtr {
-                  pointee.is_const_qualified()
-                } else {
-                  false
+                let new_is_const2 = match new_indirection {
+                  CppTypeIndirection::PtrPtr => pointee.is_const_qualified(),
+                  CppTypeIndirection::PtrPtrPtr { .. } => subtype.is_const2,
+                  _ => false,
                 };
+                if let CppTypeIndirection::PtrPtrPtr { ref mut is_const3 } = new_indirection {
+                  *is_const3 = pointee.is_const_qualified();
+                }
                 Ok(CppType {
                      indirection: new_indirection,
                      base: subtype.base,
@@ -1171,6 +1258,7 @@ impl<'a> CppParser<'a> {
              },
              // not all signals are detected here! see CppData::detect_signals_and_slots
              is_signal: is_signal,
+             is_private_signal: is_signal,
              is_slot: false,
              class_type: match self.find_type(|x| &x.name == &class_name) {
                Some(info) => info.default_class_type()?,
@@ -1260,6 +1348,59 @@ impl<'a> CppParser<'a> {
        })
   }
 
+  /// Parses a static data member declaration `entity`.
+  /// The value is only extracted for const integral and boolean
+  /// members initialized with a literal inside the class.
+  fn parse_class_static_field(&self, entity: Entity) -> Result<CppClassStaticField> {
+    let field_name = entity
+      .get_name()
+      .chain_err(|| "failed to get static field name")?;
+    let field_clang_type = entity
+      .get_type()
+      .chain_err(|| "failed to get static field type")?;
+    let field_type = self
+      .parse_type(field_clang_type, Some(entity), None)
+      .chain_err(|| {
+                   format!("failed to parse static field type: {}::{}",
+                           get_full_name(entity).unwrap_or("?".into()),
+                           field_name)
+                 })?;
+    let is_integral = field_type.base.is_integer() ||
+                      field_type.base ==
+                      CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool);
+    let value = if field_type.is_const && is_integral &&
+                   field_type.indirection == CppTypeIndirection::None {
+      match entity.get_range() {
+        Some(range) => {
+          let tokens: Vec<_> = range
+            .tokenize()
+            .into_iter()
+            .map(|token| token.get_spelling())
+            .skip_while(|spelling| spelling != "=")
+            .skip(1)
+            .take_while(|spelling| spelling != ";")
+            .collect();
+          parse_integer_literal(&tokens)
+        }
+        None => None,
+      }
+    } else {
+      None
+    };
+    Ok(CppClassStaticField {
+         name: field_name,
+         field_type: field_type,
+         visibility: match entity
+                 .get_accessibility()
+                 .unwrap_or(Accessibility::Public) {
+           Accessibility::Public => CppVisibility::Public,
+           Accessibility::Protected => CppVisibility::Protected,
+           Accessibility::Private => CppVisibility::Private,
+         },
+         value: value,
+       })
+  }
+
   /// Parses a class or a struct `entity`.
   fn parse_class(&self, entity: Entity) -> Result<CppTypeData> {
     let include_file = self
@@ -1271,6 +1412,7 @@ impl<'a> CppParser<'a> {
                  })?;
     let full_name = get_full_name(entity)?;
     let mut fields = Vec::new();
+    let mut static_fields = Vec::new();
     let mut bases = Vec::new();
     let using_directives = entity
       .get_children()
@@ -1310,6 +1452,16 @@ impl<'a> CppParser<'a> {
           }
         }
       }
+      if child.get_kind() == EntityKind::VarDecl {
+        match self.parse_class_static_field(child) {
+          Ok(field) => static_fields.push(field),
+          Err(err) => {
+            log::llog(log::DebugParserSkips,
+                      || format!("failed to parse class static field: {}", err));
+            err.discard_expected();
+          }
+        }
+      }
       if child.get_kind() == EntityKind::BaseSpecifier {
         let base_type = match self.parse_type(child.get_type().unwrap(), Some(entity), None) {
           Ok(r) => r,
@@ -1346,6 +1498,10 @@ impl<'a> CppParser<'a> {
     if template_arguments.is_none() && size.is_none() {
       return Err("Failed to request size, but the class is not a template class".into());
     }
+    let alignment = match entity.get_type() {
+      Some(type1) => type1.get_alignof().ok(),
+      None => None,
+    };
     if let Some(parent) = entity.get_semantic_parent() {
       if get_template_arguments(parent).is_some() {
         return Err("Types nested into template types are not supported".into());
@@ -1358,8 +1514,10 @@ impl<'a> CppParser<'a> {
          kind: CppTypeKind::Class {
            bases: bases,
            fields: fields,
+           static_fields: static_fields,
            using_directives: using_directives,
            template_arguments: template_arguments,
+           alignment: alignment,
          },
          doc: None,
        })
@@ -1421,6 +1579,91 @@ impl<'a> CppParser<'a> {
     true
   }
 
+  /// Returns true if `name` is in the allowlist or is declared
+  /// inside an entity from the allowlist. Returns true
+  /// if the allowlist is empty.
+  fn is_allowed_name(&self, name: &str) -> bool {
+    self.config.name_allowlist.is_empty() ||
+    self
+      .config
+      .name_allowlist
+      .iter()
+      .any(|x| name == x || name.starts_with(&format!("{}::", x)))
+  }
+
+  /// Removes methods and types not matching the allowlist.
+  /// Types used by the remaining methods (including types used
+  /// by the remaining types) are kept, along with their destructors.
+  /// Returns the remaining methods.
+  fn apply_allowlist(&mut self, methods: Vec<CppMethod>) -> Vec<CppMethod> {
+    if self.config.name_allowlist.is_empty() {
+      return methods;
+    }
+    let (mut good_methods, other_methods): (Vec<_>, Vec<_>) =
+      methods
+        .into_iter()
+        .partition(|method| self.is_allowed_name(&method.doc_id()));
+    let mut used_names = HashSet::new();
+    for t in &self.types {
+      if self.is_allowed_name(&t.name) {
+        used_names.insert(t.name.clone());
+      }
+    }
+    for method in &good_methods {
+      if let Some(ref info) = method.class_membership {
+        add_used_type_names(&CppTypeBase::Class(info.class_type.clone()),
+                            &mut used_names);
+      }
+      add_used_type_names(&method.return_type.base, &mut used_names);
+      for arg in &method.arguments {
+        add_used_type_names(&arg.argument_type.base, &mut used_names);
+      }
+    }
+    loop {
+      let mut new_names = HashSet::new();
+      for t in &self.types {
+        if !used_names.contains(&t.name) {
+          continue;
+        }
+        if let CppTypeKind::Class {
+                 ref bases,
+                 ref fields,
+                 ref static_fields,
+                 ..
+               } = t.kind {
+          for base in bases {
+            add_used_type_names(&base.base_type.base, &mut new_names);
+          }
+          for field in fields {
+            add_used_type_names(&field.field_type.base, &mut new_names);
+          }
+          for field in static_fields {
+            add_used_type_names(&field.field_type.base, &mut new_names);
+          }
+        }
+      }
+      let old_count = used_names.len();
+      used_names.extend(new_names);
+      if used_names.len() == old_count {
+        break;
+      }
+    }
+    self.types.retain(|t| used_names.contains(&t.name));
+    for method in other_methods {
+      let is_used_destructor = method.is_destructor() &&
+                               method
+                                 .class_name()
+                                 .map_or(false, |name| used_names.contains(name));
+      if is_used_destructor {
+        good_methods.push(method);
+      } else {
+        log::llog(log::DebugParserSkips,
+                  || format!("Method is not in the allowlist: {}", method.short_text()));
+      }
+    }
+    good_methods
+  }
+
   /// Parses type declarations in translation unit `entity`
   /// and saves them to `self`.
   fn parse_types(&mut self, entity: Entity) {
@@ -1650,6 +1893,7 @@ impl<'a> CppParser<'a> {
       if let CppTypeKind::Class {
                ref mut bases,
                ref mut fields,
+               ref mut static_fields,
                ..
              } = good_type.kind {
         let mut valid_bases = Vec::new();
@@ -1684,6 +1928,22 @@ impl<'a> CppParser<'a> {
         fields.clear();
         fields.append(&mut valid_fields);
 
+        let mut valid_static_fields = Vec::new();
+        for field in static_fields.iter() {
+          if let Err(msg) = self.check_type_integrity(&field.field_type) {
+            log::llog(log::DebugParserSkips, || {
+              format!("Class {}: static field removed because type is not available: {:?}: {}",
+                      t.name,
+                      field,
+                      msg)
+            });
+          } else {
+            valid_static_fields.push(field.clone());
+          }
+        }
+        static_fields.clear();
+        static_fields.append(&mut valid_static_fields);
+
       }
       good_types.push(good_type);
     }
@@ -1,6 +1,6 @@
 use cpp_data::{CppDataWithDeps, CppData, ParserCppData, ProcessedCppData, CppTypeAllocationPlace,
                CppTypeKind, CppVisibility, CppTemplateInstantiations, CppTemplateInstantiation,
-               CppTypeData, CppBaseSpecifier};
+               CppTypeData, CppBaseSpecifier, CppSignalTypes, template_instantiation_key};
 use cpp_method::{CppMethod, CppMethodKind, CppMethodClassMembership};
 use cpp_type::{CppType, CppTypeClassBase, CppTypeBase, CppTypeIndirection};
 use common::log;
@@ -36,7 +36,7 @@ pub fn cpp_post_process<'a>(parser_data: ParserCppData,
     implicit_destructors: implicit_destructors,
     template_instantiations: processor.find_template_instantiations(),
     inherited_methods: inherited_methods,
-    signal_argument_types: processor.detect_signal_argument_types()?,
+    signal_types: processor.detect_signal_types()?,
     type_allocation_places: type_allocation_places,
   };
 
@@ -51,6 +51,26 @@ pub fn cpp_post_process<'a>(parser_data: ParserCppData,
 
 
 
+/// Returns return type of a slot wrapper for a signal with `return_type`.
+/// Only built-in numeric types can be forwarded from the slot,
+/// and `void` is returned for other types.
+fn signal_return_type(return_type: &CppType) -> CppType {
+  if return_type.indirection != CppTypeIndirection::None {
+    return CppType::void();
+  }
+  match return_type.base {
+    CppTypeBase::BuiltInNumeric(..) |
+    CppTypeBase::SpecificNumeric(..) |
+    CppTypeBase::PointerSizedInteger { .. } => {
+      CppType {
+        is_const: false,
+        ..return_type.clone()
+      }
+    }
+    _ => CppType::void(),
+  }
+}
+
 impl<'a> CppPostProcessor<'a> {
   /// Checks if specified class has virtual destructor (own or inherited).
   pub fn has_virtual_destructor(&self, class_name: &str, inherited_methods: &[CppMethod]) -> bool {
@@ -109,6 +129,7 @@ impl<'a> CppPostProcessor<'a> {
                                                   is_static: false,
                                                   visibility: CppVisibility::Public,
                                                   is_signal: false,
+                                                  is_private_signal: false,
                                                   is_slot: false,
                                                   kind: CppMethodKind::Destructor,
                                                 }),
@@ -380,26 +401,28 @@ impl<'a> CppPostProcessor<'a> {
   }
 
 
-  fn detect_signal_argument_types(&self) -> Result<Vec<Vec<CppType>>> {
+  /// Collects argument and return types of all signals
+  /// that are not already present in the dependencies.
+  fn detect_signal_types(&self) -> Result<Vec<CppSignalTypes>> {
+    let in_dependencies = |types: &CppSignalTypes| {
+      self
+        .dependencies
+        .iter()
+        .any(|d| d.processed.signal_types.iter().any(|t| t == types))
+    };
     let mut all_types = HashSet::new();
     for method in &self.parser_data.methods {
       if let Some(ref method_info) = method.class_membership {
         if method_info.is_signal {
-          let types: Vec<_> = method
-            .arguments
-            .iter()
-            .map(|x| x.argument_type.clone())
-            .collect();
-          if !all_types.contains(&types) &&
-             !self
-                .dependencies
-                .iter()
-                .any(|d| {
-                       d.processed
-                         .signal_argument_types
-                         .iter()
-                         .any(|t| t == &types)
-                     }) {
+          let types = CppSignalTypes {
+            arguments: method
+              .arguments
+              .iter()
+              .map(|x| x.argument_type.clone())
+              .collect(),
+            return_type: signal_return_type(&method.return_type),
+          };
+          if !all_types.contains(&types) && !in_dependencies(&types) {
             all_types.insert(types);
           }
         }
@@ -409,28 +432,21 @@ impl<'a> CppPostProcessor<'a> {
     let mut types_with_omitted_args = HashSet::new();
     for t in &all_types {
       let mut types = t.clone();
-      while let Some(_) = types.pop() {
+      while let Some(_) = types.arguments.pop() {
         if !types_with_omitted_args.contains(&types) && !all_types.contains(&types) &&
-           !self
-              .dependencies
-              .iter()
-              .any(|d| {
-                     d.processed
-                       .signal_argument_types
-                       .iter()
-                       .any(|t| t == &types)
-                   }) {
+           !in_dependencies(&types) {
           types_with_omitted_args.insert(types.clone());
         }
       }
     }
     all_types.extend(types_with_omitted_args.into_iter());
 
-    log::llog(log::DebugSignals, || "Signal argument types:");
+    log::llog(log::DebugSignals, || "Signal types:");
     for t in &all_types {
       log::llog(log::DebugSignals, || {
-        format!("  ({})",
-                t.iter().map(|x| x.to_cpp_pseudo_code()).join(", "))
+        format!("  ({}) -> {}",
+                t.arguments.iter().map(|x| x.to_cpp_pseudo_code()).join(", "),
+                t.return_type.to_cpp_pseudo_code())
       });
     }
     Ok(all_types.into_iter().collect())
@@ -553,6 +569,13 @@ impl<'a> CppPostProcessor<'a> {
       };
       results.insert(name.clone(), result);
     }
+    // overrides of specific template instantiations (e.g. "QList<int>")
+    // are checked by `CppDataWithDeps::class_type_allocation_place`
+    for (name, place) in overrides {
+      if name.contains('<') {
+        results.insert(template_instantiation_key(name), place.clone());
+      }
+    }
     log::llog(log::DebugAllocationPlace, || {
       format!("Allocation place is heap for: {}",
               results
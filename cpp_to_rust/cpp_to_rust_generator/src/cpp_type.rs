@@ -21,6 +21,9 @@ pub enum CppTypeIndirection {
   PtrRef,
   /// Pointer to pointer, like int**
   PtrPtr,
+  /// Pointer to pointer to pointer, like int***.
+  /// `is_const3` is true if the second pointer is const, like int** const*
+  PtrPtrPtr { is_const3: bool },
   /// R-value reference, like Class&&
   RValueRef,
 }
@@ -178,12 +181,32 @@ impl CppTypeIndirection {
              CppTypeIndirection::None => CppTypeIndirection::Ptr,
              CppTypeIndirection::Ptr => CppTypeIndirection::PtrPtr,
              CppTypeIndirection::Ref => CppTypeIndirection::PtrRef,
+             CppTypeIndirection::PtrPtr => CppTypeIndirection::PtrPtrPtr { is_const3: false },
              _ => return Err(err()),
            }
          }
+         // reference collapsing rules: the result is an rvalue reference
+         // only if both indirections are rvalue references
          CppTypeIndirection::Ref => {
            match *right {
-             CppTypeIndirection::None => CppTypeIndirection::Ref,
+             CppTypeIndirection::None |
+             CppTypeIndirection::Ref |
+             CppTypeIndirection::RValueRef => CppTypeIndirection::Ref,
+             _ => return Err(err()),
+           }
+         }
+         CppTypeIndirection::RValueRef => {
+           match *right {
+             CppTypeIndirection::None => CppTypeIndirection::RValueRef,
+             CppTypeIndirection::Ref => CppTypeIndirection::Ref,
+             CppTypeIndirection::RValueRef => CppTypeIndirection::RValueRef,
+             _ => return Err(err()),
+           }
+         }
+         CppTypeIndirection::PtrPtr => {
+           match *right {
+             CppTypeIndirection::None => CppTypeIndirection::PtrPtr,
+             CppTypeIndirection::Ptr => CppTypeIndirection::PtrPtrPtr { is_const3: false },
              _ => return Err(err()),
            }
          }
@@ -305,6 +328,34 @@ impl CppTypeClassBase {
        })
   }
 
+  /// Returns element type `T` of a `std::optional<T>` type.
+  /// Only non-class element types passed by value are supported.
+  pub fn optional_element_type(&self) -> Result<&CppType> {
+    if let CppTypeBase::Class(CppTypeClassBase {
+                                ref name,
+                                ref template_arguments,
+                              }) = self.base {
+      if name != "std::optional" {
+        return Err(format!("std::optional expected, got {}", name).into());
+      }
+      let args = template_arguments
+        .as_ref()
+        .chain_err(|| "std::optional type must have template arguments")?;
+      if args.len() != 1 {
+        return Err("std::optional type must have exactly 1 template argument".into());
+      }
+      let element = &args[0];
+      if element.indirection != CppTypeIndirection::None || element.is_void() ||
+         element.base.is_class() || element.base.is_template_parameter() ||
+         element.base.is_function_pointer() {
+        return Err(format!("unsupported std::optional element type: {:?}", element).into());
+      }
+      Ok(element)
+    } else {
+      Err("std::optional expected".into())
+    }
+  }
+
   /// Attempts to replace template types at `nested_level1`
   /// within this type with `template_arguments1`.
   pub fn instantiate_class(&self,
@@ -357,6 +408,39 @@ impl CppTypeBase {
       _ => false,
     }
   }
+  /// Returns true if this is an integer type of any size and signedness.
+  pub fn is_integer(&self) -> bool {
+    match *self {
+      CppTypeBase::BuiltInNumeric(ref numeric) => {
+        numeric.is_signed_integer() || numeric.is_unsigned_integer() ||
+        numeric.is_integer_with_undefined_signedness()
+      }
+      CppTypeBase::SpecificNumeric(CppSpecificNumericType { ref kind, .. }) => {
+        kind != &CppSpecificNumericTypeKind::FloatingPoint
+      }
+      CppTypeBase::PointerSizedInteger { .. } => true,
+      _ => false,
+    }
+  }
+  /// Returns size of this numeric type in bits, or `None` if this is not
+  /// a numeric type or its size depends on the platform (e.g. `long`).
+  pub fn numeric_bits(&self) -> Option<usize> {
+    use self::CppBuiltInNumericType::*;
+    match *self {
+      CppTypeBase::BuiltInNumeric(ref numeric) => {
+        match *numeric {
+          Char | SChar | UChar => Some(8),
+          Char16 | Short | UShort => Some(16),
+          Char32 | Int | UInt | Float => Some(32),
+          LongLong | ULongLong | Double => Some(64),
+          Int128 | UInt128 => Some(128),
+          Bool | WChar | Long | ULong | LongDouble => None,
+        }
+      }
+      CppTypeBase::SpecificNumeric(CppSpecificNumericType { ref bits, .. }) => Some(*bits),
+      _ => None,
+    }
+  }
   /// Returns true if this is a template parameter.
   pub fn is_template_parameter(&self) -> bool {
     match *self {
@@ -526,6 +610,14 @@ impl CppType {
               CppTypeIndirection::Ref => "&",
               CppTypeIndirection::PtrRef => if self.is_const2 { "* const &" } else { "*&" },
               CppTypeIndirection::PtrPtr => if self.is_const2 { "* const *" } else { "**" },
+              CppTypeIndirection::PtrPtrPtr { is_const3 } => {
+                match (self.is_const2, is_const3) {
+                  (false, false) => "***",
+                  (true, false) => "* const **",
+                  (false, true) => "** const *",
+                  (true, true) => "* const * const *",
+                }
+              }
               CppTypeIndirection::RValueRef => "&&",
             })
   }
@@ -585,7 +677,8 @@ impl CppType {
                        .chain_err(&err);
             }
             CppTypeIndirection::Ptr |
-            CppTypeIndirection::PtrPtr => {}
+            CppTypeIndirection::PtrPtr |
+            CppTypeIndirection::PtrPtrPtr { .. } => {}
             CppTypeIndirection::None => {
               if arg.base.is_class() {
                 return Err(Error::from("Function pointers containing classes by value are not \
@@ -608,7 +701,8 @@ impl CppType {
     match self.indirection {
       CppTypeIndirection::None |
       CppTypeIndirection::Ptr |
-      CppTypeIndirection::PtrPtr => {
+      CppTypeIndirection::PtrPtr |
+      CppTypeIndirection::PtrPtrPtr { .. } => {
         // no change needed
       }
       CppTypeIndirection::Ref => {
@@ -620,7 +714,16 @@ impl CppType {
         conversion = CppIndirectionChange::ReferenceToPointer;
       }
       CppTypeIndirection::RValueRef => {
-        return Err(Error::from("rvalue references are not supported")).chain_err(&err);
+        if role == CppTypeRole::ReturnType {
+          return Err(Error::from("rvalue reference return types are not supported"))
+                   .chain_err(&err);
+        }
+        if !self.base.is_class() {
+          return Err(Error::from("rvalue references to non-class types are not supported"))
+                   .chain_err(&err);
+        }
+        result.indirection = CppTypeIndirection::Ptr;
+        conversion = CppIndirectionChange::RValueReferenceToPointer;
       }
     }
     if let CppTypeBase::Class(CppTypeClassBase { ref name, .. }) = self.base {
@@ -636,6 +739,19 @@ impl CppType {
         result.base = CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::UInt);
         result.is_const = false;
         result.indirection = CppTypeIndirection::None;
+      } else if name == "std::optional" {
+        if !(self.indirection == CppTypeIndirection::None ||
+             (self.indirection == CppTypeIndirection::Ref && self.is_const)) {
+          return Err(Error::from(format!("std::optional type can only be values or const \
+                                          references: {:?}",
+                                         self)))
+                     .chain_err(&err);
+        }
+        let element = self.optional_element_type().chain_err(&err)?;
+        conversion = CppIndirectionChange::OptionalToPointer;
+        result = element.clone();
+        result.indirection = CppTypeIndirection::Ptr;
+        result.is_const = role != CppTypeRole::ReturnType;
       } else {
         // structs can't be passed by value
         if self.indirection == CppTypeIndirection::None {
@@ -673,6 +789,12 @@ impl CppType {
             r = format!("{}_ptr_ptr", r);
           }
         }
+        CppTypeIndirection::PtrPtrPtr { is_const3 } => {
+          r = format!("{}_ptr{}_ptr{}_ptr",
+                      r,
+                      if self.is_const2 { "_const" } else { "" },
+                      if is_const3 { "_const" } else { "" });
+        }
         CppTypeIndirection::RValueRef => r = format!("{}_rvalue_ref", r),
       }
       if self.is_const {
@@ -690,6 +812,9 @@ impl CppType {
       if name == "QFlags" {
         return false; // converted to uint in FFI
       }
+      if name == "std::optional" {
+        return false; // converted to an output argument in FFI
+      }
     }
     self.indirection == CppTypeIndirection::None && self.base.is_class()
   }
@@ -733,6 +858,7 @@ impl CppType {
             }
           }
           CppTypeIndirection::PtrPtr |
+          CppTypeIndirection::PtrPtrPtr { .. } |
           CppTypeIndirection::PtrRef => {
             if self.indirection == new_type.indirection {
               new_type.is_const = self.is_const;
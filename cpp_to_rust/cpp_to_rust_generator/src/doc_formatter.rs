@@ -47,6 +47,19 @@ pub fn wrap_inline_cpp_code(code: &str) -> String {
   format!("<span style='color: green;'>```{}```</span>", code)
 }
 
+/// Returns URL of the page of C++ class `class_name` or, if `method_name`
+/// is specified, of its method in the online C++ documentation
+/// located at `base_url` (see `Config::set_cpp_doc_base_url`).
+pub fn cpp_doc_url(base_url: &str, class_name: &str, method_name: Option<&str>) -> String {
+  let page_url = format!("{}/{}.html",
+                         base_url.trim_right_matches('/'),
+                         class_name.to_lowercase().replace("::", "-"));
+  match method_name {
+    Some(name) => format!("{}#{}", page_url, name),
+    None => page_url,
+  }
+}
+
 pub fn wrap_cpp_doc_block(html: &str) -> String {
   format!("<div style='border: 1px solid #5CFF95; \
                        background: #D6FFE4; padding: 16px;'>{}</div>",
@@ -54,6 +67,9 @@ pub fn wrap_cpp_doc_block(html: &str) -> String {
 }
 
 pub fn type_doc(type1: &RustTypeDeclaration) -> String {
+  if let Some(ref doc) = type1.doc_override {
+    return doc.clone();
+  }
   let auto_doc = match type1.kind {
     RustTypeDeclarationKind::CppTypeWrapper {
       ref cpp_type_name,
@@ -101,7 +117,7 @@ pub fn type_doc(type1: &RustTypeDeclaration) -> String {
       };
       let method_link = match *method_scope {
         RustMethodScope::Impl { ref target_type } => {
-          format!("../struct.{}.html#method.{}",
+          format!("struct.{}.html#method.{}",
                   if let RustType::Common { ref base, .. } = *target_type {
                     base.last_name().unwrap()
                   } else {
@@ -112,7 +128,7 @@ pub fn type_doc(type1: &RustTypeDeclaration) -> String {
         RustMethodScope::TraitImpl => {
           panic!("TraitImpl is totally not expected here");
         }
-        RustMethodScope::Free => format!("../fn.{}.html", method_name.last_name().unwrap()),
+        RustMethodScope::Free => format!("fn.{}.html", method_name.last_name().unwrap()),
       };
       format!("This trait represents a set of arguments accepted by [{name}]({link}) \
                       method.",
@@ -151,6 +167,12 @@ pub fn enum_value_doc(value: &RustEnumValue) -> String {
                               String::new()
                             }));
     }
+    // the first C++ variant gives the name to the Rust variant
+    doc.push_str(&format!("\nAlso known as: {}.",
+                          value.cpp_docs[1..]
+                            .iter()
+                            .map(|cpp_doc| format!("`{}`", cpp_doc.variant_name))
+                            .join(", ")));
     doc
   } else {
     let cpp_doc = &value.cpp_docs[0];
@@ -165,17 +187,26 @@ pub fn enum_value_doc(value: &RustEnumValue) -> String {
 }
 
 pub fn method_doc(method: &RustMethod) -> String {
+  if let Some(ref doc) = method.doc_override {
+    return doc.clone();
+  }
 
   let cpp_method_name = match method.arguments {
     RustMethodArguments::SingleVariant(ref v) => v.cpp_method.cpp_method.full_name(),
     RustMethodArguments::MultipleVariants { ref cpp_method_name, .. } => cpp_method_name.clone(),
   };
 
+  let cpp_method_caption = match method.cpp_doc_url {
+    Some(ref url) => format!("<a href=\"{}\">C++ method:</a>", url),
+    None => "C++ method:".to_string(),
+  };
   let overloaded = method.variant_docs.len() > 1 ||
                    (method.variant_docs.len() == 1 && method.variant_docs[0].rust_fns.len() > 1);
   let mut doc = Vec::new();
   if overloaded {
-    doc.push(format!("C++ method: {}\n\n", wrap_inline_cpp_code(&cpp_method_name)));
+    doc.push(format!("{} {}\n\n",
+                     cpp_method_caption,
+                     wrap_inline_cpp_code(&cpp_method_name)));
     doc.push("This is an overloaded function. Available variants:\n\n".to_string());
   }
 
@@ -235,7 +266,9 @@ pub fn method_doc(method: &RustMethod) -> String {
       })
                          .join("")));
     }
-    doc.push(format!("C++ method: {}", wrap_inline_cpp_code(&doc_item.cpp_fn)));
+    doc.push(format!("{} {}",
+                     cpp_method_caption,
+                     wrap_inline_cpp_code(&doc_item.cpp_fn)));
     doc.push("\n\n".to_string());
     // TODO: use inheritance_chain to generate documentation
     //    if let Some(ref inherited_from) = doc_item.inherited_from {
@@ -265,6 +298,25 @@ pub fn method_doc(method: &RustMethod) -> String {
   }
 }
 
+pub fn prelude_module_doc(crate_name: &str) -> String {
+  format!("Traits commonly used with types of this crate.\n\n\
+           This module re-exports all traits implemented in this crate, including \
+           casting traits from `cpp_utils`. Use `use {}::prelude::*;` \
+           to bring them into scope.",
+          crate_name)
+}
+
+pub fn protected_constructor_doc() -> String {
+  "Calls a protected C++ constructor.\n\n\
+  This constructor is only intended to be used by subclasses. The object is created \
+  as an instance of a helper C++ subclass that doesn't add any fields and \
+  doesn't override any virtual methods.\n\n\
+  # Safety\n\n\
+  The caller must ensure that the class can be used without \
+  a C++ subclass implementing any additional behavior."
+      .into()
+}
+
 pub fn slots_module_doc() -> String {
   "Binding Qt signals to Rust closures or extern functions.\n\n\
   Types in this module allow to connect Qt signals with certain argument types \
@@ -287,10 +339,6 @@ pub fn slots_raw_module_doc() -> String {
 }
 
 
-pub fn overloading_module_doc() -> String {
-  "Types for emulating overloading for overloaded functions in this module".into()
-}
-
 pub fn doc_for_qt_builtin_receiver(cpp_type_name: &str,
                                    rust_type_name: &str,
                                    receiver: &RustQtReceiverDeclaration)
@@ -0,0 +1,44 @@
+//! Types describing results of a dry run (see `Config::set_dry_run`).
+
+use common::errors::Result;
+use common::file_utils::{PathBufWithAdded, load_json};
+use rust_generator::SkippedItem;
+
+use std::path::{Path, PathBuf};
+
+/// Summary of a Rust module that would be generated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct DryRunModule {
+  /// Full name of the module (e.g. `"crate1::module1::submodule1"`).
+  pub name: String,
+  /// Number of public types declared in the module.
+  pub types_count: usize,
+  /// Number of methods and free functions declared in the module.
+  pub methods_count: usize,
+}
+
+/// Report produced by the generator in dry run mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct DryRunReport {
+  /// Name of the crate.
+  pub crate_name: String,
+  /// All modules of the crate, including nested modules.
+  pub modules: Vec<DryRunModule>,
+  /// Methods that would not be wrapped. Only items of
+  /// `SkippedItemKind::Method` kind are included.
+  pub skipped_methods: Vec<SkippedItem>,
+}
+
+impl DryRunReport {
+  /// Loads the report saved by a dry run that used `cache_dir`.
+  pub fn load<P: AsRef<Path>>(cache_dir: P) -> Result<DryRunReport> {
+    load_json(report_path(cache_dir))
+  }
+}
+
+/// Returns path to the file the dry run report is saved to.
+pub fn report_path<P: AsRef<Path>>(cache_dir: P) -> PathBuf {
+  cache_dir.as_ref().with_added("dry_run_report.json")
+}
@@ -4,6 +4,7 @@ use config::{Config, DebugLoggingConfig};
 use cpp_code_generator::{CppCodeGenerator, generate_cpp_type_size_requester, CppTypeSizeRequest};
 use cpp_type::CppTypeClassBase;
 use cpp_data::{CppData, CppDataWithDeps, ParserCppData};
+use cpp_ffi_data::CppFfiHeaderData;
 use cpp_ffi_generator;
 use cpp_parser;
 use cpp_post_processor::cpp_post_process;
@@ -14,9 +15,10 @@ use common::file_utils::{PathBufWithAdded, move_files, create_dir_all, save_json
                          create_file, path_to_str};
 use common::BuildScriptData;
 use common::log;
+use dry_run::{self, DryRunReport, DryRunModule};
 use rust_code_generator;
-use rust_generator;
-use rust_info::{RustTypeWrapperKind, RustExportInfo, DependencyInfo};
+use rust_generator::{self, SkippedItemKind};
+use rust_info::{RustTypeWrapperKind, RustExportInfo, DependencyInfo, RustModuleInventory};
 
 use std::path::{Path, PathBuf};
 use std::collections::HashMap;
@@ -149,6 +151,7 @@ fn load_or_create_cpp_data<'a>(config: &Config,
       target_include_paths: Vec::from(config.target_include_paths()),
       tmp_cpp_path: config.cache_dir_path().with_added("1.cpp"),
       name_blacklist: Vec::from(config.cpp_parser_blocked_names()),
+      name_allowlist: Vec::from(config.cpp_parser_allowed_names()),
       clang_arguments: Vec::from(config.cpp_parser_arguments()),
     };
     let mut parser_cpp_data: ParserCppData = cpp_parser::run(parser_config, &dependencies_cpp_data)
@@ -217,6 +220,59 @@ fn load_or_create_cpp_data<'a>(config: &Config,
   Ok(full_cpp_data)
 }
 
+/// Collects data required by the Rust generator.
+fn rust_generator_input_data<'a>(config: &'a Config,
+                                 cpp_data: &'a CppDataWithDeps<'a>,
+                                 cpp_ffi_headers: Vec<CppFfiHeaderData>,
+                                 dependencies: &'a [DependencyInfo],
+                                 remove_qt_prefix: bool)
+                                 -> rust_generator::RustGeneratorInputData<'a> {
+  rust_generator::RustGeneratorInputData {
+    cpp_data: cpp_data,
+    cpp_ffi_headers: cpp_ffi_headers,
+    dependency_types: dependencies
+      .iter()
+      .map(|dep| &dep.rust_export_info.rust_types as &[_])
+      .collect(),
+    crate_name: config.crate_properties().name().clone(),
+    // TODO: more universal prefix removal (#25)
+    remove_qt_prefix: remove_qt_prefix,
+    filtered_namespaces: config.cpp_filtered_namespaces().clone(),
+    settings: config.rust_generator_settings(),
+  }
+}
+
+/// Builds a summary of the Rust API produced by the generator.
+fn dry_run_report(crate_name: &str,
+                  rust_data: &rust_generator::RustGeneratorOutput)
+                  -> DryRunReport {
+  fn add_module(inventory: RustModuleInventory, modules: &mut Vec<DryRunModule>) {
+    modules.push(DryRunModule {
+                   name: inventory.name,
+                   types_count: inventory.types.len(),
+                   methods_count: inventory.methods.len(),
+                 });
+    for submodule in inventory.submodules {
+      add_module(submodule, modules);
+    }
+  }
+  let mut modules = Vec::new();
+  for module in &rust_data.modules {
+    add_module(rust_code_generator::module_inventory(module, crate_name),
+               &mut modules);
+  }
+  DryRunReport {
+    crate_name: crate_name.to_string(),
+    modules: modules,
+    skipped_methods: rust_data
+      .skipped
+      .iter()
+      .filter(|item| item.kind == SkippedItemKind::Method)
+      .cloned()
+      .collect(),
+  }
+}
+
 /// Executes the generator for a single config.
 pub fn exec_one(config: Config) -> Result<()> {
   exec(::std::iter::once(config))
@@ -314,7 +370,49 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
       };
       dependencies.push(data);
     }
-    {
+    if config.dry_run() {
+      let cpp_data =
+        load_or_create_cpp_data(&config,
+                                dependencies.iter().map(|dep| &dep.cpp_data).collect())?;
+      let cpp_ffi_lib_name = format!("{}_c", &config.crate_properties().name());
+      log::status("Preparing C++ FFI functions (dry run)");
+      let cpp_ffi_headers = cpp_ffi_generator::run(&cpp_data,
+                                                   cpp_ffi_lib_name,
+                                                   config.cpp_ffi_generator_filters(),
+                                                   config.protected_constructor_classes(),
+                                                   config.inline_inherited_methods())
+          .chain_err(|| "FFI generator failed")?;
+      log::status("Preparing Rust functions (dry run)");
+      let rust_data = rust_generator_input_data(&config,
+                                                &cpp_data,
+                                                cpp_ffi_headers,
+                                                &dependencies,
+                                                remove_qt_prefix)
+          .run()
+          .chain_err(|| "Rust data generator failed")?;
+      let report = dry_run_report(config.crate_properties().name(), &rust_data);
+      for module in &report.modules {
+        log::status(format!("Module {}: {} types, {} methods",
+                            module.name,
+                            module.types_count,
+                            module.methods_count));
+      }
+      log::status(format!("Skipped methods: {}", report.skipped_methods.len()));
+      let report_path = dry_run::report_path(config.cache_dir_path());
+      save_json(&report_path, &report)?;
+      log::status(format!("Dry run report is saved to file: {}", report_path.display()));
+      dependency_cache.insert(config.cache_dir_path().clone(),
+                              DependencyInfo {
+                                cpp_data: cpp_data.current,
+                                rust_export_info: RustExportInfo {
+                                  crate_name: config.crate_properties().name().clone(),
+                                  crate_version: config.crate_properties().version().clone(),
+                                  rust_types: rust_data.processed_types,
+                                  output_path: path_to_str(config.output_dir_path())?.to_string(),
+                                },
+                                cache_path: config.cache_dir_path().clone(),
+                              });
+    } else {
       let cpp_data =
         load_or_create_cpp_data(&config,
                                 dependencies.iter().map(|dep| &dep.cpp_data).collect())?;
@@ -339,11 +437,16 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
 
       let cpp_ffi_headers = cpp_ffi_generator::run(&cpp_data,
                                                    cpp_ffi_lib_name.clone(),
-                                                   config.cpp_ffi_generator_filters())
+                                                   config.cpp_ffi_generator_filters(),
+                                                   config.protected_constructor_classes(),
+                                                   config.inline_inherited_methods())
           .chain_err(|| "FFI generator failed")?;
 
       log::status(format!("Generating C++ wrapper code"));
-      let code_gen = CppCodeGenerator::new(cpp_ffi_lib_name.clone(), c_lib_tmp_path.clone());
+      let code_gen =
+        CppCodeGenerator::new(cpp_ffi_lib_name.clone(),
+                              c_lib_tmp_path.clone(),
+                              config.rust_generator_settings().platform_methods().clone());
       code_gen
         .generate_template_files(config.include_directives())?;
       code_gen.generate_files(&cpp_ffi_headers)?;
@@ -367,21 +470,16 @@ pub fn exec<T: Iterator<Item = Config>>(configs: T) -> Result<()> {
         cpp_ffi_lib_name: cpp_ffi_lib_name.clone(),
         generator_dependencies: &dependencies,
         write_dependencies_local_paths: config.write_dependencies_local_paths(),
+        settings: config.rust_generator_settings(),
+        built_in_modules: rust_code_generator::default_built_in_modules(),
         cpp_lib_version: config.cpp_lib_version().map(|s| s.into()),
       };
       log::status("Preparing Rust functions");
-      let rust_data = rust_generator::RustGeneratorInputData {
-          cpp_data: &cpp_data,
-          cpp_ffi_headers: cpp_ffi_headers,
-          dependency_types: dependencies
-            .iter()
-            .map(|dep| &dep.rust_export_info.rust_types as &[_])
-            .collect(),
-          crate_name: config.crate_properties().name().clone(),
-          // TODO: more universal prefix removal (#25)
-          remove_qt_prefix: remove_qt_prefix,
-          filtered_namespaces: config.cpp_filtered_namespaces().clone(),
-        }
+      let rust_data = rust_generator_input_data(&config,
+                                                &cpp_data,
+                                                cpp_ffi_headers,
+                                                &dependencies,
+                                                remove_qt_prefix)
         .run()
         .chain_err(|| "Rust data generator failed")?;
       log::status(format!("Generating Rust crate code ({})",
@@ -17,6 +17,7 @@
 
 extern crate rustfmt;
 extern crate tempdir;
+extern crate rayon;
 extern crate regex;
 extern crate clang;
 
@@ -39,6 +40,7 @@ mod cpp_post_processor;
 mod cpp_ffi_data;
 pub mod cpp_method;
 pub mod cpp_type;
+pub mod dry_run;
 mod cpp_operator;
 mod doc_formatter;
 mod launcher;
@@ -2,25 +2,33 @@
 
 use common::errors::{Result, ChainErr, unexpected};
 use common::file_utils::{PathBufWithAdded, copy_recursively, file_to_string, copy_file,
-                         create_file, create_dir_all, read_dir, os_str_to_str, save_toml,
+                         create_file, create_dir_all, read_dir, os_str_to_str, save_toml, save_json,
                          path_to_str, repo_crate_local_path};
 use common::log;
-use rust_generator::RustGeneratorOutput;
+use cpp_ffi_data::{CppFfiArgumentMeaning, CppIndirectionChange};
+use rust_generator::{RustGeneratorOutput, RustItemKind, enum_repr_type, size_const_name,
+                     align_type_name, used_ffi_functions, used_type_names,
+                     ffi_function_type_names};
 use rust_info::{RustTypeDeclarationKind, RustTypeWrapperKind, RustModule, RustMethod,
                 RustMethodArguments, RustMethodArgumentsVariant, RustMethodScope,
                 RustMethodArgument, TraitImpl, TraitImplExtra, RustQtReceiverType, DependencyInfo,
-                RustFFIFunction};
+                RustFFIFunction, RustModuleInventory, RustQtHashTypes, RustVectorInfo,
+                RustIteratorAdapter, RustTypeDeclaration};
 use rust_type::{RustName, RustType, RustTypeIndirection, RustToCTypeConversion, CompleteType};
 use common::string_utils::{JoinWithSeparator, CaseOperations};
 use common::utils::MapIfOk;
 use doc_formatter;
 use std::path::{Path, PathBuf};
+use std::cell::Cell;
+use std::iter::once;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use common::toml;
+use regex::Regex;
 use rustfmt;
 use versions;
 
-use config::CrateProperties;
+use config::{CrateProperties, RustEdition, LinkLibrary, RustGeneratorSettings};
 
 /// Data required for Rust code generation.
 pub struct RustCodeGeneratorConfig<'a> {
@@ -39,6 +47,117 @@ pub struct RustCodeGeneratorConfig<'a> {
   pub generator_dependencies: &'a [DependencyInfo],
   /// As in `Config`.
   pub write_dependencies_local_paths: bool,
+  /// Settings configured in `Config`.
+  pub settings: &'a RustGeneratorSettings,
+  /// Modules declared in `lib.rs` in addition to the generated modules.
+  /// Generated code refers to `ffi` and `type_sizes` modules,
+  /// so `default_built_in_modules()` should always be included.
+  pub built_in_modules: Vec<RustBuiltInModule>,
+}
+
+/// A module of the generated crate that is declared in `lib.rs`
+/// and includes a file generated by the build script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustBuiltInModule {
+  /// Name of the module.
+  pub name: String,
+  /// Attributes of the module declaration (e.g. `"#[allow(dead_code)]"`).
+  pub attributes: Vec<String>,
+  /// Name of the included file in the build script's output directory.
+  pub out_dir_file: String,
+}
+
+/// Returns built-in modules required by the generated code.
+pub fn default_built_in_modules() -> Vec<RustBuiltInModule> {
+  vec![RustBuiltInModule {
+         name: "ffi".to_string(),
+         // some ffi functions are not used because
+         // some Rust methods are filtered
+         attributes: vec!["#[allow(dead_code)]".to_string()],
+         out_dir_file: "ffi.rs".to_string(),
+       },
+       RustBuiltInModule {
+         name: "type_sizes".to_string(),
+         attributes: Vec::new(),
+         out_dir_file: "type_sizes.rs".to_string(),
+       }]
+}
+
+/// Generates a `#[link]` attribute for `library`. If the library
+/// has a target filter, the attribute is wrapped in `#[cfg_attr]`.
+fn link_attribute(library: &LinkLibrary) -> String {
+  let link = format!("link(name = \"{}\", kind = \"{}\")",
+                     library.name,
+                     library.kind.name());
+  match library.target_filter {
+    Some(ref condition) => format!("#[cfg_attr({}, {})]\n", condition.to_cfg(), link),
+    None => format!("#[{}]\n", link),
+  }
+}
+
+/// Returns the only public type of `module` if the module contains
+/// nothing else (helper traits for overloaded methods are not counted).
+/// Such types are re-exported in the parent module if
+/// `Config::set_flatten_single_type_modules` is enabled.
+fn single_public_type(module: &RustModule) -> Option<&RustTypeDeclaration> {
+  if !module.functions.is_empty() || !module.trait_impls.is_empty() ||
+     !module.submodules.is_empty() {
+    return None;
+  }
+  let mut wrappers = module
+    .types
+    .iter()
+    .filter(|t| match t.kind {
+              RustTypeDeclarationKind::CppTypeWrapper { .. } => true,
+              RustTypeDeclarationKind::MethodParametersTrait { .. } => false,
+            });
+  match (wrappers.next(), wrappers.next()) {
+    (Some(type1), None) if type1.is_public => Some(type1),
+    _ => None,
+  }
+}
+
+/// Returns names of traits implemented in `modules` (including
+/// nested modules and implementations attached to types) that should be
+/// re-exported by the `prelude` module of the crate. Traits from `std`
+/// are excluded. If multiple traits have the same name, only the first one
+/// (in alphabetical order of full names) is returned.
+pub fn prelude_traits(modules: &[RustModule]) -> Vec<RustName> {
+  fn add(trait_impl: &TraitImpl, result: &mut Vec<RustName>) {
+    if let RustType::Common { ref base, .. } = trait_impl.trait_type {
+      if base.parts.len() > 1 && base.parts[0] != "std" && !result.contains(base) {
+        result.push(base.clone());
+      }
+    }
+  }
+  fn collect(module: &RustModule, result: &mut Vec<RustName>) {
+    for type1 in &module.types {
+      if let RustTypeDeclarationKind::CppTypeWrapper { ref trait_impls, .. } = type1.kind {
+        for trait_impl in trait_impls {
+          add(trait_impl, result);
+        }
+      }
+    }
+    for trait_impl in &module.trait_impls {
+      add(trait_impl, result);
+    }
+    for submodule in &module.submodules {
+      collect(submodule, result);
+    }
+  }
+
+  let mut names = Vec::new();
+  for module in modules {
+    collect(module, &mut names);
+  }
+  names.sort_by(|a, b| a.parts.cmp(&b.parts));
+  let mut result: Vec<RustName> = Vec::new();
+  for name in names {
+    if !result.iter().any(|x| x.last_name().ok() == name.last_name().ok()) {
+      result.push(name);
+    }
+  }
+  result
 }
 
 /// Generates documentation comments containing
@@ -59,9 +178,110 @@ fn format_doc(doc: &str) -> String {
   }
 }
 
+/// Calls `f` with the full name and the kind of each public item of `module`.
+/// Items of submodules are not included. Trait implementations are named
+/// in `impl Trait for Type` form with types written in absolute form.
+pub fn for_each_module_item<F: FnMut(String, RustItemKind)>(module: &RustModule, mut f: F) {
+  let trait_impl_name = |trait_impl: &TraitImpl| {
+    format!("impl {} for {}",
+            rust_type_to_code(&trait_impl.trait_type, ""),
+            rust_type_to_code(&trait_impl.target_type, ""))
+  };
+  for type1 in module.types.iter().filter(|t| t.is_public) {
+    let type_name = type1.name.parts.join("::");
+    f(type_name.clone(), RustItemKind::Type);
+    if let RustTypeDeclarationKind::CppTypeWrapper {
+             ref methods,
+             ref trait_impls,
+             ..
+           } = type1.kind {
+      for method in methods {
+        if let Some(method_name) = method.name.parts.last() {
+          f(format!("{}::{}", type_name, method_name), RustItemKind::Method);
+        }
+      }
+      for trait_impl in trait_impls {
+        f(trait_impl_name(trait_impl), RustItemKind::TraitImpl);
+      }
+    }
+  }
+  for function in &module.functions {
+    f(function.name.parts.join("::"), RustItemKind::Function);
+  }
+  for trait_impl in &module.trait_impls {
+    f(trait_impl_name(trait_impl), RustItemKind::TraitImpl);
+  }
+}
+
+/// Generates list of public items of `module`. `parent_name` is
+/// the full name of the parent module (or the crate name for top level modules).
+/// Types in trait implementations are written in absolute form.
+pub fn module_inventory(module: &RustModule, parent_name: &str) -> RustModuleInventory {
+  let name = format!("{}::{}", parent_name, module.name);
+  let mut types = Vec::new();
+  let mut methods = Vec::new();
+  let mut trait_impls = Vec::new();
+  for_each_module_item(module, |item_name, kind| match kind {
+    RustItemKind::Type => types.push(item_name),
+    RustItemKind::Function | RustItemKind::Method => methods.push(item_name),
+    RustItemKind::TraitImpl => trait_impls.push(item_name),
+  });
+  RustModuleInventory {
+    submodules: module
+      .submodules
+      .iter()
+      .map(|m| module_inventory(m, &name))
+      .collect(),
+    name: name,
+    types: types,
+    methods: methods,
+    trait_impls: trait_impls,
+  }
+}
+
+/// Returns `#[deprecated]` attribute for `method`,
+/// or an empty string if the method is not deprecated.
+fn deprecated_attribute(method: &RustMethod) -> String {
+  match method.deprecated {
+    Some(ref note) => format!("#[deprecated(note = {:?})]\n", note),
+    None => String::new(),
+  }
+}
+
+/// Generates code of a call of method `name` from `methods` with arguments `args`,
+/// taking overloading emulation into account. Returns `None` if there is no such method.
+fn method_call_code(methods: &[RustMethod], name: &str, args: &[&str]) -> Option<String> {
+  let method = match methods
+          .iter()
+          .find(|m| m.name.last_name().ok().map_or(false, |n| n == name)) {
+    Some(method) => method,
+    None => return None,
+  };
+  let args_code = match method.arguments {
+    RustMethodArguments::SingleVariant(..) => args.join(", "),
+    RustMethodArguments::MultipleVariants { .. } => {
+      match args.len() {
+        0 => "()".to_string(),
+        1 => args[0].to_string(),
+        _ => format!("({})", args.join(", ")),
+      }
+    }
+  };
+  Some(format!("{}({})", name, args_code))
+}
+
 /// Generates Rust code representing type `rust_type` inside crate `crate_name`.
 /// Same as `RustCodeGenerator::rust_type_to_code`, but accessible by other modules.
 pub fn rust_type_to_code(rust_type: &RustType, crate_name: &str) -> String {
+  rust_type_to_code_with_local_prefix(rust_type, crate_name, "::")
+}
+
+/// Same as `rust_type_to_code`, but uses `local_prefix` for paths
+/// to items within crate `crate_name`.
+fn rust_type_to_code_with_local_prefix(rust_type: &RustType,
+                                       crate_name: &str,
+                                       local_prefix: &str)
+                                       -> String {
   match *rust_type {
     RustType::EmptyTuple => "()".to_string(),
     RustType::Common {
@@ -72,13 +292,13 @@ pub fn rust_type_to_code(rust_type: &RustType, crate_name: &str) -> String {
       ref generic_arguments,
       ..
     } => {
-      let mut base_s = base.full_name(Some(crate_name));
+      let mut base_s = base.full_name_with_local_prefix(Some(crate_name), local_prefix);
       if let Some(ref args) = *generic_arguments {
         base_s = format!("{}<{}>",
                          base_s,
                          args
                            .iter()
-                           .map(|x| rust_type_to_code(x, crate_name))
+                           .map(|x| rust_type_to_code_with_local_prefix(x, crate_name, local_prefix))
                            .join(", "));
       }
       match *indirection {
@@ -106,6 +326,12 @@ pub fn rust_type_to_code(rust_type: &RustType, crate_name: &str) -> String {
           let const_text2 = if *is_const2 { "*const " } else { "*mut " };
           format!("{}{}{}", const_text2, const_text1, base_s)
         }
+        RustTypeIndirection::PtrPtrPtr { ref is_const3 } => {
+          let const_text1 = if *is_const { "*const " } else { "*mut " };
+          let const_text2 = if *is_const2 { "*const " } else { "*mut " };
+          let const_text3 = if *is_const3 { "*const " } else { "*mut " };
+          format!("{}{}{}{}", const_text3, const_text2, const_text1, base_s)
+        }
         RustTypeIndirection::PtrRef { ref lifetime } => {
           let const_text1 = if *is_const { "*const " } else { "*mut " };
           let lifetime_text = match *lifetime {
@@ -128,13 +354,20 @@ pub fn rust_type_to_code(rust_type: &RustType, crate_name: &str) -> String {
       format!("extern \"C\" fn({}){}",
               arguments
                 .iter()
-                .map(|arg| rust_type_to_code(arg, crate_name))
+                .map(|arg| rust_type_to_code_with_local_prefix(arg, crate_name, local_prefix))
                 .join(", "),
               match return_type.as_ref() {
                 &RustType::EmptyTuple => String::new(),
-                return_type => format!(" -> {}", rust_type_to_code(return_type, crate_name)),
+                return_type => format!(" -> {}", rust_type_to_code_with_local_prefix(return_type, crate_name, local_prefix)),
               })
     }
+    RustType::Tuple(ref items) => {
+      format!("({})",
+              items
+                .iter()
+                .map(|item| rust_type_to_code_with_local_prefix(item, crate_name, local_prefix))
+                .join(", "))
+    }
   }
 }
 
@@ -164,24 +397,131 @@ pub fn run(config: RustCodeGeneratorConfig, data: &RustGeneratorOutput) -> Resul
   let generator = RustCodeGenerator {
     config: config,
     rustfmt_config: rustfmt_config,
+    errors_module_used: Cell::new(false),
+  };
+  let modules_code = data
+    .modules
+    .iter()
+    .map_if_ok(|module| generator.generate_module_code(module))?;
+  let features = if generator.config.settings.modules_behind_features() {
+    Some(generator.module_features(&data.modules, &data.ffi_functions)?)
+  } else {
+    None
   };
-  generator.generate_template()?;
-  for module in &data.modules {
-    generator.generate_module_file(module)?;
+  // the errors module is only generated if any of its types is used
+  let errors_module = generator.errors_module_used.get();
+  generator.generate_template(features.as_ref())?;
+  for (module, code) in data.modules.iter().zip(&modules_code) {
+    generator.generate_module_file(module, code)?;
   }
   //let mut module_names: Vec<_> = data.modules.iter().map(|x| &x.name).collect();
   //module_names.sort();
-  generator.generate_ffi_file(&data.ffi_functions)?;
-  generator.generate_lib_file(&data.modules)?;
+  let used_ffi_functions = if generator.config.settings.prune_ffi_functions() {
+    Some(&data.used_ffi_functions)
+  } else {
+    None
+  };
+  generator.generate_ffi_file(&data.ffi_functions, used_ffi_functions, features.as_ref())?;
+  if errors_module {
+    generator.generate_errors_file()?;
+  }
+  generator.generate_lib_file(&data.modules, features.as_ref(), errors_module)?;
   Ok(())
 }
 
+/// Cargo features of the generated crate produced when
+/// `Config::set_modules_behind_features` is enabled.
+/// Each top level module has a feature with the same name.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleFeatures {
+  /// Names of all top level modules.
+  modules: Vec<String>,
+  /// Other top level modules referenced by the code of each top level module.
+  dependencies: BTreeMap<String, BTreeSet<String>>,
+  /// Top level modules calling each FFI function.
+  ffi_function_users: HashMap<String, BTreeSet<String>>,
+  /// Top level modules declaring types used in the signature of each FFI function.
+  ffi_function_type_modules: HashMap<String, BTreeSet<String>>,
+}
+
+impl ModuleFeatures {
+  /// Returns attribute enabling an item only if at least one
+  /// of `features` is enabled.
+  fn cfg_attribute(features: &BTreeSet<String>) -> String {
+    if features.len() == 1 {
+      format!("#[cfg(feature = \"{}\")]\n", features.iter().next().unwrap())
+    } else {
+      format!("#[cfg(any({}))]\n",
+              features
+                .iter()
+                .map(|f| format!("feature = \"{}\"", f))
+                .join(", "))
+    }
+  }
+
+  /// Returns attribute for the declaration of top level module `name`.
+  fn module_cfg_attribute(&self, name: &str) -> String {
+    let mut features = BTreeSet::new();
+    features.insert(name.to_string());
+    ModuleFeatures::cfg_attribute(&features)
+  }
+
+  /// Returns attribute for the import of FFI function `name`.
+  /// A function used by modules is enabled if any of them is enabled.
+  /// Other functions are enabled if all modules declaring types of
+  /// their signature are enabled. Returns an empty string if the function
+  /// doesn't depend on any module.
+  fn ffi_function_cfg_attribute(&self, name: &str) -> String {
+    if let Some(features) = self.ffi_function_users.get(name) {
+      return ModuleFeatures::cfg_attribute(features);
+    }
+    match self.ffi_function_type_modules.get(name) {
+      Some(features) if features.len() > 1 => {
+        format!("#[cfg(all({}))]\n",
+                features
+                  .iter()
+                  .map(|f| format!("feature = \"{}\"", f))
+                  .join(", "))
+      }
+      Some(features) if !features.is_empty() => ModuleFeatures::cfg_attribute(features),
+      _ => String::new(),
+    }
+  }
+}
+
+/// Returns the top level module of `crate_name` declaring type `type_name`
+/// if it's one of `modules`.
+fn top_level_module<'a>(type_name: &'a RustName,
+                        crate_name: &str,
+                        modules: &[String])
+                        -> Option<&'a String> {
+  // names of the crate's types start with the crate name
+  // followed by the top level module name
+  if type_name.parts.len() > 2 && type_name.parts[0] == crate_name &&
+     modules.contains(&type_name.parts[1]) {
+    Some(&type_name.parts[1])
+  } else {
+    None
+  }
+}
+
+/// Returns plugin code of `module` and its submodules.
+fn all_plugin_code(module: &RustModule) -> Vec<&String> {
+  let mut result: Vec<_> = module.plugin_code.iter().collect();
+  for submodule in &module.submodules {
+    result.extend(all_plugin_code(submodule));
+  }
+  result
+}
+
 /// Instance of the Rust code generator.
 struct RustCodeGenerator<'a> {
   /// Configuration of the generator.
   config: RustCodeGeneratorConfig<'a>,
   /// Configuration of `rustfmt`.
   rustfmt_config: rustfmt::config::Config,
+  /// Whether generated code refers to the `errors` module.
+  errors_module_used: Cell<bool>,
 }
 
 /// Merges `a` and `b` recursively. `b` take precedence over `a`.
@@ -220,7 +560,7 @@ impl<'a> RustCodeGenerator<'a> {
   /// Generates `Cargo.toml` file and skeleton of the crate.
   /// If a crate template was supplied, files from it are
   /// copied to the output location.
-  pub fn generate_template(&self) -> Result<()> {
+  pub fn generate_template(&self, features: Option<&ModuleFeatures>) -> Result<()> {
     let template_rustfmt_config_path = self
       .config
       .crate_template_path
@@ -280,8 +620,41 @@ impl<'a> RustCodeGenerator<'a> {
                                                                             .crate_properties
                                                                             .version()
                                                                             .clone()));
+                                         // fields present in custom fields are replaced
+                                         // instead of being merged
+                                         let is_custom = |key: &str| {
+                                           self
+                                             .config
+                                             .crate_properties
+                                             .custom_fields()
+                                             .get("package")
+                                             .and_then(|package| package.as_table())
+                                             .map_or(false, |package| package.contains_key(key))
+                                         };
+                                         let authors: Vec<_> = self
+                                           .config
+                                           .crate_properties
+                                           .authors()
+                                           .iter()
+                                           .filter(|author| !author.is_empty())
+                                           .map(|author| toml::Value::String(author.clone()))
+                                           .collect();
+                                         if !authors.is_empty() && !is_custom("authors") {
+                                           table.insert("authors".to_string(),
+                                                        toml::Value::Array(authors));
+                                         }
+                                         let license = self.config.crate_properties.license();
+                                         if !license.is_empty() && !is_custom("license") {
+                                           table.insert("license".to_string(),
+                                                        toml::Value::String(license.clone()));
+                                         }
                                          table.insert("build".to_string(),
                                                       toml::Value::String("build.rs".to_string()));
+                                         if self.config.settings.rust_edition() ==
+                                            RustEdition::Edition2018 {
+                                           table.insert("edition".to_string(),
+                                                        toml::Value::String("2018".to_string()));
+                                         }
                                          table
                                        });
       let dep_value = |version: &str, local_path: Option<PathBuf>| -> Result<toml::Value> {
@@ -354,6 +727,24 @@ impl<'a> RustCodeGenerator<'a> {
       table.insert("package".to_string(), package);
       table.insert("dependencies".to_string(), dependencies);
       table.insert("build-dependencies".to_string(), build_dependencies);
+      if let Some(features) = features {
+        let mut features_table = toml::Table::new();
+        // all modules are available unless default features are disabled
+        features_table.insert("default".to_string(),
+                              toml::Value::Array(features
+                                                   .modules
+                                                   .iter()
+                                                   .map(|m| toml::Value::String(m.clone()))
+                                                   .collect()));
+        for (module, dependencies) in &features.dependencies {
+          features_table.insert(module.clone(),
+                                toml::Value::Array(dependencies
+                                                     .iter()
+                                                     .map(|m| toml::Value::String(m.clone()))
+                                                     .collect()));
+        }
+        table.insert("features".to_string(), toml::Value::Table(features_table));
+      }
       recursive_merge_toml(toml::Value::Table(table),
                            toml::Value::Table(self.config.crate_properties.custom_fields().clone()))
     };
@@ -375,7 +766,34 @@ impl<'a> RustCodeGenerator<'a> {
 
   /// Generates Rust code representing type `rust_type`.
   fn rust_type_to_code(&self, rust_type: &RustType) -> String {
-    rust_type_to_code(rust_type, &self.config.crate_properties.name())
+    rust_type_to_code_with_local_prefix(rust_type,
+                                        &self.config.crate_properties.name(),
+                                        self.local_prefix())
+  }
+
+  /// Returns prefix of paths to items within the generated crate.
+  fn local_prefix(&self) -> &'static str {
+    match self.config.settings.rust_edition() {
+      RustEdition::Edition2015 => "::",
+      RustEdition::Edition2018 => "crate::",
+    }
+  }
+
+  /// Returns path to error type `name` exported by `cpp_utils`
+  /// or re-exported by the `errors` module (see `Config::set_errors_module`).
+  fn error_type_path(&self, name: &str) -> String {
+    if self.config.settings.errors_module() {
+      self.errors_module_used.set(true);
+      format!("{}errors::{}", self.local_prefix(), name)
+    } else {
+      format!("::cpp_utils::{}", name)
+    }
+  }
+
+  /// Generates full path to `name` usable within the generated crate.
+  fn full_name(&self, name: &RustName) -> String {
+    name.full_name_with_local_prefix(Some(&self.config.crate_properties.name()),
+                                     self.local_prefix())
   }
 
   /// Generates Rust code containing declaration of a FFI function `func`.
@@ -417,6 +835,39 @@ impl<'a> RustCodeGenerator<'a> {
     if type1.rust_api_to_c_conversion == RustToCTypeConversion::None {
       return Ok(expression);
     }
+    if type1.rust_api_to_c_conversion == RustToCTypeConversion::CharPtrToCStr {
+      // returned pointer may be null
+      return Ok(format!("{{\nlet ffi_result = {};\n\
+                         if ffi_result.is_null() {{\n\
+                         None\n\
+                         }} else {{\n\
+                         Some({unsafe_start}::std::ffi::CStr::from_ptr(ffi_result){unsafe_end})\n\
+                         }}\n\
+                         }}",
+                        expression,
+                        unsafe_start = unsafe_start,
+                        unsafe_end = unsafe_end));
+    }
+    if type1.rust_api_to_c_conversion == RustToCTypeConversion::WCharPtrToOsString {
+      // returned pointer may be null
+      return Ok(format!("{{\nlet ffi_result = {};\n\
+                         if ffi_result.is_null() {{\n\
+                         None\n\
+                         }} else {{\n\
+                         let mut len = 0;\n\
+                         while {unsafe_start}*ffi_result.offset(len){unsafe_end} != 0 {{\n\
+                         len += 1;\n\
+                         }}\n\
+                         let slice = {unsafe_start}::std::slice::from_raw_parts(ffi_result, \
+                         len as usize){unsafe_end};\n\
+                         Some(<::std::ffi::OsString as \
+                         ::std::os::windows::ffi::OsStringExt>::from_wide(slice))\n\
+                         }}\n\
+                         }}",
+                        expression,
+                        unsafe_start = unsafe_start,
+                        unsafe_end = unsafe_end));
+    }
 
     let (code1, source_expr) = if use_ffi_result_var {
       (format!("let ffi_result = {};\n", expression), "ffi_result".to_string())
@@ -426,7 +877,8 @@ impl<'a> RustCodeGenerator<'a> {
     let code2 = match type1.rust_api_to_c_conversion {
       RustToCTypeConversion::None => unreachable!(),
       RustToCTypeConversion::RefToPtr |
-      RustToCTypeConversion::OptionRefToPtr => {
+      RustToCTypeConversion::OptionRefToPtr |
+      RustToCTypeConversion::ResultRefToPtr => {
         let api_is_const = if type1.rust_api_to_c_conversion ==
                               RustToCTypeConversion::OptionRefToPtr {
           if let RustType::Common { ref generic_arguments, .. } = type1.rust_api_type {
@@ -440,12 +892,25 @@ impl<'a> RustCodeGenerator<'a> {
           } else {
             return Err("Option type expected".into());
           }
+        } else if type1.rust_api_to_c_conversion == RustToCTypeConversion::ResultRefToPtr {
+          if let RustType::Common { ref generic_arguments, .. } = type1.rust_api_type {
+            let args = generic_arguments
+              .as_ref()
+              .chain_err(|| "Result with no generic_arguments")?;
+            if args.len() != 2 {
+              return Err("Result with invalid args count".into());
+            }
+            args[0].last_is_const()?
+          } else {
+            return Err("Result type expected".into());
+          }
         } else {
           type1.rust_api_type.last_is_const()?
         };
         let unwrap_code = match type1.rust_api_to_c_conversion {
           RustToCTypeConversion::RefToPtr => ".expect(\"Attempted to convert null pointer to reference\")",
           RustToCTypeConversion::OptionRefToPtr => "",
+          RustToCTypeConversion::ResultRefToPtr => ".ok_or_else(::std::default::Default::default)",
           _ => unreachable!(),
         };
         format!("{unsafe_start}{}.{}(){unsafe_end}{}",
@@ -462,7 +927,8 @@ impl<'a> RustCodeGenerator<'a> {
                 unsafe_end = unsafe_end)
       }
       RustToCTypeConversion::CppBoxToPtr => {
-        format!("{unsafe_start}::cpp_utils::CppBox::new({}){unsafe_end}",
+        format!("{unsafe_start}{}::new({}){unsafe_end}",
+                self.full_name(self.config.settings.box_type()),
                 source_expr,
                 unsafe_start = unsafe_start,
                 unsafe_end = unsafe_end)
@@ -478,6 +944,41 @@ impl<'a> RustCodeGenerator<'a> {
                 self.rust_type_to_code(&qflags_type),
                 source_expr)
       }
+      RustToCTypeConversion::CharPtrToCStr |
+      RustToCTypeConversion::WCharPtrToOsString => unreachable!(),
+      RustToCTypeConversion::OptionToPtr => {
+        return Err(unexpected("OptionToPtr must be handled in generate_ffi_call").into());
+      }
+      RustToCTypeConversion::ToCppRefToPtr => {
+        return Err(unexpected("ToCppRefToPtr is not supported for return types").into());
+      }
+      RustToCTypeConversion::RefToSelf => {
+        return Err(unexpected("RefToSelf must be handled in generate_ffi_call").into());
+      }
+      RustToCTypeConversion::MaybeOwnedToPtr => {
+        return Err(unexpected("MaybeOwnedToPtr must be handled in generate_ffi_call").into());
+      }
+      RustToCTypeConversion::OutValueToPtr => {
+        return Err(unexpected("OutValueToPtr is not supported for return types").into());
+      }
+      RustToCTypeConversion::QStringToString => {
+        if type1.cpp_to_ffi_conversion == CppIndirectionChange::ValueToPointer {
+          // the caller owns the returned object, so it's deleted after the conversion
+          format!("{{\nlet object = {unsafe_start}{}::new({}){unsafe_end};\n\
+                   ::std::string::String::from({unsafe_start}&*object.as_ptr(){unsafe_end})\n\
+                   }}",
+                  self.full_name(self.config.settings.box_type()),
+                  source_expr,
+                  unsafe_start = unsafe_start,
+                  unsafe_end = unsafe_end)
+        } else {
+          format!("::std::string::String::from({unsafe_start}{}.as_ref(){unsafe_end}\
+                   .expect(\"Attempted to convert null pointer to reference\"))",
+                  source_expr,
+                  unsafe_start = unsafe_start,
+                  unsafe_end = unsafe_end)
+        }
+      }
     };
     Ok(code1 + &code2)
   }
@@ -495,6 +996,38 @@ impl<'a> RustCodeGenerator<'a> {
     } else {
       ("unsafe { ", " }")
     };
+    if let Some(ref check_function) = variant.validity_check_function {
+      // the object is created as usual and checked before returning
+      let mut unchecked_variant = variant.clone();
+      unchecked_variant.validity_check_function = None;
+      unchecked_variant.return_type.rust_api_type = match variant.return_type.rust_api_type {
+        RustType::Common { generic_arguments: Some(ref args), .. } if args.len() == 2 => {
+          args[0].clone()
+        }
+        _ => return Err(unexpected("Result type expected for a fallible constructor").into()),
+      };
+      let object_ptr = if variant.return_type.rust_api_to_c_conversion ==
+                          RustToCTypeConversion::CppBoxToPtr {
+        "object.as_ptr()"
+      } else {
+        "&object as *const _"
+      };
+      return Ok(format!("{{\nlet object = {{\n{}\n}};\n\
+                         if {unsafe_start}{local_prefix}ffi::{}({}){unsafe_end} {{\n\
+                         Ok(object)\n\
+                         }} else {{\n\
+                         Err({})\n\
+                         }}\n}}",
+                        self.generate_ffi_call(&unchecked_variant,
+                                               shared_arguments,
+                                               in_unsafe_context)?,
+                        check_function,
+                        object_ptr,
+                        self.error_type_path("InvalidObjectError"),
+                        local_prefix = self.local_prefix(),
+                        unsafe_start = unsafe_start,
+                        unsafe_end = unsafe_end));
+    }
     let mut final_args = Vec::new();
     final_args.resize(variant.cpp_method.c_signature.arguments.len(), None);
     let mut all_args: Vec<RustMethodArgument> = Vec::from(shared_arguments);
@@ -509,6 +1042,17 @@ impl<'a> RustCodeGenerator<'a> {
         RustToCTypeConversion::OptionRefToPtr => {
           return Err("OptionRefToPtr is not supported here yet".into());
         }
+        RustToCTypeConversion::ResultRefToPtr => {
+          return Err(unexpected("ResultRefToPtr is not supported for arguments").into());
+        }
+        RustToCTypeConversion::OutValueToPtr => {
+          code = format!("&mut {}", code);
+        }
+        RustToCTypeConversion::RefToSelf |
+        RustToCTypeConversion::MaybeOwnedToPtr |
+        RustToCTypeConversion::QStringToString => {
+          return Err(unexpected("return type conversion is not supported for arguments").into());
+        }
         RustToCTypeConversion::RefToPtr => {
           if arg.argument_type.rust_api_type.is_const()? &&
              !arg.argument_type.rust_ffi_type.is_const()? {
@@ -554,10 +1098,41 @@ impl<'a> RustCodeGenerator<'a> {
         RustToCTypeConversion::QFlagsToUInt => {
           code = format!("{}.to_int() as ::libc::c_uint", code);
         }
+        RustToCTypeConversion::OptionToPtr => {
+          // the pointer refers to the argument itself, so it's valid during the call
+          code = format!("match {} {{ Some(ref value) => value as {t}, \
+                          None => ::std::ptr::null() }}",
+                         code,
+                         t = self.rust_type_to_code(&arg.argument_type.rust_ffi_type));
+        }
+        RustToCTypeConversion::CharPtrToCStr => {
+          code = format!("{}.map_or(::std::ptr::null(), |s| s.as_ptr())", code);
+        }
+        RustToCTypeConversion::WCharPtrToOsString => {
+          // the temporary null-terminated buffer lives until the end of the call
+          code = format!("{}.map(|s| ::std::os::windows::ffi::OsStrExt::encode_wide(s)\
+                          .chain(::std::iter::once(0))\
+                          .collect::<Vec<u16>>())\
+                          .as_ref()\
+                          .map_or(::std::ptr::null(), |v| v.as_ptr())",
+                         code);
+        }
+        RustToCTypeConversion::ToCppRefToPtr => {
+          // the holder of a temporary object lives until the end of the call
+          code = format!("&*::cpp_utils::ToCppRef::to_cpp_ref({}) as {}",
+                         code,
+                         self.rust_type_to_code(&arg.argument_type.rust_ffi_type));
+        }
       }
       final_args[arg.ffi_index] = Some(code);
     }
 
+    let out_args: Vec<_> = all_args
+      .iter()
+      .filter(|arg| {
+                arg.argument_type.rust_api_to_c_conversion == RustToCTypeConversion::OutValueToPtr
+              })
+      .collect();
     let mut result = Vec::new();
     let mut maybe_result_var_name = None;
     if let Some(ref i) = variant.return_type_ffi_index {
@@ -583,38 +1158,171 @@ impl<'a> RustCodeGenerator<'a> {
         } else {
           return Err(unexpected("CppBox type expected").into());
         }
+      } else if variant.return_type.rust_api_to_c_conversion ==
+                RustToCTypeConversion::QStringToString {
+        // the object is created on the stack and converted after the call
+        let mut qstring_type = variant.return_type.rust_ffi_type.clone();
+        if let RustType::Common {
+                 ref mut indirection,
+                 ref mut is_const,
+                 ..
+               } = qstring_type {
+          *indirection = RustTypeIndirection::None;
+          *is_const = false;
+        } else {
+          return Err(unexpected("QString type expected").into());
+        }
+        self.rust_type_to_code(&qstring_type)
+      } else if variant.return_type.rust_api_to_c_conversion ==
+                RustToCTypeConversion::OptionToPtr {
+        if let RustType::Common { ref generic_arguments, .. } = variant.return_type.rust_api_type {
+          let arg = generic_arguments
+            .as_ref()
+            .and_then(|args| args.get(0))
+            .chain_err(|| "Option must have a generic argument")?;
+          self.rust_type_to_code(arg)
+        } else {
+          return Err(unexpected("Option type expected").into());
+        }
       } else {
         self.rust_type_to_code(&variant.return_type.rust_api_type)
       };
-      result.push(format!("{{\nlet mut {var}: {t} = {unsafe_start}\
-                           ::cpp_utils::new_uninitialized::NewUninitialized::new_uninitialized()\
-                           {unsafe_end};\n",
-                          var = return_var_name,
-                          t = struct_name,
-                          unsafe_start = unsafe_start,
-                          unsafe_end = unsafe_end));
-      final_args[*i as usize] = Some(format!("&mut {}", return_var_name));
+      let is_option = variant.return_type.rust_api_to_c_conversion ==
+                      RustToCTypeConversion::OptionToPtr;
+      if is_option {
+        // the value is only read if the FFI function has written it
+        result.push(format!("{{\nlet mut {var}: ::std::mem::MaybeUninit<{t}> = \
+                             ::std::mem::MaybeUninit::uninit();\n",
+                            var = return_var_name,
+                            t = struct_name));
+      } else {
+        result.push(format!("{{\nlet mut {var}: {t} = {unsafe_start}\
+                             ::cpp_utils::new_uninitialized::NewUninitialized::new_uninitialized()\
+                             {unsafe_end};\n",
+                            var = return_var_name,
+                            t = struct_name,
+                            unsafe_start = unsafe_start,
+                            unsafe_end = unsafe_end));
+      }
+      if self.config.settings.debug_size_assertions() &&
+         variant.cpp_method.cpp_method.is_constructor() &&
+         variant.return_type.rust_api_to_c_conversion == RustToCTypeConversion::ValueToPtr {
+        result.push(self.size_debug_assertion(&variant.return_type.rust_ffi_type));
+      }
+      final_args[*i as usize] = Some(if is_option {
+                                       format!("{}.as_mut_ptr()", return_var_name)
+                                     } else {
+                                       format!("&mut {}", return_var_name)
+                                     });
       maybe_result_var_name = Some(return_var_name);
     }
     let final_args = final_args
       .into_iter()
       .map_if_ok(|x| x.chain_err(|| "ffi argument is missing"))?;
 
-    result.push(format!("{unsafe_start}::ffi::{}({}){maybe_semicolon}{unsafe_end}",
+    if variant.return_type.rust_api_to_c_conversion == RustToCTypeConversion::MaybeOwnedToPtr {
+      // the object is owned by the caller only if the passed parent is null
+      let cpp_arguments = &variant.cpp_method.cpp_method.arguments;
+      let parent_index = variant
+        .cpp_method
+        .c_signature
+        .arguments
+        .iter()
+        .position(|arg| match arg.meaning {
+                    CppFfiArgumentMeaning::Argument(i) => {
+                      cpp_arguments.get(i as usize).map_or(false, |x| x.name == "parent")
+                    }
+                    _ => false,
+                  })
+        .chain_err(|| unexpected("parented constructor must have a parent argument"))?;
+      if maybe_result_var_name.is_some() || !out_args.is_empty() {
+        return Err(unexpected("parented constructor must return a pointer").into());
+      }
+      let mut final_args = final_args;
+      let parent_code = ::std::mem::replace(&mut final_args[parent_index],
+                                            "parent_ptr".to_string());
+      return Ok(format!("{{\nlet parent_ptr = {};\n\
+                         {unsafe_start}::cpp_utils::MaybeOwned::new(\
+                         {local_prefix}ffi::{}({}), parent_ptr.is_null()){unsafe_end}\n}}",
+                        parent_code,
+                        variant.cpp_method.c_name,
+                        final_args.join(", "),
+                        local_prefix = self.local_prefix(),
+                        unsafe_start = unsafe_start,
+                        unsafe_end = unsafe_end));
+    }
+
+    let is_optional_return = maybe_result_var_name.is_some() &&
+                             variant.return_type.rust_api_to_c_conversion ==
+                             RustToCTypeConversion::OptionToPtr;
+    result.push(format!("{maybe_if}{unsafe_start}{local_prefix}ffi::{}({}){maybe_semicolon}\
+                         {unsafe_end}",
                         variant.cpp_method.c_name,
                         final_args.join(", "),
-                        maybe_semicolon = if maybe_result_var_name.is_some() {
+                        maybe_if = if is_optional_return { "if " } else { "" },
+                        maybe_semicolon = if maybe_result_var_name.is_some() &&
+                                             !is_optional_return {
                           ";"
                         } else {
                           ""
                         },
+                        local_prefix = self.local_prefix(),
                         unsafe_start = unsafe_start,
                         unsafe_end = unsafe_end));
     if let Some(ref name) = maybe_result_var_name {
-      result.push(format!("{}\n}}", name));
+      if is_optional_return {
+        // the FFI function returns whether the value is present
+        result.push(format!(" {{ Some({unsafe_start}{name}.assume_init(){unsafe_end}) }} \
+                             else {{ None }}\n}}",
+                            name = name,
+                            unsafe_start = unsafe_start,
+                            unsafe_end = unsafe_end));
+      } else if variant.return_type.rust_api_to_c_conversion ==
+                RustToCTypeConversion::QStringToString {
+        result.push(format!("::std::string::String::from(&{})\n}}", name));
+      } else {
+        result.push(format!("{}\n}}", name));
+      }
     }
     let code = result.join("");
-    if maybe_result_var_name.is_none() {
+    if !out_args.is_empty() {
+      // out-parameters are written to local variables and returned
+      // together with the original return value
+      let declarations: Vec<_> = out_args
+        .iter()
+        .map(|arg| {
+               format!("let mut {}: {} = Default::default();\n",
+                       arg.name,
+                       self.rust_type_to_code(&arg.argument_type.rust_api_type))
+             })
+        .collect();
+      let mut items = Vec::new();
+      let call = if variant.return_type.rust_ffi_type == RustType::EmptyTuple {
+        format!("{};\n", code)
+      } else {
+        items.push("ffi_result".to_string());
+        format!("let ffi_result = {};\n", code)
+      };
+      items.extend(out_args.iter().map(|arg| arg.name.clone()));
+      let value = if items.len() == 1 {
+        items[0].clone()
+      } else {
+        format!("({})", items.join(", "))
+      };
+      return Ok(format!("{}{}{}", declarations.join(""), call, value));
+    }
+    if variant.return_type.rust_api_to_c_conversion == RustToCTypeConversion::RefToSelf {
+      // the C++ method returns `*this`, so the receiver is returned
+      // instead of converting the pointer to a new reference
+      let self_arg = all_args
+        .iter()
+        .find(|arg| {
+                variant.cpp_method.c_signature.arguments[arg.ffi_index].meaning ==
+                CppFfiArgumentMeaning::This
+              })
+        .chain_err(|| "chainable setter must have a self argument")?;
+      Ok(format!("{};\n{}", code, self_arg.name))
+    } else if maybe_result_var_name.is_none() {
       self.convert_type_from_ffi(&variant.return_type, code, in_unsafe_context, true)
     } else {
       Ok(code)
@@ -625,6 +1333,9 @@ impl<'a> RustCodeGenerator<'a> {
   fn arg_texts(&self, args: &[RustMethodArgument], lifetime: Option<&String>) -> Vec<String> {
     args
       .iter()
+      .filter(|arg| {
+                arg.argument_type.rust_api_to_c_conversion != RustToCTypeConversion::OutValueToPtr
+              })
       .map(|arg| if &arg.name == "self" {
              let self_type = match lifetime {
                Some(lifetime) => {
@@ -667,9 +1378,17 @@ impl<'a> RustCodeGenerator<'a> {
                }
              }
 
-             format!("{}{}: {}",
+             // string class arguments accept any type implementing `ToCppRef`
+             let maybe_impl = if arg.argument_type.rust_api_to_c_conversion ==
+                                 RustToCTypeConversion::ToCppRefToPtr {
+               "impl "
+             } else {
+               ""
+             };
+             format!("{}{}: {}{}",
                      maybe_mut_declaration,
                      arg.name,
+                     maybe_impl,
                      match lifetime {
                        Some(lifetime) => {
                          self.rust_type_to_code(&arg
@@ -702,11 +1421,16 @@ impl<'a> RustCodeGenerator<'a> {
         format!(" -> {}",
                 self.rust_type_to_code(&variant.return_type.rust_api_type))
       };
-      let all_lifetimes: Vec<_> = variant
-        .arguments
-        .iter()
-        .filter_map(|x| x.argument_type.rust_api_type.lifetime())
-        .collect();
+      // lifetimes of trait implementation methods are declared by the impl
+      let all_lifetimes: Vec<_> = if func.scope == RustMethodScope::TraitImpl {
+        Vec::new()
+      } else {
+        variant
+          .arguments
+          .iter()
+          .filter_map(|x| x.argument_type.rust_api_type.lifetime())
+          .collect()
+      };
       let lifetimes_text = if all_lifetimes.is_empty() {
         String::new()
       } else {
@@ -717,9 +1441,19 @@ impl<'a> RustCodeGenerator<'a> {
                   .join(", "))
       };
 
-      format!("{doc}{maybe_pub}{maybe_unsafe}fn {name}{lifetimes_text}({args}){return_type} \
-                 {{\n{body}}}\n\n",
+      format!("{doc}{maybe_cfg}{maybe_deprecated}{maybe_inline}{maybe_pub}{maybe_unsafe}\
+                 fn {name}{lifetimes_text}({args}){return_type} {{\n{body}}}\n\n",
               doc = format_doc(&doc_formatter::method_doc(&func)),
+              maybe_cfg = match func.cfg {
+                Some(ref cfg) => format!("#[cfg({})]\n", cfg),
+                None => String::new(),
+              },
+              maybe_deprecated = deprecated_attribute(func),
+              maybe_inline = if self.config.settings.inline_wrappers() {
+                "#[inline]\n"
+              } else {
+                ""
+              },
               maybe_pub = maybe_pub,
               maybe_unsafe = maybe_unsafe,
               lifetimes_text = lifetimes_text,
@@ -736,6 +1470,9 @@ impl<'a> RustCodeGenerator<'a> {
            ref variant_argument_name,
            ..
          } => {
+      if func.cfg.is_some() {
+        return Err(unexpected("cfg is not supported for overloaded methods").into());
+      }
       let tpl_type = variant_argument_name.to_class_case();
       let body = format!("{}.exec({})",
                          variant_argument_name,
@@ -771,6 +1508,7 @@ impl<'a> RustCodeGenerator<'a> {
       };
       format!(include_str!("../templates/crate/overloaded_function.rs.in"),
               doc = format_doc(&doc_formatter::method_doc(&func)),
+              maybe_deprecated = deprecated_attribute(func),
               maybe_pub = maybe_pub,
               maybe_unsafe = maybe_unsafe,
               tpl_decl = tpl_decl,
@@ -785,34 +1523,110 @@ impl<'a> RustCodeGenerator<'a> {
        })
   }
 
+  /// Determines Cargo features for top level `modules` based on
+  /// the types and FFI functions used by their items and plugin code.
+  /// FFI functions not called by any module are gated by the modules
+  /// of the types in their signatures.
+  fn module_features(&self,
+                     modules: &[RustModule],
+                     ffi_functions: &[(String, Vec<RustFFIFunction>)])
+                     -> Result<ModuleFeatures> {
+    let mut features = ModuleFeatures::default();
+    features.modules = modules.iter().map(|m| m.name.clone()).collect();
+    if features.modules.iter().any(|m| m == "default") {
+      return Err("Module 'default' conflicts with the default Cargo feature".into());
+    }
+    let crate_name = self.config.crate_properties.name();
+    // plugin code and raw trait implementations are only available as text
+    let module_path_regex = Regex::new(&format!(r"(?:^|[^\w:]){}(\w+)::", self.local_prefix()))?;
+    let ffi_call_regex = Regex::new(&format!(r"(?:^|[^\w:]){}ffi::(\w+)", self.local_prefix()))?;
+    for module in modules {
+      let mut dependencies = BTreeSet::new();
+      for type_name in used_type_names(module) {
+        if let Some(name) = top_level_module(&type_name, crate_name, &features.modules) {
+          if name != &module.name {
+            dependencies.insert(name.clone());
+          }
+        }
+      }
+      let mut used_functions = used_ffi_functions(once(module));
+      for code in all_plugin_code(module) {
+        for captures in module_path_regex.captures_iter(code) {
+          if let Some(name) = captures.at(1) {
+            if name != module.name && features.modules.iter().any(|m| m == name) {
+              dependencies.insert(name.to_string());
+            }
+          }
+        }
+        for captures in ffi_call_regex.captures_iter(code) {
+          if let Some(name) = captures.at(1) {
+            used_functions.insert(name.to_string());
+          }
+        }
+      }
+      features
+        .dependencies
+        .insert(module.name.clone(), dependencies);
+      for name in used_functions {
+        features
+          .ffi_function_users
+          .entry(name)
+          .or_insert_with(BTreeSet::new)
+          .insert(module.name.clone());
+      }
+    }
+    for &(_, ref functions) in ffi_functions {
+      for function in functions {
+        let type_modules: BTreeSet<_> = ffi_function_type_names(function)
+          .iter()
+          .filter_map(|name| top_level_module(name, crate_name, &features.modules))
+          .cloned()
+          .collect();
+        if !type_modules.is_empty() {
+          features
+            .ffi_function_type_modules
+            .insert(function.name.clone(), type_modules);
+        }
+      }
+    }
+    Ok(features)
+  }
+
   /// Generates `lib.rs` file.
   #[cfg_attr(feature="clippy", allow(collapsible_if))]
-  pub fn generate_lib_file(&self, modules: &[RustModule]) -> Result<()> {
+  pub fn generate_lib_file(&self,
+                           modules: &[RustModule],
+                           features: Option<&ModuleFeatures>,
+                           errors_module: bool)
+                           -> Result<()> {
     let mut code = String::new();
 
 
-    code.push_str("pub extern crate libc;\n");
-    code.push_str("pub extern crate cpp_utils;\n\n");
+    // dependencies are available without `extern crate` in Rust 2018,
+    // but they are still re-exported for the crate's users
+    let reexport = match self.config.settings.rust_edition() {
+      RustEdition::Edition2015 => "pub extern crate",
+      RustEdition::Edition2018 => "pub use",
+    };
+    code.push_str(&format!("{} libc;\n", reexport));
+    code.push_str(&format!("{} cpp_utils;\n\n", reexport));
     for dep in self.config.generator_dependencies {
-      code.push_str(&format!("pub extern crate {};\n\n", &dep.rust_export_info.crate_name));
+      code.push_str(&format!("{} {};\n\n", reexport, &dep.rust_export_info.crate_name));
     }
 
-    // some ffi functions are not used because
-    // some Rust methods are filtered
-    code.push_str("\
-      #[allow(dead_code)]\nmod ffi { \ninclude!(concat!(env!(\"OUT_DIR\"), \
-              \"/ffi.rs\")); \n}\n\n");
-    code.push_str("\
-      mod type_sizes { \ninclude!(concat!(env!(\"OUT_DIR\"), \
-              \"/type_sizes.rs\")); \n}\n\n");
-
-    for name in &["ffi", "type_sizes"] {
-      if modules.iter().any(|x| &x.name.as_str() == name) {
+    for built_in_module in &self.config.built_in_modules {
+      if modules.iter().any(|x| x.name == built_in_module.name) {
         return Err(format!("Automatically generated module '{}' conflicts with a mandatory \
                             module",
-                           name)
+                           built_in_module.name)
                        .into());
       }
+      for attribute in &built_in_module.attributes {
+        code.push_str(&format!("{}\n", attribute));
+      }
+      code.push_str(&format!("mod {} {{\ninclude!(concat!(env!(\"OUT_DIR\"), \"/{}\"));\n}}\n\n",
+                             built_in_module.name,
+                             built_in_module.out_dir_file));
     }
     for name in &["lib", "main"] {
       if modules.iter().any(|x| &x.name.as_str() == name) {
@@ -822,13 +1636,60 @@ impl<'a> RustCodeGenerator<'a> {
       }
     }
 
+    // nested modules are declared inline in the files of their parent modules
+    // (see `generate_module_code`), so only top level modules are declared here
+    let module_cfg = |name: &str| {
+      features
+        .map(|features| features.module_cfg_attribute(name))
+        .unwrap_or_default()
+    };
     for module in modules {
       let doc = module
         .doc
         .as_ref()
         .map(|d| format_doc(d))
         .unwrap_or_default();
-      code.push_str(&format!("{}pub mod {};\n", doc, &module.name));
+      code.push_str(&format!("{}{}pub mod {};\n", doc, module_cfg(&module.name), &module.name));
+    }
+    if self.config.settings.flatten_single_type_modules() {
+      for module in modules {
+        if let Some(type1) = single_public_type(module) {
+          code.push_str(&format!("{}pub use self::{}::{};\n",
+                                 module_cfg(&module.name),
+                                 module.name,
+                                 type1.name.last_name()?));
+        }
+      }
+    }
+
+    if errors_module {
+      if modules.iter().any(|x| x.name == "errors") {
+        return Err("Automatically generated module 'errors' conflicts with \
+                    the errors module"
+                       .into());
+      }
+      code.push_str("/// Error types returned by fallible operations of this crate.\n");
+      code.push_str("pub mod errors;\n");
+    }
+
+    let prelude_traits = prelude_traits(modules);
+    if !prelude_traits.is_empty() {
+      if modules.iter().any(|x| x.name == "prelude") {
+        return Err("Automatically generated module 'prelude' conflicts with \
+                    the prelude module"
+                       .into());
+      }
+      code.push_str(&format_doc(&doc_formatter::prelude_module_doc(&self.config
+                                                                      .crate_properties
+                                                                      .name())));
+      code.push_str("pub mod prelude {\n");
+      for name in &prelude_traits {
+        if name.parts.len() > 2 && &name.parts[0] == self.config.crate_properties.name() {
+          code.push_str(&module_cfg(&name.parts[1]));
+        }
+        code.push_str(&format!("pub use {};\n", self.full_name(name)));
+      }
+      code.push_str("}\n");
     }
 
     let src_path = self.config.output_path.with_added("src");
@@ -849,18 +1710,174 @@ impl<'a> RustCodeGenerator<'a> {
         .map(|t| format!("type {} = {};", t.name, self.rust_type_to_code(&t.value)))
         .join("\n");
 
-      let trait_content = if let Some(TraitImplExtra::CppDeletable { ref deleter_name }) =
-        trait1.extra {
-        format!("fn deleter() -> ::cpp_utils::Deleter<Self> {{\n  ::ffi::{}\n}}\n",
-                deleter_name)
+      let trait_content = match trait1.extra {
+        Some(TraitImplExtra::CppDeletable { ref deleter_name }) => {
+          format!("fn deleter() -> ::cpp_utils::Deleter<Self> {{\n  {}ffi::{}\n}}\n",
+                  self.local_prefix(),
+                  deleter_name)
+        }
+        Some(TraitImplExtra::QtHashFunction {
+               ref ffi_function_name,
+               ref has_seed,
+             }) => {
+          let (seed_declaration, seed_arg) = if *has_seed {
+            ("let seed = state.finish() as ::libc::c_uint;\n", ", seed")
+          } else {
+            ("", "")
+          };
+          format!("fn hash<H: ::std::hash::Hasher>(&self, state: &mut H) {{\n\
+                   {}\
+                   let value = unsafe {{ {}ffi::{}(self as *const Self{}) }};\n\
+                   state.write_u64(value as u64);\n\
+                   }}\n",
+                  seed_declaration,
+                  self.local_prefix(),
+                  ffi_function_name,
+                  seed_arg)
+        }
+        Some(TraitImplExtra::Debug { ref to_string_call }) => {
+          let body = match *to_string_call {
+            Some(ref call) => format!("::std::fmt::Debug::fmt(&self.{}, f)", call),
+            None => {
+              format!("write!(f, \"{}({{:p}})\", self as *const Self)",
+                      self.rust_type_to_code(&trait1.target_type))
+            }
+          };
+          format!("fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {{\n\
+                   {}\n\
+                   }}\n",
+                  body)
+        }
+        Some(TraitImplExtra::Display { ref call }) => {
+          format!("fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {{\n\
+                   ::std::fmt::Display::fmt(&self.{}, f)\n\
+                   }}\n",
+                  call)
+        }
+        Some(TraitImplExtra::DynamicCastTryFrom { ref uses_cast_result }) => {
+          let from_type = match trait1.trait_type {
+            RustType::Common { generic_arguments: Some(ref args), .. } if args.len() == 1 => {
+              &args[0]
+            }
+            _ => return Err(unexpected("TryFrom: one generic argument expected").into()),
+          };
+          let cast_code = if *uses_cast_result {
+            "::cpp_utils::DynamicCastResult::dynamic_cast(value)".to_string()
+          } else {
+            format!("::cpp_utils::DynamicCast::dynamic_cast(value).ok_or({})",
+                    self.error_type_path("CastError"))
+          };
+          format!("fn try_from(value: {}) -> ::std::result::Result<Self, Self::Error> {{\n\
+                   {}\n\
+                   }}\n",
+                  self.rust_type_to_code(from_type),
+                  cast_code)
+        }
+        Some(TraitImplExtra::NarrowingConversionTryFrom { ref ffi_function_name }) => {
+          let from_type = match trait1.trait_type {
+            RustType::Common { generic_arguments: Some(ref args), .. } if args.len() == 1 => {
+              &args[0]
+            }
+            _ => return Err(unexpected("TryFrom: one generic argument expected").into()),
+          };
+          format!("fn try_from(value: {}) -> ::std::result::Result<Self, Self::Error> {{\n\
+                   Ok(unsafe {{ {}ffi::{}(value as *const _) }})\n\
+                   }}\n",
+                  self.rust_type_to_code(from_type),
+                  self.local_prefix(),
+                  ffi_function_name)
+        }
+        Some(TraitImplExtra::FlagsFromEnum) => {
+          let enum_type = match trait1.trait_type {
+            RustType::Common { generic_arguments: Some(ref args), .. } if args.len() == 1 => {
+              &args[0]
+            }
+            _ => return Err(unexpected("From: one generic argument expected").into()),
+          };
+          format!("fn from(value: {}) -> Self {{\n\
+                   Self::from_enum(value)\n\
+                   }}\n",
+                  self.rust_type_to_code(enum_type))
+        }
+        Some(TraitImplExtra::StreamWriteOperator { ref ffi_function_name }) => {
+          let value_type = match trait1.trait_type {
+            RustType::Common { generic_arguments: Some(ref args), .. } if args.len() == 1 => {
+              &args[0]
+            }
+            _ => return Err(unexpected("Shl: one generic argument expected").into()),
+          };
+          format!("fn shl(self, value: {}) -> Self::Output {{\n\
+                   unsafe {{ {}ffi::{}(self as *mut _, value as *const _); }}\n\
+                   self\n\
+                   }}\n",
+                  self.rust_type_to_code(value_type),
+                  self.local_prefix(),
+                  ffi_function_name)
+        }
+        Some(TraitImplExtra::StringFromUtf8 { ref ffi_function_name }) => {
+          format!("fn from(value: &'a str) -> Self {{\n\
+                   let len = ::std::convert::TryFrom::try_from(value.len())\
+                   .expect(\"string is too long\");\n\
+                   let mut object: Self = unsafe {{ \
+                   ::cpp_utils::new_uninitialized::NewUninitialized::new_uninitialized() }};\n\
+                   unsafe {{ {}ffi::{}(value.as_ptr() as *const _, len, &mut object) }};\n\
+                   object\n\
+                   }}\n",
+                  self.local_prefix(),
+                  ffi_function_name)
+        }
+        Some(TraitImplExtra::StringFromStdString) => {
+          "fn from(value: ::std::string::String) -> Self {\n\
+           Self::from(value.as_str())\n\
+           }\n"
+            .to_string()
+        }
+        Some(TraitImplExtra::StaticUpcastOwned) => String::new(),
+        Some(TraitImplExtra::OrdFromPartialOrd) => {
+          "fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {\n\
+           ::std::cmp::PartialOrd::partial_cmp(self, other).unwrap()\n\
+           }\n"
+            .to_string()
+        }
+        None => {
+          trait1
+            .methods
+            .iter()
+            .map_if_ok(|method| self.generate_rust_final_function(method))?
+            .join("")
+        }
+      };
+      let mut lifetimes = match trait1.trait_type {
+        RustType::Common { generic_arguments: Some(ref args), .. } => {
+          args
+            .iter()
+            .filter_map(|t| t.lifetime())
+            .map(|l| format!("'{}", l))
+            .collect::<Vec<_>>()
+        }
+        _ => Vec::new(),
+      };
+      // the trait may be implemented for a reference (e.g. `Add<i32> for &'a Point`)
+      if let Some(lifetime) = trait1.target_type.lifetime() {
+        let lifetime = format!("'{}", lifetime);
+        if !lifetimes.contains(&lifetime) {
+          lifetimes.push(lifetime);
+        }
+      }
+      let impl_generics = if lifetimes.is_empty() {
+        String::new()
       } else {
-        trait1
-          .methods
-          .iter()
-          .map_if_ok(|method| self.generate_rust_final_function(method))?
-          .join("")
+        format!("<{}>", lifetimes.join(", "))
+      };
+      // `StaticUpcastOwned` is an unsafe marker trait
+      let unsafe_text = if trait1.extra == Some(TraitImplExtra::StaticUpcastOwned) {
+        "unsafe "
+      } else {
+        ""
       };
-      results.push(format!("impl {} for {} {{\n{}{}}}\n\n",
+      results.push(format!("{}impl{} {} for {} {{\n{}{}}}\n\n",
+                           unsafe_text,
+                           impl_generics,
                            self.rust_type_to_code(&trait1.trait_type),
                            self.rust_type_to_code(&trait1.target_type),
                            associated_types_text,
@@ -869,6 +1886,176 @@ impl<'a> RustCodeGenerator<'a> {
     Ok(results.join(""))
   }
 
+  /// Generates iteration and conversion helpers for a `QHash` instantiation
+  /// named `name`. Helpers are omitted if the required methods are not
+  /// available in `methods`.
+  fn generate_qt_hash_impls(&self,
+                            name: &str,
+                            kind: &RustTypeWrapperKind,
+                            methods: &[RustMethod],
+                            types: &RustQtHashTypes)
+                            -> String {
+    let key_type = self.rust_type_to_code(&types.key_type);
+    let value_type = self.rust_type_to_code(&types.value_type);
+    let mut code = String::new();
+    if let (Some(keys_call), Some(value_call)) =
+      (method_call_code(methods, "keys", &[]), method_call_code(methods, "value", &["&key"])) {
+      code.push_str(&format!(include_str!("../templates/crate/impl_qt_hash.rs.in"),
+                             name = name,
+                             key_type = key_type,
+                             value_type = value_type,
+                             keys_call = keys_call,
+                             value_call = value_call));
+    }
+    // `From` can't be implemented for `CppBox`, so only
+    // stack-allocated hashes are supported
+    let is_stack_allocated = match *kind {
+      RustTypeWrapperKind::Struct { ref size_const_name, .. } => size_const_name.is_some(),
+      RustTypeWrapperKind::Enum { .. } => false,
+    };
+    if is_stack_allocated {
+      if let (Some(new_call), Some(insert_call)) =
+        (method_call_code(methods, "new", &[]),
+         method_call_code(methods, "insert", &["key", "value"])) {
+        code.push_str(&format!(include_str!("../templates/crate/impl_qt_hash_from.rs.in"),
+                               name = name,
+                               key_type = key_type,
+                               value_type = value_type,
+                               new_call = format!("{}::{}", name, new_call),
+                               insert_call = insert_call));
+      }
+    }
+    code
+  }
+
+  /// Generates conversion helpers for a `std::vector` instantiation
+  /// named `name`. Helpers are omitted if the required methods are not
+  /// available in `methods`. `to_vec` is only generated if the elements
+  /// can be cloned, `from_slice` requires `push_back(const T&)`, and
+  /// `from_boxes` requires `push_back(T&&)`.
+  fn generate_vector_impls(&self,
+                           name: &str,
+                           kind: &RustTypeWrapperKind,
+                           methods: &[RustMethod],
+                           info: &RustVectorInfo)
+                           -> String {
+    let element_type = self.rust_type_to_code(&info.element_type);
+    let mut code = String::new();
+    if let (true, Some(size_call), Some(at_call)) =
+      (info.element_is_clone,
+       method_call_code(methods, "size", &[]),
+       method_call_code(methods, "at", &["i"])) {
+      code.push_str(&format!(include_str!("../templates/crate/impl_vector.rs.in"),
+                             name = name,
+                             element_type = element_type,
+                             size_call = size_call,
+                             at_call = at_call));
+    }
+    let is_stack_allocated = match *kind {
+      RustTypeWrapperKind::Struct { ref size_const_name, .. } => size_const_name.is_some(),
+      RustTypeWrapperKind::Enum { .. } => false,
+    };
+    let self_type = if is_stack_allocated {
+      name.to_string()
+    } else {
+      format!("{}<{}>", self.full_name(self.config.settings.box_type()), name)
+    };
+    if let (Some(new_call), Some(push_back_call)) =
+      (method_call_code(methods, "new", &[]), method_call_code(methods, "push_back", &["item"])) {
+      let new_call = format!("{}::{}", name, new_call);
+      if info.has_copy_push_back {
+        code.push_str(&format!(include_str!("../templates/crate/impl_vector_from.rs.in"),
+                               name = name,
+                               self_type = self_type,
+                               element_type = element_type,
+                               new_call = new_call,
+                               push_back_call = push_back_call));
+      }
+      if info.has_move_push_back {
+        code.push_str(&format!(include_str!("../templates/crate/impl_vector_from_boxes.rs.in"),
+                               name = name,
+                               self_type = self_type,
+                               box_type = self.full_name(self.config.settings.box_type()),
+                               element_type = element_type,
+                               new_call = new_call,
+                               push_back_call = push_back_call));
+      }
+    }
+    code
+  }
+
+  /// Generates an index-based iterator struct and `iter()` method
+  /// for `QList` or `QVector` instantiation `name`. Returns an empty string
+  /// if `size()` or `at()` method is not available in `methods`.
+  fn generate_list_iterator(&self,
+                            name: &str,
+                            methods: &[RustMethod],
+                            element_type: &RustType)
+                            -> String {
+    match (method_call_code(methods, "size", &[]),
+           method_call_code(methods, "at", &["self.index"])) {
+      (Some(size_call), Some(at_call)) => {
+        format!(include_str!("../templates/crate/list_iterator.rs.in"),
+                name = name,
+                iterator_name = format!("{}Iter", name),
+                element_type = self.rust_type_to_code(element_type),
+                size_call = size_call,
+                at_call = at_call)
+      }
+      _ => String::new(),
+    }
+  }
+
+  /// Generates an iterator adapter struct and `iter()` method
+  /// for container type `name`. Returns an empty string if
+  /// `begin()` or `end()` method is not available in `methods`.
+  fn generate_iterator_adapter(&self,
+                               name: &str,
+                               methods: &[RustMethod],
+                               adapter: &RustIteratorAdapter)
+                               -> String {
+    let (begin_call, end_call) = match (method_call_code(methods, "begin", &[]),
+                                        method_call_code(methods, "end", &[])) {
+      (Some(begin_call), Some(end_call)) => (begin_call, end_call),
+      _ => return String::new(),
+    };
+    let iterator_type = self.rust_type_to_code(&adapter.iterator_type);
+    let (iterator_type, current_ptr, end_ptr, current_mut_ptr) = if adapter.iterator_is_boxed {
+      (format!("{}<{}>", self.full_name(self.config.settings.box_type()), iterator_type),
+       "self.current.as_ptr()",
+       "self.end.as_ptr()",
+       "self.current.as_mut_ptr()")
+    } else {
+      (iterator_type,
+       "&self.current as *const _",
+       "&self.end as *const _",
+       "&mut self.current as *mut _")
+    };
+    let item_code = format!("{}ffi::{}({})",
+                            self.local_prefix(),
+                            adapter.indirection_function,
+                            current_ptr);
+    let item_code = if adapter.item_type.is_ref() {
+      format!("&*{}", item_code)
+    } else {
+      item_code
+    };
+    format!(include_str!("../templates/crate/iterator_adapter.rs.in"),
+            name = name,
+            local_prefix = self.local_prefix(),
+            adapter_name = adapter.name,
+            iterator_type = iterator_type,
+            item_type = self.rust_type_to_code(&adapter.item_type),
+            not_equal_function = adapter.not_equal_function,
+            increment_function = adapter.increment_function,
+            current_ptr = current_ptr,
+            end_ptr = end_ptr,
+            current_mut_ptr = current_mut_ptr,
+            item = item_code,
+            begin_call = begin_call,
+            end_call = end_call)
+  }
+
   /// Generates code for a module of the output crate.
   /// This may be a top level or nested module.
   #[cfg_attr(feature="clippy", allow(single_match_else))]
@@ -876,14 +2063,23 @@ impl<'a> RustCodeGenerator<'a> {
     let mut results = Vec::new();
     for type1 in &data.types {
       results.push(format_doc(&doc_formatter::type_doc(type1)));
-      let maybe_pub = if type1.is_public { "pub " } else { "" };
+      let maybe_pub = if type1.is_public { "pub " } else { "pub(crate) " };
       match type1.kind {
         RustTypeDeclarationKind::CppTypeWrapper {
           ref cpp_type_name,
           ref kind,
           ref methods,
+          ref constants,
           ref trait_impls,
           ref qt_receivers,
+          ref qt_hash_types,
+          ref vector_info,
+          ref list_element_type,
+          ref pod_traits,
+          ref transparent_type,
+          ref iterator_adapter,
+          ref is_send,
+          ref is_sync,
           ..
         } => {
           let r = match *kind {
@@ -894,24 +2090,41 @@ impl<'a> RustCodeGenerator<'a> {
               let mut r = format!(include_str!("../templates/crate/enum_declaration.rs.in"),
                                   maybe_pub = maybe_pub,
                                   name = type1.name.last_name()?,
+                                  repr_type = enum_repr_type(values),
                                   variants = values
                                     .iter()
                                     .map(|item| {
-                                           format!("{}  {} = {}",
+                                           format!("{}{}  {} = {}",
                                               format_doc(&doc_formatter::enum_value_doc(&item)),
+                                              if item.is_hidden { "#[doc(hidden)]\n" } else { "" },
                                               item.name,
                                               item.value)
                                          })
                                     .join(", \n"));
+              r = r +
+                  &format!(include_str!("../templates/crate/impl_enum_try_from.rs.in"),
+                           name = type1.name.last_name()?,
+                           error_type = self.error_type_path("UnknownEnumValue"),
+                           repr_type = enum_repr_type(values),
+                           arms = values
+                             .iter()
+                             // dummy variants don't correspond to any C++ values
+                             .filter(|item| !item.is_dummy)
+                             .map(|item| {
+                                    format!("{} => Ok({}::{}),",
+                                            item.value,
+                                            type1.name.last_name().unwrap(),
+                                            item.name)
+                                  })
+                             .join("\n"));
               if *is_flaggable {
                 r = r +
                     &format!(include_str!("../templates/crate/impl_flaggable.rs.in"),
                              name = type1.name.last_name()?,
                              trait_type =
-                               RustName::new(vec!["qt_core".to_string(),
-                                                  "flags".to_string(),
-                                                  "FlaggableEnum".to_string()])?
-                                   .full_name(Some(&self.config.crate_properties.name())));
+                               self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                  "flags".to_string(),
+                                                                  "FlaggableEnum".to_string()])?));
               }
               r
             }
@@ -921,10 +2134,32 @@ impl<'a> RustCodeGenerator<'a> {
               ..
             } => {
               let mut r = if let Some(ref size_const_name) = *size_const_name {
-                format!(include_str!("../templates/crate/struct_declaration.rs.in"),
-                        maybe_pub = maybe_pub,
-                        name = type1.name.last_name()?,
-                        size_const_name = size_const_name)
+                let declaration = if let Some(ref transparent_type) = *transparent_type {
+                  format!(include_str!("../templates/crate/transparent_struct_declaration.rs.in"),
+                          maybe_pub = maybe_pub,
+                          name = type1.name.last_name()?,
+                          inner_type = self.rust_type_to_code(transparent_type),
+                          size_check_name = format!("{}_SIZE_CHECK", size_const_name),
+                          local_prefix = self.local_prefix(),
+                          size_const_name = size_const_name)
+                } else {
+                  format!(include_str!("../templates/crate/struct_declaration.rs.in"),
+                          maybe_pub = maybe_pub,
+                          name = type1.name.last_name()?,
+                          local_prefix = self.local_prefix(),
+                          size_const_name = size_const_name,
+                          align_type_name = align_type_name(size_const_name))
+                };
+                if let Some(ref pod_traits) = *pod_traits {
+                  let mut r = format!("#[derive(Clone, Copy)]\n{}", declaration);
+                  if pod_traits.partial_eq {
+                    r.push_str(&format!(include_str!("../templates/crate/impl_pod_partial_eq.rs.in"),
+                                        name = type1.name.last_name()?));
+                  }
+                  r
+                } else {
+                  declaration
+                }
               } else {
                 format!("#[repr(C)]\n{maybe_pub}struct {}(u8);\n\n",
                         type1.name.last_name()?,
@@ -939,18 +2174,14 @@ impl<'a> RustCodeGenerator<'a> {
                   .collect();
                 let args = arg_texts.join(", ");
                 let args_tuple = format!("{}{}", args, if arg_texts.len() == 1 { "," } else { "" });
-                let connections_mod = RustName::new(vec!["qt_core".to_string(),
-                                                         "connection".to_string()])?
-                    .full_name(Some(&self.config.crate_properties.name()));
-                let object_type_name = RustName::new(vec!["qt_core".to_string(),
-                                                          "object".to_string(),
-                                                          "Object".to_string()])?
-                    .full_name(Some(&self.config.crate_properties.name()));
+                let connections_mod = self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                         "connection".to_string()])?);
+                let object_type_name = self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                          "object".to_string(),
+                                                                          "Object".to_string()])?);
                 r.push_str(&format!(include_str!("../templates/crate/extern_slot_impl_receiver.rs.in"),
                                     type_name =
-                                      type1
-                                        .name
-                                        .full_name(Some(&self.config.crate_properties.name())),
+                                      self.full_name(&type1.name),
                                     args_tuple = args_tuple,
                                     receiver_id = slot_wrapper.receiver_id,
                                     connections_mod = connections_mod,
@@ -960,9 +2191,19 @@ impl<'a> RustCodeGenerator<'a> {
             }
           };
           results.push(r);
-          if !methods.is_empty() {
-            results.push(format!("impl {} {{\n{}}}\n\n",
+          if !methods.is_empty() || !constants.is_empty() {
+            let constants_code: Vec<_> = constants
+              .iter()
+              .map(|constant| {
+                     format!("pub const {}: {} = {};\n",
+                             constant.name,
+                             self.rust_type_to_code(&constant.value_type),
+                             constant.value)
+                   })
+              .collect();
+            results.push(format!("impl {} {{\n{}{}}}\n\n",
                                  type1.name.last_name()?,
+                                 constants_code.join(""),
                                  methods
                                    .iter()
                                    .map_if_ok(|method| {
@@ -971,18 +2212,42 @@ impl<'a> RustCodeGenerator<'a> {
                                    .join("")));
           }
           results.push(self.generate_trait_impls(trait_impls)?);
+          if *is_send {
+            results.push(format!("unsafe impl Send for {} {{}}\n\n", type1.name.last_name()?));
+          }
+          if *is_sync {
+            results.push(format!("unsafe impl Sync for {} {{}}\n\n", type1.name.last_name()?));
+          }
+          if let Some(ref qt_hash_types) = *qt_hash_types {
+            results.push(self.generate_qt_hash_impls(type1.name.last_name()?,
+                                                     kind,
+                                                     methods,
+                                                     qt_hash_types));
+          }
+          if let Some(ref vector_info) = *vector_info {
+            results.push(self.generate_vector_impls(type1.name.last_name()?,
+                                                    kind,
+                                                    methods,
+                                                    vector_info));
+          }
+          if let Some(ref element_type) = *list_element_type {
+            results.push(self.generate_list_iterator(type1.name.last_name()?,
+                                                     methods,
+                                                     element_type));
+          }
+          if let Some(ref adapter) = *iterator_adapter {
+            results.push(self.generate_iterator_adapter(type1.name.last_name()?,
+                                                        methods,
+                                                        adapter));
+          }
           if !qt_receivers.is_empty() {
-            let connections_mod = RustName::new(vec!["qt_core".to_string(),
-                                                     "connection".to_string()])?
-                .full_name(Some(&self.config.crate_properties.name()));
-            let object_type_name = RustName::new(vec!["qt_core".to_string(),
-                                                      "object".to_string(),
-                                                      "Object".to_string()])?
-                .full_name(Some(&self.config.crate_properties.name()));
+            let connections_mod = self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                     "connection".to_string()])?);
+            let object_type_name = self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                      "object".to_string(),
+                                                                      "Object".to_string()])?);
             let mut content = Vec::new();
-            let obj_name = type1
-              .name
-              .full_name(Some(&self.config.crate_properties.name()));
+            let obj_name = self.full_name(&type1.name);
             content.push("use ::cpp_utils::StaticCast;\n".to_string());
             let mut type_impl_content = Vec::new();
             for receiver_type in &[RustQtReceiverType::Signal, RustQtReceiverType::Slot] {
@@ -1090,10 +2355,11 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
             "Self::ReturnType".to_string()
           };
           let maybe_unsafe = if *is_unsafe { "unsafe " } else { "" };
-          results.push(format!("pub trait {name}{trait_lifetime_specifier} {{\n\
+          results.push(format!("{maybe_pub}trait {name}{trait_lifetime_specifier} {{\n\
               {return_type_decl}\n\
               {maybe_unsafe}fn exec(self, {arg_list}) -> {return_type_string};
             }}",
+                               maybe_pub = maybe_pub,
                                name = type1.name.last_name()?,
                                maybe_unsafe = maybe_unsafe,
                                arg_list = arg_list,
@@ -1101,12 +2367,18 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                                return_type_decl = return_type_decl,
                                return_type_string = return_type_string));
           for variant in impls {
+            // a `'static` reference returned by a variant without reference
+            // arguments can't use a lifetime of the impl because
+            // it wouldn't be constrained by anything
+            let returns_borrowed_ref = variant.return_type.rust_api_type.is_ref() &&
+                                       variant.return_type.rust_api_type.lifetime() !=
+                                       Some(&"static".to_string());
             let final_lifetime = if lifetime.is_none() &&
                                     (variant
                                        .arguments
                                        .iter()
                                        .any(|t| t.argument_type.rust_api_type.is_ref()) ||
-                                     variant.return_type.rust_api_type.is_ref()) {
+                                     returns_borrowed_ref) {
               Some("a".to_string())
             } else {
               lifetime.clone()
@@ -1177,6 +2449,9 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
       results.push(self.generate_rust_final_function(method)?);
     }
     results.push(self.generate_trait_impls(&data.trait_impls)?);
+    for code in &data.plugin_code {
+      results.push(format!("{}\n\n", code));
+    }
     for submodule in &data.submodules {
       let submodule_doc = submodule
         .doc
@@ -1187,6 +2462,13 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                            submodule_doc,
                            submodule.name,
                            self.generate_module_code(submodule)?));
+      if self.config.settings.flatten_single_type_modules() {
+        if let Some(type1) = single_public_type(submodule) {
+          results.push(format!("pub use self::{}::{};\n\n",
+                               submodule.name,
+                               type1.name.last_name()?));
+        }
+      }
       for type1 in &submodule.types {
         if let RustTypeDeclarationKind::CppTypeWrapper { ref kind, .. } = type1.kind {
           if let RustTypeWrapperKind::Struct { ref slot_wrapper, .. } = *kind {
@@ -1203,13 +2485,11 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                 .join(", ");
               let args = arg_texts.join(", ");
               let args_tuple = format!("{}{}", args, if arg_texts.len() == 1 { "," } else { "" });
-              let connections_mod = RustName::new(vec!["qt_core".to_string(),
-                                                       "connection".to_string()])?
-                  .full_name(Some(&self.config.crate_properties.name()));
-              let object_type_name = RustName::new(vec!["qt_core".to_string(),
-                                                        "object".to_string(),
-                                                        "Object".to_string()])?
-                  .full_name(Some(&self.config.crate_properties.name()));
+              let connections_mod = self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                       "connection".to_string()])?);
+              let object_type_name = self.full_name(&RustName::new(vec!["qt_core".to_string(),
+                                                                        "object".to_string(),
+                                                                        "Object".to_string()])?);
               let callback_args = slot_wrapper
                 .arguments
                 .iter()
@@ -1226,11 +2506,20 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                              self.convert_type_from_ffi(t, format!("arg{}", num), false, false)
                            })?
                 .join(", ");
+              let (return_type, callback_return_type, return_doc) =
+                if slot_wrapper.return_type.rust_api_type == RustType::EmptyTuple {
+                  (String::new(), String::new(), "")
+                } else {
+                  (format!(" -> {}",
+                           self.rust_type_to_code(&slot_wrapper.return_type.rust_api_type)),
+                   format!(" -> {}",
+                           self.rust_type_to_code(&slot_wrapper.return_type.rust_ffi_type)),
+                   "/// The value returned by the closure is returned from the slot.\n\
+                    /// If the closure is not set, the default value is returned.\n")
+                };
               results.push(format!(include_str!("../templates/crate/closure_slot_wrapper.rs.in"),
                                    type_name =
-                                     type1
-                                       .name
-                                       .full_name(Some(&self.config.crate_properties.name())),
+                                     self.full_name(&type1.name),
                                    pub_type_name = slot_wrapper.public_type_name,
                                    callback_name = slot_wrapper.callback_name,
                                    args = args,
@@ -1239,17 +2528,70 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
                                    object_type_name = object_type_name,
                                    func_args = func_args,
                                    callback_args = callback_args,
+                                   return_type = return_type,
+                                   callback_return_type = callback_return_type,
+                                   return_doc = return_doc,
                                    cpp_args = cpp_args));
             }
           }
         }
       }
     }
+    if self.config.settings.generate_layout_tests() {
+      results.push(self.generate_layout_tests(data)?);
+    }
     Ok(results.join(""))
   }
 
-  /// Runs `rustfmt` on a Rust file `path`.
+  /// Generates a `debug_assert_eq!` checking that size of the struct pointed to
+  /// by `ffi_type` matches its size constant. Returns an empty string if
+  /// the struct is declared in another crate.
+  fn size_debug_assertion(&self, ffi_type: &RustType) -> String {
+    match *ffi_type {
+      RustType::Common { ref base, .. } if base.crate_name() ==
+                                           Some(self.config.crate_properties.name()) => {
+        format!("debug_assert_eq!(::std::mem::size_of::<{}>(), {}type_sizes::{});\n",
+                self.full_name(base),
+                self.local_prefix(),
+                size_const_name(base))
+      }
+      _ => String::new(),
+    }
+  }
+
+  /// Generates a test module checking that sizes of stack-allocated
+  /// structs declared in `data` match their size constants.
+  /// Returns an empty string if `data` has no such structs.
+  fn generate_layout_tests(&self, data: &RustModule) -> Result<String> {
+    let mut tests = Vec::new();
+    for type1 in &data.types {
+      if let RustTypeDeclarationKind::CppTypeWrapper {
+               kind: RustTypeWrapperKind::Struct { size_const_name: Some(ref size_const_name), .. },
+               ..
+             } = type1.kind {
+        let name = type1.name.last_name()?;
+        tests.push(format!("#[test]\nfn {}_size() {{\n  \
+                            assert_eq!(::std::mem::size_of::<super::{}>(), {}type_sizes::{});\n\
+                            }}\n\n",
+                           name.to_snake_case(),
+                           name,
+                           self.local_prefix(),
+                           size_const_name));
+      }
+    }
+    if tests.is_empty() {
+      return Ok(String::new());
+    }
+    Ok(format!("#[cfg(test)]\nmod layout_tests {{\n{}}}\n\n", tests.join("")))
+  }
+
+  /// Runs `rustfmt` on a Rust file `path`, unless it's disabled
+  /// with `Config::set_format_generated_code`. Formatting errors
+  /// are logged and don't abort the generation.
   fn call_rustfmt(&self, path: &PathBuf) {
+    if !self.config.settings.format_generated_code() {
+      return;
+    }
     let result = ::std::panic::catch_unwind(|| {
                                               rustfmt::format_input(rustfmt::Input::File(path.clone()),
                             &self.rustfmt_config,
@@ -1265,27 +2607,65 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
         log::error(format!("rustfmt paniced on file: {:?}: {:?}", path, cause));
       }
     }
-    assert!(path.as_path().is_file());
+    if !path.as_path().is_file() {
+      log::error(format!("file is missing after running rustfmt: {:?}", path));
+    }
   }
 
-  /// Creates a top level module file.
-  pub fn generate_module_file(&self, data: &RustModule) -> Result<()> {
+  /// Creates a top level module file containing `code`
+  /// produced by `generate_module_code`.
+  pub fn generate_module_file(&self, data: &RustModule, code: &str) -> Result<()> {
     let mut file_path = self.config.output_path.clone();
     file_path.push("src");
     file_path.push(format!("{}.rs", &data.name));
-    self
-      .save_src_file(&file_path, &self.generate_module_code(data)?)?;
+    self.save_src_file(&file_path, code)?;
     self.call_rustfmt(&file_path);
+    if self.config.settings.write_module_inventories() {
+      let mut inventory_path = self.config.output_path.clone();
+      inventory_path.push("src");
+      inventory_path.push(format!("{}.inventory.json", &data.name));
+      save_json(&inventory_path,
+                &module_inventory(data, &self.config.crate_properties.name()))?;
+    }
     Ok(())
   }
 
-  /// Generates `ffi.in.rs` file.
-  pub fn generate_ffi_file(&self, functions: &[(String, Vec<RustFFIFunction>)]) -> Result<()> {
+  /// Generates `ffi.in.rs` file. Headers are sorted by name, and functions
+  /// within each header are sorted by name.
+  /// If `used_functions` is set, functions not listed in it are omitted.
+  pub fn generate_ffi_file(&self,
+                           functions: &[(String, Vec<RustFFIFunction>)],
+                           used_functions: Option<&BTreeSet<String>>,
+                           features: Option<&ModuleFeatures>)
+                           -> Result<()> {
     let mut code = String::new();
+    for library in self.config.settings.link_libraries() {
+      code.push_str(&link_attribute(library));
+    }
     code.push_str("extern \"C\" {\n");
-    for &(ref include_file, ref functions) in functions {
+    // headers and functions are sorted to keep the output stable
+    // regardless of the order of the source data
+    let mut headers: Vec<_> = functions.iter().collect();
+    headers.sort_by(|a, b| a.0.cmp(&b.0));
+    for &&(ref include_file, ref functions) in &headers {
       code.push_str(&format!("  // Header: {}\n", include_file));
+      let mut functions: Vec<_> = functions.iter().collect();
+      functions.sort_by(|a, b| a.name.cmp(&b.name));
       for function in functions {
+        if let Some(used_functions) = used_functions {
+          if !used_functions.contains(&function.name) {
+            continue;
+          }
+        }
+        if let Some(features) = features {
+          let attribute = features.ffi_function_cfg_attribute(&function.name);
+          if !attribute.is_empty() {
+            code.push_str(&format!("  {}", attribute));
+          }
+        }
+        if let Some(ref cfg) = function.cfg {
+          code.push_str(&format!("  #[cfg({})]\n", cfg));
+        }
         code.push_str(&self.rust_ffi_function_to_code(function));
       }
       code.push_str("\n");
@@ -1300,6 +2680,18 @@ impl<'a> {connections_mod}::Receiver for {type_name}<'a> {{
     Ok(())
   }
 
+  /// Generates `errors.rs` file (see `Config::set_errors_module`).
+  fn generate_errors_file(&self) -> Result<()> {
+    let file_path = self
+      .config
+      .output_path
+      .with_added("src")
+      .with_added("errors.rs");
+    self.save_src_file(&file_path, include_str!("../templates/crate/errors.rs.in"))?;
+    self.call_rustfmt(&file_path);
+    Ok(())
+  }
+
   /// Creates new Rust source file or merges it with the existing file.
   fn save_src_file(&self, path: &Path, code: &str) -> Result<()> {
     const INCLUDE_GENERATED_MARKER: &'static str = "include_generated!();";
@@ -1,7 +1,8 @@
 //! Generates Rust public API and FFI functions
 
 use caption_strategy::TypeCaptionStrategy;
-use cpp_data::{CppTypeKind, CppEnumValue, CppTypeAllocationPlace, CppDataWithDeps};
+use cpp_data::{CppTypeKind, CppEnumValue, CppTypeAllocationPlace, CppDataWithDeps,
+               CppClassField, CppClassStaticField, CppVisibility};
 use cpp_ffi_data::{CppAndFfiMethod, CppFfiArgumentMeaning, CppFfiType, CppIndirectionChange,
                    CppFfiMethodKind, CppCast, CppFfiHeaderData};
 use cpp_method::{CppMethod, ReturnValueAllocationPlace};
@@ -11,18 +12,26 @@ use cpp_type::{CppType, CppTypeBase, CppBuiltInNumericType, CppTypeIndirection,
                CppFunctionPointerType};
 use common::errors::{Result, ChainErr, unexpected};
 use common::log;
+use common::target;
+use config::{DocHookFn, TypeVisibility, RustGeneratorSettings};
 use rust_info::{RustTypeDeclaration, RustTypeDeclarationKind, RustTypeWrapperKind, RustModule,
                 RustMethod, RustMethodScope, RustMethodArgument, RustMethodArgumentsVariant,
                 RustMethodArguments, TraitImpl, TraitImplExtra, RustEnumValue,
                 RustProcessedTypeInfo, RustMethodDocItem, RustQtReceiverDeclaration,
                 RustQtReceiverType, RustQtSlotWrapper, RustMethodSelfArgKind,
-                RustMethodCaptionStrategy, RustFFIFunction, RustFFIArgument, TraitAssociatedType};
+                RustMethodCaptionStrategy, RustFFIFunction, RustFFIArgument, TraitAssociatedType,
+                RustQtHashTypes, RustVectorInfo, RustPodTraits, RustIteratorAdapter,
+                RustAssociatedConstant};
 use rust_type::{RustName, RustType, CompleteType, RustTypeIndirection, RustToCTypeConversion};
+use rust_code_generator::for_each_module_item;
 use common::string_utils::{CaseOperations, WordIterator};
 use common::utils::{add_to_multihash, MapIfOk};
 use common::string_utils::JoinWithSeparator;
 use doc_formatter;
-use std::collections::{HashMap, HashSet, hash_map};
+use std::collections::{HashMap, HashSet, BTreeMap, BTreeSet, hash_map};
+use rayon::prelude::*;
+use std::sync::Mutex;
+use std::iter::once;
 
 
 /// Intermediate data of a single C++ method converted to
@@ -44,7 +53,7 @@ struct RustSingleMethod {
 
 /// Returns name of the constant that will hold size of `type_name`'s buffer in
 /// the generated `type_sizes.rs`.
-fn size_const_name(type_name: &RustName) -> String {
+pub fn size_const_name(type_name: &RustName) -> String {
   type_name
     .parts
     .iter()
@@ -52,6 +61,45 @@ fn size_const_name(type_name: &RustName) -> String {
     .join("_")
 }
 
+/// Returns true if `value` can be written as a literal of integer type `base`
+/// on any target. Signedness and size of some types (e.g. `char` and `long`)
+/// depend on the target, so the smallest possible range is used for them.
+fn integer_literal_fits(base: &CppTypeBase, value: i64) -> bool {
+  let is_signed = match *base {
+    CppTypeBase::BuiltInNumeric(ref numeric) => {
+      if numeric.is_signed_integer() {
+        Some(true)
+      } else if numeric.is_unsigned_integer() {
+        Some(false)
+      } else {
+        None
+      }
+    }
+    CppTypeBase::SpecificNumeric(CppSpecificNumericType {
+                                   kind: CppSpecificNumericTypeKind::Integer { is_signed },
+                                   ..
+                                 }) |
+    CppTypeBase::PointerSizedInteger { is_signed, .. } => Some(is_signed),
+    _ => None,
+  };
+  let bits = match *base {
+    CppTypeBase::PointerSizedInteger { .. } => 32,
+    _ => base.numeric_bits().unwrap_or(16),
+  };
+  if value < 0 && is_signed != Some(true) {
+    return false;
+  }
+  let value_bits = if is_signed == Some(false) { bits } else { bits - 1 };
+  value_bits >= 63 || (value >= -(1 << value_bits) && value < (1 << value_bits))
+}
+
+/// Returns name of the type alias in the generated `type_sizes.rs`
+/// that refers to a zero-sized type from `cpp_utils::align` with
+/// the same alignment as the C++ type of the struct with `size_const_name`.
+pub fn align_type_name(size_const_name: &str) -> String {
+  format!("{}_ALIGN", size_const_name)
+}
+
 
 /// Mode of case conversion
 enum Case {
@@ -66,6 +114,11 @@ enum Case {
 /// with trait implementations in the future.
 fn operator_rust_name(operator: &CppOperator) -> Result<String> {
   Ok(match *operator {
+       CppOperator::Conversion(CppType {
+                                 base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+                                 indirection: CppTypeIndirection::None,
+                                 ..
+                               }) => "to_bool".to_string(),
        CppOperator::Conversion(ref type1) => {
          format!("as_{}",
                  type1
@@ -76,11 +129,87 @@ fn operator_rust_name(operator: &CppOperator) -> Result<String> {
      })
 }
 
+/// Returns `snake_name` of a method returning `bool` with added `is_` prefix
+/// if the name looks like an adjective (e.g. `visible`), or `None` if the name
+/// already starts with a predicate word or doesn't look like an adjective.
+fn bool_getter_rust_name(snake_name: &str) -> Option<String> {
+  const PREDICATE_WORDS: &'static [&'static str] = &["is", "has", "can", "should"];
+  const ADJECTIVE_SUFFIXES: &'static [&'static str] = &["able", "ible", "ed", "ive", "ful", "ent",
+                                                        "ant", "al", "ic", "ous", "y"];
+  let first_word = snake_name.split('_').next().unwrap_or("");
+  if PREDICATE_WORDS.contains(&first_word) || snake_name.contains('_') {
+    return None;
+  }
+  if ADJECTIVE_SUFFIXES
+       .iter()
+       .any(|suffix| snake_name.len() > suffix.len() && snake_name.ends_with(suffix)) {
+    Some(format!("is_{}", snake_name))
+  } else {
+    None
+  }
+}
+
+/// Returns Rust code calling the wrapper of a non-static const C++ method
+/// `cpp_name` without arguments (without `self.`), or `None` if there is
+/// no such method. `cpp_methods` and `rust_methods` are all methods of the class.
+fn const_method_call(cpp_name: &str,
+                     cpp_methods: &[&CppAndFfiMethod],
+                     rust_methods: &[RustMethod])
+                     -> Option<String> {
+  let cpp_method = match cpp_methods
+          .iter()
+          .find(|m| {
+                  &m.cpp_method.name == cpp_name && m.cpp_method.arguments.is_empty() &&
+                  m.cpp_method
+                    .class_membership
+                    .as_ref()
+                    .map_or(false, |info| info.is_const && !info.is_static)
+                }) {
+    Some(m) => m,
+    None => return None,
+  };
+  let rust_name = cpp_method.cpp_method.name.to_snake_case();
+  let method = match rust_methods
+          .iter()
+          .find(|m| m.name.last_name().ok().map_or(false, |n| n == &rust_name)) {
+    Some(m) => m,
+    None => return None,
+  };
+  if method.is_unsafe {
+    return None;
+  }
+  let is_const_self = |args: &[RustMethodArgument]| {
+    args.len() == 1 && &args[0].name == "self" &&
+    args[0]
+      .argument_type
+      .rust_api_type
+      .last_is_const()
+      .unwrap_or(false)
+  };
+  match method.arguments {
+    RustMethodArguments::SingleVariant(ref v) => {
+      if is_const_self(&v.arguments) {
+        return Some(format!("{}()", rust_name));
+      }
+    }
+    RustMethodArguments::MultipleVariants { ref shared_arguments, .. } => {
+      if is_const_self(shared_arguments) {
+        return Some(format!("{}(())", rust_name));
+      }
+    }
+  }
+  None
+}
+
 /// If `remove_qt_prefix` is true, removes "Q" or "Qt"
 /// if it is first word of the string and not the only one word.
 /// Also converts case of the words.
 #[cfg_attr(feature="clippy", allow(collapsible_if))]
-fn remove_qt_prefix_and_convert_case(s: &str, case: Case, remove_qt_prefix: bool) -> String {
+fn remove_qt_prefix_and_convert_case(s: &str,
+                                     case: Case,
+                                     remove_qt_prefix: bool,
+                                     acronyms: &[String])
+                                     -> String {
   let mut parts: Vec<_> = WordIterator::new(s).collect();
   if remove_qt_prefix && parts.len() > 1 {
     if (parts[0] == "Q" || parts[0] == "q" || parts[0] == "Qt") &&
@@ -89,19 +218,28 @@ fn remove_qt_prefix_and_convert_case(s: &str, case: Case, remove_qt_prefix: bool
     }
   }
   match case {
-    Case::Snake => parts.to_snake_case(),
+    Case::Snake => parts.to_snake_case_with_acronyms(acronyms),
     Case::Class => parts.to_class_case(),
   }
 }
 
 /// Removes ".h" from include file name and performs the same
 /// processing as `remove_qt_prefix_and_convert_case()` for snake case.
-fn include_file_to_module_name(include_file: &str, remove_qt_prefix: bool) -> String {
+fn include_file_to_module_name(include_file: &str,
+                               remove_qt_prefix: bool,
+                               acronyms: &[String])
+                               -> String {
   let mut r = include_file.to_string();
   if let Some(index) = r.find('.') {
     r = r[0..index].to_string();
   }
-  remove_qt_prefix_and_convert_case(&r, Case::Snake, remove_qt_prefix)
+  remove_qt_prefix_and_convert_case(&r, Case::Snake, remove_qt_prefix, acronyms)
+}
+
+/// Returns true if `part` of a C++ name refers to an anonymous namespace,
+/// e.g. `(anonymous namespace)` in `(anonymous namespace)::helper`.
+fn is_anonymous_namespace(part: &str) -> bool {
+  part.is_empty() || part.starts_with("(anonymous")
 }
 
 /// Adds "_" to a string if it is a reserved word in Rust
@@ -120,17 +258,184 @@ fn sanitize_rust_identifier(name: &str) -> String {
   }
 }
 
+/// Returns final name of a method with `last_name` and
+/// name suffix `suffix` (see `RustGenerator::generate_final_method`).
+fn final_method_last_name(last_name: &str, suffix: Option<&String>) -> String {
+  let name = match suffix {
+    Some(suffix) => vec![last_name, suffix.as_ref()].to_snake_case(),
+    None => last_name.to_string(),
+  };
+  sanitize_rust_identifier(&name)
+}
+
+/// Detects methods that would receive identical final names in the same scope.
+/// Each element of `names` contains last name of a method and its name suffix
+/// produced by `RustGenerator::overload_functions`. Suffixes of colliding methods
+/// (except for the first one) are extended with a numeric index that makes
+/// the final name unique.
+fn resolve_method_name_collisions(names: &mut [(String, Option<String>)]) {
+  let all_names: HashSet<_> = names
+    .iter()
+    .map(|&(ref name, ref suffix)| final_method_last_name(name, suffix.as_ref()))
+    .collect();
+  let mut used_names = HashSet::new();
+  for &mut (ref name, ref mut suffix) in names {
+    let final_name = final_method_last_name(name, suffix.as_ref());
+    if used_names.insert(final_name.clone()) {
+      continue;
+    }
+    let mut index = 1;
+    let mut new_suffix;
+    loop {
+      new_suffix = match *suffix {
+        Some(ref suffix) => format!("{}_{}", suffix, index),
+        None => index.to_string(),
+      };
+      let candidate_name = final_method_last_name(name, Some(&new_suffix));
+      if !all_names.contains(&candidate_name) && used_names.insert(candidate_name) {
+        break;
+      }
+      index += 1;
+    }
+    log::llog(log::DebugRustSkips, || {
+      format!("Method name collision: {}; using suffix {:?} instead of {:?}",
+              final_name,
+              new_suffix,
+              suffix)
+    });
+    *suffix = Some(new_suffix);
+  }
+}
+
+/// Renames conversion operator methods that received identical names and
+/// arguments (e.g. conversions to C++ types with the same caption), which would
+/// otherwise make overloading impossible. The caption of the Rust return type
+/// is appended to the names of colliding methods, and a numeric index is appended
+/// if that's not enough to make them unique.
+fn disambiguate_conversion_operators(methods: &mut [RustSingleMethod]) -> Result<()> {
+  let arg_types = |method: &RustSingleMethod| -> Vec<RustType> {
+    method
+      .arguments
+      .arguments
+      .iter()
+      .map(|arg| arg.argument_type.rust_api_type.clone())
+      .collect()
+  };
+  let mut groups: BTreeMap<(String, Vec<RustType>), Vec<usize>> = BTreeMap::new();
+  for (index, method) in methods.iter().enumerate() {
+    groups
+      .entry((method.name.last_name()?.clone(), arg_types(method)))
+      .or_insert_with(Vec::new)
+      .push(index);
+  }
+  let all_names: HashSet<_> = groups.keys().map(|&(ref name, _)| name.clone()).collect();
+  let mut used_names = all_names.clone();
+  for ((name, _), indexes) in groups {
+    if indexes.len() < 2 {
+      continue;
+    }
+    used_names.remove(&name);
+    let mut captioned_names = Vec::new();
+    for &index in &indexes {
+      let method = &methods[index];
+      let caption = method
+        .arguments
+        .return_type
+        .rust_api_type
+        .caption(&method.name)?;
+      captioned_names.push(format!("{}_{}", name, caption.to_snake_case()));
+    }
+    let captions_are_unique = captioned_names
+      .iter()
+      .all(|n| {
+             !used_names.contains(n) && captioned_names.iter().filter(|x| x == &n).count() == 1
+           });
+    for (i, &index) in indexes.iter().enumerate() {
+      let new_name = if captions_are_unique {
+        captioned_names[i].clone()
+      } else {
+        let mut suffix = i + 1;
+        while used_names.contains(&format!("{}_{}", name, suffix)) {
+          suffix += 1;
+        }
+        format!("{}_{}", name, suffix)
+      };
+      log::llog(log::DebugRustSkips, || {
+        format!("Conversion operator name collision: {}; using {} instead",
+                name,
+                new_name)
+      });
+      used_names.insert(new_name.clone());
+      let method = &mut methods[index];
+      let mut parts = method.name.parts.clone();
+      if let Some(last) = parts.last_mut() {
+        *last = new_name;
+      }
+      method.name = RustName::new(parts)?;
+    }
+  }
+  Ok(())
+}
+
+/// Detects a const and a non-const overload of a getter
+/// (e.g. `const T& data() const` and `T& data()`). Returns name suffixes
+/// for `methods` (`None` for the const method and `"mut"` for the non-const one)
+/// if `methods` consist of exactly such a pair, and `None` otherwise.
+/// Both methods must return references and have identical arguments
+/// apart from `self`.
+fn const_getter_pair_suffixes(methods: &[RustSingleMethod])
+                              -> Result<Option<Vec<Option<String>>>> {
+  if methods.len() != 2 {
+    return Ok(None);
+  }
+  let mut suffixes = Vec::new();
+  for method in methods {
+    match method.self_arg_kind()? {
+      RustMethodSelfArgKind::ConstRef => suffixes.push(None),
+      RustMethodSelfArgKind::MutRef => suffixes.push(Some("mut".to_string())),
+      _ => return Ok(None),
+    }
+    if let RustType::Common { ref indirection, .. } = method.arguments.return_type.rust_api_type {
+      if let RustTypeIndirection::Ref { .. } = *indirection {
+        continue;
+      }
+    }
+    return Ok(None);
+  }
+  if suffixes[0] == suffixes[1] {
+    return Ok(None);
+  }
+  let other_args = |method: &RustSingleMethod| -> Vec<CppType> {
+    method
+      .arguments
+      .arguments
+      .iter()
+      .filter(|arg| arg.name != "self")
+      .map(|arg| arg.argument_type.cpp_type.clone())
+      .collect()
+  };
+  if other_args(&methods[0]) != other_args(&methods[1]) {
+    return Ok(None);
+  }
+  Ok(Some(suffixes))
+}
+
 /// Prepares enum variants for being represented in Rust:
+/// - Hides variants with names listed in `blocked_names`. Their values
+/// are kept because a `#[repr(C)]` enum must be able to hold any value
+/// returned by C++;
 /// - Converts variant names to proper case;
 /// - Removes duplicate variants that have the same associated value.
 /// Rust does not allow such duplicates.
 /// - If there is only one variant, adds another variant.
 /// Rust does not allow repr(C) enums having only one variant.
-fn prepare_enum_values(values: &[CppEnumValue]) -> Vec<RustEnumValue> {
+fn prepare_enum_values(values: &[CppEnumValue], blocked_names: &[String]) -> Vec<RustEnumValue> {
   use rust_info::CppEnumValueDocItem as DocItem;
 
   let mut value_to_variant: HashMap<i64, RustEnumValue> = HashMap::new();
-  for variant in values {
+  // blocked variants are added after renaming, so that
+  // they don't affect common prefix and suffix removal
+  for variant in values.iter().filter(|v| !blocked_names.contains(&v.name)) {
     let value = variant.value;
     let doc_item = DocItem {
       variant_name: variant.name.clone(),
@@ -146,24 +451,45 @@ fn prepare_enum_values(values: &[CppEnumValue]) -> Vec<RustEnumValue> {
                        value: variant.value,
                        cpp_docs: vec![doc_item],
                        is_dummy: false,
+                       is_hidden: false,
                      });
 
       }
     }
   }
+  let mut hidden_values: Vec<RustEnumValue> = Vec::new();
+  for variant in values.iter().filter(|v| blocked_names.contains(&v.name)) {
+    if value_to_variant.contains_key(&variant.value) ||
+       hidden_values.iter().any(|v| v.value == variant.value) {
+      continue;
+    }
+    // the prefix prevents conflicts with names of visible variants
+    hidden_values.push(RustEnumValue {
+                         name: format!("_{}", variant.name.to_class_case()),
+                         value: variant.value,
+                         cpp_docs: vec![DocItem {
+                                          variant_name: variant.name.clone(),
+                                          doc: variant.doc.clone(),
+                                        }],
+                         is_dummy: false,
+                         is_hidden: true,
+                       });
+  }
   let more_than_one = value_to_variant.len() > 1;
-  let dummy_value: i64 = if value_to_variant.contains_key(&0) {
+  let dummy_value: i64 = if value_to_variant.contains_key(&0) ||
+                            hidden_values.iter().any(|v| v.value == 0) {
     1
   } else {
     0
   };
   let mut result: Vec<_> = value_to_variant.into_iter().map(|(_k, v)| v).collect();
-  if result.len() == 1 {
+  if result.len() == 1 && hidden_values.is_empty() {
     result.push(RustEnumValue {
                   name: "_Invalid".to_string(),
                   value: dummy_value,
                   cpp_docs: Vec::new(),
                   is_dummy: true,
+                  is_hidden: false,
                 });
   }
 
@@ -187,34 +513,66 @@ fn prepare_enum_values(values: &[CppEnumValue]) -> Vec<RustEnumValue> {
           common_suffix = &common_suffix[1..];
         }
       }
-      let new_names: Vec<_> = all_words
+      if all_words
+           .iter()
+           .any(|item| item.len() <= common_prefix.len() + common_suffix.len()) {
+        // removing the suffix would leave an empty name,
+        // so only the prefix can be removed
+        common_suffix = &[];
+      }
+      if all_words.iter().any(|item| item.len() <= common_prefix.len()) {
+        common_prefix = &[];
+      }
+      let new_names: Vec<String> = all_words
         .iter()
-        .map(|item| item[common_prefix.len()..item.len() - common_suffix.len()].join(""))
+        .map(|item| {
+          let name = item[common_prefix.len()..item.len() - common_suffix.len()].join("");
+          if name.chars().next().map_or(false, |ch| ch.is_digit(10)) {
+            // a name can't start with a digit, so we put back
+            // the last word of the removed prefix
+            let prefix = common_prefix.last().map_or("N", |x| *x);
+            format!("{}{}", prefix, name)
+          } else {
+            name
+          }
+        })
         .collect();
-      if new_names
-           .iter()
-           .any(|item| if let Some(ch) = item.chars().next() {
-                  ch.is_digit(10)
-                } else {
-                  true
-                }) {
-        None
-      } else {
+      let unique_names: HashSet<_> = new_names.iter().collect();
+      if unique_names.len() == new_names.len() {
         Some(new_names)
+      } else {
+        None
       }
     };
     if let Some(new_names) = new_names {
       assert_eq!(new_names.len(), result.len());
       for i in 0..new_names.len() {
-        result[i].name = sanitize_rust_identifier(&new_names[i].clone());
+        result[i].name = sanitize_rust_identifier(&new_names[i]);
       }
     }
 
   }
+  result.append(&mut hidden_values);
   result.sort_by(|a, b| a.value.cmp(&b.value));
   result
 }
 
+/// Returns name of the integer type that should be used in `#[repr]`
+/// attribute of a Rust enum with variants `values`.
+/// C++ enums are at least as large as `int`, so the result is never
+/// narrower than 32 bits to keep the enum FFI-compatible.
+pub fn enum_repr_type(values: &[RustEnumValue]) -> &'static str {
+  let min = values.iter().map(|x| x.value).min().unwrap_or(0);
+  let max = values.iter().map(|x| x.value).max().unwrap_or(0);
+  if min >= ::std::i32::MIN as i64 && max <= ::std::i32::MAX as i64 {
+    "i32"
+  } else if min >= 0 && max <= ::std::u32::MAX as i64 {
+    "u32"
+  } else {
+    "i64"
+  }
+}
+
 /// Generator of the Rust public API of the crate.
 pub struct RustGenerator<'a> {
   /// Data collected on previous step of the generator workflow
@@ -223,6 +581,54 @@ pub struct RustGenerator<'a> {
   top_module_names: HashMap<String, RustName>,
   /// Type wrappers created for this crate
   processed_types: Vec<RustProcessedTypeInfo>,
+  /// Index of `processed_types` and dependency types
+  type_index: RustTypeIndex,
+  /// All skipped items, with reasons
+  skipped: Mutex<Vec<SkippedItem>>,
+}
+
+/// Kind of an item listed in `RustGeneratorOutput::skipped`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub enum SkippedItemKind {
+  /// A C++ method that was not wrapped.
+  Method,
+  /// A C++ type that was not converted to a Rust type.
+  Type,
+  /// Any other item, e.g. a field accessor or a trait implementation.
+  Other,
+}
+
+/// A C++ method or type that was not wrapped, or a trait implementation
+/// that could not be generated for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize)]
+pub struct SkippedItem {
+  /// Kind of the item.
+  pub kind: SkippedItemKind,
+  /// Short text of the C++ method or name of the C++ type.
+  pub cpp_item: String,
+  /// Reason why the item is skipped.
+  pub reason: String,
+}
+
+/// Result of processing a C++ type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeManifestStatus {
+  /// The type was converted to a Rust type with this name.
+  Generated(RustName),
+  /// The type was not converted. Contains the reason.
+  Skipped(String),
+}
+
+/// Information about a C++ type listed in `RustGeneratorOutput::type_manifest`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypeManifestItem {
+  /// Name of the C++ type, including template arguments
+  /// for template instantiations.
+  pub cpp_type: String,
+  /// Whether the type was converted to a Rust type.
+  pub status: TypeManifestStatus,
 }
 
 /// Results of adapting API for Rust wrapper.
@@ -232,8 +638,70 @@ pub struct RustGeneratorOutput {
   pub modules: Vec<RustModule>,
   /// List of FFI function imports to be generated.
   pub ffi_functions: Vec<(String, Vec<RustFFIFunction>)>,
+  /// Names of FFI functions called by the generated Rust API.
+  /// FFI functions of skipped methods are not included.
+  pub used_ffi_functions: BTreeSet<String>,
   /// List of processed C++ types and their corresponding Rust names
   pub processed_types: Vec<RustProcessedTypeInfo>,
+  /// All items skipped during generation, including methods,
+  /// types, FFI functions and trait implementations
+  pub skipped: Vec<SkippedItem>,
+  /// All C++ types of the crate, sorted by name, with their
+  /// corresponding Rust names or reasons why they were skipped
+  pub type_manifest: Vec<TypeManifestItem>,
+}
+
+/// Kind of a generated item listed by `RustGeneratorOutput::items`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RustItemKind {
+  /// A struct, an enum or a trait.
+  Type,
+  /// A free function.
+  Function,
+  /// A method in an `impl` block of a type.
+  Method,
+  /// A trait implementation.
+  TraitImpl,
+}
+
+/// A public item of a generated module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RustItem {
+  /// Full path of the module containing the item.
+  pub module_path: String,
+  /// Full name of the item, or `impl Trait for Type` text
+  /// for trait implementations.
+  pub name: String,
+  /// Kind of the item.
+  pub kind: RustItemKind,
+}
+
+impl RustGeneratorOutput {
+  /// Returns a flat list of public items of all generated modules,
+  /// including nested modules. `crate_name` is used as the first part
+  /// of module paths.
+  pub fn items(&self, crate_name: &str) -> Vec<RustItem> {
+    let mut items = Vec::new();
+    for module in &self.modules {
+      add_module_items(module, crate_name, &mut items);
+    }
+    items
+  }
+}
+
+/// Adds public items of `module` and its submodules to `items`.
+fn add_module_items(module: &RustModule, parent_path: &str, items: &mut Vec<RustItem>) {
+  let module_path = format!("{}::{}", parent_path, module.name);
+  for_each_module_item(module, |name, kind| {
+    items.push(RustItem {
+                 module_path: module_path.clone(),
+                 name: name,
+                 kind: kind,
+               })
+  });
+  for submodule in &module.submodules {
+    add_module_items(submodule, &module_path, items);
+  }
 }
 
 // TODO: implement removal of arbitrary prefixes (#25)
@@ -253,6 +721,8 @@ pub struct RustGeneratorInputData<'a> {
   pub remove_qt_prefix: bool,
   /// List of namespaces to filter out during code generation
   pub filtered_namespaces: Vec<String>,
+  /// Settings configured in `Config`
+  pub settings: &'a RustGeneratorSettings,
 }
 
 impl<'a> RustGeneratorInputData<'a> {
@@ -263,11 +733,20 @@ impl<'a> RustGeneratorInputData<'a> {
     let mut generator = RustGenerator {
       top_module_names: HashMap::new(),
       processed_types: Vec::new(),
+      type_index: RustTypeIndex::default(),
+      skipped: Mutex::new(Vec::new()),
       input_data: self,
     };
     generator.top_module_names = generator.calc_top_module_names()?;
 
+    generator.check_flattened_namespace_collisions()?;
     generator.processed_types = generator.calc_processed_types()?;
+    generator.type_index = RustTypeIndex::new(&generator.processed_types,
+                                              &generator.input_data.dependency_types);
+    generator.check_raw_trait_impls()?;
+    generator.check_thread_safe_types()?;
+    generator.check_type_visibility()?;
+    check_box_type(generator.input_data.settings.box_type())?;
     let mut modules = Vec::new();
     {
       let mut cpp_methods: Vec<&CppAndFfiMethod> = Vec::new();
@@ -284,16 +763,9 @@ impl<'a> RustGeneratorInputData<'a> {
         .into_iter()
         .filter(|method| {
           if let Some(ref info) = method.cpp_method.class_membership {
-            if !generator
-                  .processed_types
-                  .iter()
-                  .any(|t| {
-                         t.cpp_name == info.class_type.name &&
-                         t.cpp_template_arguments == info.class_type.template_arguments
-                       }) {
-              log::llog(log::DebugRustSkips,
-                        || "Warning: method is skipped because class type is not available in Rust:");
-              log::llog(log::DebugRustSkips, || format!("{}\n", method.short_text()));
+            if !generator.type_index.crate_has_class(&info.class_type) {
+              generator.add_skipped_method(method,
+                                           "class type is not available in Rust".to_string());
               return false;
             }
           }
@@ -311,17 +783,38 @@ impl<'a> RustGeneratorInputData<'a> {
 
       let mut module_names: Vec<_> = module_names_set.into_iter().collect();
       module_names.sort();
-      let module_count = module_names.len();
-      for (i, module_name) in module_names.into_iter().enumerate() {
-        log::status(format!("({}/{}) Generating module: {}",
-                            i + 1,
-                            module_count,
-                            module_name));
-        let full_module_name = RustName::new(vec![generator.input_data.crate_name.clone(),
-                                                  module_name])?;
-        let (module, tmp_cpp_methods) = generator
-          .generate_module(cpp_methods, &full_module_name)?;
-        cpp_methods = tmp_cpp_methods;
+
+      // Methods are partitioned by top level module in advance
+      // so that modules can be generated independently.
+      let mut module_methods: HashMap<String, Vec<&CppAndFfiMethod>> = HashMap::new();
+      for method in cpp_methods {
+        let module_name = generator.top_module_name_for_method(method)?;
+        module_methods
+          .entry(module_name)
+          .or_insert_with(Vec::new)
+          .push(method);
+      }
+      let module_inputs: Vec<_> = module_names
+        .into_iter()
+        .map(|name| {
+               let methods = module_methods.remove(&name).unwrap_or_default();
+               (name, methods)
+             })
+        .collect();
+      let module_count = module_inputs.len();
+      let results: Vec<_> = module_inputs
+        .into_par_iter()
+        .map(|(module_name, methods)| {
+          log::status(format!("Generating module: {} ({} total)", module_name, module_count));
+          let full_module_name = RustName::new(vec![generator.input_data.crate_name.clone(),
+                                                    module_name])?;
+          generator.generate_module(methods, &full_module_name)
+        })
+        .collect();
+      cpp_methods = Vec::new();
+      for result in results {
+        let (module, mut remaining_methods) = result?;
+        cpp_methods.append(&mut remaining_methods);
         if let Some(module) = module {
           modules.push(module);
         }
@@ -345,20 +838,70 @@ impl<'a> RustGeneratorInputData<'a> {
         return Err(unexpected("unprocessed cpp methods left").into());
       }
     }
-    let mut any_not_declared = false;
+    for hook in &generator.input_data.settings.module_tree_hooks() {
+      hook(&mut modules).chain_err(|| "module tree hook failed")?;
+    }
+    let crate_name = RustName::new(vec![generator.input_data.crate_name.clone()])?;
+    check_module_paths(&modules, &crate_name)
+      .chain_err(|| "module tree hook changed paths of items")?;
+    // types removed from the module tree are skipped
+    // unless other items still refer to them
+    let mut used_names = BTreeSet::new();
+    for module in &modules {
+      used_names.extend(used_type_names(module));
+    }
+    let mut removed_names = BTreeSet::new();
     for type1 in &generator.processed_types {
       if !type1.is_declared_in(&modules) {
-        log::error(format!("type is not processed: {:?}", type1));
-        any_not_declared = true;
+        removed_names.insert(type1.rust_name.clone());
+        if used_names.contains(&type1.rust_name) {
+          log::error(format!("type is not processed: {:?}", type1));
+          return Err(unexpected(format!("type {} is used but not declared in any module",
+                                        type1.rust_name.full_name(None)))
+                         .into());
+        }
+        generator.add_skipped_type(CppTypeClassBase {
+                                       name: type1.cpp_name.clone(),
+                                       template_arguments: type1.cpp_template_arguments.clone(),
+                                     }
+                                     .to_cpp_pseudo_code(),
+                                   "type is not declared in any module".to_string());
       }
     }
-    if any_not_declared {
-      return Err(unexpected("unprocessed cpp types left").into());
+    // FFI functions of removed types are not generated
+    let mut ffi_functions = generator.generate_ffi_functions();
+    for &mut (_, ref mut functions) in &mut ffi_functions {
+      functions.retain(|function| {
+                         ffi_function_type_names(function)
+                           .intersection(&removed_names)
+                           .next()
+                           .is_none()
+                       });
+    }
+    generator
+      .processed_types
+      .retain(|type1| !removed_names.contains(&type1.rust_name));
+    let private_type_names: Vec<_> = generator
+      .processed_types
+      .iter()
+      .filter(|t| !t.is_public)
+      .map(|t| t.rust_name.clone())
+      .collect();
+    if !private_type_names.is_empty() {
+      for module in &modules {
+        check_private_types_usage(module, &private_type_names)?;
+      }
     }
     Ok(RustGeneratorOutput {
-         ffi_functions: generator.generate_ffi_functions(),
+         ffi_functions: ffi_functions,
+         used_ffi_functions: used_ffi_functions(&modules),
          modules: modules,
          processed_types: generator.processed_types,
+         type_manifest: generator.type_manifest(),
+         skipped: generator
+           .skipped
+           .into_inner()
+           .expect("skipped mutex is poisoned"),
        })
   }
 }
@@ -380,1220 +923,1720 @@ struct ProcessFunctionsResult {
   trait_impls: Vec<TraitImpl>,
   /// Rust declarations of the types created for overloading emulation.
   overloading_types: Vec<RustTypeDeclaration>,
+  /// `write_to` methods generated from free stream `operator<<` functions
+  /// that must be added to the written types.
+  stream_write_methods: Vec<RustMethod>,
 }
 
-/// Generates `CompleteType` from `CppFfiType`, adding
-/// Rust API type, Rust FFI type and conversion between them.
-fn complete_type(processed_types: &[RustProcessedTypeInfo],
-                 dependency_types: &[&[RustProcessedTypeInfo]],
-                 cpp_ffi_type: &CppFfiType,
-                 argument_meaning: &CppFfiArgumentMeaning,
-                 is_template_argument: bool,
-                 allocation_place: &ReturnValueAllocationPlace)
-                 -> Result<CompleteType> {
-  let rust_ffi_type = ffi_type(processed_types, dependency_types, &cpp_ffi_type.ffi_type)?;
-  let mut rust_api_type = rust_ffi_type.clone();
-  let mut rust_api_to_c_conversion = RustToCTypeConversion::None;
-  if let RustType::Common {
-           ref mut indirection,
-           ref mut base,
-           ref mut generic_arguments,
-           ref mut is_const,
-           ref mut is_const2,
-         } = rust_api_type {
-    match cpp_ffi_type.conversion {
-      CppIndirectionChange::NoChange => {
-        if argument_meaning == &CppFfiArgumentMeaning::This {
-          assert!(indirection == &RustTypeIndirection::Ptr);
-          *indirection = RustTypeIndirection::Ref { lifetime: None };
-          rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
-        }
-      }
-      CppIndirectionChange::ValueToPointer => {
-        assert!(indirection == &RustTypeIndirection::Ptr);
-        if argument_meaning == &CppFfiArgumentMeaning::ReturnValue {
-          if let Some(info) = find_type_info(processed_types,
-                                             dependency_types,
-                                             |x| &x.rust_name == base) {
-            match info.kind {
-              RustTypeWrapperKind::Struct { ref is_deletable, .. } => {
-                if !*is_deletable {
-                  return Err(format!("{} is not deletable", base.full_name(None)).into());
-                }
-              }
-              RustTypeWrapperKind::Enum { .. } => {
-                return Err(unexpected("class type expected here").into())
-              }
-            }
-          } else {
-            return Err(unexpected("find_type_info failed in complete_type() after success in \
-                                   ffi_type()")
-                           .into());
-          }
-          match *allocation_place {
-            ReturnValueAllocationPlace::Stack => {
-              *indirection = RustTypeIndirection::None;
-              rust_api_to_c_conversion = RustToCTypeConversion::ValueToPtr;
-            }
-            ReturnValueAllocationPlace::Heap => {
-              *indirection = RustTypeIndirection::None;
-              rust_api_to_c_conversion = RustToCTypeConversion::CppBoxToPtr;
-              assert!(generic_arguments.is_none());
-              assert!(!*is_const);
-              assert!(!*is_const2);
-              let new_generic_argument = RustType::Common {
-                base: base.clone(),
-                generic_arguments: None,
-                is_const: false,
-                is_const2: false,
-                indirection: RustTypeIndirection::None,
-              };
-              *base = RustName::new(vec!["cpp_utils".to_string(), "CppBox".to_string()])?;
-              *generic_arguments = Some(vec![new_generic_argument]);
+/// Returns true if `method` is a free `operator<<` function
+/// writing to a `QDataStream` or a `QTextStream`.
+fn is_stream_write_operator(method: &CppMethod) -> bool {
+  if method.class_membership.is_some() ||
+     method.operator != Some(CppOperator::BitwiseLeftShift) ||
+     method.arguments.len() != 2 {
+    return false;
+  }
+  let stream_type = &method.arguments[0].argument_type;
+  if stream_type.indirection != CppTypeIndirection::Ref || stream_type.is_const {
+    return false;
+  }
+  if let CppTypeBase::Class(ref class_base) = stream_type.base {
+    class_base.template_arguments.is_none() &&
+    ["QDataStream", "QTextStream"].contains(&class_base.name.as_str())
+  } else {
+    false
+  }
+}
 
-            }
-            ReturnValueAllocationPlace::NotApplicable => {
-              return Err(unexpected("NotApplicable conflicts with ValueToPointer").into());
-            }
-          }
-        } else {
-          if is_template_argument {
-            *indirection = RustTypeIndirection::None;
-            rust_api_to_c_conversion = RustToCTypeConversion::ValueToPtr;
-          } else {
-            *indirection = RustTypeIndirection::Ref { lifetime: None };
-            rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
-          }
-          *is_const = true;
-          *is_const2 = true;
+/// Returns Rust API types of the stream and the written value
+/// of a wrapper of a free stream `operator<<` function.
+fn stream_write_operator_types(method: &RustSingleMethod) -> Result<(RustType, RustType)> {
+  let args = &method.arguments.arguments;
+  if args.len() != 2 || args[0].ffi_index != 0 || args[1].ffi_index != 1 ||
+     method.arguments.return_type_ffi_index.is_some() {
+    return Err("unexpected arguments of the FFI function".into());
+  }
+  for (arg, expected_const) in args.iter().zip(&[false, true]) {
+    let is_ref = match arg.argument_type.rust_api_type {
+      RustType::Common {
+        indirection: RustTypeIndirection::Ref { .. },
+        ref is_const,
+        ..
+      } => is_const == expected_const,
+      _ => false,
+    };
+    if !is_ref || arg.argument_type.rust_api_to_c_conversion != RustToCTypeConversion::RefToPtr {
+      return Err(format!("argument {} must be a {} reference",
+                         arg.name,
+                         if *expected_const { "const" } else { "mutable" })
+                     .into());
+    }
+  }
+  Ok((args[0].argument_type.rust_api_type.clone(), args[1].argument_type.rust_api_type.clone()))
+}
+
+/// Returns true if `rust_type` or any of its generic arguments
+/// refers to the type `name`.
+fn rust_type_uses_name(rust_type: &RustType, name: &RustName) -> bool {
+  match *rust_type {
+    RustType::EmptyTuple => false,
+    RustType::Common {
+      ref base,
+      ref generic_arguments,
+      ..
+    } => {
+      base == name ||
+      generic_arguments
+        .as_ref()
+        .map_or(false, |args| args.iter().any(|arg| rust_type_uses_name(arg, name)))
+    }
+    RustType::FunctionPointer {
+      ref return_type,
+      ref arguments,
+    } => {
+      rust_type_uses_name(return_type, name) ||
+      arguments.iter().any(|arg| rust_type_uses_name(arg, name))
+    }
+    RustType::Tuple(ref items) => items.iter().any(|item| rust_type_uses_name(item, name)),
+  }
+}
+
+/// Returns names of FFI functions called by the generated code
+/// of `modules` and their submodules.
+pub fn used_ffi_functions<'a, I: IntoIterator<Item = &'a RustModule>>(modules: I)
+                                                                     -> BTreeSet<String> {
+  fn add_variant(result: &mut BTreeSet<String>, variant: &RustMethodArgumentsVariant) {
+    result.insert(variant.cpp_method.c_name.clone());
+    if let Some(ref name) = variant.validity_check_function {
+      result.insert(name.clone());
+    }
+  }
+  fn add_methods(result: &mut BTreeSet<String>, methods: &[RustMethod]) {
+    for method in methods {
+      // variants of overloaded methods are listed in their parameters traits
+      if let RustMethodArguments::SingleVariant(ref variant) = method.arguments {
+        add_variant(result, variant);
+      }
+    }
+  }
+  fn add_trait_impls(result: &mut BTreeSet<String>, trait_impls: &[TraitImpl]) {
+    for trait1 in trait_impls {
+      match trait1.extra {
+        Some(TraitImplExtra::CppDeletable { ref deleter_name }) => {
+          result.insert(deleter_name.clone());
+        }
+        Some(TraitImplExtra::QtHashFunction { ref ffi_function_name, .. }) |
+        Some(TraitImplExtra::NarrowingConversionTryFrom { ref ffi_function_name }) |
+        Some(TraitImplExtra::StreamWriteOperator { ref ffi_function_name }) |
+        Some(TraitImplExtra::StringFromUtf8 { ref ffi_function_name }) => {
+          result.insert(ffi_function_name.clone());
         }
+        _ => {}
       }
-      CppIndirectionChange::ReferenceToPointer => {
-        match *indirection {
-          RustTypeIndirection::Ptr => {
-            *indirection = RustTypeIndirection::Ref { lifetime: None };
+      add_methods(result, &trait1.methods);
+    }
+  }
+  fn add_module(result: &mut BTreeSet<String>, module: &RustModule) {
+    for type1 in &module.types {
+      match type1.kind {
+        RustTypeDeclarationKind::CppTypeWrapper {
+          ref methods,
+          ref trait_impls,
+          ref iterator_adapter,
+          ..
+        } => {
+          add_methods(result, methods);
+          add_trait_impls(result, trait_impls);
+          if let Some(ref adapter) = *iterator_adapter {
+            result.insert(adapter.increment_function.clone());
+            result.insert(adapter.indirection_function.clone());
+            result.insert(adapter.not_equal_function.clone());
           }
-          RustTypeIndirection::PtrPtr => {
-            *indirection = RustTypeIndirection::PtrRef { lifetime: None };
+        }
+        RustTypeDeclarationKind::MethodParametersTrait { ref impls, .. } => {
+          for variant in impls {
+            add_variant(result, variant);
           }
-          _ => return Err(unexpected("invalid indirection for ReferenceToPointer").into()),
         }
-        rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
       }
-      CppIndirectionChange::QFlagsToUInt => {}
+    }
+    add_methods(result, &module.functions);
+    add_trait_impls(result, &module.trait_impls);
+    for submodule in &module.submodules {
+      add_module(result, submodule);
     }
   }
-  if cpp_ffi_type.conversion == CppIndirectionChange::QFlagsToUInt {
-    rust_api_to_c_conversion = RustToCTypeConversion::QFlagsToUInt;
-    let enum_type = if let CppTypeBase::Class(CppTypeClassBase {
-                                                ref template_arguments, ..
-                                              }) = cpp_ffi_type.original_type.base {
-      let args = template_arguments
-        .as_ref()
-        .chain_err(|| "QFlags type must have template arguments")?;
-      if args.len() != 1 {
-        return Err("QFlags type must have exactly 1 template argument".into());
-      }
-      if let CppTypeBase::Enum { ref name } = args[0].base {
-        match find_type_info(processed_types, dependency_types, |x| &x.cpp_name == name) {
-          None => return Err(format!("type has no Rust equivalent: {}", name).into()),
-          Some(info) => info.rust_name.clone(),
+
+  let mut result = BTreeSet::new();
+  for module in modules {
+    add_module(&mut result, module);
+  }
+  result
+}
+
+/// Adds names of `rust_type` and all types nested in it
+/// (e.g. generic arguments) to `result`.
+fn add_type_names(result: &mut BTreeSet<RustName>, rust_type: &RustType) {
+  match *rust_type {
+    RustType::EmptyTuple => {}
+    RustType::Common {
+      ref base,
+      ref generic_arguments,
+      ..
+    } => {
+      result.insert(base.clone());
+      if let Some(ref args) = *generic_arguments {
+        for arg in args {
+          add_type_names(result, arg);
         }
-      } else {
-        return Err(unexpected("invalid original type for QFlags").into());
       }
-    } else {
-      return Err(unexpected("invalid original type for QFlags").into());
-    };
-    rust_api_type = RustType::Common {
-      base: RustName::new(vec!["qt_core".to_string(),
-                               "flags".to_string(),
-                               "Flags".to_string()])?,
-      generic_arguments: Some(vec![RustType::Common {
-                                     base: enum_type,
-                                     generic_arguments: None,
-                                     indirection: RustTypeIndirection::None,
-                                     is_const: false,
-                                     is_const2: false,
-                                   }]),
-      indirection: RustTypeIndirection::None,
-      is_const: false,
-      is_const2: false,
+    }
+    RustType::FunctionPointer {
+      ref return_type,
+      ref arguments,
+    } => {
+      add_type_names(result, return_type);
+      for arg in arguments {
+        add_type_names(result, arg);
+      }
+    }
+    RustType::Tuple(ref items) => {
+      for item in items {
+        add_type_names(result, item);
+      }
     }
   }
-
-  Ok(CompleteType {
-       cpp_ffi_type: cpp_ffi_type.ffi_type.clone(),
-       cpp_type: cpp_ffi_type.original_type.clone(),
-       cpp_to_ffi_conversion: cpp_ffi_type.conversion.clone(),
-       rust_ffi_type: rust_ffi_type,
-       rust_api_type: rust_api_type,
-       rust_api_to_c_conversion: rust_api_to_c_conversion,
-     })
 }
 
-fn find_type_info<'a, F>(processed_types: &'a [RustProcessedTypeInfo],
-                         dependency_types: &'a [&'a [RustProcessedTypeInfo]],
-                         f: F)
-                         -> Option<&'a RustProcessedTypeInfo>
-  where F: Fn(&RustProcessedTypeInfo) -> bool
-{
-  match processed_types.iter().find(|x| f(x)) {
-    None => {
-      for dep in dependency_types {
-        if let Some(r) = dep.iter().find(|x| f(x)) {
-          return Some(r);
-        }
-      }
-      None
-    }//dependency_types.iter().find(|x| x.iter().find(|y| f(y))),
-    Some(info) => Some(info),
+/// Returns names of all types used in the signature of FFI function `function`.
+pub fn ffi_function_type_names(function: &RustFFIFunction) -> BTreeSet<RustName> {
+  let mut result = BTreeSet::new();
+  add_type_names(&mut result, &function.return_type);
+  for arg in &function.arguments {
+    add_type_names(&mut result, &arg.argument_type);
   }
+  result
 }
 
-/// Converts `CppType` to its exact Rust equivalent (FFI-compatible)
-fn ffi_type(processed_types: &[RustProcessedTypeInfo],
-            dependency_types: &[&[RustProcessedTypeInfo]],
-            cpp_ffi_type: &CppType)
-            -> Result<RustType> {
-  let rust_name = match cpp_ffi_type.base {
-    CppTypeBase::Void => {
-      match cpp_ffi_type.indirection {
-        CppTypeIndirection::None => return Ok(RustType::EmptyTuple),
-        _ => RustName::new(vec!["libc".to_string(), "c_void".to_string()])?,
+/// Returns names of all types used in signatures of methods, functions
+/// and trait implementations of `module` and its submodules,
+/// including generic arguments and element types of template instantiations.
+pub fn used_type_names(module: &RustModule) -> BTreeSet<RustName> {
+  fn add_complete_type(result: &mut BTreeSet<RustName>, complete_type: &CompleteType) {
+    add_type_names(result, &complete_type.rust_api_type);
+    add_type_names(result, &complete_type.rust_ffi_type);
+  }
+  fn add_arguments(result: &mut BTreeSet<RustName>, arguments: &[RustMethodArgument]) {
+    for arg in arguments {
+      add_complete_type(result, &arg.argument_type);
+    }
+  }
+  fn add_variant(result: &mut BTreeSet<RustName>, variant: &RustMethodArgumentsVariant) {
+    add_arguments(result, &variant.arguments);
+    add_complete_type(result, &variant.return_type);
+  }
+  fn add_methods(result: &mut BTreeSet<RustName>, methods: &[RustMethod]) {
+    for method in methods {
+      match method.arguments {
+        RustMethodArguments::SingleVariant(ref variant) => add_variant(result, variant),
+        RustMethodArguments::MultipleVariants {
+          ref shared_arguments,
+          ref common_return_type,
+          ..
+        } => {
+          add_arguments(result, shared_arguments);
+          if let Some(ref return_type) = *common_return_type {
+            add_type_names(result, return_type);
+          }
+        }
       }
     }
-    CppTypeBase::BuiltInNumeric(ref numeric) => {
-      if numeric == &CppBuiltInNumericType::Bool {
-        RustName::new(vec!["bool".to_string()])?
-      } else {
-        let own_name = match *numeric {
-          CppBuiltInNumericType::Bool => unreachable!(),
-          CppBuiltInNumericType::Char => "c_char",
-          CppBuiltInNumericType::SChar => "c_schar",
-          CppBuiltInNumericType::UChar => "c_uchar",
-          CppBuiltInNumericType::WChar => "wchar_t",
-          CppBuiltInNumericType::Short => "c_short",
-          CppBuiltInNumericType::UShort => "c_ushort",
-          CppBuiltInNumericType::Int => "c_int",
-          CppBuiltInNumericType::UInt => "c_uint",
-          CppBuiltInNumericType::Long => "c_long",
-          CppBuiltInNumericType::ULong => "c_ulong",
-          CppBuiltInNumericType::LongLong => "c_longlong",
-          CppBuiltInNumericType::ULongLong => "c_ulonglong",
-          CppBuiltInNumericType::Float => "c_float",
-          CppBuiltInNumericType::Double => "c_double",
-          _ => return Err(format!("unsupported numeric type: {:?}", numeric).into()),
-        };
-        RustName::new(vec!["libc".to_string(), own_name.to_string()])?
+  }
+  fn add_trait_impls(result: &mut BTreeSet<RustName>, trait_impls: &[TraitImpl]) {
+    for trait1 in trait_impls {
+      add_type_names(result, &trait1.target_type);
+      add_type_names(result, &trait1.trait_type);
+      for associated_type in &trait1.associated_types {
+        add_type_names(result, &associated_type.value);
       }
+      add_methods(result, &trait1.methods);
     }
-    CppTypeBase::SpecificNumeric(CppSpecificNumericType { ref bits, ref kind, .. }) => {
-      let letter = match *kind {
-        CppSpecificNumericTypeKind::Integer { ref is_signed } => if *is_signed { "i" } else { "u" },
-        CppSpecificNumericTypeKind::FloatingPoint => "f",
-      };
-      RustName::new(vec![format!("{}{}", letter, bits)])?
+  }
+  fn add_module(result: &mut BTreeSet<RustName>, module: &RustModule) {
+    for type1 in &module.types {
+      match type1.kind {
+        RustTypeDeclarationKind::CppTypeWrapper {
+          ref kind,
+          ref methods,
+          ref constants,
+          ref trait_impls,
+          ref qt_receivers,
+          ref qt_hash_types,
+          ref vector_info,
+          ref list_element_type,
+          ref transparent_type,
+          ref iterator_adapter,
+          ..
+        } => {
+          add_methods(result, methods);
+          add_trait_impls(result, trait_impls);
+          for constant in constants {
+            add_type_names(result, &constant.value_type);
+          }
+          for receiver in qt_receivers {
+            for arg in &receiver.arguments {
+              add_type_names(result, arg);
+            }
+          }
+          if let RustTypeWrapperKind::Struct { slot_wrapper: Some(ref slot_wrapper), .. } = *kind {
+            for arg in &slot_wrapper.arguments {
+              add_complete_type(result, arg);
+            }
+            add_complete_type(result, &slot_wrapper.return_type);
+          }
+          if let Some(ref types) = *qt_hash_types {
+            add_type_names(result, &types.key_type);
+            add_type_names(result, &types.value_type);
+          }
+          if let Some(ref adapter) = *iterator_adapter {
+            add_type_names(result, &adapter.iterator_type);
+            add_type_names(result, &adapter.item_type);
+          }
+          let element_types = vector_info
+            .iter()
+            .map(|info| &info.element_type)
+            .chain(list_element_type.iter())
+            .chain(transparent_type.iter());
+          for element_type in element_types {
+            add_type_names(result, element_type);
+          }
+        }
+        RustTypeDeclarationKind::MethodParametersTrait {
+          ref shared_arguments,
+          ref common_return_type,
+          ref impls,
+          ..
+        } => {
+          add_arguments(result, shared_arguments);
+          if let Some(ref return_type) = *common_return_type {
+            add_type_names(result, return_type);
+          }
+          for variant in impls {
+            add_variant(result, variant);
+          }
+        }
+      }
     }
-    CppTypeBase::PointerSizedInteger { ref is_signed, .. } => {
-      RustName::new(vec![if *is_signed { "isize" } else { "usize" }.to_string()])?
+    add_methods(result, &module.functions);
+    add_trait_impls(result, &module.trait_impls);
+    for submodule in &module.submodules {
+      add_module(result, submodule);
     }
-    CppTypeBase::Enum { ref name } => {
-      match find_type_info(processed_types, dependency_types, |x| &x.cpp_name == name) {
-        None => return Err(format!("type has no Rust equivalent: {}", name).into()),
-        Some(info) => info.rust_name.clone(),
+  }
+
+  let mut result = BTreeSet::new();
+  add_module(&mut result, module);
+  result
+}
+
+/// Returns an error if a type or a free function in `modules` is not
+/// declared directly in the module its full name refers to. Other items
+/// refer to types and functions by their full names, so a module tree hook
+/// can't rename or move modules, types and free functions.
+fn check_module_paths(modules: &[RustModule], parent: &RustName) -> Result<()> {
+  for module in modules {
+    let mut parts = parent.parts.clone();
+    parts.push(module.name.clone());
+    let module_name = RustName::new(parts)?;
+    let names = module
+      .types
+      .iter()
+      .map(|t| &t.name)
+      .chain(module.functions.iter().map(|f| &f.name));
+    for name in names {
+      if !module_name.includes_directly(name) {
+        return Err(format!("{} is declared in module {}",
+                           name.full_name(None),
+                           module_name.full_name(None))
+                     .into());
       }
     }
-    CppTypeBase::Class(ref name_and_args) => {
-      match find_type_info(processed_types, dependency_types, |x| {
-        &x.cpp_name == &name_and_args.name &&
-        &x.cpp_template_arguments == &name_and_args.template_arguments
-      }) {
-        None => return Err(format!("type has no Rust equivalent: {:?}", name_and_args).into()),
-        Some(info) => info.rust_name.clone(),
-      }
+    check_module_paths(&module.submodules, &module_name)?;
+  }
+  Ok(())
+}
+
+/// Returns an error if any public method or function in `module`
+/// (or its submodules) uses one of `private_type_names` in its signature.
+/// Such code would not compile because crate-private types can't
+/// appear in public interfaces.
+fn check_private_types_usage(module: &RustModule, private_type_names: &[RustName]) -> Result<()> {
+  let check_type = |rust_type: &RustType, item_name: &RustName| -> Result<()> {
+    if let Some(name) = private_type_names
+         .iter()
+         .find(|name| rust_type_uses_name(rust_type, name)) {
+      return Err(format!("crate-private type {} is used in public API: {}",
+                         name.full_name(None),
+                         item_name.full_name(None))
+                   .into());
     }
-    CppTypeBase::FunctionPointer(CppFunctionPointerType {
-                                   ref return_type,
-                                   ref arguments,
-                                   ref allows_variadic_arguments,
-                                 }) => {
-      if *allows_variadic_arguments {
-        return Err("function pointers with variadic arguments are not supported".into());
-      }
-      let mut rust_args = Vec::new();
-      for arg in arguments {
-        rust_args.push(ffi_type(processed_types, dependency_types, arg)?);
+    Ok(())
+  };
+  // Implementations of parameters traits for overloaded methods
+  // may use private types, so only shared arguments and
+  // the common return type are checked for them.
+  let check_signature = |arguments: &[RustMethodArgument],
+                         return_type: Option<&RustType>,
+                         item_name: &RustName|
+   -> Result<()> {
+    for arg in arguments {
+      check_type(&arg.argument_type.rust_api_type, item_name)?;
+    }
+    if let Some(return_type) = return_type {
+      check_type(return_type, item_name)?;
+    }
+    Ok(())
+  };
+  let check_method = |method: &RustMethod| -> Result<()> {
+    match method.arguments {
+      RustMethodArguments::SingleVariant(ref variant) => {
+        check_signature(&variant.arguments,
+                        Some(&variant.return_type.rust_api_type),
+                        &method.name)
       }
-      let rust_return_type = ffi_type(processed_types, dependency_types, return_type)?;
-      return Ok(RustType::FunctionPointer {
-                  arguments: rust_args,
-                  return_type: Box::new(rust_return_type),
-                });
+      RustMethodArguments::MultipleVariants {
+        ref shared_arguments,
+        ref common_return_type,
+        ..
+      } => check_signature(shared_arguments, common_return_type.as_ref(), &method.name),
     }
-    CppTypeBase::TemplateParameter { .. } => return Err(unexpected("invalid cpp type").into()),
   };
+  for type1 in module.types.iter().filter(|t| t.is_public) {
+    match type1.kind {
+      RustTypeDeclarationKind::CppTypeWrapper { ref methods, .. } => {
+        for method in methods {
+          check_method(method)?;
+        }
+      }
+      RustTypeDeclarationKind::MethodParametersTrait {
+        ref shared_arguments,
+        ref common_return_type,
+        ..
+      } => check_signature(shared_arguments, common_return_type.as_ref(), &type1.name)?,
+    }
+  }
+  for function in &module.functions {
+    check_method(function)?;
+  }
+  for submodule in &module.submodules {
+    check_private_types_usage(submodule, private_type_names)?;
+  }
+  Ok(())
+}
+
+/// Returns `qt_core::flags::Flags` type for enum `enum_name`.
+fn qt_flags_type(enum_name: RustName) -> Result<RustType> {
   Ok(RustType::Common {
-       base: rust_name,
-       is_const: cpp_ffi_type.is_const,
-       is_const2: cpp_ffi_type.is_const2,
-       indirection: match cpp_ffi_type.indirection {
-         CppTypeIndirection::None => RustTypeIndirection::None,
-         CppTypeIndirection::Ptr => RustTypeIndirection::Ptr,
-         CppTypeIndirection::PtrPtr => RustTypeIndirection::PtrPtr,
-         _ => {
-           return Err(format!("invalid FFI type indirection: {:?}",
-                              cpp_ffi_type.indirection)
-                          .into())
-         }
-       },
-       generic_arguments: None,
+       base: RustName::new(vec!["qt_core".to_string(),
+                                "flags".to_string(),
+                                "Flags".to_string()])?,
+       generic_arguments: Some(vec![RustType::Common {
+                                      base: enum_name,
+                                      generic_arguments: None,
+                                      indirection: RustTypeIndirection::None,
+                                      is_const: false,
+                                      is_const2: false,
+                                    }]),
+       indirection: RustTypeIndirection::None,
+       is_const: false,
+       is_const2: false,
      })
 }
 
+/// Returns the default wrapper type for heap-allocated objects (`cpp_utils::CppBox`).
+pub fn default_box_type() -> RustName {
+  RustName { parts: vec!["cpp_utils".to_string(), "CppBox".to_string()] }
+}
 
-impl<'aa> RustGenerator<'aa> {
-  fn calc_top_module_names(&self) -> Result<HashMap<String, RustName>> {
-    let mut result = HashMap::new();
-    {
-      let mut check_header = |header: &str| -> Result<()> {
-        if !result.contains_key(header) {
-          let mut parts = Vec::new();
-          parts.push(self.input_data.crate_name.clone());
-          parts.push(include_file_to_module_name(header, self.input_data.remove_qt_prefix));
-          result.insert(header.to_string(), RustName::new(parts)?);
+/// Returns acronyms kept as single words in snake case names by default.
+pub fn default_acronyms() -> Vec<String> {
+  ["URL", "URI", "HTML", "XML", "JSON", "HTTP", "UUID", "RGB", "RGBA", "DPI", "SQL", "SSL"]
+    .iter()
+    .map(|x| x.to_string())
+    .collect()
+}
+
+/// Checks that `box_type` set by `Config::set_box_type` is a full path
+/// to a type that can be used with exactly one generic argument.
+fn check_box_type(box_type: &RustName) -> Result<()> {
+  if box_type.parts.len() < 2 {
+    return Err(format!("box type must be a full path: {}", box_type.full_name(None)).into());
+  }
+  if box_type
+       .parts
+       .iter()
+       .any(|part| part.is_empty() || part.contains('<') || part.contains('>')) {
+    return Err(format!("box type must be a path without generic arguments \
+                        (exactly one argument will be added by the generator): {}",
+                       box_type.parts.join("::"))
+                   .into());
+  }
+  Ok(())
+}
+
+/// Returns implementation of `From<E>` for `qt_core::flags::Flags<E>`
+/// where `E` is a flaggable enum `enum_name`.
+pub fn flags_from_enum_impl(enum_name: &RustName) -> Result<TraitImpl> {
+  let flags_type = qt_flags_type(enum_name.clone())?;
+  let enum_type = match flags_type {
+    RustType::Common { generic_arguments: Some(ref args), .. } => args[0].clone(),
+    _ => unreachable!(),
+  };
+  Ok(TraitImpl {
+       target_type: flags_type.clone(),
+       associated_types: Vec::new(),
+       trait_type: RustType::Common {
+         base: RustName::new(vec!["std".to_string(), "convert".to_string(), "From".to_string()])?,
+         indirection: RustTypeIndirection::None,
+         is_const: false,
+         is_const2: false,
+         generic_arguments: Some(vec![enum_type]),
+       },
+       extra: Some(TraitImplExtra::FlagsFromEnum),
+       methods: Vec::new(),
+     })
+}
+
+/// Returns true if a class with no base classes and `fields` has no padding bytes,
+/// so its values can be compared bytewise. All fields must be integers, enums or
+/// pointers with known sizes, and fields are assumed to be aligned to their size.
+/// Floating point fields are not allowed because bytewise comparison
+/// differs from their `PartialEq` implementation.
+fn is_padding_free_layout(fields: &[CppClassField]) -> bool {
+  let mut offset = 0;
+  let mut max_alignment = 1;
+  for field in fields {
+    let is_scalar = match field.field_type.indirection {
+      CppTypeIndirection::None => {
+        match field.field_type.base {
+          CppTypeBase::BuiltInNumeric(ref t) => !t.is_float(),
+          CppTypeBase::SpecificNumeric(CppSpecificNumericType {
+                                         kind: CppSpecificNumericTypeKind::Integer { .. }, ..
+                                       }) |
+          CppTypeBase::PointerSizedInteger { .. } |
+          CppTypeBase::Enum { .. } => true,
+          _ => false,
         }
-        Ok(())
-      };
-      for header in self.input_data.cpp_data.all_include_files()? {
-        check_header(&header)?;
       }
-      for header in &self.input_data.cpp_ffi_headers {
-        check_header(&header.include_file_base_name)?;
+      CppTypeIndirection::Ptr => true,
+      _ => false,
+    };
+    let size = match field.size {
+      Some(size) if size > 0 => size,
+      _ => return false,
+    };
+    if !is_scalar || offset % size != 0 {
+      return false;
+    }
+    offset += size;
+    if size > max_alignment {
+      max_alignment = size;
+    }
+  }
+  offset % max_alignment == 0
+}
+
+/// Removes trait implementations that conflict with `Clone` and `Copy`
+/// derived for a type marked with `Config::set_pod_type`. `Clone` is derived
+/// instead of calling the copy constructor, and `Drop` can't be implemented
+/// for `Copy` types, so the destructor is not called.
+fn remove_pod_trait_impls(trait_impls: &mut Vec<TraitImpl>) -> Result<()> {
+  let removed_traits = [RustName::new(vec!["Clone".to_string()])?,
+                        RustName::new(vec!["Drop".to_string()])?];
+  trait_impls.retain(|trait_impl| match trait_impl.trait_type {
+                       RustType::Common { ref base, .. } => !removed_traits.contains(base),
+                       _ => true,
+                     });
+  Ok(())
+}
+
+/// Returns implementations of `Eq` and `Ord` for type `type_name` marked
+/// with `Config::mark_total_order`. `trait_impls` are trait implementations
+/// generated for the type, and `raw_trait_impls` is code added for it by
+/// `Config::add_raw_trait_impl`. Returns an error if `PartialEq` or `PartialOrd`
+/// is not implemented by any of them or by `pod_traits`.
+pub fn total_order_impls(type_name: &RustName,
+                         trait_impls: &[TraitImpl],
+                         raw_trait_impls: &[String],
+                         pod_traits: Option<&RustPodTraits>)
+                         -> Result<Vec<TraitImpl>> {
+  let is_implemented = |trait_name: &str| {
+    trait_impls
+      .iter()
+      .any(|trait_impl| match trait_impl.trait_type {
+             RustType::Common { ref base, .. } => {
+               base.last_name().ok().map_or(false, |name| name == trait_name)
+             }
+             _ => false,
+           }) ||
+    raw_trait_impls
+      .iter()
+      .any(|code| code.contains(&format!("{} for", trait_name)))
+  };
+  if !is_implemented("PartialEq") && !pod_traits.map_or(false, |traits| traits.partial_eq) {
+    return Err(format!("total order type {} doesn't implement PartialEq",
+                       type_name.full_name(None))
+                   .into());
+  }
+  if !is_implemented("PartialOrd") {
+    return Err(format!("total order type {} doesn't implement PartialOrd",
+                       type_name.full_name(None))
+                   .into());
+  }
+  let target_type = RustType::Common {
+    base: type_name.clone(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let cmp_trait = |name: &str| -> Result<RustType> {
+    Ok(RustType::Common {
+         base: RustName::new(vec!["std".to_string(), "cmp".to_string(), name.to_string()])?,
+         generic_arguments: None,
+         indirection: RustTypeIndirection::None,
+         is_const: false,
+         is_const2: false,
+       })
+  };
+  Ok(vec![TraitImpl {
+            target_type: target_type.clone(),
+            associated_types: Vec::new(),
+            trait_type: cmp_trait("Eq")?,
+            extra: None,
+            methods: Vec::new(),
+          },
+          TraitImpl {
+            target_type: target_type,
+            associated_types: Vec::new(),
+            trait_type: cmp_trait("Ord")?,
+            extra: Some(TraitImplExtra::OrdFromPartialOrd),
+            methods: Vec::new(),
+          }])
+}
+
+/// Returns the value type referenced by the return type
+/// of the method of `Index` or `IndexMut` implementation.
+fn index_output_type(trait_impl: &TraitImpl) -> Option<RustType> {
+  if let Some(method) = trait_impl.methods.get(0) {
+    if let RustMethodArguments::SingleVariant(ref variant) = method.arguments {
+      if let RustType::Common {
+               ref base,
+               ref generic_arguments,
+               ..
+             } = variant.return_type.rust_api_type {
+        return Some(RustType::Common {
+                      base: base.clone(),
+                      generic_arguments: generic_arguments.clone(),
+                      indirection: RustTypeIndirection::None,
+                      is_const: false,
+                      is_const2: false,
+                    });
       }
     }
-    Ok(result)
   }
+  None
+}
 
+/// Returns true if all methods of `trait_impl` take `self`
+/// by const reference or don't take `self`.
+fn is_read_only_trait_impl(trait_impl: &TraitImpl) -> bool {
+  trait_impl
+    .methods
+    .iter()
+    .all(|method| match method.arguments {
+           RustMethodArguments::SingleVariant(ref variant) => {
+             match variant.arguments.get(0) {
+               Some(arg) if arg.name == "self" => {
+                 match arg.argument_type.rust_api_type {
+                   RustType::Common {
+                     indirection: RustTypeIndirection::Ref { .. },
+                     ref is_const,
+                     ..
+                   } => *is_const,
+                   _ => false,
+                 }
+               }
+               _ => true,
+             }
+           }
+           RustMethodArguments::MultipleVariants { .. } => true,
+         })
+}
 
-  /// Converts specified C++ type to Rust.
-  /// Returns:
-  /// - main_type - representation of the target type, including
-  /// directly implemented methods of the type and trait implementations;
-  /// - overloading_types - traits and their implementations that
-  /// emulate C++ method overloading.
-  fn generate_type<'a>(&'a self,
-                       info: &'a RustProcessedTypeInfo,
-                       mut cpp_methods: Vec<&'a CppAndFfiMethod>)
-                       -> Result<(GenerateTypeResult, Vec<&'a CppAndFfiMethod>)> {
-    Ok(match info.kind {
-         RustTypeWrapperKind::Enum { .. } => {
-           (GenerateTypeResult {
-              main_type: RustTypeDeclaration {
-                name: info.rust_name.clone(),
-                kind: RustTypeDeclarationKind::CppTypeWrapper {
-                  kind: info.kind.clone(),
-                  cpp_type_name: info.cpp_name.clone(),
-                  cpp_template_arguments: None,
-                  cpp_doc: info.cpp_doc.clone(),
-                  methods: Vec::new(),
-                  trait_impls: Vec::new(),
-                  qt_receivers: Vec::new(),
-                },
-                is_public: info.is_public,
-                rust_doc: None,
-              },
-              overloading_types: Vec::new(),
-            },
-            cpp_methods)
-         }
-         RustTypeWrapperKind::Struct { .. } => {
-      let methods_scope = RustMethodScope::Impl {
-        target_type: RustType::Common {
-          base: info.rust_name.clone(),
-          generic_arguments: None,
-          indirection: RustTypeIndirection::None,
-          is_const: false,
-          is_const2: false,
-        },
-      };
-      let class_type = CppTypeClassBase {
-        name: info.cpp_name.clone(),
-        template_arguments: info.cpp_template_arguments.clone(),
-      };
-      let mut good_methods = Vec::new();
-      let mut tmp_cpp_methods = Vec::new();
-      for method in cpp_methods {
-        if let Some(ref info) = method.cpp_method.class_membership {
-          if &info.class_type == &class_type {
-            good_methods.push(method);
-            continue;
-          }
+/// Removes conflicting trait implementations. Const and non-const overloads
+/// of a C++ operator may produce multiple implementations of the same trait
+/// (including generic arguments) for the same type. Only one of them is kept,
+/// and read-only implementations are preferred. `IndexMut` implementations
+/// without a matching `Index` implementation are also removed.
+fn dedup_trait_impls(trait_impls: &mut Vec<TraitImpl>) {
+  let all_impls: Vec<_> = trait_impls.drain(..).collect();
+  for trait_impl in all_impls {
+    let existing_index = trait_impls
+      .iter()
+      .position(|x| {
+                  x.trait_type == trait_impl.trait_type && x.target_type == trait_impl.target_type
+                });
+    match existing_index {
+      Some(index) => {
+        if !is_read_only_trait_impl(&trait_impls[index]) && is_read_only_trait_impl(&trait_impl) {
+          trait_impls[index] = trait_impl;
         }
-        tmp_cpp_methods.push(method);
       }
-      cpp_methods = tmp_cpp_methods;
-      let functions_result =
-        self
-          .process_all_sibling_functions(good_methods.into_iter(), &methods_scope)?;
-
-      let mut qt_receivers_by_name: HashMap<String, Vec<_>> = HashMap::new();
-      if self
-           .input_data
-           .cpp_data
-           .inherits(&info.cpp_name, "QObject") {
-        for method in self
-              .input_data
-              .cpp_data
-              .current
-              .parser
-              .methods
-              .iter()
-              .chain(self
-                       .input_data
-                       .cpp_data
-                       .current
-                       .processed
-                       .inherited_methods
-                       .iter()) {
-          if let Some(ref info) = method.class_membership {
-            if &info.class_type == &class_type && (info.is_signal || info.is_slot) {
-              add_to_multihash(&mut qt_receivers_by_name,
-                               method.name.clone(),
-                               RustQtReceiverDeclaration {
-                                 type_name: method.name.to_class_case(),
-                                 method_name: method.name.to_snake_case(),
-                                 receiver_type: if info.is_signal {
-                                   RustQtReceiverType::Signal
-                                 } else {
-                                   RustQtReceiverType::Slot
-                                 },
-                                 original_method_name: method.name.clone(),
-                                 receiver_id: method.receiver_id()?,
-                                 arguments: method
-                                   .arguments
-                                   .iter()
-                                   .map_if_ok(|arg| -> Result<_> {
-                Ok(complete_type(&self.processed_types,
-                                 &self.input_data.dependency_types,
-                                 &arg
-                                    .argument_type
-                                    .to_cpp_ffi_type(CppTypeRole::NotReturnType)?,
-                                 &CppFfiArgumentMeaning::Argument(0),
-                                 false,
-                                 &ReturnValueAllocationPlace::NotApplicable)?
-                       .rust_api_type
-                       .with_lifetime("static".to_string()))
-              })?,
-                               });
-            }
-          }
-        }
-      }
-      let qt_receivers = qt_receivers_by_name
-        .into_iter()
-        .flat_map(|(_, receivers)| if receivers.len() == 1 {
-                    receivers
-                  } else {
-                    receivers
-                      .into_iter()
-                      .map(|r| {
-            let name = format!("{}_{}",
-                               r.method_name,
-                               r.arguments
-                                 .iter()
-                                 .map(|x| {
-                                        x.caption(&info.rust_name)
-                                          .expect("receiver argument caption failed")
-                                      })
-                                 .join("_"));
-            RustQtReceiverDeclaration {
-              type_name: name.to_class_case(),
-              method_name: name.to_snake_case(),
-              ..r
-            }
-          })
-                      .collect()
-                  })
-        .collect();
-
-      (GenerateTypeResult {
-         main_type: RustTypeDeclaration {
-           name: info.rust_name.clone(),
-           kind: RustTypeDeclarationKind::CppTypeWrapper {
-             kind: info.kind.clone(),
-             cpp_type_name: info.cpp_name.clone(),
-             cpp_template_arguments: info.cpp_template_arguments.clone(),
-             cpp_doc: info.cpp_doc.clone(),
-             methods: functions_result.methods,
-             trait_impls: functions_result.trait_impls,
-             qt_receivers: qt_receivers,
-           },
-           is_public: info.is_public,
-           rust_doc: None,
-         },
-         overloading_types: functions_result.overloading_types,
-       },
-       cpp_methods)
+      None => trait_impls.push(trait_impl),
     }
-       })
   }
+  let trait_name = |trait_impl: &TraitImpl| -> Option<(String, Option<Vec<RustType>>)> {
+    match trait_impl.trait_type {
+      RustType::Common {
+        ref base,
+        ref generic_arguments,
+        ..
+      } => Some((base.parts.join("::"), generic_arguments.clone())),
+      RustType::EmptyTuple | RustType::FunctionPointer { .. } | RustType::Tuple(..) => None,
+    }
+  };
+  let index_impls: Vec<_> = trait_impls
+    .iter()
+    .filter_map(|x| match trait_name(x) {
+                  Some((ref name, ref args)) if name == "std::ops::Index" => {
+                    Some((x.target_type.clone(), args.clone(), index_output_type(x)))
+                  }
+                  _ => None,
+                })
+    .collect();
+  trait_impls.retain(|x| match trait_name(x) {
+                       Some((ref name, ref args)) if name == "std::ops::IndexMut" => {
+                         index_impls
+                           .iter()
+                           .any(|&(ref target_type, ref index_args, ref output)| {
+                                  target_type == &x.target_type && index_args == args &&
+                                  output == &index_output_type(x)
+                                })
+                       }
+                       _ => true,
+                     });
+}
 
-  /// Returns full name of the Rust method corresponding to `cpp_method`.
-  fn free_function_rust_name(&self, cpp_method: &CppMethod) -> Result<RustName> {
-    self.calculate_rust_name(&cpp_method.name,
-                             &cpp_method.include_file,
-                             true,
-                             cpp_method.operator.as_ref())
+/// Returns implementation of `AsRef<Base>` (if `is_const` is true) or `AsMut<Base>`
+/// for a direct static cast from `from_type` to `to_type`. `cast_method` must be
+/// the cast method already converted to take `self` by reference, so the generated
+/// method calls the same FFI function. Returns `None` if both types are the same.
+fn base_as_ref_impl(cast_method: &RustSingleMethod,
+                    is_const: bool,
+                    from_type: &CompleteType,
+                    to_type: &CompleteType)
+                    -> Result<Option<TraitImpl>> {
+  let target_type = from_type.ptr_to_value()?.rust_api_type;
+  let base_type = to_type.ptr_to_value()?.rust_api_type;
+  if target_type == base_type {
+    return Ok(None);
   }
+  let (trait_name, method_name) = if is_const {
+    ("AsRef", "as_ref")
+  } else {
+    ("AsMut", "as_mut")
+  };
+  let mut method = cast_method.clone();
+  method.name = RustName::new(vec![method_name.to_string()])?;
+  Ok(Some(TraitImpl {
+            target_type: target_type,
+            associated_types: Vec::new(),
+            trait_type: RustType::Common {
+              base: RustName::new(vec!["std".to_string(),
+                                       "convert".to_string(),
+                                       trait_name.to_string()])?,
+              indirection: RustTypeIndirection::None,
+              is_const: false,
+              is_const2: false,
+              generic_arguments: Some(vec![base_type]),
+            },
+            extra: None,
+            methods: vec![method.to_rust_method()],
+          }))
+}
 
-  /// Returns method name. For class member functions, the name doesn't
-  /// include class name and scope. For free functions, the name includes
-  /// modules.
-  fn method_rust_name(&self, method: &CppAndFfiMethod) -> Result<RustName> {
-    let name = if method.cpp_method.class_membership.is_none() {
-      self.free_function_rust_name(&method.cpp_method)?
-    } else {
-      let x = if method.cpp_method.is_constructor() {
-        "new".to_string()
-      } else if let Some(ref operator) = method.cpp_method.operator {
-        operator_rust_name(operator)?
-      } else {
-        method.cpp_method.name.to_snake_case()
-      };
-      RustName::new(vec![x])?
-    };
-    Ok(name)
+/// Replaces name of the overloading trait `old_name` with `new_name`
+/// in all `methods` that use it.
+fn rename_params_trait(methods: &mut [RustMethod], old_name: &str, new_name: &str) {
+  for method in methods {
+    if let RustMethodArguments::MultipleVariants { ref mut params_trait_name, .. } =
+      method.arguments {
+      if params_trait_name == old_name {
+        *params_trait_name = new_name.to_string();
+      }
+    }
   }
+}
 
-
-  /// Converts one function to a `RustSingleMethod`.
-  fn generate_rust_single_method(&self,
-                                 method: &CppAndFfiMethod,
-                                 scope: &RustMethodScope,
-                                 generate_doc: bool)
-                                 -> Result<RustSingleMethod> {
-    let mut arguments = Vec::new();
-    for (arg_index, arg) in method.c_signature.arguments.iter().enumerate() {
-      if arg.meaning != CppFfiArgumentMeaning::ReturnValue {
-        let arg_type = complete_type(&self.processed_types,
-                                     &self.input_data.dependency_types,
-                                     &arg.argument_type,
-                                     &arg.meaning,
-                                     false,
-                                     &method.allocation_place)?;
-        arguments.push(RustMethodArgument {
-                         ffi_index: arg_index,
-                         argument_type: arg_type,
-                         name: if arg.meaning == CppFfiArgumentMeaning::This {
-                           "self".to_string()
-                         } else {
-                           sanitize_rust_identifier(&arg.name.to_snake_case())
-                         },
-                       });
+/// Runs functions added by `Config::add_doc_hook` on the final documentation
+/// of types and methods declared directly in `module` and sets `doc_override`
+/// of the items with modified documentation.
+pub fn apply_doc_hooks(hooks: &[&Box<DocHookFn>], module: &mut RustModule) {
+  let run_hooks = |name: &str, doc: String| -> Option<String> {
+    let new_doc = hooks.iter().fold(doc.clone(), |doc, hook| hook(name, doc));
+    if new_doc == doc { None } else { Some(new_doc) }
+  };
+  for type1 in &mut module.types {
+    let type_name = type1.name.parts.join("::");
+    type1.doc_override = run_hooks(&type_name, doc_formatter::type_doc(type1));
+    if let RustTypeDeclarationKind::CppTypeWrapper { ref mut methods, .. } = type1.kind {
+      for method in methods {
+        if let Ok(last_name) = method.name.last_name() {
+          let method_name = format!("{}::{}", type_name, last_name);
+          method.doc_override = run_hooks(&method_name, doc_formatter::method_doc(method));
+        }
       }
     }
-    let (mut return_type, return_arg_index) = if let Some((arg_index, arg)) =
-      method
-        .c_signature
-        .arguments
-        .iter()
-        .enumerate()
-        .find(|&(_arg_index, arg)| arg.meaning == CppFfiArgumentMeaning::ReturnValue) {
-      // an argument has return value meaning, so
-      // FFI return type must be void
-      assert!(method.c_signature.return_type == CppFfiType::void());
-      (complete_type(&self.processed_types,
-                     &self.input_data.dependency_types,
-                     &arg.argument_type,
-                     &arg.meaning,
-                     false,
-                     &method.allocation_place)?,
-       Some(arg_index))
-    } else {
-      // none of the arguments has return value meaning,
-      // so FFI return value must be used
-      let return_type = complete_type(&self.processed_types,
-                                      &self.input_data.dependency_types,
-                                      &method.c_signature.return_type,
-                                      &CppFfiArgumentMeaning::ReturnValue,
-                                      false,
-                                      &method.allocation_place)?;
-      (return_type, None)
-    };
-    if return_type.rust_api_type.is_ref() && return_type.rust_api_type.lifetime().is_none() {
-      let mut found = false;
-      for arg in &arguments {
-        if let Some(lifetime) = arg.argument_type.rust_api_type.lifetime() {
-          return_type.rust_api_type = return_type.rust_api_type.with_lifetime(lifetime.clone());
-          found = true;
-          break;
+  }
+  for method in &mut module.functions {
+    let method_name = method.name.parts.join("::");
+    method.doc_override = run_hooks(&method_name, doc_formatter::method_doc(method));
+  }
+}
+
+/// Generates `CompleteType` from `CppFfiType`, adding
+/// Rust API type, Rust FFI type and conversion between them.
+fn complete_type(types: &RustTypes,
+                 cpp_ffi_type: &CppFfiType,
+                 argument_meaning: &CppFfiArgumentMeaning,
+                 is_template_argument: bool,
+                 allocation_place: &ReturnValueAllocationPlace)
+                 -> Result<CompleteType> {
+  let rust_ffi_type = ffi_type(types, &cpp_ffi_type.ffi_type)?;
+  let mut rust_api_type = rust_ffi_type.clone();
+  let mut rust_api_to_c_conversion = RustToCTypeConversion::None;
+  if let RustType::Common {
+           ref mut indirection,
+           ref mut base,
+           ref mut generic_arguments,
+           ref mut is_const,
+           ref mut is_const2,
+         } = rust_api_type {
+    match cpp_ffi_type.conversion {
+      CppIndirectionChange::NoChange => {
+        if argument_meaning == &CppFfiArgumentMeaning::This {
+          assert!(indirection == &RustTypeIndirection::Ptr);
+          *indirection = RustTypeIndirection::Ref { lifetime: None };
+          rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
         }
       }
-      if !found {
-        let mut next_lifetime_num = 0;
-        for arg in &mut arguments {
-          if arg.argument_type.rust_api_type.is_ref() &&
-             arg.argument_type.rust_api_type.lifetime().is_none() {
-            arg.argument_type.rust_api_type = arg
-              .argument_type
-              .rust_api_type
-              .with_lifetime(format!("l{}", next_lifetime_num));
-            next_lifetime_num += 1;
+      CppIndirectionChange::ValueToPointer => {
+        assert!(indirection == &RustTypeIndirection::Ptr);
+        if argument_meaning == &CppFfiArgumentMeaning::ReturnValue {
+          if let Some(info) = types.find(|x| &x.rust_name == base) {
+            match info.kind {
+              RustTypeWrapperKind::Struct { ref is_deletable, .. } => {
+                if !*is_deletable {
+                  return Err(format!("{} is not deletable", base.full_name(None)).into());
+                }
+              }
+              RustTypeWrapperKind::Enum { .. } => {
+                return Err(unexpected("class type expected here").into())
+              }
+            }
+          } else {
+            return Err(unexpected("type lookup failed in complete_type() after success in \
+                                   ffi_type()")
+                           .into());
           }
-        }
-        let return_lifetime = if next_lifetime_num == 0 {
-          log::llog(log::DebugGeneral, || {
-            format!("Method returns a reference but doesn't receive a reference: {}",
-                    method.short_text())
-          });
-          log::llog(log::DebugGeneral,
-                    || "Assuming static lifetime of return value.");
-          "static".to_string()
-        } else {
-          "l0".to_string()
-        };
-        return_type.rust_api_type = return_type.rust_api_type.with_lifetime(return_lifetime);
-      }
-    }
+          match *allocation_place {
+            ReturnValueAllocationPlace::Stack => {
+              *indirection = RustTypeIndirection::None;
+              rust_api_to_c_conversion = RustToCTypeConversion::ValueToPtr;
+            }
+            ReturnValueAllocationPlace::Heap => {
+              *indirection = RustTypeIndirection::None;
+              rust_api_to_c_conversion = RustToCTypeConversion::CppBoxToPtr;
+              assert!(generic_arguments.is_none());
+              if *is_const || *is_const2 {
+                // the caller receives a new object that it owns,
+                // so constness of the returned C++ value doesn't matter
+                log::llog(log::DebugRustSkips, || {
+                  format!("Removing const from heap-allocated return type: {}",
+                          base.full_name(None))
+                });
+                *is_const = false;
+                *is_const2 = false;
+              }
+              let new_generic_argument = RustType::Common {
+                base: base.clone(),
+                generic_arguments: None,
+                is_const: false,
+                is_const2: false,
+                indirection: RustTypeIndirection::None,
+              };
+              *base = types.box_type.clone();
+              *generic_arguments = Some(vec![new_generic_argument]);
 
-    let doc = if generate_doc {
-      Some(RustMethodDocItem {
-             cpp_fn: method.short_text(),
-             rust_fns: Vec::new(),
-             doc: method.cpp_method.doc.clone(),
-           })
-    } else {
-      None
-    };
-    let is_unsafe = arguments
-      .iter()
-      .any(|arg| arg.argument_type.rust_api_type.is_unsafe_argument());
-    Ok(RustSingleMethod {
-         name: self.method_rust_name(method)?,
-         scope: scope.clone(),
-         arguments: RustMethodArgumentsVariant {
-           arguments: arguments,
-           cpp_method: method.clone(),
-           return_type: return_type,
-           return_type_ffi_index: return_arg_index,
-         },
-         doc: doc,
-         is_unsafe: is_unsafe,
-       })
-  }
-
-  /// Generates `Drop` or `CppDeletable` trait implementation
-  /// from a C++ destructor.
-  fn process_destructor(&self,
-                        method: &CppAndFfiMethod,
-                        scope: &RustMethodScope)
-                        -> Result<TraitImpl> {
-    if let RustMethodScope::Impl { ref target_type } = *scope {
-      match method.allocation_place {
-        ReturnValueAllocationPlace::Stack => {
-          let mut method = self.generate_rust_single_method(method, scope, true)?;
-          method.name = RustName::new(vec!["drop".to_string()])?;
-          method.scope = RustMethodScope::TraitImpl;
-          Ok(TraitImpl {
-               target_type: target_type.clone(),
-               associated_types: Vec::new(),
-               trait_type: RustType::Common {
-                 base: RustName::new(vec!["Drop".to_string()])?,
-                 indirection: RustTypeIndirection::None,
-                 is_const: false,
-                 is_const2: false,
-                 generic_arguments: None,
-               },
-               extra: None,
-               methods: vec![method.to_rust_method()],
-             })
+            }
+            ReturnValueAllocationPlace::NotApplicable => {
+              return Err(unexpected("NotApplicable conflicts with ValueToPointer").into());
+            }
+          }
+        } else {
+          if is_template_argument {
+            *indirection = RustTypeIndirection::None;
+            rust_api_to_c_conversion = RustToCTypeConversion::ValueToPtr;
+          } else {
+            *indirection = RustTypeIndirection::Ref { lifetime: None };
+            rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
+          }
+          *is_const = true;
+          *is_const2 = true;
         }
-        ReturnValueAllocationPlace::Heap => {
-          Ok(TraitImpl {
-               target_type: target_type.clone(),
-               associated_types: Vec::new(),
-               trait_type: RustType::Common {
-                 base: RustName::new(vec!["cpp_utils".to_string(), "CppDeletable".to_string()])?,
-                 indirection: RustTypeIndirection::None,
-                 is_const: false,
-                 is_const2: false,
-                 generic_arguments: None,
-               },
-               extra: Some(TraitImplExtra::CppDeletable { deleter_name: method.c_name.clone() }),
-               methods: Vec::new(),
-             })
+      }
+      CppIndirectionChange::ReferenceToPointer => {
+        match *indirection {
+          RustTypeIndirection::Ptr => {
+            *indirection = RustTypeIndirection::Ref { lifetime: None };
+          }
+          RustTypeIndirection::PtrPtr => {
+            *indirection = RustTypeIndirection::PtrRef { lifetime: None };
+          }
+          _ => return Err(unexpected("invalid indirection for ReferenceToPointer").into()),
         }
-        ReturnValueAllocationPlace::NotApplicable => {
-          return Err(unexpected("destructor must have allocation place").into())
+        rust_api_to_c_conversion = RustToCTypeConversion::RefToPtr;
+      }
+      CppIndirectionChange::RValueReferenceToPointer => {
+        assert!(indirection == &RustTypeIndirection::Ptr);
+        // the C++ function moves the contents out of the argument,
+        // so the Rust API takes ownership of the passed object
+        if let Some(info) = types.find(|x| &x.rust_name == base) {
+          match info.kind {
+            RustTypeWrapperKind::Struct { ref is_deletable, .. } => {
+              if !*is_deletable {
+                return Err(format!("{} is not deletable", base.full_name(None)).into());
+              }
+            }
+            RustTypeWrapperKind::Enum { .. } => {
+              return Err(unexpected("class type expected here").into())
+            }
+          }
+        } else {
+          return Err(unexpected("type lookup failed in complete_type() after success in \
+                                 ffi_type()")
+                         .into());
         }
+        *indirection = RustTypeIndirection::None;
+        rust_api_to_c_conversion = RustToCTypeConversion::CppBoxToPtr;
+        let new_generic_argument = RustType::Common {
+          base: base.clone(),
+          generic_arguments: generic_arguments.clone(),
+          is_const: false,
+          is_const2: false,
+          indirection: RustTypeIndirection::None,
+        };
+        *base = types.box_type.clone();
+        *generic_arguments = Some(vec![new_generic_argument]);
+        *is_const = false;
+        *is_const2 = false;
+      }
+      CppIndirectionChange::QFlagsToUInt => {}
+      CppIndirectionChange::OptionalToPointer => {
+        assert!(indirection == &RustTypeIndirection::Ptr);
+        rust_api_to_c_conversion = RustToCTypeConversion::OptionToPtr;
+        // the element type is passed by value in the Rust API
+        let element_type = RustType::Common {
+          base: base.clone(),
+          generic_arguments: generic_arguments.clone(),
+          is_const: false,
+          is_const2: false,
+          indirection: RustTypeIndirection::None,
+        };
+        *base = RustName::new(vec!["std".to_string(),
+                                   "option".to_string(),
+                                   "Option".to_string()])?;
+        *generic_arguments = Some(vec![element_type]);
+        *indirection = RustTypeIndirection::None;
+        *is_const = false;
+        *is_const2 = false;
       }
-    } else {
-      return Err(unexpected("destructor must be in class scope").into());
     }
   }
-
-  /// Generates trait implementations from `static_cast`, `dynamic_cast`
-  /// or `qobject_cast` (to be implemented) C++ function wrappers.
-  fn process_cpp_cast(&self, method: RustSingleMethod) -> Result<Vec<TraitImpl>> {
-    let mut results = Vec::new();
-    // TODO: qobject_cast
-    let mut final_methods = vec![(method.clone(), false), (method.clone(), true)];
-    let args = &method.arguments;
-    let cpp_cast = if let CppFfiMethodKind::Cast(ref cast) = args.cpp_method.kind {
-      cast
-    } else {
-      return Err("not a cast method".into());
-    };
-    let trait_name = match *cpp_cast {
-      CppCast::Static { ref is_unsafe, .. } => {
-        if *is_unsafe {
-          vec!["cpp_utils".to_string(), "UnsafeStaticCast".to_string()]
-        } else {
-          vec!["cpp_utils".to_string(), "StaticCast".to_string()]
-        }
+  if cpp_ffi_type.conversion == CppIndirectionChange::QFlagsToUInt {
+    rust_api_to_c_conversion = RustToCTypeConversion::QFlagsToUInt;
+    let enum_type = if let CppTypeBase::Class(CppTypeClassBase {
+                                                ref template_arguments, ..
+                                              }) = cpp_ffi_type.original_type.base {
+      let args = template_arguments
+        .as_ref()
+        .chain_err(|| "QFlags type must have template arguments")?;
+      if args.len() != 1 {
+        return Err("QFlags type must have exactly 1 template argument".into());
       }
-      CppCast::Dynamic => vec!["cpp_utils".to_string(), "DynamicCast".to_string()],
-      CppCast::QObject => {
-        vec!["qt_core".to_string(),
-             "object".to_string(),
-             "Cast".to_string()]
+      if let CppTypeBase::Enum { ref name } = args[0].base {
+        match types.find_by_cpp_name(name) {
+          None => return Err(format!("type has no Rust equivalent: {}", name).into()),
+          Some(info) => info.rust_name.clone(),
+        }
+      } else {
+        return Err(unexpected("invalid original type for QFlags").into());
       }
+    } else {
+      return Err(unexpected("invalid original type for QFlags").into());
     };
-    if args.arguments.len() != 1 {
-      return Err(unexpected("1 argument expected").into());
-    }
-    let from_type = &args.arguments[0].argument_type;
-    let to_type = &args.return_type;
+    rust_api_type = qt_flags_type(enum_type)?;
+  }
 
-    for &mut (ref mut final_method, ref mut final_is_const) in &mut final_methods {
-      let method_name = if *final_is_const {
-        args.cpp_method.cpp_method.name.clone()
-      } else {
-        format!("{}_mut", args.cpp_method.cpp_method.name)
-      };
-      final_method.scope = RustMethodScope::TraitImpl;
-      final_method.name = RustName::new(vec![method_name])?;
-      final_method.is_unsafe = cpp_cast.is_unsafe_static_cast();
-      let return_ref_type = args.return_type.ptr_to_ref(*final_is_const)?;
-      if &final_method.arguments.cpp_method.cpp_method.name == "static_cast" {
-        final_method.arguments.return_type = return_ref_type;
-      } else {
-        final_method
-          .arguments
-          .return_type
-          .rust_api_to_c_conversion = RustToCTypeConversion::OptionRefToPtr;
-        final_method.arguments.return_type.rust_api_type = RustType::Common {
-          base: RustName::new(vec!["std".to_string(),
-                                   "option".to_string(),
-                                   "Option".to_string()])?,
-          indirection: RustTypeIndirection::None,
-          is_const: false,
-          is_const2: false,
-          generic_arguments: Some(vec![return_ref_type.rust_api_type]),
+  Ok(CompleteType {
+       cpp_ffi_type: cpp_ffi_type.ffi_type.clone(),
+       cpp_type: cpp_ffi_type.original_type.clone(),
+       cpp_to_ffi_conversion: cpp_ffi_type.conversion.clone(),
+       rust_ffi_type: rust_ffi_type,
+       rust_api_type: rust_api_type,
+       rust_api_to_c_conversion: rust_api_to_c_conversion,
+     })
+}
+
+/// Replaces Rust API type of a method argument with `impl cpp_utils::ToCppRef<T>`
+/// if the argument is of a string class type `T` listed in `string_types`
+/// and is passed by value or by const reference, so that both references to `T`
+/// and Rust strings are accepted. Other types are not changed.
+fn convert_string_argument(arg_type: &mut CompleteType, string_types: &[String]) -> Result<()> {
+  if arg_type.rust_api_to_c_conversion != RustToCTypeConversion::RefToPtr ||
+     !arg_type.rust_api_type.last_is_const()? {
+    return Ok(());
+  }
+  match arg_type.cpp_type.indirection {
+    CppTypeIndirection::None |
+    CppTypeIndirection::Ref => {}
+    _ => return Ok(()),
+  }
+  if let CppTypeBase::Class(ref base) = arg_type.cpp_type.base {
+    if base.template_arguments.is_none() && string_types.iter().any(|t| t == &base.name) {
+      let class_type = match arg_type.rust_api_type {
+        RustType::Common {
+          ref base,
+          ref generic_arguments,
+          ..
+        } => {
+          RustType::Common {
+            base: base.clone(),
+            generic_arguments: generic_arguments.clone(),
+            indirection: RustTypeIndirection::None,
+            is_const: false,
+            is_const2: false,
+          }
         }
+        _ => return Err(unexpected("string class type expected").into()),
       };
-      final_method.arguments.arguments[0].argument_type = final_method.arguments.arguments[0]
-        .argument_type
-        .ptr_to_ref(*final_is_const)?;
-      final_method.arguments.arguments[0].name = "self".to_string();
+      arg_type.rust_api_type = RustType::Common {
+        base: RustName::new(vec!["cpp_utils".to_string(), "ToCppRef".to_string()])?,
+        generic_arguments: Some(vec![class_type]),
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      };
+      arg_type.rust_api_to_c_conversion = RustToCTypeConversion::ToCppRefToPtr;
+    }
+  }
+  Ok(())
+}
 
-      if !cpp_cast.is_unsafe_static_cast() && cpp_cast.is_direct_static_cast() {
+/// Replaces Rust API type of a non-const pointer or reference argument
+/// with the pointed value type, making it an out-parameter
+/// (see `Config::add_out_parameter`). Only numeric and `bool` types are supported.
+fn convert_out_parameter(arg_type: &mut CompleteType) -> Result<()> {
+  match arg_type.cpp_type.base {
+    CppTypeBase::BuiltInNumeric(..) |
+    CppTypeBase::SpecificNumeric(..) |
+    CppTypeBase::PointerSizedInteger { .. } => {}
+    _ => return Err("out-parameter must point to a numeric type".into()),
+  }
+  match arg_type.cpp_type.indirection {
+    CppTypeIndirection::Ptr |
+    CppTypeIndirection::Ref => {}
+    _ => return Err("out-parameter must be a pointer or a reference".into()),
+  }
+  if arg_type.cpp_type.is_const {
+    return Err("out-parameter can't be const".into());
+  }
+  let mut value_type = arg_type.rust_ffi_type.clone();
+  if let RustType::Common {
+           ref mut indirection,
+           ref mut is_const,
+           ..
+         } = value_type {
+    *indirection = RustTypeIndirection::None;
+    *is_const = false;
+  } else {
+    return Err(unexpected("out-parameter must have a common FFI type").into());
+  }
+  arg_type.rust_api_type = value_type;
+  arg_type.rust_api_to_c_conversion = RustToCTypeConversion::OutValueToPtr;
+  Ok(())
+}
 
-        let mut deref_method = final_method.clone();
-        deref_method.name = RustName::new(vec![if *final_is_const {
-                                                   "deref"
-                                                 } else {
-                                                   "deref_mut"
-                                                 }
-                                                 .to_string()])?;
-        let deref_trait_name = if *final_is_const { "Deref" } else { "DerefMut" }.to_string();
-        let associated_types = if *final_is_const {
-          vec![TraitAssociatedType {
-                 name: "Target".to_string(),
-                 value: to_type.ptr_to_value()?.rust_api_type,
-               }]
-        } else {
-          Vec::new()
-        };
-        results.push(TraitImpl {
-                       target_type: from_type.ptr_to_value()?.rust_api_type,
-                       associated_types: associated_types,
-                       trait_type: RustType::Common {
-                         base: RustName::new(vec!["std".to_string(),
-                                                  "ops".to_string(),
-                                                  deref_trait_name])?,
-                         indirection: RustTypeIndirection::None,
-                         is_const: false,
-                         is_const2: false,
-                         generic_arguments: None,
-                       },
-                       extra: None,
-                       methods: vec![deref_method.to_rust_method()],
-                     });
+/// Replaces Rust API type of a `QString` or `const QString&` return type
+/// with `std::string::String` (see `Config::set_qstring_return_conversion`).
+/// Other types are not changed.
+fn convert_qstring_return(type1: &mut CompleteType) -> Result<()> {
+  match type1.rust_api_to_c_conversion {
+    RustToCTypeConversion::ValueToPtr |
+    RustToCTypeConversion::CppBoxToPtr => {}
+    RustToCTypeConversion::RefToPtr => {
+      if !type1.rust_api_type.last_is_const()? {
+        return Ok(());
+      }
+    }
+    _ => return Ok(()),
+  }
+  let is_qstring = match type1.cpp_type.base {
+    CppTypeBase::Class(ref base) => base.name == "QString" && base.template_arguments.is_none(),
+    _ => false,
+  };
+  if !is_qstring {
+    return Ok(());
+  }
+  match type1.cpp_type.indirection {
+    CppTypeIndirection::None |
+    CppTypeIndirection::Ref => {}
+    _ => return Ok(()),
+  }
+  type1.rust_api_type = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "string".to_string(), "String".to_string()])?,
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  type1.rust_api_to_c_conversion = RustToCTypeConversion::QStringToString;
+  Ok(())
+}
+
+/// Wraps Rust API return type of a fallible constructor in `Result`
+/// (see `Config::add_fallible_constructor`). Only constructors returning
+/// the object by value or in a box are supported.
+fn convert_fallible_constructor_return(type1: &mut CompleteType,
+                                       error_type: RustType)
+                                       -> Result<()> {
+  match type1.rust_api_to_c_conversion {
+    RustToCTypeConversion::ValueToPtr |
+    RustToCTypeConversion::CppBoxToPtr => {}
+    _ => return Err("unsupported return type of a fallible constructor".into()),
+  }
+  let object_type = type1.rust_api_type.clone();
+  type1.rust_api_type = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "result".to_string(), "Result".to_string()])?,
+    generic_arguments: Some(vec![object_type, error_type]),
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  Ok(())
+}
 
+/// Replaces `CppBox<T>` returned by a constructor of a `QObject` with a parent
+/// with `cpp_utils::MaybeOwned<T>` because the created object is owned
+/// and deleted by its parent unless the parent is null.
+/// Other return types are not changed.
+fn convert_parented_constructor_return(type1: &mut CompleteType) -> Result<()> {
+  if type1.rust_api_to_c_conversion != RustToCTypeConversion::CppBoxToPtr {
+    return Ok(());
+  }
+  let object_type = match type1.rust_ffi_type {
+    RustType::Common {
+      indirection: RustTypeIndirection::Ptr,
+      ref base,
+      ref generic_arguments,
+      ..
+    } => {
+      RustType::Common {
+        base: base.clone(),
+        generic_arguments: generic_arguments.clone(),
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
       }
     }
-    let trait_type = RustType::Common {
-      base: RustName::new(trait_name)?,
-      indirection: RustTypeIndirection::None,
-      is_const: false,
-      is_const2: false,
-      generic_arguments: Some(vec![to_type.ptr_to_value()?.rust_api_type]),
-    };
-    results.push(TraitImpl {
-                   target_type: from_type.ptr_to_value()?.rust_api_type,
-                   associated_types: Vec::new(),
-                   trait_type: trait_type,
-                   extra: None,
-                   methods: final_methods
-                     .into_iter()
-                     .map(|x| x.0.to_rust_method())
-                     .collect(),
-                 });
-    Ok(results)
+    _ => return Err(unexpected("pointer FFI type expected for a heap-allocated value").into()),
+  };
+  type1.rust_api_type = RustType::Common {
+    base: RustName::new(vec!["cpp_utils".to_string(), "MaybeOwned".to_string()])?,
+    generic_arguments: Some(vec![object_type]),
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  type1.rust_api_to_c_conversion = RustToCTypeConversion::MaybeOwnedToPtr;
+  Ok(())
+}
+
+/// Returns `Option<type1>`.
+fn option_type(type1: RustType) -> Result<RustType> {
+  Ok(RustType::Common {
+       base: RustName::new(vec!["std".to_string(), "option".to_string(), "Option".to_string()])?,
+       indirection: RustTypeIndirection::None,
+       is_const: false,
+       is_const2: false,
+       generic_arguments: Some(vec![type1]),
+     })
+}
+
+/// Replaces Rust API type of a `const char*` method argument or
+/// return type with `&CStr`. Other types
+/// (including `char*` and `const wchar_t*`) are not changed.
+/// Argument and return types are additionally wrapped in `Option` by
+/// `RustGenerator::generate_rust_single_method` because
+/// the pointer may be null.
+fn convert_char_ptr(type1: &mut CompleteType) -> Result<()> {
+  if type1.rust_api_to_c_conversion != RustToCTypeConversion::None {
+    return Ok(());
+  }
+  let char_ptr_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+    indirection: CppTypeIndirection::Ptr,
+    is_const: true,
+    is_const2: false,
+  };
+  if type1.cpp_ffi_type != char_ptr_type {
+    return Ok(());
   }
+  type1.rust_api_type = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "ffi".to_string(), "CStr".to_string()])?,
+    generic_arguments: None,
+    indirection: RustTypeIndirection::Ref { lifetime: None },
+    is_const: true,
+    is_const2: false,
+  };
+  type1.rust_api_to_c_conversion = RustToCTypeConversion::CharPtrToCStr;
+  Ok(())
+}
 
-  /// Generates a single overloaded method from all specified methods or
-  /// accepts a single method without change. Adds self argument caption if needed.
-  /// All passed methods must be valid for overloading:
-  /// - they must have the same name and be in the same scope;
-  /// - they must have the same self argument type;
-  /// - they must be all safe or all unsafe;
-  /// - they must not have exactly the same argument types on any of target platforms.
-  ///
-  /// Use `RustGenerator::overload_functions` function to group available functions
-  /// based on these conditions.
-  fn generate_final_method(&self,
-                           mut filtered_methods: Vec<RustSingleMethod>,
-                           scope: &RustMethodScope,
-                           self_arg_kind_caption: Option<String>)
-                           -> Result<(RustMethod, Option<RustTypeDeclaration>)> {
-    filtered_methods.sort_by(|a, b| {
-                               a.arguments
-                                 .cpp_method
-                                 .c_name
-                                 .cmp(&b.arguments.cpp_method.c_name)
-                             });
-    let methods_count = filtered_methods.len();
-    let mut type_declaration = None;
-    let method = if methods_count > 1 {
-      let first_method = filtered_methods[0].clone();
-      let self_argument = if !first_method.arguments.arguments.is_empty() &&
-                             first_method.arguments.arguments[0].name == "self" {
-        Some(first_method.arguments.arguments[0].clone())
-      } else {
-        None
-      };
-      let cpp_method_name = first_method
-        .arguments
-        .cpp_method
-        .cpp_method
-        .full_name();
-      let mut args_variants = Vec::new();
-      let mut method_name = first_method.name.clone();
-      let mut method_last_name = method_name
-        .parts
-        .pop()
-        .chain_err(|| "name can't be empty")?;
-      if let Some(self_arg_kind_caption) = self_arg_kind_caption {
-        method_last_name = vec![method_last_name.as_ref(), self_arg_kind_caption.as_ref()]
-          .to_snake_case();
-      }
-      let mut trait_name = method_last_name.to_class_case() + "Args";
-      method_last_name = sanitize_rust_identifier(&method_last_name);
-      method_name.parts.push(method_last_name);
-      if let RustMethodScope::Impl { ref target_type } = *scope {
-        let target_type_name = if let RustType::Common { ref base, .. } = *target_type {
-          base.last_name()
-        } else {
-          Err("RustType::Common expected".into())
-        }?;
-        trait_name = format!("{}{}", target_type_name, trait_name);
-      }
-      let mut grouped_by_cpp_method: HashMap<_, Vec<_>> = HashMap::new();
-      for mut method in filtered_methods {
-        assert!(method.name == first_method.name);
-        assert!(method.scope == first_method.scope);
-        if let Some(ref self_argument) = self_argument {
-          assert!(method.arguments.arguments.len() > 0 &&
-                  &method.arguments.arguments[0] == self_argument);
-          method.arguments.arguments.remove(0);
-        }
+/// Replaces Rust API type of a `const wchar_t*` method argument with `&OsStr`
+/// and of a return type with `OsString` if `target_family` is `Windows`.
+/// On other targets `wchar_t` is not UTF-16, so the type is not changed.
+/// Argument and return types are additionally wrapped in `Option` by
+/// `RustGenerator::generate_rust_single_method` because
+/// the pointer may be null.
+fn convert_wchar_ptr(type1: &mut CompleteType,
+                     is_return_type: bool,
+                     target_family: target::Family)
+                     -> Result<()> {
+  if target_family != target::Family::Windows ||
+     type1.rust_api_to_c_conversion != RustToCTypeConversion::None {
+    return Ok(());
+  }
+  let wchar_ptr_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::WChar),
+    indirection: CppTypeIndirection::Ptr,
+    is_const: true,
+    is_const2: false,
+  };
+  if type1.cpp_ffi_type != wchar_ptr_type {
+    return Ok(());
+  }
+  type1.rust_api_type = if is_return_type {
+    RustType::Common {
+      base: RustName::new(vec!["std".to_string(), "ffi".to_string(), "OsString".to_string()])?,
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  } else {
+    RustType::Common {
+      base: RustName::new(vec!["std".to_string(), "ffi".to_string(), "OsStr".to_string()])?,
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ref { lifetime: None },
+      is_const: true,
+      is_const2: false,
+    }
+  };
+  type1.rust_api_to_c_conversion = RustToCTypeConversion::WCharPtrToOsString;
+  Ok(())
+}
 
-        let cpp_method_key = method.arguments.cpp_method.cpp_method.clone();
-        //        if let Some(v) = cpp_method_key.arguments_before_omitting {
-        //          cpp_method_key.arguments = v;
-        //          cpp_method_key.arguments_before_omitting = None;
-        //        }
-        add_to_multihash(&mut grouped_by_cpp_method,
-                         cpp_method_key,
-                         method.arguments.clone());
-        args_variants.push(method.arguments);
+/// Adds a lifetime to `return_type` if it's a reference without a lifetime.
+/// If the method has a `self` reference argument, the returned reference
+/// is tied to it because it usually points to data owned by the object.
+/// Otherwise, the first argument that already has a lifetime or the first
+/// reference argument is used. `'static` lifetime is only assumed if
+/// there are no reference arguments. `method_text` is used for logging.
+fn add_return_lifetime(arguments: &mut [RustMethodArgument],
+                       return_type: &mut RustType,
+                       method_text: &str) {
+  if !return_type.is_ref() || return_type.lifetime().is_some() {
+    return;
+  }
+  let candidate = arguments
+    .iter()
+    .position(|arg| &arg.name == "self" && arg.argument_type.rust_api_type.is_ref())
+    .or_else(|| {
+               arguments
+                 .iter()
+                 .position(|arg| arg.argument_type.rust_api_type.lifetime().is_some())
+             });
+  if let Some(index) = candidate {
+    if let Some(lifetime) = arguments[index].argument_type.rust_api_type.lifetime() {
+      *return_type = return_type.with_lifetime(lifetime.clone());
+      return;
+    }
+  }
+  let mut next_lifetime_num = 0;
+  let mut return_lifetime = None;
+  for (index, arg) in arguments.iter_mut().enumerate() {
+    if arg.argument_type.rust_api_type.is_ref() &&
+       arg.argument_type.rust_api_type.lifetime().is_none() {
+      let lifetime = format!("l{}", next_lifetime_num);
+      arg.argument_type.rust_api_type = arg
+        .argument_type
+        .rust_api_type
+        .with_lifetime(lifetime.clone());
+      next_lifetime_num += 1;
+      if return_lifetime.is_none() || candidate == Some(index) {
+        return_lifetime = Some(lifetime);
       }
+    }
+  }
+  let return_lifetime = return_lifetime.unwrap_or_else(|| {
+    log::llog(log::DebugGeneral, || {
+      format!("Method returns a reference but doesn't receive a reference: {}",
+              method_text)
+    });
+    log::llog(log::DebugGeneral,
+              || "Assuming static lifetime of return value.");
+    "static".to_string()
+  });
+  *return_type = return_type.with_lifetime(return_lifetime);
+}
 
-      let mut doc_items = Vec::new();
-      let mut grouped_by_cpp_method_vec: Vec<_> = grouped_by_cpp_method.into_iter().collect();
-      grouped_by_cpp_method_vec.sort_by(|&(ref a, _), &(ref b, _)| {
-                                          a.short_text().cmp(&b.short_text())
-                                        });
-      for (cpp_method, variants) in grouped_by_cpp_method_vec {
-        doc_items.push(RustMethodDocItem {
-                         doc: cpp_method.doc.clone(),
-                         cpp_fn: cpp_method.short_text(),
-                         rust_fns: variants
-                           .iter()
-                           .map_if_ok(|args| -> Result<_> {
-                                        Ok(doc_formatter::rust_method_variant(args,
-                                                    method_name.last_name()?,
-                                                    first_method.self_arg_kind()?,
-                                                    &self.input_data.crate_name))
-                                      })?,
-                       });
+/// Positions of processed types of the crate and its dependencies
+/// indexed by C++ name. A position is a pair of
+/// list index (0 for the current crate, `i + 1` for `dependency_types[i]`)
+/// and item index in that list.
+#[derive(Debug, Default)]
+struct RustTypeIndex {
+  by_cpp_name: HashMap<String, (usize, usize)>,
+  by_cpp_class: HashMap<CppTypeClassBase, (usize, usize)>,
+  by_rust_name: HashMap<RustName, (usize, usize)>,
+}
+
+impl RustTypeIndex {
+  /// Builds the index. If multiple types have the same key, the first one
+  /// is kept, so lookup results are the same as for a linear search.
+  fn new(processed_types: &[RustProcessedTypeInfo],
+         dependency_types: &[&[RustProcessedTypeInfo]])
+         -> RustTypeIndex {
+    let mut index = RustTypeIndex::default();
+    let lists = Some(processed_types)
+      .into_iter()
+      .chain(dependency_types.iter().cloned());
+    for (list_index, list) in lists.enumerate() {
+      for (item_index, item) in list.iter().enumerate() {
+        let position = (list_index, item_index);
+        index
+          .by_cpp_name
+          .entry(item.cpp_name.clone())
+          .or_insert(position);
+        let class_base = CppTypeClassBase {
+          name: item.cpp_name.clone(),
+          template_arguments: item.cpp_template_arguments.clone(),
+        };
+        index.by_cpp_class.entry(class_base).or_insert(position);
+        index
+          .by_rust_name
+          .entry(item.rust_name.clone())
+          .or_insert(position);
       }
+    }
+    index
+  }
 
-      // overloaded methods
-      let shared_arguments_for_trait = match self_argument {
-        None => Vec::new(),
-        Some(ref arg) => {
-          let mut renamed_self = arg.clone();
-          renamed_self.name = "original_self".to_string();
-          vec![renamed_self]
-        }
-      };
-      let mut shared_arguments = match self_argument {
-        None => Vec::new(),
-        Some(arg) => vec![arg],
-      };
-      let trait_lifetime_name = "largs";
-      let mut has_trait_lifetime = shared_arguments
-        .iter()
-        .any(|x| x.argument_type.rust_api_type.is_ref());
-      let first_return_type = args_variants[0].return_type.rust_api_type.clone();
-      let common_return_type = if args_variants
-           .iter()
-           .all(|x| &x.return_type.rust_api_type == &first_return_type) {
-        if first_return_type.is_ref() {
-          has_trait_lifetime = true;
-          Some(first_return_type.with_lifetime(trait_lifetime_name.to_string()))
-        } else {
-          Some(first_return_type)
-        }
-      } else {
-        None
-      };
-      if has_trait_lifetime {
-        for arg in &mut shared_arguments {
-          if arg.argument_type.rust_api_type.is_ref() {
-            arg.argument_type.rust_api_type = arg
-              .argument_type
-              .rust_api_type
-              .with_lifetime(trait_lifetime_name.to_string());
+  /// Returns `true` if the current crate has a type corresponding
+  /// to C++ class `class_base`.
+  fn crate_has_class(&self, class_base: &CppTypeClassBase) -> bool {
+    self
+      .by_cpp_class
+      .get(class_base)
+      .map_or(false, |&(list_index, _)| list_index == 0)
+  }
+
+  /// Returns `true` if the current crate has a type with Rust name `name`.
+  fn crate_has_rust_name(&self, name: &RustName) -> bool {
+    self
+      .by_rust_name
+      .get(name)
+      .map_or(false, |&(list_index, _)| list_index == 0)
+  }
+}
+
+/// Processed types of the crate and its dependencies available
+/// for type conversion.
+struct RustTypes<'a> {
+  processed_types: &'a [RustProcessedTypeInfo],
+  dependency_types: &'a [&'a [RustProcessedTypeInfo]],
+  /// Index for fast lookups. If `None`, linear search is used.
+  index: Option<&'a RustTypeIndex>,
+  /// Wrapper type for heap-allocated objects, as in `Config::set_box_type`.
+  box_type: &'a RustName,
+}
+
+impl<'a> RustTypes<'a> {
+  fn get(&self, position: (usize, usize)) -> &'a RustProcessedTypeInfo {
+    let (list_index, item_index) = position;
+    if list_index == 0 {
+      &self.processed_types[item_index]
+    } else {
+      &self.dependency_types[list_index - 1][item_index]
+    }
+  }
+
+  /// Returns first type satisfying the predicate `f`.
+  fn find<F>(&self, f: F) -> Option<&'a RustProcessedTypeInfo>
+    where F: Fn(&RustProcessedTypeInfo) -> bool
+  {
+    match self.processed_types.iter().find(|x| f(x)) {
+      None => {
+        for dep in self.dependency_types {
+          if let Some(r) = dep.iter().find(|x| f(x)) {
+            return Some(r);
           }
         }
-      }
-      let params_trait_lifetime = if has_trait_lifetime {
-        Some(trait_lifetime_name.to_string())
-      } else {
         None
-      };
-      type_declaration = Some(RustTypeDeclaration {
-                                name: {
-                                  let mut name = first_method.name.clone();
-                                  name.parts.pop().unwrap();
-                                  name.parts.push("overloading".to_string());
-                                  name.parts.push(trait_name.clone());
-                                  name
-                                },
-                                kind: RustTypeDeclarationKind::MethodParametersTrait {
-                                  shared_arguments: shared_arguments_for_trait,
-                                  impls: args_variants,
-                                  lifetime: params_trait_lifetime.clone(),
-                                  common_return_type: common_return_type.clone(),
-                                  method_name: method_name.clone(),
-                                  method_scope: first_method.scope.clone(),
-                                  is_unsafe: first_method.is_unsafe,
-                                },
-                                is_public: true,
-                                rust_doc: None,
-                              });
-
-      RustMethod {
-        name: method_name,
-        scope: first_method.scope,
-        arguments: RustMethodArguments::MultipleVariants {
-          params_trait_name: trait_name.clone(),
-          params_trait_lifetime: params_trait_lifetime,
-          common_return_type: common_return_type,
-          shared_arguments: shared_arguments,
-          variant_argument_name: "args".to_string(),
-          cpp_method_name: cpp_method_name,
-        },
-        variant_docs: doc_items,
-        common_doc: None,
-        is_unsafe: first_method.is_unsafe,
-      }
-    } else {
-      let mut method = filtered_methods
-        .pop()
-        .chain_err(|| "filtered_methods can't be empty")?;
-      let mut last_name = method
-        .name
-        .parts
-        .pop()
-        .chain_err(|| "name can't be empty")?;
-      if let Some(self_arg_kind_caption) = self_arg_kind_caption {
-        last_name = vec![last_name.as_ref(), self_arg_kind_caption.as_ref()].to_snake_case();
       }
-      method
-        .name
-        .parts
-        .push(sanitize_rust_identifier(&last_name));
+      Some(info) => Some(info),
+    }
+  }
 
-      method.doc = Some(RustMethodDocItem {
-                          cpp_fn: method.arguments.cpp_method.cpp_method.short_text(),
-                          rust_fns: Vec::new(),
-                          doc: method.arguments.cpp_method.cpp_method.doc.clone(),
-                        });
-      method.to_rust_method()
-    };
-    Ok((method, type_declaration))
+  /// Returns first type with C++ name `name`.
+  fn find_by_cpp_name(&self, name: &str) -> Option<&'a RustProcessedTypeInfo> {
+    match self.index {
+      Some(index) => index.by_cpp_name.get(name).map(|&p| self.get(p)),
+      None => self.find(|x| x.cpp_name == name),
+    }
   }
 
-  /// Splits `methods` to groups based on overloading constraints.
-  /// See `RustGenerator::generate_final_method` documentation for full list of these constraints.
-  /// Each element of the returned vector contains a list of methods that
-  /// can be safely overloaded together and a name suffix for these methods.
-  fn overload_functions(&self,
-                        methods: Vec<RustSingleMethod>)
-                        -> Result<Vec<(Option<String>, Vec<RustSingleMethod>)>> {
-    let mut buckets: Vec<Vec<RustSingleMethod>> = Vec::new();
-    for method in methods {
-      if let Some(mut b) = buckets
-           .iter_mut()
-           .find(|b| {
-                   b.iter()
-                     .all(|m| m.can_be_overloaded_with(&method).unwrap())
-                 }) {
-        b.push(method);
-        continue;
+  /// Returns first type corresponding to C++ class `class_base`.
+  fn find_class(&self, class_base: &CppTypeClassBase) -> Option<&'a RustProcessedTypeInfo> {
+    match self.index {
+      Some(index) => index.by_cpp_class.get(class_base).map(|&p| self.get(p)),
+      None => {
+        self.find(|x| {
+                    x.cpp_name == class_base.name &&
+                    x.cpp_template_arguments == class_base.template_arguments
+                  })
       }
-      buckets.push(vec![method]);
-    }
-    let mut all_self_args: HashSet<_> = HashSet::new();
-    for bucket in &buckets {
-      all_self_args.insert(bucket[0].self_arg_kind()?.clone());
     }
+  }
+}
 
-    let mut final_names = None;
-    {
-      let try_strategy = |strategy| -> Result<Vec<Option<String>>> {
-        let mut result = Vec::new();
-        for (bucket_index, bucket) in buckets.iter().enumerate() {
-          let mut bucket_caption: Option<Option<String>> = None;
-          for method in bucket {
-            let caption = method
-              .name_suffix(strategy, &all_self_args, bucket_index)?;
-            if bucket_caption.is_none() {
-              bucket_caption = Some(caption);
-            } else if Some(caption) != bucket_caption {
-              return Err("different captions within a bucket".into());
-            }
-          }
-          let bucket_caption = bucket_caption.expect("can't be None here");
-          if result.iter().any(|c| c == &bucket_caption) {
-            return Err("same captions for two buckets".into());
-          }
-          result.push(bucket_caption);
-        }
-        Ok(result)
+/// Converts `CppType` to its exact Rust equivalent (FFI-compatible)
+fn ffi_type(types: &RustTypes,
+            cpp_ffi_type: &CppType)
+            -> Result<RustType> {
+  let rust_name = match cpp_ffi_type.base {
+    CppTypeBase::Void => {
+      match cpp_ffi_type.indirection {
+        CppTypeIndirection::None => return Ok(RustType::EmptyTuple),
+        _ => RustName::new(vec!["libc".to_string(), "c_void".to_string()])?,
+      }
+    }
+    CppTypeBase::BuiltInNumeric(ref numeric) => {
+      if numeric == &CppBuiltInNumericType::Bool {
+        RustName::new(vec!["bool".to_string()])?
+      } else {
+        let own_name = match *numeric {
+          CppBuiltInNumericType::Bool => unreachable!(),
+          CppBuiltInNumericType::Char => "c_char",
+          CppBuiltInNumericType::SChar => "c_schar",
+          CppBuiltInNumericType::UChar => "c_uchar",
+          CppBuiltInNumericType::WChar => "wchar_t",
+          CppBuiltInNumericType::Short => "c_short",
+          CppBuiltInNumericType::UShort => "c_ushort",
+          CppBuiltInNumericType::Int => "c_int",
+          CppBuiltInNumericType::UInt => "c_uint",
+          CppBuiltInNumericType::Long => "c_long",
+          CppBuiltInNumericType::ULong => "c_ulong",
+          CppBuiltInNumericType::LongLong => "c_longlong",
+          CppBuiltInNumericType::ULongLong => "c_ulonglong",
+          CppBuiltInNumericType::Float => "c_float",
+          CppBuiltInNumericType::Double => "c_double",
+          _ => return Err(format!("unsupported numeric type: {:?}", numeric).into()),
+        };
+        RustName::new(vec!["libc".to_string(), own_name.to_string()])?
+      }
+    }
+    CppTypeBase::SpecificNumeric(CppSpecificNumericType { ref bits, ref kind, .. }) => {
+      // typedefs like `qint32` are not wrapped in separate Rust types,
+      // so they are interchangeable with primitive types without conversions
+      let letter = match *kind {
+        CppSpecificNumericTypeKind::Integer { ref is_signed } => if *is_signed { "i" } else { "u" },
+        CppSpecificNumericTypeKind::FloatingPoint => "f",
       };
-
-      for strategy in RustMethodCaptionStrategy::all() {
-        if let Ok(names) = try_strategy(&strategy) {
-          final_names = Some(names);
-          break;
-        }
+      RustName::new(vec![format!("{}{}", letter, bits)])?
+    }
+    CppTypeBase::PointerSizedInteger { ref is_signed, .. } => {
+      RustName::new(vec![if *is_signed { "isize" } else { "usize" }.to_string()])?
+    }
+    CppTypeBase::Enum { ref name } => {
+      match types.find_by_cpp_name(name) {
+        None => return Err(format!("type has no Rust equivalent: {}", name).into()),
+        Some(info) => info.rust_name.clone(),
       }
     }
-    if let Some(final_names) = final_names {
-      return Ok(final_names
-                  .into_iter()
-                  .zip(buckets.into_iter())
-                  .collect());
-    } else {
-      return Err(unexpected("all Rust caption strategies failed").into());
+    CppTypeBase::Class(ref name_and_args) => {
+      match types.find_class(name_and_args) {
+        None => return Err(format!("type has no Rust equivalent: {:?}", name_and_args).into()),
+        Some(info) => info.rust_name.clone(),
+      }
+    }
+    CppTypeBase::FunctionPointer(CppFunctionPointerType {
+                                   ref return_type,
+                                   ref arguments,
+                                   ref allows_variadic_arguments,
+                                 }) => {
+      if *allows_variadic_arguments {
+        return Err("function pointers with variadic arguments are not supported".into());
+      }
+      let mut rust_args = Vec::new();
+      for arg in arguments {
+        rust_args.push(ffi_type(types, arg)?);
+      }
+      let rust_return_type = ffi_type(types, return_type)?;
+      return Ok(RustType::FunctionPointer {
+                  arguments: rust_args,
+                  return_type: Box::new(rust_return_type),
+                });
+    }
+    CppTypeBase::TemplateParameter { .. } => return Err(unexpected("invalid cpp type").into()),
+  };
+  Ok(RustType::Common {
+       base: rust_name,
+       is_const: cpp_ffi_type.is_const,
+       is_const2: cpp_ffi_type.is_const2,
+       indirection: match cpp_ffi_type.indirection {
+         CppTypeIndirection::None => RustTypeIndirection::None,
+         CppTypeIndirection::Ptr => RustTypeIndirection::Ptr,
+         CppTypeIndirection::PtrPtr => RustTypeIndirection::PtrPtr,
+         CppTypeIndirection::PtrPtrPtr { is_const3 } => {
+           RustTypeIndirection::PtrPtrPtr { is_const3: is_const3 }
+         }
+         _ => {
+           return Err(format!("invalid FFI type indirection: {:?}",
+                              cpp_ffi_type.indirection)
+                          .into())
+         }
+       },
+       generic_arguments: None,
+     })
+}
+
+
+impl<'aa> RustGenerator<'aa> {
+  /// Returns all available processed types with the index
+  /// built after processing types of the crate.
+  fn types(&self) -> RustTypes {
+    RustTypes {
+      processed_types: &self.processed_types,
+      dependency_types: &self.input_data.dependency_types,
+      index: Some(&self.type_index),
+      box_type: self.input_data.settings.box_type(),
     }
   }
 
-  /// Generates methods, trait implementations and overloading types
-  /// for all specified methods. All methods must either be in the same
-  /// `RustMethodScope::Impl` scope or be free functions in the same module.
-  #[cfg_attr(feature="clippy", allow(for_kv_map))]
-  fn process_all_sibling_functions<'b, I>(&self,
-                                          methods: I,
-                                          scope: &RustMethodScope)
-                                          -> Result<ProcessFunctionsResult>
-    where I: Iterator<Item = &'b CppAndFfiMethod>
-  {
-    // Step 1: convert all methods to SingleVariant Rust methods and
-    // split them by last name.
-    let mut single_rust_methods: HashMap<String, Vec<RustSingleMethod>> = HashMap::new();
-    let mut result = ProcessFunctionsResult::default();
-    for method in methods {
-      if method.cpp_method.is_destructor() {
-        match self.process_destructor(method, scope) {
-          Ok(r) => result.trait_impls.push(r),
-          Err(msg) => {
-            log::llog(log::DebugRustSkips,
-                      || format!("Failed to generate destructor: {}\n{:?}\n", msg, method))
-          }
-        }
-        continue;
-      }
-      match self.generate_rust_single_method(method, scope, false) {
-        Ok(rust_method) => {
-          if (&method.cpp_method.name == "static_cast" ||
-              &method.cpp_method.name == "dynamic_cast" ||
-              &method.cpp_method.name == "qobject_cast") &&
-             method.cpp_method.class_membership.is_none() {
-            match self.process_cpp_cast(rust_method) {
-              Ok(mut r) => result.trait_impls.append(&mut r),
-              Err(msg) => {
-                log::llog(log::DebugRustSkips,
-                          || format!("Failed to generate cast wrapper: {}\n{:?}\n", msg, method))
-              }
+  /// Returns types of the crate that are not yet indexed
+  /// (`processed_types`) and types of the dependencies.
+  fn unindexed_types<'b>(&'b self,
+                         processed_types: &'b [RustProcessedTypeInfo])
+                         -> RustTypes<'b> {
+    RustTypes {
+      processed_types: processed_types,
+      dependency_types: &self.input_data.dependency_types,
+      index: None,
+      box_type: self.input_data.settings.box_type(),
+    }
+  }
+
+  /// Logs that `method` will not be wrapped and records it
+  /// so that it can be included in the dry run report.
+  fn add_skipped_method(&self, method: &CppAndFfiMethod, reason: String) {
+    self.add_skipped(SkippedItemKind::Method, method.short_text(), reason);
+  }
+
+  /// Logs that `cpp_item` is skipped because of `reason` and records it
+  /// in `RustGeneratorOutput::skipped`.
+  fn add_skipped_item(&self, cpp_item: String, reason: String) {
+    self.add_skipped(SkippedItemKind::Other, cpp_item, reason);
+  }
+
+  /// Records a C++ type that couldn't be converted to a Rust type.
+  fn add_skipped_type(&self, cpp_type: String, reason: String) {
+    self.add_skipped(SkippedItemKind::Type, cpp_type, reason);
+  }
+
+  /// Logs that `cpp_item` is skipped and records it with `kind`.
+  fn add_skipped(&self, kind: SkippedItemKind, cpp_item: String, reason: String) {
+    log::llog(log::DebugRustSkips,
+              || format!("Warning: skipped: {}\n{}\n", reason, cpp_item));
+    self
+      .skipped
+      .lock()
+      .expect("skipped mutex is poisoned")
+      .push(SkippedItem {
+              kind: kind,
+              cpp_item: cpp_item,
+              reason: reason,
+            });
+  }
+
+  /// Returns all processed and skipped C++ types of the crate, sorted by name.
+  fn type_manifest(&self) -> Vec<TypeManifestItem> {
+    let mut result: Vec<_> = self
+      .processed_types
+      .iter()
+      .map(|t| {
+             TypeManifestItem {
+               cpp_type: CppTypeClassBase {
+                   name: t.cpp_name.clone(),
+                   template_arguments: t.cpp_template_arguments.clone(),
+                 }
+                 .to_cpp_pseudo_code(),
+               status: TypeManifestStatus::Generated(t.rust_name.clone()),
+             }
+           })
+      .collect();
+    for item in self
+          .skipped
+          .lock()
+          .expect("skipped mutex is poisoned")
+          .iter()
+          .filter(|item| item.kind == SkippedItemKind::Type) {
+      result.push(TypeManifestItem {
+                    cpp_type: item.cpp_item.clone(),
+                    status: TypeManifestStatus::Skipped(item.reason.clone()),
+                  });
+    }
+    result.sort_by(|a, b| a.cpp_type.cmp(&b.cpp_type));
+    result
+  }
+
+  fn calc_top_module_names(&self) -> Result<HashMap<String, RustName>> {
+    let mut result = HashMap::new();
+    let mut overridden_headers = Vec::new();
+    {
+      let mut check_header = |header: &str| -> Result<()> {
+        if !result.contains_key(header) {
+          let custom_name = self
+            .input_data
+            .settings
+            .module_name_hook()
+            .and_then(|hook| hook(header));
+          let module_name = match custom_name {
+            Some(name) => {
+              overridden_headers.push(header.to_string());
+              name
             }
-          } else {
-            let name = rust_method.name.last_name()?.clone();
-            add_to_multihash(&mut single_rust_methods, name, rust_method);
-          }
-        }
-        Err(err) => {
-          log::llog(log::DebugRustSkips,
-                    || format!("failed to generate Rust function: {}", err))
+            None => {
+              include_file_to_module_name(header,
+                                          self.input_data.remove_qt_prefix,
+                                          self.input_data.settings.acronyms())
+            }
+          };
+          let parts = vec![self.input_data.crate_name.clone(), module_name];
+          result.insert(header.to_string(), RustName::new(parts)?);
         }
+        Ok(())
+      };
+      for header in self.input_data.cpp_data.all_include_files()? {
+        check_header(&header)?;
+      }
+      for header in &self.input_data.cpp_ffi_headers {
+        check_header(&header.include_file_base_name)?;
       }
     }
-    for (_, current_methods) in single_rust_methods {
-      assert!(!current_methods.is_empty());
-
-      for (name_suffix, overloaded_methods) in self.overload_functions(current_methods)? {
-        let (method, type_declaration) =
-          self
-            .generate_final_method(overloaded_methods, scope, name_suffix)?;
-        if method.variant_docs.is_empty() {
-          return Err(unexpected(format!("docs are empty! {:?}", method)).into());
-        }
-        result.methods.push(method);
-        if let Some(r) = type_declaration {
-          result.overloading_types.push(r);
-        }
+    for header in &overridden_headers {
+      let name = &result[header];
+      if let Some((other_header, _)) = result
+           .iter()
+           .find(|&(k, v)| k != header && v == name) {
+        return Err(format!("module name \"{}\" returned by module name hook for \"{}\" \
+                            conflicts with module name of \"{}\"",
+                           name.last_name()?,
+                           header,
+                           other_header)
+                       .into());
       }
     }
-    result
-      .methods
-      .sort_by(|a, b| {
-                 a.name
-                   .last_name()
-                   .unwrap_or(&String::new())
-                   .cmp(b.name.last_name().unwrap_or(&String::new()))
-               });
-    result
-      .trait_impls
-      .sort_by(|a, b| a.trait_type.cmp(&b.trait_type));
     Ok(result)
   }
 
-  /// Generates a Rust module with specified name from specified
-  /// C++ header. If the module should have nested modules,
-  /// this function calls itself recursively with nested module name
-  /// but the same header data.
-  pub fn generate_module<'a, 'b>(&'a self,
-                                 mut cpp_methods: Vec<&'a CppAndFfiMethod>,
-                                 module_name: &'b RustName)
-                                 -> Result<(Option<RustModule>, Vec<&'a CppAndFfiMethod>)> {
-    let mut direct_submodules = HashSet::new();
-    let cpp_header = if module_name.parts.len() == 2 {
-      self
-        .top_module_names
-        .iter()
-        .find(|&(_k, v)| v == module_name)
-        .and_then(|(k, _v)| Some(k.clone()))
-    } else {
-      None
-    };
-    let mut module = RustModule {
-      name: module_name.last_name()?.clone(),
-      types: Vec::new(),
-      functions: Vec::new(),
-      submodules: Vec::new(),
-      trait_impls: Vec::new(),
-      doc: if module_name.parts.len() >= 2 && module_name.parts[1] == "slots" {
-        if module_name.parts.len() == 3 && module_name.parts[2] == "raw" {
-          Some(doc_formatter::slots_raw_module_doc())
-        } else if module_name.parts.len() == 2 {
-          Some(doc_formatter::slots_module_doc())
-        } else {
-          return Err(unexpected("unknown slots submodule").into());
-        }
-      } else {
-        cpp_header
-          .as_ref()
-          .map(|h| format!("Entities from `{}` C++ header", h))
-      },
-    };
-    let mut rust_overloading_types = Vec::new();
-    let mut good_methods = Vec::new();
-    {
-      // Checks if the name should be processed.
-      // Returns true if the name is directly in this module.
-      // If the name is in this module's submodule, adds
-      // name of the direct submodule to direct_submodules list.
-      let mut check_name = |rust_name: &RustName| {
-        if module_name.includes(rust_name) {
-          if module_name.includes_directly(rust_name) {
-            return true;
-          } else {
-            let direct_submodule = &rust_name.parts[module_name.parts.len()];
-            if !direct_submodules.contains(direct_submodule) {
-              direct_submodules.insert(direct_submodule.clone());
-            }
-          }
-        }
-        false
-      };
-
-      for type_data in &self.processed_types {
-        if check_name(&type_data.rust_name) {
-          let (mut result, tmp_cpp_methods) = self.generate_type(type_data, cpp_methods)?;
-          cpp_methods = tmp_cpp_methods;
-          if let Some(ref cpp_header) = cpp_header {
-            if &type_data.cpp_name == cpp_header {
-              if let RustTypeDeclarationKind::CppTypeWrapper { ref cpp_doc, .. } =
-                result.main_type.kind {
-                if let Some(ref cpp_doc) = *cpp_doc {
-                  let mut doc = cpp_doc.html.as_str();
-                  if let Some(index) = doc.find("\n") {
-                    doc = &doc[0..index];
-                  }
-                  module.doc = Some(doc.to_string());
-                }
-              }
-            }
-          }
-          doc_formatter::add_special_type_docs(&mut result.main_type)?;
-          module.types.push(result.main_type);
-          rust_overloading_types.append(&mut result.overloading_types);
-        }
-      }
 
+  /// Converts specified C++ type to Rust.
+  /// Returns:
+  /// - main_type - representation of the target type, including
+  /// directly implemented methods of the type and trait implementations;
+  /// - overloading_types - traits and their implementations that
+  /// emulate C++ method overloading.
+  fn generate_type<'a>(&'a self,
+                       info: &'a RustProcessedTypeInfo,
+                       mut cpp_methods: Vec<&'a CppAndFfiMethod>)
+                       -> Result<(GenerateTypeResult, Vec<&'a CppAndFfiMethod>)> {
+    Ok(match info.kind {
+         RustTypeWrapperKind::Enum { ref is_flaggable, .. } => {
+           let mut trait_impls = Vec::new();
+           if *is_flaggable {
+             trait_impls.push(flags_from_enum_impl(&info.rust_name)?);
+           }
+           (GenerateTypeResult {
+              main_type: RustTypeDeclaration {
+                name: info.rust_name.clone(),
+                kind: RustTypeDeclarationKind::CppTypeWrapper {
+                  kind: info.kind.clone(),
+                  cpp_type_name: info.cpp_name.clone(),
+                  cpp_template_arguments: None,
+                  cpp_doc: info.cpp_doc.clone(),
+                  methods: Vec::new(),
+                  constants: Vec::new(),
+                  trait_impls: trait_impls,
+                  qt_receivers: Vec::new(),
+                  qt_hash_types: None,
+                  vector_info: None,
+                  list_element_type: None,
+                  pod_traits: None,
+                  transparent_type: None,
+                  iterator_adapter: None,
+                  is_send: false,
+                  is_sync: false,
+                },
+                is_public: info.is_public,
+                rust_doc: None,
+                doc_override: None,
+              },
+              overloading_types: Vec::new(),
+            },
+            cpp_methods)
+         }
+         RustTypeWrapperKind::Struct { .. } => {
+      let methods_scope = RustMethodScope::Impl {
+        target_type: RustType::Common {
+          base: info.rust_name.clone(),
+          generic_arguments: None,
+          indirection: RustTypeIndirection::None,
+          is_const: false,
+          is_const2: false,
+        },
+      };
+      let class_type = CppTypeClassBase {
+        name: info.cpp_name.clone(),
+        template_arguments: info.cpp_template_arguments.clone(),
+      };
+      let mut good_methods = Vec::new();
       let mut tmp_cpp_methods = Vec::new();
       for method in cpp_methods {
-        if method.cpp_method.class_membership.is_none() {
-          let rust_name = self.free_function_rust_name(&method.cpp_method)?;
-
-          if check_name(&rust_name) {
+        if let Some(ref info) = method.cpp_method.class_membership {
+          if &info.class_type == &class_type {
             good_methods.push(method);
             continue;
           }
@@ -1601,519 +2644,6187 @@ impl<'aa> RustGenerator<'aa> {
         tmp_cpp_methods.push(method);
       }
       cpp_methods = tmp_cpp_methods;
-    }
-    for name in direct_submodules {
-      let mut new_name = module_name.clone();
-      new_name.parts.push(name);
-      let (submodule, tmp_cpp_methods) = self.generate_module(cpp_methods, &new_name)?;
-      cpp_methods = tmp_cpp_methods;
-      if let Some(submodule) = submodule {
-        module.submodules.push(submodule);
+      let mut functions_result =
+        self
+          .process_all_sibling_functions(good_methods.iter().cloned(), &methods_scope)?;
+      if self.input_data.settings.debug_impl_types().contains(&info.cpp_name) {
+        let r = self.process_debug_impl(&good_methods, &functions_result.methods, &methods_scope)?;
+        functions_result.trait_impls.push(r);
+      }
+      if let Some(r) = self
+           .process_display_impl(&good_methods, &functions_result.methods, &methods_scope)? {
+        functions_result.trait_impls.push(r);
+      }
+      let pod_traits = self.pod_traits(info)?;
+      if self.input_data.settings.total_order_types().contains(&info.cpp_name) {
+        let raw_trait_impls = self
+          .input_data
+          .settings
+          .raw_trait_impls()
+          .get(&info.cpp_name)
+          .cloned()
+          .unwrap_or_default();
+        let r = total_order_impls(&info.rust_name,
+                                  &functions_result.trait_impls,
+                                  &raw_trait_impls,
+                                  pod_traits.as_ref())?;
+        functions_result.trait_impls.extend(r);
+      }
+      if pod_traits.is_some() {
+        remove_pod_trait_impls(&mut functions_result.trait_impls)?;
       }
-    }
-    let mut free_functions_result =
-      self
-        .process_all_sibling_functions(good_methods.into_iter(), &RustMethodScope::Free)?;
-    module.trait_impls = free_functions_result.trait_impls;
-    module.functions = free_functions_result.methods;
-    rust_overloading_types.append(&mut free_functions_result.overloading_types);
-    if !rust_overloading_types.is_empty() {
-      rust_overloading_types.sort_by(|a, b| a.name.cmp(&b.name));
-      module
-        .submodules
-        .push(RustModule {
-                name: "overloading".to_string(),
-                types: rust_overloading_types,
-                functions: Vec::new(),
-                submodules: Vec::new(),
-                trait_impls: Vec::new(),
-                doc: Some(doc_formatter::overloading_module_doc()),
-              });
-    }
-    module.types.sort_by(|a, b| a.name.cmp(&b.name));
-    module.submodules.sort_by(|a, b| a.name.cmp(&b.name));
-    if module.types.is_empty() && module.functions.is_empty() && module.submodules.is_empty() {
-      log::llog(log::DebugRustSkips,
-                || format!("Skipping empty module: {}", module.name));
-      return Ok((None, cpp_methods));
-    }
-    Ok((Some(module), cpp_methods))
-  }
 
+      let mut qt_receivers_by_name: HashMap<String, Vec<_>> = HashMap::new();
+      if self
+           .input_data
+           .cpp_data
+           .inherits(&info.cpp_name, "QObject") {
+        for method in self
+              .input_data
+              .cpp_data
+              .current
+              .parser
+              .methods
+              .iter()
+              .chain(self
+                       .input_data
+                       .cpp_data
+                       .current
+                       .processed
+                       .inherited_methods
+                       .iter()) {
+          if let Some(ref info) = method.class_membership {
+            if &info.class_type == &class_type && (info.is_signal || info.is_slot) {
+              add_to_multihash(&mut qt_receivers_by_name,
+                               method.name.clone(),
+                               RustQtReceiverDeclaration {
+                                 type_name: method.name.to_class_case(),
+                                 method_name: method.name.to_snake_case(),
+                                 receiver_type: if info.is_signal {
+                                   RustQtReceiverType::Signal
+                                 } else {
+                                   RustQtReceiverType::Slot
+                                 },
+                                 original_method_name: method.name.clone(),
+                                 receiver_id: method.receiver_id()?,
+                                 arguments: method
+                                   .arguments
+                                   .iter()
+                                   .map_if_ok(|arg| -> Result<_> {
+                Ok(complete_type(&self.types(),
+                                 &arg
+                                    .argument_type
+                                    .to_cpp_ffi_type(CppTypeRole::NotReturnType)?,
+                                 &CppFfiArgumentMeaning::Argument(0),
+                                 false,
+                                 &ReturnValueAllocationPlace::NotApplicable)?
+                       .rust_api_type
+                       .with_lifetime("static".to_string()))
+              })?,
+                               });
+            }
+          }
+        }
+      }
+      let qt_receivers = qt_receivers_by_name
+        .into_iter()
+        .flat_map(|(_, receivers)| if receivers.len() == 1 {
+                    receivers
+                  } else {
+                    receivers
+                      .into_iter()
+                      .map(|r| {
+            let name = format!("{}_{}",
+                               r.method_name,
+                               r.arguments
+                                 .iter()
+                                 .map(|x| {
+                                        x.caption(&info.rust_name)
+                                          .expect("receiver argument caption failed")
+                                      })
+                                 .join("_"));
+            RustQtReceiverDeclaration {
+              type_name: name.to_class_case(),
+              method_name: name.to_snake_case(),
+              ..r
+            }
+          })
+                      .collect()
+                  })
+        .collect();
 
-  /// Generates exact (FFI-compatible) Rust equivalent of `CppAndFfiMethod` object.
-  fn generate_ffi_function(&self, data: &CppAndFfiMethod) -> Result<RustFFIFunction> {
-    let mut args = Vec::new();
-    for arg in &data.c_signature.arguments {
-      let rust_type = ffi_type(&self.processed_types,
-                               &self.input_data.dependency_types,
-                               &arg.argument_type.ffi_type)?;
-      args.push(RustFFIArgument {
-                  name: sanitize_rust_identifier(&arg.name),
-                  argument_type: rust_type,
-                });
+      (GenerateTypeResult {
+         main_type: RustTypeDeclaration {
+           name: info.rust_name.clone(),
+           kind: RustTypeDeclarationKind::CppTypeWrapper {
+             kind: info.kind.clone(),
+             cpp_type_name: info.cpp_name.clone(),
+             cpp_template_arguments: info.cpp_template_arguments.clone(),
+             cpp_doc: info.cpp_doc.clone(),
+             methods: functions_result.methods,
+             constants: self.associated_constants(info),
+             trait_impls: functions_result.trait_impls,
+             qt_receivers: qt_receivers,
+             qt_hash_types: self.qt_hash_types(info),
+             vector_info: self.vector_info(info),
+             list_element_type: self.list_element_type(info),
+             pod_traits: pod_traits,
+             transparent_type: self.transparent_type(info)?,
+             iterator_adapter: self.iterator_adapter(info, &good_methods),
+             is_send: info.cpp_template_arguments.is_none() &&
+                      self.input_data.settings.send_types().contains(&info.cpp_name),
+             is_sync: info.cpp_template_arguments.is_none() &&
+                      self.input_data.settings.sync_types().contains(&info.cpp_name),
+           },
+           is_public: info.is_public,
+           rust_doc: None,
+           doc_override: None,
+         },
+         overloading_types: functions_result.overloading_types,
+       },
+       cpp_methods)
     }
-    Ok(RustFFIFunction {
-         return_type: ffi_type(&self.processed_types,
-                               &self.input_data.dependency_types,
-                               &data.c_signature.return_type.ffi_type)?,
-         name: data.c_name.clone(),
-         arguments: args,
        })
   }
 
-  /// Generates Rust representations of all FFI functions
-  pub fn generate_ffi_functions(&self) -> Vec<(String, Vec<RustFFIFunction>)> {
-    log::status("Generating Rust FFI functions");
-    let mut ffi_functions = Vec::new();
-
-    for header in &self.input_data.cpp_ffi_headers {
-      let mut functions = Vec::new();
-      for method in &header.methods {
-        match self.generate_ffi_function(method) {
-          Ok(function) => {
-            functions.push(function);
-          }
-          Err(msg) => {
-            log::llog(log::DebugRustSkips, || {
-              format!("Can't generate Rust FFI function for method:\n{}\n{}\n",
-                      method.short_text(),
-                      msg)
-            });
-          }
-        }
+  /// Returns key and value types of `QHash` instantiation `info`
+  /// if conversions to and from Rust collections should be
+  /// generated for it. Keys must be integers so that they can be
+  /// used in `std::collections::HashMap`, and values must be numbers.
+  fn qt_hash_types(&self, info: &RustProcessedTypeInfo) -> Option<RustQtHashTypes> {
+    if info.cpp_name != "QHash" {
+      return None;
+    }
+    let args = match info.cpp_template_arguments {
+      Some(ref args) if args.len() == 2 => args,
+      _ => return None,
+    };
+    if args.iter().any(|arg| arg.indirection != CppTypeIndirection::None) {
+      return None;
+    }
+    let key_is_integer = args[0].base.is_integer();
+    let value_is_numeric = match args[1].base {
+      CppTypeBase::BuiltInNumeric(..) |
+      CppTypeBase::SpecificNumeric(..) |
+      CppTypeBase::PointerSizedInteger { .. } => true,
+      _ => false,
+    };
+    if !key_is_integer || !value_is_numeric {
+      return None;
+    }
+    let rust_type = |cpp_type: &CppType| {
+      ffi_type(&self.types(),
+               cpp_type)
+    };
+    match (rust_type(&args[0]), rust_type(&args[1])) {
+      (Ok(key_type), Ok(value_type)) => {
+        Some(RustQtHashTypes {
+               key_type: key_type,
+               value_type: value_type,
+             })
       }
-      ffi_functions.push((header.include_file_base_name.clone(), functions));
+      _ => None,
     }
-    ffi_functions
   }
 
-  /// Generates Rust names and type information for all available C++ types.
-  fn calc_processed_types(&self) -> Result<Vec<RustProcessedTypeInfo>> {
-    let mut result = Vec::new();
-    for type_info in &self.input_data.cpp_data.current.parser.types {
-      if let CppTypeKind::Class { ref template_arguments, .. } = type_info.kind {
-        if template_arguments.is_some() {
-          continue;
-        }
+  /// Returns traits that should be implemented for `info`
+  /// if it was marked as POD with `Config::set_pod_type`.
+  /// Returns an error if the marked type is not stack-allocated
+  /// or if `PartialEq` is requested for a type that may have padding bytes.
+  fn pod_traits(&self, info: &RustProcessedTypeInfo) -> Result<Option<RustPodTraits>> {
+    let partial_eq = match self.input_data.settings.pod_types().get(&info.cpp_name) {
+      Some(&partial_eq) => partial_eq,
+      None => return Ok(None),
+    };
+    match info.kind {
+      RustTypeWrapperKind::Struct { size_const_name: Some(..), .. } => {}
+      _ => {
+        return Err(format!("POD type must be a stack-allocated class: {}", info.cpp_name).into())
       }
-      let rust_name =
-        self
-          .calculate_rust_name(&type_info.name, &type_info.include_file, false, None)?;
-      let rust_type_info = RustProcessedTypeInfo {
-        cpp_name: type_info.name.clone(),
-        cpp_doc: type_info.doc.clone(),
-        cpp_template_arguments: None,
-        kind: match type_info.kind {
-          CppTypeKind::Class { .. } => {
-            match self
-                    .input_data
-                    .cpp_data
-                    .type_allocation_place(&type_info.name) {
-              Err(err) => {
-                log::llog(log::DebugRustSkips,
-                          || format!("Can't process type: {}: {}", type_info.name, err));
-                continue;
-              }
-              Ok(place) => {
-                RustTypeWrapperKind::Struct {
-                  size_const_name: match place {
-                    CppTypeAllocationPlace::Stack => Some(size_const_name(&rust_name)),
-                    CppTypeAllocationPlace::Heap => None,
-                  },
-                  is_deletable: !self
-                                   .input_data
-                                   .cpp_data
-                                   .current
-                                   .parser
-                                   .has_non_public_destructor(&CppTypeClassBase {
-                                                                 name: type_info.name.clone(),
-                                                                 template_arguments: None,
-                                                               }),
-                  slot_wrapper: None,
-                }
-              }
+    }
+    if partial_eq {
+      let is_padding_free = match self
+              .input_data
+              .cpp_data
+              .find_type_info(|x| x.name == info.cpp_name) {
+        Some(type_info) => {
+          match type_info.kind {
+            CppTypeKind::Class { ref bases, ref fields, .. } => {
+              bases.is_empty() && is_padding_free_layout(fields)
             }
+            CppTypeKind::Enum { .. } => false,
           }
-          CppTypeKind::Enum { ref values } => {
+        }
+        None => false,
+      };
+      if !is_padding_free {
+        return Err(format!("PartialEq can't be implemented for POD type that may have \
+                            padding: {}",
+                           info.cpp_name)
+                       .into());
+      }
+    }
+    Ok(Some(RustPodTraits { partial_eq: partial_eq }))
+  }
 
-            let mut is_flaggable = false;
-            let template_arg_sample = CppType {
+  /// Returns the inner type of `info` if it was marked with
+  /// `Config::set_transparent_type`.
+  /// Returns an error if the marked type is not stack-allocated.
+  fn transparent_type(&self, info: &RustProcessedTypeInfo) -> Result<Option<RustType>> {
+    let inner_type = match self.input_data.settings.transparent_types().get(&info.cpp_name) {
+      Some(inner_type) => inner_type,
+      None => return Ok(None),
+    };
+    match info.kind {
+      RustTypeWrapperKind::Struct { size_const_name: Some(..), .. } => {}
+      _ => {
+        return Err(format!("transparent type must be a stack-allocated class: {}",
+                           info.cpp_name)
+                       .into())
+      }
+    }
+    Ok(Some(RustType::Common {
+              base: inner_type.clone(),
+              generic_arguments: None,
+              indirection: RustTypeIndirection::None,
               is_const: false,
               is_const2: false,
-              indirection: CppTypeIndirection::None,
-              base: CppTypeBase::Enum { name: type_info.name.clone() },
-            };
+            }))
+  }
 
-            for flag_owner_name in &["QFlags", "QUrlTwoFlags"] {
-              if let Some(instantiations) =
-                self
-                  .input_data
-                  .cpp_data
-                  .current
-                  .processed
-                  .template_instantiations
-                  .iter()
-                  .find(|x| &x.class_name == &flag_owner_name.to_string()) {
-                if instantiations
-                     .instantiations
-                     .iter()
-                     .any(|ins| {
-                            ins
-                              .template_arguments
-                              .iter()
-                              .any(|arg| arg == &template_arg_sample)
-                          }) {
-                  is_flaggable = true;
-                  break;
-                }
-              }
-            }
-            RustTypeWrapperKind::Enum {
-              values: prepare_enum_values(values),
-              is_flaggable: is_flaggable,
-            }
-          }
-        },
-        rust_name: rust_name,
-        is_public: true,
-      };
-      result.push(rust_type_info);
+  /// Returns information required to generate an iterator adapter for `info`
+  /// if it was marked with `Config::add_iterable_type`. `methods` are
+  /// the methods of `info`. The adapter is only generated if the container has
+  /// `begin() const` and `end() const` methods returning the same class by value
+  /// and the iterator class has suitable `operator++`, `operator*` and `operator!=`.
+  fn iterator_adapter(&self,
+                      info: &RustProcessedTypeInfo,
+                      methods: &[&CppAndFfiMethod])
+                      -> Option<RustIteratorAdapter> {
+    if !self.input_data.settings.iterable_types().contains(&info.cpp_name) {
+      return None;
     }
-    let template_final_name =
-      |result: &Vec<RustProcessedTypeInfo>, item: &RustProcessedTypeInfo| -> Result<RustName> {
-        let mut name = item.rust_name.clone();
-        let last_name = name
-          .parts
-          .pop()
-          .chain_err(|| "name.parts can't be empty")?;
-        let mut arg_captions = Vec::new();
-        if let Some(ref args) = item.cpp_template_arguments {
-          for x in args {
-            let rust_type = complete_type(result,
-                                          &self.input_data.dependency_types,
-                                          &x.to_cpp_ffi_type(CppTypeRole::NotReturnType)?,
-                                          &CppFfiArgumentMeaning::Argument(0),
-                                          true,
-                                          &ReturnValueAllocationPlace::NotApplicable)?;
-            arg_captions.push(rust_type.rust_api_type.caption(&name)?.to_class_case());
-          }
-        } else {
-          return Err("template arguments expected".into());
+    let skip = |reason: &str| {
+      log::llog(log::DebugRustSkips, || {
+        format!("Can't generate iterator adapter for {}: {}", info.cpp_name, reason)
+      });
+      None
+    };
+    let find_accessor = |name: &str| {
+      methods
+        .iter()
+        .find(|m| {
+                m.cpp_method.name == name && m.cpp_method.arguments.is_empty() &&
+                m.kind == CppFfiMethodKind::Real &&
+                m.cpp_method.class_membership.as_ref().map_or(false, |info| {
+                  info.is_const && !info.is_static
+                })
+              })
+        .map(|m| &m.cpp_method.return_type)
+    };
+    let iterator_class = match (find_accessor("begin"), find_accessor("end")) {
+      (Some(begin_type), Some(end_type)) if begin_type == end_type &&
+                                            begin_type.indirection ==
+                                            CppTypeIndirection::None => {
+        match begin_type.base {
+          CppTypeBase::Class(ref class_base) => class_base.clone(),
+          _ => return skip("begin() doesn't return a class"),
         }
-        name.parts.push(last_name + &arg_captions.join(""));
-        Ok(name)
-      };
-    let mut unnamed_items = Vec::new();
-    for template_instantiations in
-      &self
-         .input_data
-         .cpp_data
-         .current
-         .processed
-         .template_instantiations {
-      let type_info = self
-        .input_data
-        .cpp_data
-        .find_type_info(|x| &x.name == &template_instantiations.class_name)
-        .chain_err(|| {
-                     format!("type info not found for {}",
-                             &template_instantiations.class_name)
-                   })?;
-      if template_instantiations.class_name == "QFlags" {
-        // special processing is implemented for QFlags
-        continue;
       }
-      for ins in &template_instantiations.instantiations {
-        let rust_name = self
-          .calculate_rust_name(&template_instantiations.class_name,
-                               &type_info.include_file,
-                               false,
-                               None)?;
-        unnamed_items.push(RustProcessedTypeInfo {
-                             cpp_name: template_instantiations.class_name.clone(),
-                             cpp_doc: type_info.doc.clone(),
-                             cpp_template_arguments: Some(ins.template_arguments.clone()),
-                             kind: RustTypeWrapperKind::Struct {
-                               size_const_name: None,
-                               is_deletable:
-                                 !self
-                                    .input_data
-                                    .cpp_data
-                                    .current
-                                    .parser
-                                    .has_non_public_destructor(&CppTypeClassBase {
-                                                                  name: template_instantiations
-                                                                    .class_name
-                                                                    .clone(),
-                                                                  template_arguments:
-                                                                    Some(ins
-                                                                           .template_arguments
-                                                                           .clone()),
-                                                                }),
-                               slot_wrapper: None,
-                             },
-                             rust_name: rust_name,
-                             is_public: true,
-                           });
+      _ => return skip("suitable begin() and end() methods not found"),
+    };
+    let iterator_info = match self.types().find_class(&iterator_class) {
+      Some(r) => r,
+      None => return skip("iterator type is not available"),
+    };
+    let iterator_is_boxed = match iterator_info.kind {
+      RustTypeWrapperKind::Struct { ref size_const_name, .. } => size_const_name.is_none(),
+      RustTypeWrapperKind::Enum { .. } => return skip("iterator type is an enum"),
+    };
+    let find_operator = |operator: CppOperator, is_const: bool, arguments_count: usize| {
+      self
+        .input_data
+        .cpp_ffi_headers
+        .iter()
+        .flat_map(|header| header.methods.iter())
+        .find(|m| {
+                m.cpp_method.operator.as_ref() == Some(&operator) &&
+                m.cpp_method.arguments.len() == arguments_count &&
+                m.kind == CppFfiMethodKind::Real &&
+                m.cpp_method.class_membership.as_ref().map_or(false, |info| {
+                  info.class_type == iterator_class && info.is_const == is_const
+                })
+              })
+    };
+    let (increment, indirection, not_equal) =
+      match (find_operator(CppOperator::PrefixIncrement, false, 0),
+             find_operator(CppOperator::Indirection, true, 0),
+             find_operator(CppOperator::NotEqualTo, true, 1)) {
+        (Some(increment), Some(indirection), Some(not_equal)) => {
+          (increment, indirection, not_equal)
+        }
+        _ => return skip("iterator operators not found"),
+      };
+    if indirection
+         .c_signature
+         .arguments
+         .iter()
+         .any(|arg| arg.meaning == CppFfiArgumentMeaning::ReturnValue) {
+      return skip("operator* returns a class by value");
+    }
+    let item_type = match ffi_type(&self.types(), &indirection.c_signature.return_type.ffi_type) {
+      Ok(RustType::Common {
+           base,
+           generic_arguments,
+           indirection: RustTypeIndirection::Ptr,
+           ..
+         }) => {
+        RustType::Common {
+          base: base,
+          generic_arguments: generic_arguments,
+          is_const: true,
+          is_const2: false,
+          indirection: RustTypeIndirection::Ref { lifetime: Some("a".to_string()) },
+        }
       }
+      Ok(r @ RustType::Common { indirection: RustTypeIndirection::None, .. }) => r,
+      _ => return skip("unsupported return type of operator*"),
+    };
+    let mut name_parts = info.rust_name.parts.clone();
+    match name_parts.last_mut() {
+      Some(last) => last.push_str("Iter"),
+      None => return skip("empty Rust name"),
     }
-    let mut any_success = true;
-    while !unnamed_items.is_empty() {
-      if !any_success {
-        log::error("Failed to generate Rust names for template types:");
-        for r in unnamed_items {
-          log::error(format!("  {:?}\n  {}\n\n",
-                             r,
-                             if let Err(err) = template_final_name(&result, &r) {
-                               err
-                             } else {
-                               return Err("template_final_name must return Err at this stage"
-                                            .into());
-                             }));
+    if self
+         .processed_types
+         .iter()
+         .any(|t| t.rust_name.parts == name_parts) {
+      return skip("adapter name is already taken");
+    }
+    Some(RustIteratorAdapter {
+           name: name_parts.pop().unwrap(),
+           iterator_type: RustType::Common {
+             base: iterator_info.rust_name.clone(),
+             generic_arguments: None,
+             is_const: false,
+             is_const2: false,
+             indirection: RustTypeIndirection::None,
+           },
+           iterator_is_boxed: iterator_is_boxed,
+           item_type: item_type,
+           increment_function: increment.c_name.clone(),
+           indirection_function: indirection.c_name.clone(),
+           not_equal_function: not_equal.c_name.clone(),
+         })
+  }
+
+  /// Returns element type and available operations of `std::vector`
+  /// instantiation `info` if conversions to and from Rust collections
+  /// should be generated for it. Elements must be passed by value and must be
+  /// either non-class types or stack-allocated class types. Class types
+  /// can only be cloned if they have a public copy constructor.
+  fn vector_info(&self, info: &RustProcessedTypeInfo) -> Option<RustVectorInfo> {
+    if info.cpp_name != "std::vector" {
+      return None;
+    }
+    let element_type = match self.element_type(info) {
+      Some(r) => r,
+      None => return None,
+    };
+    let cpp_element_type = match info.cpp_template_arguments {
+      Some(ref args) => &args[0],
+      None => return None,
+    };
+    let element_class_name = match cpp_element_type.base {
+      CppTypeBase::Class(ref class_type) => Some(&class_type.name),
+      _ => None,
+    };
+    if element_class_name.is_some() {
+      if let RustType::Common { ref base, .. } = element_type {
+        match self.types().find(|x| &x.rust_name == base) {
+          Some(&RustProcessedTypeInfo {
+                 kind: RustTypeWrapperKind::Struct { size_const_name: Some(..), .. }, ..
+               }) => {}
+          _ => return None,
         }
-        break;
       }
-      any_success = false;
-      let mut unnamed_items_new = Vec::new();
-      for mut r in unnamed_items {
-        match template_final_name(&result, &r) {
-          Ok(name) => {
-            r.rust_name = name.clone();
-            if let RustTypeWrapperKind::Struct { ref mut size_const_name, .. } = r.kind {
-              match self
-                      .input_data
-                      .cpp_data
-                      .type_allocation_place(&r.cpp_name) {
-                Err(err) => {
-                  log::log(log::DebugRustSkips,
-                           format!("Can't process type: {}: {}", r.cpp_name, err));
-                  continue;
-                }
-                Ok(place) => {
-                  *size_const_name = match place {
-                    CppTypeAllocationPlace::Stack => Some(self::size_const_name(&name)),
-                    CppTypeAllocationPlace::Heap => None,
-                  };
-                }
-              }
-            } else {
-              unreachable!();
+    }
+    let push_back_methods: Vec<_> = self
+      .input_data
+      .cpp_ffi_headers
+      .iter()
+      .flat_map(|header| header.methods.iter())
+      .map(|method| &method.cpp_method)
+      .filter(|method| {
+                method.name == "push_back" && method.arguments.len() == 1 &&
+                method
+                  .class_membership
+                  .as_ref()
+                  .map_or(false, |m| {
+                    m.class_type.name == info.cpp_name &&
+                    m.class_type.template_arguments == info.cpp_template_arguments
+                  })
+              })
+      .collect();
+    let is_move = |method: &&CppMethod| {
+      method.arguments[0].argument_type.indirection == CppTypeIndirection::RValueRef
+    };
+    Some(RustVectorInfo {
+           element_type: element_type,
+           element_is_clone: element_class_name.map_or(true, |name| {
+             self
+               .input_data
+               .cpp_data
+               .has_public_copy_constructor(name)
+           }),
+           has_copy_push_back: push_back_methods.iter().any(|m| !is_move(m)),
+           // only class types can be moved to the vector
+           has_move_push_back: element_class_name.is_some() &&
+                               push_back_methods.iter().any(|m| is_move(m)),
+         })
+  }
+
+  /// Returns Rust type of the elements of `QList` or `QVector` instantiation `info`
+  /// if an index-based iterator should be generated for it. The iterator
+  /// is not generated for types marked with `Config::add_iterable_type`
+  /// because their iterator adapter also provides `iter()`.
+  fn list_element_type(&self, info: &RustProcessedTypeInfo) -> Option<RustType> {
+    if info.cpp_name != "QList" && info.cpp_name != "QVector" {
+      return None;
+    }
+    if self.input_data.settings.iterable_types().contains(&info.cpp_name) {
+      return None;
+    }
+    let mut name_parts = info.rust_name.parts.clone();
+    if let Some(last) = name_parts.last_mut() {
+      last.push_str("Iter");
+    }
+    if self
+         .processed_types
+         .iter()
+         .any(|t| t.rust_name.parts == name_parts) {
+      return None;
+    }
+    self.element_type(info)
+  }
+
+  /// Returns associated constants for public static const members
+  /// of C++ class `info` that have a known integral value.
+  fn associated_constants(&self, info: &RustProcessedTypeInfo) -> Vec<RustAssociatedConstant> {
+    if info.cpp_template_arguments.is_some() {
+      return Vec::new();
+    }
+    let type_data = match self
+            .input_data
+            .cpp_data
+            .current
+            .parser
+            .types
+            .iter()
+            .find(|t| t.name == info.cpp_name) {
+      Some(r) => r,
+      None => return Vec::new(),
+    };
+    let mut constants = Vec::new();
+    if let CppTypeKind::Class { ref static_fields, .. } = type_data.kind {
+      for field in static_fields {
+        if field.visibility != CppVisibility::Public {
+          continue;
+        }
+        if let Some(value) = field.value {
+          match self.associated_constant(field, value) {
+            Ok(constant) => constants.push(constant),
+            Err(err) => {
+              self.add_skipped_item(format!("{}::{}", info.cpp_name, field.name),
+                                    format!("failed to generate associated constant: {}", err));
             }
-            result.push(r);
-            any_success = true;
           }
-          Err(_) => unnamed_items_new.push(r),
+        } else if field.field_type.is_const &&
+                  field.field_type.indirection == CppTypeIndirection::None {
+          if let CppTypeBase::Enum { .. } = field.field_type.base {
+            self.add_skipped_item(format!("{}::{}", info.cpp_name, field.name),
+                                  "enum-valued constants are not supported".to_string());
+          }
         }
-
       }
-      unnamed_items = unnamed_items_new;
     }
-    for header in &self.input_data.cpp_ffi_headers {
-      for qt_slot_wrapper in &header.qt_slot_wrappers {
-        let incomplete_rust_name = self
-          .calculate_rust_name(&format!("raw_slot"),
-                               &header.include_file_base_name,
-                               false,
-                               None)?;
-        let arg_names = qt_slot_wrapper
-          .arguments
-          .iter()
-          .map_if_ok(|x| -> Result<_> {
-            let rust_type = complete_type(&result,
-                                          &self.input_data.dependency_types,
-                                          x,
-                                          &CppFfiArgumentMeaning::Argument(0),
-                                          false,
-                                          &ReturnValueAllocationPlace::NotApplicable)?;
-            rust_type.rust_api_type.caption(&incomplete_rust_name)
-          })?;
-        let args_text = if arg_names.is_empty() {
-          "no_args".to_string()
+    constants
+  }
+
+  /// Converts a static const member `field` with a known `value`
+  /// to a Rust associated constant.
+  fn associated_constant(&self,
+                         field: &CppClassStaticField,
+                         value: i64)
+                         -> Result<RustAssociatedConstant> {
+    let mut cpp_type = field.field_type.clone();
+    cpp_type.is_const = false;
+    let rust_type = complete_type(&self.types(),
+                                  &cpp_type.to_cpp_ffi_type(CppTypeRole::NotReturnType)?,
+                                  &CppFfiArgumentMeaning::Argument(0),
+                                  true,
+                                  &ReturnValueAllocationPlace::NotApplicable)?
+      .rust_api_type;
+    let value_code = if cpp_type.base == CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool) {
+      if value != 0 { "true" } else { "false" }.to_string()
+    } else if integer_literal_fits(&cpp_type.base, value) {
+      value.to_string()
+    } else {
+      // e.g. `static const unsigned int max = -1;`: the value is
+      // converted the same way as in C++
+      format!("{}i64 as _", value)
+    };
+    Ok(RustAssociatedConstant {
+         name: field.name.to_upper_case_words(),
+         value_type: rust_type,
+         value: value_code,
+       })
+  }
+
+  /// Returns Rust API type of the first template argument of container
+  /// instantiation `info` if it's passed by value and can be converted
+  /// to a Rust type without indirection.
+  fn element_type(&self, info: &RustProcessedTypeInfo) -> Option<RustType> {
+    // the second template argument, if present, is the allocator
+    let element_type = match info.cpp_template_arguments {
+      Some(ref args) if !args.is_empty() => &args[0],
+      _ => return None,
+    };
+    if element_type.indirection != CppTypeIndirection::None {
+      return None;
+    }
+    let ffi_type = match element_type.to_cpp_ffi_type(CppTypeRole::NotReturnType) {
+      Ok(r) => r,
+      Err(_) => return None,
+    };
+    let complete_type = match complete_type(&self.types(),
+                                            &ffi_type,
+                                            &CppFfiArgumentMeaning::Argument(0),
+                                            true,
+                                            &ReturnValueAllocationPlace::NotApplicable) {
+      Ok(r) => r,
+      Err(_) => return None,
+    };
+    match complete_type.rust_api_type {
+      RustType::Common { indirection: RustTypeIndirection::None, .. } => {
+        Some(complete_type.rust_api_type)
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns full name of the Rust method corresponding to `cpp_method`.
+  fn free_function_rust_name(&self, cpp_method: &CppMethod) -> Result<RustName> {
+    self.calculate_rust_name(&cpp_method.name,
+                             &cpp_method.include_file,
+                             true,
+                             cpp_method.operator.as_ref())
+  }
+
+  /// Returns true if `method` is a constructor of `QObject` or a class derived
+  /// from it that receives a `parent` pointer to such a class and
+  /// `Config::set_parented_qobject_constructors` is enabled. If a non-null
+  /// parent is passed, the created object is deleted by its parent,
+  /// so it can't be owned by the caller.
+  fn is_parented_qobject_constructor(&self, method: &CppMethod) -> bool {
+    if !self.input_data.settings.parented_qobject_constructors() {
+      return false;
+    }
+    let is_qobject = |name: &str| {
+      name == "QObject" || self.input_data.cpp_data.inherits(name, "QObject")
+    };
+    match method.class_membership {
+      Some(ref info) if method.is_constructor() && is_qobject(&info.class_type.name) => {}
+      _ => return false,
+    }
+    method.arguments.iter().any(|arg| {
+      arg.name == "parent" && arg.argument_type.indirection == CppTypeIndirection::Ptr &&
+      match arg.argument_type.base {
+        CppTypeBase::Class(CppTypeClassBase {
+                             ref name,
+                             template_arguments: None,
+                           }) => is_qobject(name),
+        _ => false,
+      }
+    })
+  }
+
+  /// Checks `argument_renames` set by `Config::add_argument_rename` against
+  /// the full signature of `method`, including the arguments omitted
+  /// in this variant. Returns an error if an index is out of range
+  /// or a new name conflicts with another argument's name.
+  fn check_argument_renames(&self,
+                            method: &CppAndFfiMethod,
+                            argument_renames: &[(usize, String)])
+                            -> Result<()> {
+    let all_arguments = match method.kind {
+      CppFfiMethodKind::RealWithOmittedArguments { arguments_before_omitting: Some(ref args) } => {
+        args
+      }
+      _ => &method.cpp_method.arguments,
+    };
+    let acronyms = self.input_data.settings.acronyms();
+    let mut names = Vec::new();
+    for (index, arg) in all_arguments.iter().enumerate() {
+      let name = match argument_renames.iter().find(|&&(i, _)| i == index) {
+        Some(&(_, ref new_name)) => new_name.clone(),
+        None => arg.name.to_snake_case_with_acronyms(acronyms),
+      };
+      names.push(sanitize_rust_identifier(&name));
+    }
+    for &(index, _) in argument_renames {
+      let name = names
+        .get(index)
+        .chain_err(|| {
+                     format!("renamed argument index {} is out of range: {}",
+                             index,
+                             method.short_text())
+                   })?;
+      if name == "self" || names.iter().filter(|x| x == &name).count() > 1 {
+        return Err(format!("renamed argument {} conflicts with another argument: {}",
+                           name,
+                           method.short_text())
+                       .into());
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns method name. For class member functions, the name doesn't
+  /// include class name and scope. For free functions, the name includes
+  /// modules.
+  fn method_rust_name(&self, method: &CppAndFfiMethod) -> Result<RustName> {
+    let name = if method.cpp_method.class_membership.is_none() {
+      self.free_function_rust_name(&method.cpp_method)?
+    } else {
+      let x = if method.cpp_method.is_constructor() {
+        "new".to_string()
+      } else if let Some(ref operator) = method.cpp_method.operator {
+        operator_rust_name(operator)?
+      } else if method
+                  .cpp_method
+                  .class_membership
+                  .as_ref()
+                  .map_or(false, |info| info.is_signal) {
+        format!("emit_{}",
+                method
+                  .cpp_method
+                  .name
+                  .to_snake_case_with_acronyms(self.input_data.settings.acronyms()))
+      } else {
+        let name = method
+          .cpp_method
+          .name
+          .to_snake_case_with_acronyms(self.input_data.settings.acronyms());
+        let is_bool_getter = method.cpp_method.arguments.is_empty() &&
+                             method.cpp_method.return_type ==
+                             CppType {
+                               is_const: false,
+                               is_const2: false,
+                               indirection: CppTypeIndirection::None,
+                               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+                             };
+        if self.input_data.settings.bool_getter_is_prefix() && is_bool_getter &&
+           !self
+              .input_data
+              .settings
+              .bool_getter_is_prefix_exceptions()
+              .contains(&method.cpp_method.full_name()) {
+          bool_getter_rust_name(&name).unwrap_or(name)
         } else {
-          arg_names.join("_")
+          name
+        }
+      };
+      RustName::new(vec![x])?
+    };
+    Ok(name)
+  }
+
+
+  /// Converts one function to a `RustSingleMethod`.
+  fn generate_rust_single_method(&self,
+                                 method: &CppAndFfiMethod,
+                                 scope: &RustMethodScope,
+                                 generate_doc: bool)
+                                 -> Result<RustSingleMethod> {
+    let no_out_parameters = Vec::new();
+    let out_parameters = self
+      .input_data
+      .settings
+      .out_parameters()
+      .get(&method.cpp_method.full_name())
+      .unwrap_or(&no_out_parameters);
+    for name in out_parameters {
+      if !method.c_signature.arguments.iter().any(|arg| &arg.name == name) {
+        return Err(format!("out-parameter {} not found: {}", name, method.short_text()).into());
+      }
+    }
+    let no_argument_renames = Vec::new();
+    let argument_renames = self
+      .input_data
+      .settings
+      .argument_renames()
+      .get(&method.cpp_method.full_name())
+      .unwrap_or(&no_argument_renames);
+    if !argument_renames.is_empty() {
+      self.check_argument_renames(method, argument_renames)?;
+    }
+    let mut arguments = Vec::new();
+    for (arg_index, arg) in method.c_signature.arguments.iter().enumerate() {
+      if arg.meaning != CppFfiArgumentMeaning::ReturnValue {
+        let mut arg_type = complete_type(&self.types(),
+                                         &arg.argument_type,
+                                         &arg.meaning,
+                                         false,
+                                         &method.allocation_place)?;
+        if let CppFfiArgumentMeaning::Argument(..) = arg.meaning {
+          // `const char*` followed by an integer (e.g. `size`)
+          // argument is usually not null-terminated
+          let has_size_arg = method
+            .c_signature
+            .arguments
+            .get(arg_index + 1)
+            .map_or(false, |next| {
+              next.argument_type.original_type.indirection == CppTypeIndirection::None &&
+              next.argument_type.original_type.base.is_integer()
+            });
+          if !has_size_arg {
+            convert_char_ptr(&mut arg_type)?;
+            if self.input_data.settings.wide_string_conversion() {
+              convert_wchar_ptr(&mut arg_type, false, self.input_data.settings.target_family())?;
+            }
+            if arg_type.rust_api_to_c_conversion == RustToCTypeConversion::CharPtrToCStr ||
+               arg_type.rust_api_to_c_conversion == RustToCTypeConversion::WCharPtrToOsString {
+              // null may be passed to the C++ function
+              arg_type.rust_api_type = option_type(arg_type.rust_api_type)?;
+            }
+          }
+          if out_parameters.iter().any(|name| name == &arg.name) {
+            convert_out_parameter(&mut arg_type)
+              .chain_err(|| {
+                           format!("invalid out-parameter {}: {}", arg.name, method.short_text())
+                         })?;
+          }
+        }
+        let renamed_argument = match arg.meaning {
+          CppFfiArgumentMeaning::Argument(cpp_index) => {
+            argument_renames
+              .iter()
+              .find(|&&(index, _)| index == cpp_index as usize)
+              .map(|&(_, ref new_name)| new_name)
+          }
+          _ => None,
         };
-        let rust_type_info = RustProcessedTypeInfo {
-          cpp_name: qt_slot_wrapper.class_name.clone(),
-          cpp_template_arguments: None,
-          cpp_doc: None, // TODO: do we need doc for this?
-          rust_name: self
-            .calculate_rust_name(&format!("raw_slot_{}", args_text),
-                                 &header.include_file_base_name,
-                                 false,
-                                 None)?,
-          is_public: true,
-          kind: RustTypeWrapperKind::Struct {
-            size_const_name: None,
-            is_deletable: true,
-            slot_wrapper: Some(RustQtSlotWrapper {
-                                 arguments: qt_slot_wrapper
-                                   .arguments
-                                   .iter()
-                                   .map_if_ok(|t| -> Result<_> {
-              let mut t = complete_type(&result,
-                                        &self.input_data.dependency_types,
-                                        t,
-                                        &CppFfiArgumentMeaning::Argument(0),
-                                        false,
-                                        &ReturnValueAllocationPlace::NotApplicable)?;
-              t.rust_api_type = t.rust_api_type.with_lifetime("static".to_string());
-              Ok(t)
-            })?,
-                                 receiver_id: qt_slot_wrapper.receiver_id.clone(),
-                                 public_type_name: format!("slot_{}", args_text).to_class_case(),
-                                 callback_name: format!("slot_{}_callback", args_text)
-                                   .to_snake_case(),
-                               }),
-          },
+        arguments.push(RustMethodArgument {
+                         ffi_index: arg_index,
+                         argument_type: arg_type,
+                         name: if arg.meaning == CppFfiArgumentMeaning::This {
+                           "self".to_string()
+                         } else if let Some(new_name) = renamed_argument {
+                           sanitize_rust_identifier(new_name)
+                         } else {
+                           let acronyms = self.input_data.settings.acronyms();
+                           sanitize_rust_identifier(&arg.name.to_snake_case_with_acronyms(acronyms))
+                         },
+                       });
+      }
+    }
+    let (mut return_type, return_arg_index) = if let Some((arg_index, arg)) =
+      method
+        .c_signature
+        .arguments
+        .iter()
+        .enumerate()
+        .find(|&(_arg_index, arg)| arg.meaning == CppFfiArgumentMeaning::ReturnValue) {
+      // an argument has return value meaning, so FFI return type
+      // must be void (or bool indicating presence of a std::optional value)
+      assert!(method.c_signature.return_type == CppFfiType::void() ||
+              arg.argument_type.conversion == CppIndirectionChange::OptionalToPointer);
+      (complete_type(&self.types(),
+                     &arg.argument_type,
+                     &arg.meaning,
+                     false,
+                     &method.allocation_place)?,
+       Some(arg_index))
+    } else {
+      // none of the arguments has return value meaning,
+      // so FFI return value must be used
+      let return_type = complete_type(&self.types(),
+                                      &method.c_signature.return_type,
+                                      &CppFfiArgumentMeaning::ReturnValue,
+                                      false,
+                                      &method.allocation_place)?;
+      (return_type, None)
+    };
+    convert_char_ptr(&mut return_type)?;
+    if self.input_data.settings.wide_string_conversion() {
+      convert_wchar_ptr(&mut return_type, true, self.input_data.settings.target_family())?;
+    }
+    // methods of `QString` itself (e.g. constructors) must keep returning it
+    if self.input_data.settings.qstring_return_conversion() &&
+       method
+         .cpp_method
+         .class_membership
+         .as_ref()
+         .map_or(true, |info| info.class_type.name != "QString") {
+      convert_qstring_return(&mut return_type)?;
+    }
+    let is_chainable_setter = method.cpp_method.is_chainable_setter() ||
+                              (method.cpp_method.returns_self_reference() &&
+                               self
+                                 .input_data
+                                 .settings
+                                 .chainable_setter_methods()
+                                 .contains(&method.cpp_method.full_name()));
+    if self.input_data.settings.chainable_setters() && is_chainable_setter &&
+       return_type.rust_api_to_c_conversion == RustToCTypeConversion::RefToPtr {
+      return_type.rust_api_to_c_conversion = RustToCTypeConversion::RefToSelf;
+    }
+    // copies of valid objects don't need to be checked
+    let validity_check_function = match method.cpp_method.class_membership {
+      Some(ref info) if method.cpp_method.is_constructor() &&
+                        !method.cpp_method.is_copy_constructor() => {
+        match self.input_data.settings.fallible_constructors().get(&info.class_type.name) {
+          Some(check_method) => {
+            convert_fallible_constructor_return(&mut return_type,
+                                                self.error_type("InvalidObjectError")?)?;
+            Some(self.validity_check_function(&info.class_type, check_method)?)
+          }
+          None => None,
+        }
+      }
+      _ => None,
+    };
+    if validity_check_function.is_none() &&
+       self.is_parented_qobject_constructor(&method.cpp_method) {
+      convert_parented_constructor_return(&mut return_type)?;
+    }
+    let out_types: Vec<_> = arguments
+      .iter()
+      .filter(|arg| {
+                arg.argument_type.rust_api_to_c_conversion == RustToCTypeConversion::OutValueToPtr
+              })
+      .map(|arg| arg.argument_type.rust_api_type.clone())
+      .collect();
+    if !out_types.is_empty() {
+      if return_type.rust_api_to_c_conversion != RustToCTypeConversion::None {
+        return Err(format!("out-parameters can't be used with a converted return type: {}",
+                           method.short_text())
+                       .into());
+      }
+      // values of out-parameters are returned after the original return value
+      let mut items = Vec::new();
+      if return_type.rust_api_type != RustType::EmptyTuple {
+        items.push(return_type.rust_api_type.clone());
+      }
+      items.extend(out_types);
+      return_type.rust_api_type = if items.len() == 1 {
+        items.remove(0)
+      } else {
+        RustType::Tuple(items)
+      };
+    }
+    add_return_lifetime(&mut arguments,
+                        &mut return_type.rust_api_type,
+                        &method.short_text());
+    if return_type.rust_api_to_c_conversion == RustToCTypeConversion::CharPtrToCStr ||
+       return_type.rust_api_to_c_conversion == RustToCTypeConversion::WCharPtrToOsString {
+      // returned pointer may be null
+      return_type.rust_api_type = option_type(return_type.rust_api_type)?;
+    }
+
+    let doc = if generate_doc {
+      Some(RustMethodDocItem {
+             cpp_fn: method.short_text(),
+             rust_fns: Vec::new(),
+             doc: method.cpp_method.doc.clone(),
+           })
+    } else {
+      None
+    };
+    // protected constructors are only wrapped for subclassing
+    // (see `Config::add_protected_constructor_class`)
+    let is_unsafe = method.cpp_method.is_protected_constructor() ||
+                    arguments
+                      .iter()
+                      .any(|arg| arg.argument_type.rust_api_type.is_unsafe_argument());
+    Ok(RustSingleMethod {
+         name: self.method_rust_name(method)?,
+         scope: scope.clone(),
+         arguments: RustMethodArgumentsVariant {
+           arguments: arguments,
+           cpp_method: method.clone(),
+           return_type: return_type,
+           return_type_ffi_index: return_arg_index,
+           validity_check_function: validity_check_function,
+         },
+         doc: doc,
+         is_unsafe: is_unsafe,
+       })
+  }
+
+  /// Returns error type `name` (e.g. `CastError`) exported by `cpp_utils`.
+  /// If `Config::set_errors_module` is enabled, the re-export of the type
+  /// from the `errors` module of the generated crate is returned instead.
+  fn error_type(&self, name: &str) -> Result<RustType> {
+    let parts = if self.input_data.settings.errors_module() {
+      vec![self.input_data.crate_name.clone(), "errors".to_string(), name.to_string()]
+    } else {
+      vec!["cpp_utils".to_string(), name.to_string()]
+    };
+    Ok(RustType::Common {
+         base: RustName::new(parts)?,
+         generic_arguments: None,
+         indirection: RustTypeIndirection::None,
+         is_const: false,
+         is_const2: false,
+       })
+  }
+
+  /// Returns name of the FFI function of `check_method` of `class_type`
+  /// used by fallible constructors (see `Config::add_fallible_constructor`).
+  /// The method must be const, have no arguments and return `bool`.
+  fn validity_check_function(&self,
+                             class_type: &CppTypeClassBase,
+                             check_method: &str)
+                             -> Result<String> {
+    let function = self
+      .input_data
+      .cpp_ffi_headers
+      .iter()
+      .flat_map(|header| header.methods.iter())
+      .find(|m| {
+              m.cpp_method.name == check_method && m.cpp_method.arguments.is_empty() &&
+              m.kind == CppFfiMethodKind::Real &&
+              m.cpp_method.return_type.indirection == CppTypeIndirection::None &&
+              m.cpp_method.return_type.base ==
+              CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool) &&
+              m.cpp_method.class_membership.as_ref().map_or(false, |info| {
+                &info.class_type == class_type && info.is_const && !info.is_static
+              })
+            })
+      .chain_err(|| {
+                   format!("validity check method not found: {}::{}",
+                           class_type.name,
+                           check_method)
+                 })?;
+    Ok(function.c_name.clone())
+  }
+
+  /// Generates `Drop` or `CppDeletable` trait implementation
+  /// from a C++ destructor. The FFI function of the destructor
+  /// either calls the destructor in place (for stack-allocated types)
+  /// or deletes the object (for heap-allocated types), so the trait is selected
+  /// based on the allocation place recorded in `method`. Returns an error
+  /// if it doesn't match the allocation place of the processed type.
+  fn process_destructor(&self,
+                        method: &CppAndFfiMethod,
+                        scope: &RustMethodScope)
+                        -> Result<TraitImpl> {
+    if let RustMethodScope::Impl { ref target_type } = *scope {
+      let class_type = match method.cpp_method.class_membership {
+        Some(ref info) => &info.class_type,
+        None => return Err(unexpected("destructor must be a class member").into()),
+      };
+      if let Some(&RustProcessedTypeInfo {
+                    kind: RustTypeWrapperKind::Struct { ref size_const_name, .. }, ..
+                  }) = self.types().find_class(class_type) {
+        let type_place = if size_const_name.is_some() {
+          ReturnValueAllocationPlace::Stack
+        } else {
+          ReturnValueAllocationPlace::Heap
         };
-        result.push(rust_type_info);
+        if type_place != method.allocation_place {
+          return Err(format!("destructor was generated for {:?} allocation place, \
+                              but the type is allocated on {:?}",
+                             method.allocation_place,
+                             type_place)
+                         .into());
+        }
+      }
+      match method.allocation_place {
+        ReturnValueAllocationPlace::Stack => {
+          let mut method = self.generate_rust_single_method(method, scope, true)?;
+          method.name = RustName::new(vec!["drop".to_string()])?;
+          method.scope = RustMethodScope::TraitImpl;
+          Ok(TraitImpl {
+               target_type: target_type.clone(),
+               associated_types: Vec::new(),
+               trait_type: RustType::Common {
+                 base: RustName::new(vec!["Drop".to_string()])?,
+                 indirection: RustTypeIndirection::None,
+                 is_const: false,
+                 is_const2: false,
+                 generic_arguments: None,
+               },
+               extra: None,
+               methods: vec![method.to_rust_method()],
+             })
+        }
+        ReturnValueAllocationPlace::Heap => {
+          Ok(TraitImpl {
+               target_type: target_type.clone(),
+               associated_types: Vec::new(),
+               trait_type: RustType::Common {
+                 base: RustName::new(vec!["cpp_utils".to_string(), "CppDeletable".to_string()])?,
+                 indirection: RustTypeIndirection::None,
+                 is_const: false,
+                 is_const2: false,
+                 generic_arguments: None,
+               },
+               extra: Some(TraitImplExtra::CppDeletable { deleter_name: method.c_name.clone() }),
+               methods: Vec::new(),
+             })
+        }
+        ReturnValueAllocationPlace::NotApplicable => {
+          return Err(unexpected("destructor must have allocation place").into())
+        }
+      }
+    } else {
+      return Err(unexpected("destructor must be in class scope").into());
+    }
+  }
+
+  /// Generates `Default` trait implementation from a default
+  /// constructor of a stack-allocated type.
+  fn process_default_constructor(&self,
+                                 method: &CppAndFfiMethod,
+                                 scope: &RustMethodScope)
+                                 -> Result<TraitImpl> {
+    if let RustMethodScope::Impl { ref target_type } = *scope {
+      if method.allocation_place != ReturnValueAllocationPlace::Stack {
+        return Err("only stack-allocated types can implement Default".into());
+      }
+      let mut method = self.generate_rust_single_method(method, scope, true)?;
+      if !method.arguments.arguments.is_empty() {
+        return Err(unexpected("default constructor must not have arguments").into());
+      }
+      if method.arguments.validity_check_function.is_some() {
+        return Err("fallible constructors can't implement Default".into());
+      }
+      method.name = RustName::new(vec!["default".to_string()])?;
+      method.scope = RustMethodScope::TraitImpl;
+      Ok(TraitImpl {
+           target_type: target_type.clone(),
+           associated_types: Vec::new(),
+           trait_type: RustType::Common {
+             base: RustName::new(vec!["Default".to_string()])?,
+             indirection: RustTypeIndirection::None,
+             is_const: false,
+             is_const2: false,
+             generic_arguments: None,
+           },
+           extra: None,
+           methods: vec![method.to_rust_method()],
+         })
+    } else {
+      Err(unexpected("constructor must be in class scope").into())
+    }
+  }
+
+  /// Generates `Clone` trait implementation (for stack-allocated types)
+  /// or `cpp_utils::CppClone` trait implementation (for heap-allocated types)
+  /// from a copy constructor.
+  fn process_copy_constructor(&self,
+                              method: &CppAndFfiMethod,
+                              scope: &RustMethodScope)
+                              -> Result<TraitImpl> {
+    if let RustMethodScope::Impl { ref target_type } = *scope {
+      let (trait_name, method_name) = match method.allocation_place {
+        ReturnValueAllocationPlace::Stack => (vec!["Clone".to_string()], "clone"),
+        ReturnValueAllocationPlace::Heap => {
+          (vec!["cpp_utils".to_string(), "CppClone".to_string()], "cpp_clone")
+        }
+        ReturnValueAllocationPlace::NotApplicable => {
+          return Err(unexpected("constructor must have allocation place").into())
+        }
+      };
+      let mut method = self.generate_rust_single_method(method, scope, true)?;
+      if method.arguments.arguments.len() != 1 {
+        return Err(unexpected("copy constructor must have one argument").into());
+      }
+      method.arguments.arguments[0].name = "self".to_string();
+      method.name = RustName::new(vec![method_name.to_string()])?;
+      method.scope = RustMethodScope::TraitImpl;
+      Ok(TraitImpl {
+           target_type: target_type.clone(),
+           associated_types: Vec::new(),
+           trait_type: RustType::Common {
+             base: RustName::new(trait_name)?,
+             indirection: RustTypeIndirection::None,
+             is_const: false,
+             is_const2: false,
+             generic_arguments: None,
+           },
+           extra: None,
+           methods: vec![method.to_rust_method()],
+         })
+    } else {
+      Err(unexpected("constructor must be in class scope").into())
+    }
+  }
+
+  /// Generates `std::fmt::Debug` trait implementation for a class.
+  /// `cpp_methods` and `rust_methods` are all methods of the class.
+  /// The implementation uses `toString()` method of the class if
+  /// it's available.
+  fn process_debug_impl(&self,
+                        cpp_methods: &[&CppAndFfiMethod],
+                        rust_methods: &[RustMethod],
+                        scope: &RustMethodScope)
+                        -> Result<TraitImpl> {
+    let target_type = if let RustMethodScope::Impl { ref target_type } = *scope {
+      target_type.clone()
+    } else {
+      return Err(unexpected("Debug implementation must be in class scope").into());
+    };
+    Ok(TraitImpl {
+         target_type: target_type,
+         associated_types: Vec::new(),
+         trait_type: RustType::Common {
+           base: RustName::new(vec!["std".to_string(), "fmt".to_string(), "Debug".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: None,
+         },
+         extra: Some(TraitImplExtra::Debug {
+                       to_string_call: const_method_call("toString", cpp_methods, rust_methods),
+                     }),
+         methods: Vec::new(),
+       })
+  }
+
+  /// Generates `std::fmt::Display` trait implementation for a class
+  /// using the first available method from `Config::add_display_method_name`.
+  /// `cpp_methods` and `rust_methods` are all methods of the class.
+  /// Returns `None` if none of the methods are available.
+  fn process_display_impl(&self,
+                          cpp_methods: &[&CppAndFfiMethod],
+                          rust_methods: &[RustMethod],
+                          scope: &RustMethodScope)
+                          -> Result<Option<TraitImpl>> {
+    let target_type = if let RustMethodScope::Impl { ref target_type } = *scope {
+      target_type.clone()
+    } else {
+      return Err(unexpected("Display implementation must be in class scope").into());
+    };
+    // the method must return a value of a class type
+    // (e.g. `QString`) that implements `Display`
+    let value_cpp_methods: Vec<_> = cpp_methods
+      .iter()
+      .cloned()
+      .filter(|m| {
+                m.cpp_method.return_type.indirection == CppTypeIndirection::None &&
+                m.cpp_method.return_type.base.is_class()
+              })
+      .collect();
+    let call = self
+      .input_data
+      .settings
+      .display_method_names()
+      .iter()
+      .filter_map(|name| const_method_call(name, &value_cpp_methods, rust_methods))
+      .next();
+    Ok(match call {
+         Some(call) => {
+           Some(TraitImpl {
+                  target_type: target_type,
+                  associated_types: Vec::new(),
+                  trait_type: RustType::Common {
+                    base: RustName::new(vec!["std".to_string(),
+                                             "fmt".to_string(),
+                                             "Display".to_string()])?,
+                    indirection: RustTypeIndirection::None,
+                    is_const: false,
+                    is_const2: false,
+                    generic_arguments: None,
+                  },
+                  extra: Some(TraitImplExtra::Display { call: call }),
+                  methods: Vec::new(),
+                })
+         }
+         None => None,
+       })
+  }
+
+  /// Generates `From<&'a T>` trait implementation from a non-explicit
+  /// conversion operator of `T`. Returns `Err` if the operator
+  /// doesn't fit, e.g. if it returns a reference or a pointer.
+  fn process_conversion_operator(&self,
+                                 method: &CppAndFfiMethod,
+                                 scope: &RustMethodScope)
+                                 -> Result<TraitImpl> {
+    if !method.cpp_method.is_implicit_conversion_operator() {
+      return Err("not an implicit conversion operator".into());
+    }
+    let mut method = self.generate_rust_single_method(method, scope, true)?;
+    if method.is_unsafe {
+      return Err("conversion is unsafe".into());
+    }
+    if method.arguments.arguments.len() != 1 || method.arguments.arguments[0].name != "self" {
+      return Err("conversion operator must only have self argument".into());
+    }
+    let source_type = method.arguments.arguments[0]
+      .argument_type
+      .rust_api_type
+      .with_lifetime("a".to_string());
+    if !source_type.is_ref() || !source_type.last_is_const()? {
+      return Err("conversion operator must have const self argument".into());
+    }
+    let target_type = method.arguments.return_type.rust_api_type.clone();
+    match target_type {
+      RustType::Common { indirection: RustTypeIndirection::None, .. } => {}
+      _ => return Err("conversion operator must return a value".into()),
+    }
+    method.arguments.arguments[0].name = "value".to_string();
+    method.arguments.arguments[0].argument_type.rust_api_type = source_type.clone();
+    method.name = RustName::new(vec!["from".to_string()])?;
+    method.scope = RustMethodScope::TraitImpl;
+    Ok(TraitImpl {
+         target_type: target_type,
+         associated_types: Vec::new(),
+         trait_type: RustType::Common {
+           base: RustName::new(vec!["From".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: Some(vec![source_type]),
+         },
+         extra: None,
+         methods: vec![method.to_rust_method()],
+       })
+  }
+
+  /// Generates `TryFrom<&'a T>` trait implementation from an `explicit`
+  /// conversion operator of `T` converting to a numeric type narrower
+  /// than the only field of `T` (e.g. `explicit operator qint32()` of a class
+  /// holding a `qint64` value). The C++ operator can't report overflow, so
+  /// the conversion always succeeds. Returns `Err` if the operator doesn't fit.
+  fn process_narrowing_conversion_operator(&self,
+                                           method: &CppAndFfiMethod,
+                                           scope: &RustMethodScope)
+                                           -> Result<TraitImpl> {
+    if !method.cpp_method.is_explicit_conversion_operator() {
+      return Err("not an explicit conversion operator".into());
+    }
+    let target_cpp_type = &method.cpp_method.return_type;
+    if target_cpp_type.indirection != CppTypeIndirection::None {
+      return Err("conversion operator must return a value".into());
+    }
+    let target_bits = target_cpp_type
+      .base
+      .numeric_bits()
+      .chain_err(|| "target type is not a numeric type with known size")?;
+    let class_name = method
+      .cpp_method
+      .class_name()
+      .chain_err(|| "conversion operator must be a class member")?;
+    let class_kind = self
+      .input_data
+      .cpp_data
+      .current
+      .parser
+      .types
+      .iter()
+      .find(|t| &t.name == class_name)
+      .map(|t| &t.kind);
+    let source_field_type = match class_kind {
+      Some(&CppTypeKind::Class { ref fields, .. }) if fields.len() == 1 => {
+        fields[0].field_type.clone()
+      }
+      _ => return Err("class must have exactly one field".into()),
+    };
+    if source_field_type.indirection != CppTypeIndirection::None {
+      return Err("class field is not a numeric value".into());
+    }
+    let source_bits = source_field_type
+      .base
+      .numeric_bits()
+      .chain_err(|| "class field is not a numeric type with known size")?;
+    let is_narrowing = target_bits < source_bits ||
+                       (target_cpp_type.base.is_integer() &&
+                        !source_field_type.base.is_integer());
+    if !is_narrowing {
+      return Err("conversion is not narrowing".into());
+    }
+    let method = self.generate_rust_single_method(method, scope, true)?;
+    if method.is_unsafe {
+      return Err("conversion is unsafe".into());
+    }
+    if method.arguments.arguments.len() != 1 || method.arguments.arguments[0].name != "self" {
+      return Err("conversion operator must only have self argument".into());
+    }
+    let source_type = method.arguments.arguments[0]
+      .argument_type
+      .rust_api_type
+      .with_lifetime("a".to_string());
+    if !source_type.is_ref() || !source_type.last_is_const()? {
+      return Err("conversion operator must have const self argument".into());
+    }
+    if method.arguments.return_type.rust_api_to_c_conversion != RustToCTypeConversion::None {
+      return Err("conversion operator must return a primitive value".into());
+    }
+    Ok(TraitImpl {
+         target_type: method.arguments.return_type.rust_api_type.clone(),
+         associated_types: vec![TraitAssociatedType {
+                                  name: "Error".to_string(),
+                                  value: RustType::Common {
+                                    base: RustName::new(vec!["std".to_string(),
+                                                             "convert".to_string(),
+                                                             "Infallible".to_string()])?,
+                                    indirection: RustTypeIndirection::None,
+                                    is_const: false,
+                                    is_const2: false,
+                                    generic_arguments: None,
+                                  },
+                                }],
+         trait_type: RustType::Common {
+           base: RustName::new(vec!["std".to_string(),
+                                    "convert".to_string(),
+                                    "TryFrom".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: Some(vec![source_type]),
+         },
+         extra: Some(TraitImplExtra::NarrowingConversionTryFrom {
+                       ffi_function_name: method.arguments.cpp_method.c_name.clone(),
+                     }),
+         methods: Vec::new(),
+       })
+  }
+
+  /// Generates `From<&'a str>` and `From<String>` trait implementations
+  /// for a string class (see `Config::set_string_from_impls`) from
+  /// its static `fromUtf8(const char*, int)` method.
+  /// Returns `Err` if the method doesn't fit.
+  fn process_string_from_utf8(&self, method: &RustSingleMethod) -> Result<Vec<TraitImpl>> {
+    let cpp_method = &method.arguments.cpp_method.cpp_method;
+    let class_type = match cpp_method.class_membership {
+      Some(ref info) if info.is_static => &info.class_type,
+      _ => return Err("fromUtf8 must be a static method".into()),
+    };
+    let char_ptr_type = CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+      indirection: CppTypeIndirection::Ptr,
+      is_const: true,
+      is_const2: false,
+    };
+    let int_type = CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    };
+    let argument_types: Vec<_> = cpp_method
+      .arguments
+      .iter()
+      .map(|arg| &arg.argument_type)
+      .collect();
+    if argument_types != vec![&char_ptr_type, &int_type] {
+      return Err("fromUtf8 must have (const char*, int) arguments".into());
+    }
+    if cpp_method.return_type.indirection != CppTypeIndirection::None ||
+       cpp_method.return_type.base != CppTypeBase::Class(class_type.clone()) {
+      return Err("fromUtf8 must return the string class by value".into());
+    }
+    if method.arguments.return_type_ffi_index != Some(argument_types.len()) {
+      return Err("From can't be implemented for CppBox, so the class must be \
+                  stack-allocated"
+                   .into());
+    }
+    let target_type = method.arguments.return_type.rust_api_type.clone();
+    let from_trait_type = |argument_type: RustType| -> Result<RustType> {
+      Ok(RustType::Common {
+           base: RustName::new(vec!["std".to_string(),
+                                    "convert".to_string(),
+                                    "From".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: Some(vec![argument_type]),
+         })
+    };
+    let str_type = RustType::Common {
+      base: RustName::new(vec!["str".to_string()])?,
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ref { lifetime: Some("a".to_string()) },
+      is_const: true,
+      is_const2: false,
+    };
+    let string_type = RustType::Common {
+      base: RustName::new(vec!["std".to_string(), "string".to_string(), "String".to_string()])?,
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    };
+    Ok(vec![TraitImpl {
+              target_type: target_type.clone(),
+              associated_types: Vec::new(),
+              trait_type: from_trait_type(str_type)?,
+              extra: Some(TraitImplExtra::StringFromUtf8 {
+                            ffi_function_name: method.arguments.cpp_method.c_name.clone(),
+                          }),
+              methods: Vec::new(),
+            },
+            TraitImpl {
+              target_type: target_type,
+              associated_types: Vec::new(),
+              trait_type: from_trait_type(string_type)?,
+              extra: Some(TraitImplExtra::StringFromStdString),
+              methods: Vec::new(),
+            }])
+  }
+
+  /// Generates `std::hash::Hash` trait implementation from
+  /// a `qHash(T)` or `qHash(T, uint seed)` free function.
+  /// Returns `Err` if the function doesn't fit, e.g. if its
+  /// first argument is not a class type declared in this crate.
+  fn process_qt_hash_function(&self, method: &CppAndFfiMethod) -> Result<TraitImpl> {
+    let args = &method.cpp_method.arguments;
+    if args.is_empty() || args.len() > 2 {
+      return Err("unexpected number of arguments".into());
+    }
+    let has_seed = args.len() == 2;
+    let seed_type = CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::UInt),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    };
+    if has_seed && args[1].argument_type != seed_type {
+      return Err("second argument is not uint seed".into());
+    }
+    match method.cpp_method.return_type.base {
+      CppTypeBase::BuiltInNumeric(..) => {}
+      _ => return Err("return type is not numeric".into()),
+    }
+    if method.cpp_method.return_type.indirection != CppTypeIndirection::None {
+      return Err("return type is not numeric".into());
+    }
+    let key_type = &args[0].argument_type;
+    match key_type.indirection {
+      CppTypeIndirection::None => {}
+      CppTypeIndirection::Ref if key_type.is_const => {}
+      _ => return Err("key is not passed by value or const reference".into()),
+    }
+    let class_base = if let CppTypeBase::Class(ref class_base) = key_type.base {
+      class_base
+    } else {
+      return Err("key is not a class type".into());
+    };
+    // only types of the current crate are considered
+    // because a trait can't be implemented for a foreign type
+    let info = self
+      .processed_types
+      .iter()
+      .find(|x| {
+              x.cpp_name == class_base.name &&
+              x.cpp_template_arguments == class_base.template_arguments
+            })
+      .chain_err(|| "key type is not in this crate")?;
+    if let RustTypeWrapperKind::Enum { .. } = info.kind {
+      return Err(unexpected("class type expected here").into());
+    }
+    Ok(TraitImpl {
+         target_type: RustType::Common {
+           base: info.rust_name.clone(),
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: None,
+         },
+         associated_types: Vec::new(),
+         trait_type: RustType::Common {
+           base: RustName::new(vec!["std".to_string(), "hash".to_string(), "Hash".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: None,
+         },
+         extra: Some(TraitImplExtra::QtHashFunction {
+                       ffi_function_name: method.c_name.clone(),
+                       has_seed: has_seed,
+                     }),
+         methods: Vec::new(),
+       })
+  }
+
+  /// Generates implementation of `std::ops::Shl` for a mutable reference
+  /// to a stream from a wrapper of a free `operator<<(Stream&, const T&)` function.
+  fn process_stream_write_operator(&self, method: &RustSingleMethod) -> Result<TraitImpl> {
+    if method.is_unsafe {
+      return Err("unsafe method can't be used in a trait implementation".into());
+    }
+    let (stream_type, value_type) = stream_write_operator_types(method)?;
+    let stream_type = stream_type.with_lifetime("a".to_string());
+    Ok(TraitImpl {
+         target_type: stream_type.clone(),
+         associated_types: vec![TraitAssociatedType {
+                                  name: "Output".to_string(),
+                                  value: stream_type,
+                                }],
+         trait_type: RustType::Common {
+           base: RustName::new(vec!["std".to_string(), "ops".to_string(), "Shl".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: Some(vec![value_type.with_lifetime("a".to_string())]),
+         },
+         extra: Some(TraitImplExtra::StreamWriteOperator {
+                       ffi_function_name: method.arguments.cpp_method.c_name.clone(),
+                     }),
+         methods: Vec::new(),
+       })
+  }
+
+  /// Generates `write_to(&self, stream)` method of the written type
+  /// from a wrapper of a free `operator<<(Stream&, const T&)` function.
+  /// The written type must be declared in the module of the function.
+  fn process_stream_write_method(&self, method: &RustSingleMethod) -> Result<RustMethod> {
+    let (_, value_type) = stream_write_operator_types(method)?;
+    let target_type = match value_type {
+      RustType::Common {
+        ref base,
+        ref generic_arguments,
+        ..
+      } => {
+        if base.parts[..base.parts.len() - 1] !=
+           method.name.parts[..method.name.parts.len() - 1] {
+          return Err("written type is not declared in the module of the function".into());
+        }
+        RustType::Common {
+          base: base.clone(),
+          generic_arguments: generic_arguments.clone(),
+          indirection: RustTypeIndirection::None,
+          is_const: false,
+          is_const2: false,
+        }
+      }
+      _ => return Err(unexpected("RustType::Common expected").into()),
+    };
+    let mut write_method = method.clone();
+    write_method.scope = RustMethodScope::Impl { target_type: target_type };
+    write_method.name = RustName::new(vec!["write_to".to_string()])?;
+    write_method.arguments.arguments.swap(0, 1);
+    write_method.arguments.arguments[0].name = "self".to_string();
+    Ok(write_method.to_rust_method())
+  }
+
+  /// Generates `std::ops::Add<Rhs>` implementation for a reference to
+  /// the left operand type from a wrapper of C++ `operator+`. For a member
+  /// operator, the left operand is `self`. For a free operator, the left operand
+  /// is the first argument, and its type must be declared in this crate.
+  /// `Rhs` is the type of the right operand, so each overload of the operator
+  /// produces a separate implementation.
+  fn process_add_operator(&self, method: &RustSingleMethod) -> Result<TraitImpl> {
+    if method.is_unsafe {
+      return Err("unsafe method can't be used in a trait implementation".into());
+    }
+    if method.arguments.arguments.len() != 2 {
+      return Err("exactly two operands expected".into());
+    }
+    let lhs = &method.arguments.arguments[0];
+    if lhs.argument_type.rust_api_to_c_conversion != RustToCTypeConversion::RefToPtr {
+      return Err("left operand must be passed by reference".into());
+    }
+    let lhs_base = match lhs.argument_type.rust_api_type {
+      RustType::Common {
+        indirection: RustTypeIndirection::Ref { .. },
+        is_const: true,
+        ref base,
+        ..
+      } => base,
+      _ => return Err("left operand must be a const reference".into()),
+    };
+    let is_member = lhs.name == "self";
+    if !is_member && !self.type_index.crate_has_rust_name(lhs_base) {
+      return Err("left operand type is not declared in this crate".into());
+    }
+    match method.arguments.return_type.rust_api_type {
+      RustType::Common { indirection: RustTypeIndirection::None, .. } => {}
+      _ => return Err("operator must return a value".into()),
+    }
+    let target_type = lhs
+      .argument_type
+      .rust_api_type
+      .with_lifetime("a".to_string());
+    let rhs_type = {
+      let rhs_type = &method.arguments.arguments[1].argument_type.rust_api_type;
+      if rhs_type.is_ref() {
+        rhs_type.with_lifetime("a".to_string())
+      } else {
+        rhs_type.clone()
+      }
+    };
+    let mut add_method = method.clone();
+    add_method.scope = RustMethodScope::TraitImpl;
+    add_method.name = RustName::new(vec!["add".to_string()])?;
+    // the trait is implemented for a reference,
+    // so `self` (the reference) is taken by value
+    add_method.arguments.arguments[0].name = "self".to_string();
+    if let RustType::Common { ref mut indirection, .. } =
+      add_method.arguments.arguments[0].argument_type.rust_api_type {
+      *indirection = RustTypeIndirection::None;
+    }
+    add_method.arguments.arguments[1].name = "rhs".to_string();
+    add_method.arguments.arguments[1].argument_type.rust_api_type = rhs_type.clone();
+    Ok(TraitImpl {
+         target_type: target_type,
+         associated_types: vec![TraitAssociatedType {
+                                  name: "Output".to_string(),
+                                  value: method.arguments.return_type.rust_api_type.clone(),
+                                }],
+         trait_type: RustType::Common {
+           base: RustName::new(vec!["std".to_string(), "ops".to_string(), "Add".to_string()])?,
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+           generic_arguments: Some(vec![rhs_type]),
+         },
+         extra: None,
+         methods: vec![add_method.to_rust_method()],
+       })
+  }
+
+  /// Generates implementation of `std::ops::Index` (for a const method) or
+  /// `std::ops::IndexMut` (for a non-const method) from a wrapper of
+  /// C++ `operator[]` that returns a reference.
+  fn process_index_operator(&self, method: &RustSingleMethod) -> Result<TraitImpl> {
+    if method.is_unsafe {
+      return Err("unsafe method can't be used in a trait implementation".into());
+    }
+    let is_const = match method.self_arg_kind()? {
+      RustMethodSelfArgKind::ConstRef => true,
+      RustMethodSelfArgKind::MutRef => false,
+      _ => return Err("self must be taken by reference".into()),
+    };
+    let target_type = match method.scope {
+      RustMethodScope::Impl { ref target_type } => target_type.clone(),
+      _ => return Err("class method expected".into()),
+    };
+    if method.arguments.arguments.len() != 2 {
+      return Err("exactly one index argument expected".into());
+    }
+    let index_type = method.arguments.arguments[1]
+      .argument_type
+      .rust_api_type
+      .clone();
+    match index_type {
+      RustType::Common { indirection: RustTypeIndirection::None, .. } => {}
+      _ => return Err("index argument must be passed by value".into()),
+    }
+    match method.arguments.return_type.rust_api_type {
+      RustType::Common {
+        indirection: RustTypeIndirection::Ref { .. },
+        is_const: ref return_is_const,
+        ..
+      } if *return_is_const == is_const => {}
+      _ => {
+        return Err(format!("return type must be a {} reference",
+                           if is_const { "const" } else { "mutable" })
+                       .into())
+      }
+    }
+    let mut index_method = method.clone();
+    index_method.scope = RustMethodScope::TraitImpl;
+    // the trait impl doesn't declare lifetimes, so elided lifetimes are used
+    for arg in &mut index_method.arguments.arguments {
+      arg.argument_type.rust_api_type = arg.argument_type.rust_api_type.without_lifetime();
+    }
+    index_method.arguments.return_type.rust_api_type = index_method
+      .arguments
+      .return_type
+      .rust_api_type
+      .without_lifetime();
+    index_method.name = RustName::new(vec![if is_const { "index" } else { "index_mut" }
+                                             .to_string()])?;
+    let mut trait_impl = TraitImpl {
+      target_type: target_type,
+      associated_types: Vec::new(),
+      trait_type: RustType::Common {
+        base: RustName::new(vec!["std".to_string(),
+                                 "ops".to_string(),
+                                 if is_const { "Index" } else { "IndexMut" }.to_string()])?,
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+        generic_arguments: Some(vec![index_type]),
+      },
+      extra: None,
+      methods: vec![index_method.to_rust_method()],
+    };
+    if is_const {
+      let output_type = index_output_type(&trait_impl)
+        .chain_err(|| unexpected("index_output_type failed"))?;
+      trait_impl
+        .associated_types
+        .push(TraitAssociatedType {
+                name: "Output".to_string(),
+                value: output_type,
+              });
+    }
+    Ok(trait_impl)
+  }
+
+  /// Generates trait implementations from `static_cast`, `dynamic_cast`
+  /// or `qobject_cast` (to be implemented) C++ function wrappers.
+  fn process_cpp_cast(&self, method: RustSingleMethod) -> Result<Vec<TraitImpl>> {
+    let mut results = Vec::new();
+    // TODO: qobject_cast
+    let mut final_methods = vec![(method.clone(), false), (method.clone(), true)];
+    let args = &method.arguments;
+    let cpp_cast = if let CppFfiMethodKind::Cast(ref cast) = args.cpp_method.kind {
+      cast
+    } else {
+      return Err("not a cast method".into());
+    };
+    // `DynamicCastResult` is implemented instead of `DynamicCast`
+    // if `Config::set_cast_error_type` was used
+    let cast_error_type = match *cpp_cast {
+      CppCast::Dynamic => {
+        self
+          .input_data
+          .settings
+          .cast_error_type()
+          .map(|name| {
+                 RustType::Common {
+                   base: name.clone(),
+                   indirection: RustTypeIndirection::None,
+                   is_const: false,
+                   is_const2: false,
+                   generic_arguments: None,
+                 }
+               })
+      }
+      _ => None,
+    };
+    let trait_name = match *cpp_cast {
+      CppCast::Static { ref is_unsafe, .. } => {
+        if *is_unsafe {
+          vec!["cpp_utils".to_string(), "UnsafeStaticCast".to_string()]
+        } else {
+          vec!["cpp_utils".to_string(), "StaticCast".to_string()]
+        }
+      }
+      CppCast::Dynamic => {
+        if cast_error_type.is_some() {
+          vec!["cpp_utils".to_string(), "DynamicCastResult".to_string()]
+        } else {
+          vec!["cpp_utils".to_string(), "DynamicCast".to_string()]
+        }
+      }
+      CppCast::QObject => {
+        vec!["qt_core".to_string(),
+             "object".to_string(),
+             "Cast".to_string()]
+      }
+    };
+    if args.arguments.len() != 1 {
+      return Err(unexpected("1 argument expected").into());
+    }
+    let from_type = &args.arguments[0].argument_type;
+    let to_type = &args.return_type;
+
+    for &mut (ref mut final_method, ref mut final_is_const) in &mut final_methods {
+      let method_name = if *final_is_const {
+        args.cpp_method.cpp_method.name.clone()
+      } else {
+        format!("{}_mut", args.cpp_method.cpp_method.name)
+      };
+      final_method.scope = RustMethodScope::TraitImpl;
+      final_method.name = RustName::new(vec![method_name])?;
+      final_method.is_unsafe = cpp_cast.is_unsafe_static_cast();
+      let return_ref_type = args.return_type.ptr_to_ref(*final_is_const)?;
+      if &final_method.arguments.cpp_method.cpp_method.name == "static_cast" {
+        final_method.arguments.return_type = return_ref_type;
+      } else if let Some(ref error_type) = cast_error_type {
+        final_method
+          .arguments
+          .return_type
+          .rust_api_to_c_conversion = RustToCTypeConversion::ResultRefToPtr;
+        final_method.arguments.return_type.rust_api_type = RustType::Common {
+          base: RustName::new(vec!["std".to_string(),
+                                   "result".to_string(),
+                                   "Result".to_string()])?,
+          indirection: RustTypeIndirection::None,
+          is_const: false,
+          is_const2: false,
+          generic_arguments: Some(vec![return_ref_type.rust_api_type, error_type.clone()]),
+        }
+      } else {
+        final_method
+          .arguments
+          .return_type
+          .rust_api_to_c_conversion = RustToCTypeConversion::OptionRefToPtr;
+        final_method.arguments.return_type.rust_api_type = RustType::Common {
+          base: RustName::new(vec!["std".to_string(),
+                                   "option".to_string(),
+                                   "Option".to_string()])?,
+          indirection: RustTypeIndirection::None,
+          is_const: false,
+          is_const2: false,
+          generic_arguments: Some(vec![return_ref_type.rust_api_type]),
+        }
+      };
+      final_method.arguments.arguments[0].argument_type = final_method.arguments.arguments[0]
+        .argument_type
+        .ptr_to_ref(*final_is_const)?;
+      final_method.arguments.arguments[0].name = "self".to_string();
+
+      if !cpp_cast.is_unsafe_static_cast() && cpp_cast.is_direct_static_cast() {
+
+        let mut deref_method = final_method.clone();
+        deref_method.name = RustName::new(vec![if *final_is_const {
+                                                   "deref"
+                                                 } else {
+                                                   "deref_mut"
+                                                 }
+                                                 .to_string()])?;
+        let deref_trait_name = if *final_is_const { "Deref" } else { "DerefMut" }.to_string();
+        let associated_types = if *final_is_const {
+          vec![TraitAssociatedType {
+                 name: "Target".to_string(),
+                 value: to_type.ptr_to_value()?.rust_api_type,
+               }]
+        } else {
+          Vec::new()
+        };
+        results.push(TraitImpl {
+                       target_type: from_type.ptr_to_value()?.rust_api_type,
+                       associated_types: associated_types,
+                       trait_type: RustType::Common {
+                         base: RustName::new(vec!["std".to_string(),
+                                                  "ops".to_string(),
+                                                  deref_trait_name])?,
+                         indirection: RustTypeIndirection::None,
+                         is_const: false,
+                         is_const2: false,
+                         generic_arguments: None,
+                       },
+                       extra: None,
+                       methods: vec![deref_method.to_rust_method()],
+                     });
+        if let Some(r) = base_as_ref_impl(final_method, *final_is_const, from_type, to_type)? {
+          // the same base can be reached through different C++ typedefs
+          if !results
+                .iter()
+                .any(|x| x.target_type == r.target_type && x.trait_type == r.trait_type) {
+            results.push(r);
+          }
+        }
+      }
+    }
+    if cpp_cast == &CppCast::Dynamic {
+      // `From` and `Deref` implementations only cover upcasts,
+      // so a downcast can't conflict with them unless the types are the same
+      let from_ref_type = from_type
+        .ptr_to_ref(true)?
+        .rust_api_type
+        .with_lifetime("a".to_string());
+      let to_ref_type = to_type
+        .ptr_to_ref(true)?
+        .rust_api_type
+        .with_lifetime("a".to_string());
+      if from_ref_type != to_ref_type {
+        results.push(TraitImpl {
+                       target_type: to_ref_type,
+                       associated_types: vec![TraitAssociatedType {
+                                                name: "Error".to_string(),
+                                                value: match cast_error_type {
+                                                  Some(ref error_type) => error_type.clone(),
+                                                  None => self.error_type("CastError")?,
+                                                },
+                                              }],
+                       trait_type: RustType::Common {
+                         base: RustName::new(vec!["std".to_string(),
+                                                  "convert".to_string(),
+                                                  "TryFrom".to_string()])?,
+                         indirection: RustTypeIndirection::None,
+                         is_const: false,
+                         is_const2: false,
+                         generic_arguments: Some(vec![from_ref_type]),
+                       },
+                       extra: Some(TraitImplExtra::DynamicCastTryFrom {
+                                     uses_cast_result: cast_error_type.is_some(),
+                                   }),
+                       methods: Vec::new(),
+                     });
+      }
+    }
+    if !cpp_cast.is_unsafe_static_cast() && cpp_cast.is_direct_static_cast() {
+      if let (&CppTypeBase::Class(ref derived), &CppTypeBase::Class(ref base)) =
+        (&from_type.cpp_type.base, &to_type.cpp_type.base) {
+        if derived != base && self.is_owned_upcast_allowed(derived, base)? {
+          let base_type = to_type.ptr_to_value()?.rust_api_type;
+          results.push(TraitImpl {
+                         target_type: from_type.ptr_to_value()?.rust_api_type,
+                         associated_types: Vec::new(),
+                         trait_type: RustType::Common {
+                           base: RustName::new(vec!["cpp_utils".to_string(),
+                                                    "StaticUpcastOwned".to_string()])?,
+                           indirection: RustTypeIndirection::None,
+                           is_const: false,
+                           is_const2: false,
+                           generic_arguments: Some(vec![base_type]),
+                         },
+                         extra: Some(TraitImplExtra::StaticUpcastOwned),
+                         methods: Vec::new(),
+                       });
+        }
+      }
+    }
+    let trait_type = RustType::Common {
+      base: RustName::new(trait_name)?,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+      generic_arguments: Some(vec![to_type.ptr_to_value()?.rust_api_type]),
+    };
+    let associated_types = match cast_error_type {
+      Some(error_type) => {
+        vec![TraitAssociatedType {
+               name: "Error".to_string(),
+               value: error_type,
+             }]
+      }
+      None => Vec::new(),
+    };
+    results.push(TraitImpl {
+                   target_type: from_type.ptr_to_value()?.rust_api_type,
+                   associated_types: associated_types,
+                   trait_type: trait_type,
+                   extra: None,
+                   methods: final_methods
+                     .into_iter()
+                     .map(|x| x.0.to_rust_method())
+                     .collect(),
+                 });
+    Ok(results)
+  }
+
+  /// Checks if `CppBox<Derived>` can be converted to `CppBox<Base>`
+  /// using `cpp_utils::StaticUpcastOwned`. This is only allowed if
+  /// `derived` has exactly one base, both types are heap-allocated and
+  /// have public destructors, and `base` has a virtual destructor,
+  /// so deleting the object through the base pointer is valid.
+  fn is_owned_upcast_allowed(&self,
+                             derived: &CppTypeClassBase,
+                             base: &CppTypeClassBase)
+                             -> Result<bool> {
+    let cpp_data = self.input_data.cpp_data;
+    let has_single_base = match cpp_data.find_type_info(|x| &x.name == &derived.name) {
+      Some(info) => {
+        match info.kind {
+          CppTypeKind::Class { ref bases, .. } => bases.len() == 1,
+          CppTypeKind::Enum { .. } => false,
+        }
+      }
+      None => false,
+    };
+    if !has_single_base {
+      return Ok(false);
+    }
+    for class_type in &[derived, base] {
+      if cpp_data.class_type_allocation_place(class_type)? != CppTypeAllocationPlace::Heap {
+        return Ok(false);
+      }
+      if once(&cpp_data.current)
+           .chain(cpp_data.dependencies.iter().cloned())
+           .any(|data| data.parser.has_non_public_destructor(class_type)) {
+        return Ok(false);
+      }
+    }
+    Ok(cpp_data.has_virtual_destructor(&base.name))
+  }
+
+  /// Generates a single overloaded method from all specified methods or
+  /// accepts a single method without change. Adds self argument caption if needed.
+  /// All passed methods must be valid for overloading:
+  /// - they must have the same name and be in the same scope;
+  /// - they must have the same self argument type;
+  /// - they must be all safe or all unsafe;
+  /// - they must not be platform-specific (see `Config::add_platform_method`);
+  /// - they must not have exactly the same argument types on any of target platforms.
+  ///
+  /// Use `RustGenerator::overload_functions` function to group available functions
+  /// based on these conditions.
+  fn generate_final_method(&self,
+                           mut filtered_methods: Vec<RustSingleMethod>,
+                           scope: &RustMethodScope,
+                           self_arg_kind_caption: Option<String>)
+                           -> Result<(RustMethod, Option<RustTypeDeclaration>)> {
+    filtered_methods.sort_by(|a, b| {
+                               a.arguments
+                                 .cpp_method
+                                 .c_name
+                                 .cmp(&b.arguments.cpp_method.c_name)
+                             });
+    let methods_count = filtered_methods.len();
+    let cfg = self.platform_cfg(&filtered_methods)?;
+    let deprecated = self.deprecation_note(&filtered_methods);
+    let cpp_doc_url = self.cpp_doc_url(&filtered_methods);
+    if methods_count > 1 && filtered_methods.iter().any(|m| m.has_out_parameters()) {
+      return Err(unexpected("overloaded methods with out-parameters must be skipped").into());
+    }
+    let wraps_protected_constructor = filtered_methods
+      .iter()
+      .any(|m| m.arguments.cpp_method.cpp_method.is_protected_constructor());
+    let mut type_declaration = None;
+    let mut method = if methods_count > 1 {
+      let first_method = filtered_methods[0].clone();
+      let self_argument = if !first_method.arguments.arguments.is_empty() &&
+                             first_method.arguments.arguments[0].name == "self" {
+        Some(first_method.arguments.arguments[0].clone())
+      } else {
+        None
+      };
+      let cpp_method_name = first_method
+        .arguments
+        .cpp_method
+        .cpp_method
+        .full_name();
+      let mut args_variants = Vec::new();
+      let mut method_name = first_method.name.clone();
+      let mut method_last_name = method_name
+        .parts
+        .pop()
+        .chain_err(|| "name can't be empty")?;
+      if let Some(self_arg_kind_caption) = self_arg_kind_caption {
+        method_last_name = vec![method_last_name.as_ref(), self_arg_kind_caption.as_ref()]
+          .to_snake_case();
+      }
+      let mut trait_name = method_last_name.to_class_case() + "Args";
+      method_last_name = sanitize_rust_identifier(&method_last_name);
+      method_name.parts.push(method_last_name);
+      if let RustMethodScope::Impl { ref target_type } = *scope {
+        let target_type_name = if let RustType::Common { ref base, .. } = *target_type {
+          base.last_name()
+        } else {
+          Err("RustType::Common expected".into())
+        }?;
+        trait_name = format!("{}{}", target_type_name, trait_name);
+      }
+      let mut grouped_by_cpp_method: HashMap<_, Vec<_>> = HashMap::new();
+      for mut method in filtered_methods {
+        assert!(method.name == first_method.name);
+        assert!(method.scope == first_method.scope);
+        if let Some(ref self_argument) = self_argument {
+          assert!(method.arguments.arguments.len() > 0 &&
+                  &method.arguments.arguments[0] == self_argument);
+          method.arguments.arguments.remove(0);
+        }
+
+        let cpp_method_key = method.arguments.cpp_method.cpp_method.clone();
+        //        if let Some(v) = cpp_method_key.arguments_before_omitting {
+        //          cpp_method_key.arguments = v;
+        //          cpp_method_key.arguments_before_omitting = None;
+        //        }
+        add_to_multihash(&mut grouped_by_cpp_method,
+                         cpp_method_key,
+                         method.arguments.clone());
+        args_variants.push(method.arguments);
+      }
+
+      let mut doc_items = Vec::new();
+      let mut grouped_by_cpp_method_vec: Vec<_> = grouped_by_cpp_method.into_iter().collect();
+      grouped_by_cpp_method_vec.sort_by(|&(ref a, _), &(ref b, _)| {
+                                          a.short_text().cmp(&b.short_text())
+                                        });
+      for (cpp_method, variants) in grouped_by_cpp_method_vec {
+        doc_items.push(RustMethodDocItem {
+                         doc: cpp_method.doc.clone(),
+                         cpp_fn: cpp_method.short_text(),
+                         rust_fns: variants
+                           .iter()
+                           .map_if_ok(|args| -> Result<_> {
+                                        Ok(doc_formatter::rust_method_variant(args,
+                                                    method_name.last_name()?,
+                                                    first_method.self_arg_kind()?,
+                                                    &self.input_data.crate_name))
+                                      })?,
+                       });
+      }
+
+      // overloaded methods
+      let shared_arguments_for_trait = match self_argument {
+        None => Vec::new(),
+        Some(ref arg) => {
+          let mut renamed_self = arg.clone();
+          renamed_self.name = "original_self".to_string();
+          vec![renamed_self]
+        }
+      };
+      let mut shared_arguments = match self_argument {
+        None => Vec::new(),
+        Some(arg) => vec![arg],
+      };
+      // If `self` is a reference, returned references borrow from it
+      // (as in `add_return_lifetime`), so they use the trait lifetime
+      // shared with `self`. Lifetimes assigned to the variants separately
+      // (e.g. `'l0` and `'static`) are ignored when looking for
+      // a common return type.
+      let trait_lifetime_name = "largs";
+      let mut has_trait_lifetime = shared_arguments
+        .iter()
+        .any(|x| x.argument_type.rust_api_type.is_ref());
+      let first_return_type = args_variants[0]
+        .return_type
+        .rust_api_type
+        .with_lifetime(trait_lifetime_name.to_string());
+      let common_return_type = if args_variants.iter().all(|x| {
+        x.return_type
+          .rust_api_type
+          .with_lifetime(trait_lifetime_name.to_string()) == first_return_type
+      }) {
+        if first_return_type.is_ref() {
+          has_trait_lifetime = true;
+        }
+        Some(first_return_type)
+      } else {
+        None
+      };
+      if has_trait_lifetime {
+        for arg in &mut shared_arguments {
+          if arg.argument_type.rust_api_type.is_ref() {
+            arg.argument_type.rust_api_type = arg
+              .argument_type
+              .rust_api_type
+              .with_lifetime(trait_lifetime_name.to_string());
+          }
+        }
+      }
+      let params_trait_lifetime = if has_trait_lifetime {
+        Some(trait_lifetime_name.to_string())
+      } else {
+        None
+      };
+      // the trait is placed in the module of the type or the free function
+      // and has the same visibility as the type
+      let (mut trait_full_name, is_trait_public) = match *scope {
+        RustMethodScope::Impl { ref target_type } => {
+          if let RustType::Common { ref base, .. } = *target_type {
+            (base.clone(),
+             self
+               .processed_types
+               .iter()
+               .find(|t| &t.rust_name == base)
+               .map_or(true, |t| t.is_public))
+          } else {
+            return Err("RustType::Common expected".into());
+          }
+        }
+        _ => (first_method.name.clone(), true),
+      };
+      trait_full_name.parts.pop().unwrap();
+      trait_full_name.parts.push(trait_name.clone());
+      type_declaration = Some(RustTypeDeclaration {
+                                name: trait_full_name,
+                                kind: RustTypeDeclarationKind::MethodParametersTrait {
+                                  shared_arguments: shared_arguments_for_trait,
+                                  impls: args_variants,
+                                  lifetime: params_trait_lifetime.clone(),
+                                  common_return_type: common_return_type.clone(),
+                                  method_name: method_name.clone(),
+                                  method_scope: first_method.scope.clone(),
+                                  is_unsafe: first_method.is_unsafe,
+                                },
+                                is_public: is_trait_public,
+                                rust_doc: None,
+                                doc_override: None,
+                              });
+
+      RustMethod {
+        name: method_name,
+        scope: first_method.scope,
+        arguments: RustMethodArguments::MultipleVariants {
+          params_trait_name: trait_name.clone(),
+          params_trait_lifetime: params_trait_lifetime,
+          common_return_type: common_return_type,
+          shared_arguments: shared_arguments,
+          variant_argument_name: "args".to_string(),
+          cpp_method_name: cpp_method_name,
+        },
+        variant_docs: doc_items,
+        common_doc: None,
+        doc_override: None,
+        cfg: None,
+        deprecated: None,
+        cpp_doc_url: None,
+        is_unsafe: first_method.is_unsafe,
+      }
+    } else {
+      let mut method = filtered_methods
+        .pop()
+        .chain_err(|| "filtered_methods can't be empty")?;
+      // generic arguments can't be used in implementations of parameters traits,
+      // so string class arguments are only converted if there are no overloads.
+      // Copy constructors must keep their argument types.
+      if !method.arguments.cpp_method.cpp_method.is_copy_constructor() {
+        for arg in &mut method.arguments.arguments {
+          if let CppFfiArgumentMeaning::Argument(..) =
+            method.arguments.cpp_method.c_signature.arguments[arg.ffi_index].meaning {
+            convert_string_argument(&mut arg.argument_type,
+                                    self.input_data.settings.string_types())?;
+          }
+        }
+      }
+      let mut last_name = method
+        .name
+        .parts
+        .pop()
+        .chain_err(|| "name can't be empty")?;
+      if let Some(self_arg_kind_caption) = self_arg_kind_caption {
+        last_name = vec![last_name.as_ref(), self_arg_kind_caption.as_ref()].to_snake_case();
+      }
+      method
+        .name
+        .parts
+        .push(sanitize_rust_identifier(&last_name));
+
+      method.doc = Some(RustMethodDocItem {
+                          cpp_fn: method.arguments.cpp_method.cpp_method.short_text(),
+                          rust_fns: Vec::new(),
+                          doc: method.arguments.cpp_method.cpp_method.doc.clone(),
+                        });
+      method.to_rust_method()
+    };
+    if wraps_protected_constructor {
+      method.common_doc = Some(doc_formatter::protected_constructor_doc());
+    }
+    method.cfg = cfg;
+    method.deprecated = deprecated;
+    method.cpp_doc_url = cpp_doc_url;
+    Ok((method, type_declaration))
+  }
+
+  /// Returns URL of the C++ documentation of the C++ methods wrapped by `methods`
+  /// if `Config::set_cpp_doc_base_url` was used. Overloaded methods and
+  /// operators are linked to the page of their class. Free functions are not linked.
+  fn cpp_doc_url(&self, methods: &[RustSingleMethod]) -> Option<String> {
+    let base_url = match self.input_data.settings.cpp_doc_base_url() {
+      Some(url) => url,
+      None => return None,
+    };
+    let cpp_method = match methods.first() {
+      Some(method) => &method.arguments.cpp_method.cpp_method,
+      None => return None,
+    };
+    let class_name = match cpp_method.class_name() {
+      Some(name) => name,
+      None => return None,
+    };
+    let overloaded = cpp_method.operator.is_some() ||
+                     self
+                       .input_data
+                       .cpp_data
+                       .current
+                       .parser
+                       .methods
+                       .iter()
+                       .filter(|m| m.class_name() == Some(class_name) && m.name == cpp_method.name)
+                       .count() > 1;
+    Some(doc_formatter::cpp_doc_url(base_url,
+                                    class_name,
+                                    if overloaded {
+                                      None
+                                    } else {
+                                      Some(&cpp_method.name)
+                                    }))
+  }
+
+  /// Returns the note set with `Config::add_deprecated_method`
+  /// if all C++ methods wrapped by `methods` are deprecated.
+  fn deprecation_note(&self, methods: &[RustSingleMethod]) -> Option<String> {
+    let deprecated_methods = self.input_data.settings.deprecated_methods();
+    let notes: Vec<_> = methods
+      .iter()
+      .map(|method| {
+             let cpp_method = &method.arguments.cpp_method.cpp_method;
+             deprecated_methods
+               .get(&cpp_method.full_name())
+               .or_else(|| deprecated_methods.get(&cpp_method.name))
+           })
+      .collect();
+    if notes.is_empty() || notes.iter().any(|note| note.is_none()) {
+      None
+    } else {
+      notes[0].cloned()
+    }
+  }
+
+  /// Returns the target condition set with `Config::add_platform_method`
+  /// for the C++ method wrapped by `method`.
+  fn platform_condition(&self, method: &RustSingleMethod) -> Option<&target::Condition> {
+    method
+      .arguments
+      .cpp_method
+      .cpp_method
+      .platform_condition(self.input_data.settings.platform_methods())
+  }
+
+  /// Returns the configuration predicate for C++ methods wrapped by `methods`,
+  /// or `None` if they are not platform-specific. `RustGenerator::overload_functions`
+  /// never overloads platform-specific methods.
+  fn platform_cfg(&self, methods: &[RustSingleMethod]) -> Result<Option<String>> {
+    let conditions: Vec<_> = methods
+      .iter()
+      .filter_map(|method| self.platform_condition(method))
+      .collect();
+    if conditions.is_empty() {
+      return Ok(None);
+    }
+    if methods.len() > 1 {
+      return Err(unexpected("platform-specific methods can't be overloaded").into());
+    }
+    Ok(Some(conditions[0].to_cfg()))
+  }
+
+  /// Splits `methods` to groups based on overloading constraints.
+  /// See `RustGenerator::generate_final_method` documentation for full list of these constraints.
+  /// Each element of the returned vector contains a list of methods that
+  /// can be safely overloaded together and a name suffix for these methods.
+  fn overload_functions(&self,
+                        methods: Vec<RustSingleMethod>)
+                        -> Result<Vec<(Option<String>, Vec<RustSingleMethod>)>> {
+    let mut buckets: Vec<Vec<RustSingleMethod>> = Vec::new();
+    for method in methods {
+      if let Some(mut b) = buckets
+           .iter_mut()
+           .find(|b| {
+                   self.platform_condition(&method).is_none() &&
+                   b.iter()
+                     .all(|m| {
+                            self.platform_condition(m).is_none() &&
+                            m.can_be_overloaded_with(&method).unwrap()
+                          })
+                 }) {
+        b.push(method);
+        continue;
+      }
+      buckets.push(vec![method]);
+    }
+    let cpp_method_name = buckets[0][0].arguments.cpp_method.cpp_method.full_name();
+    if let Some(suffixes) = self.input_data.settings.overload_suffixes().get(&cpp_method_name) {
+      if buckets
+           .iter()
+           .flat_map(|b| b.iter())
+           .any(|m| m.arguments.cpp_method.cpp_method.full_name() != cpp_method_name) {
+        return Err(format!("overload suffixes for {} can't be applied because \
+                            methods with other C++ names have the same Rust name",
+                           cpp_method_name)
+                       .into());
+      }
+      if suffixes.len() != buckets.len() {
+        return Err(format!("{} overload suffixes expected for {}, got {}",
+                           buckets.len(),
+                           cpp_method_name,
+                           suffixes.len())
+                       .into());
+      }
+      if suffixes.iter().collect::<HashSet<_>>().len() != suffixes.len() {
+        return Err(format!("overload suffixes for {} are not unique: {:?}",
+                           cpp_method_name,
+                           suffixes)
+                       .into());
+      }
+      return Ok(suffixes
+                  .iter()
+                  .map(|s| if s.is_empty() { None } else { Some(s.clone()) })
+                  .zip(buckets.into_iter())
+                  .collect());
+    }
+    let mut all_self_args: HashSet<_> = HashSet::new();
+    for bucket in &buckets {
+      all_self_args.insert(bucket[0].self_arg_kind()?.clone());
+    }
+
+    let mut final_names = None;
+    {
+      let try_strategy = |strategy| -> Result<Vec<Option<String>>> {
+        let mut result = Vec::new();
+        for (bucket_index, bucket) in buckets.iter().enumerate() {
+          let mut bucket_caption: Option<Option<String>> = None;
+          for method in bucket {
+            let caption = method
+              .name_suffix(strategy, &all_self_args, bucket_index)?;
+            if bucket_caption.is_none() {
+              bucket_caption = Some(caption);
+            } else if Some(caption) != bucket_caption {
+              return Err("different captions within a bucket".into());
+            }
+          }
+          let bucket_caption = bucket_caption.expect("can't be None here");
+          if result.iter().any(|c| c == &bucket_caption) {
+            return Err("same captions for two buckets".into());
+          }
+          result.push(bucket_caption);
+        }
+        Ok(result)
+      };
+
+      for strategy in RustMethodCaptionStrategy::all() {
+        if let Ok(names) = try_strategy(&strategy) {
+          final_names = Some(names);
+          break;
+        }
+      }
+    }
+    if let Some(final_names) = final_names {
+      return Ok(final_names
+                  .into_iter()
+                  .zip(buckets.into_iter())
+                  .collect());
+    } else {
+      return Err(unexpected("all Rust caption strategies failed").into());
+    }
+  }
+
+  /// Generates methods, trait implementations and overloading types
+  /// for all specified methods. All methods must either be in the same
+  /// `RustMethodScope::Impl` scope or be free functions in the same module.
+  #[cfg_attr(feature="clippy", allow(for_kv_map))]
+  fn process_all_sibling_functions<'b, I>(&self,
+                                          methods: I,
+                                          scope: &RustMethodScope)
+                                          -> Result<ProcessFunctionsResult>
+    where I: Iterator<Item = &'b CppAndFfiMethod>
+  {
+    // Step 1: convert all methods to SingleVariant Rust methods and
+    // split them by last name.
+    let mut single_rust_methods: HashMap<String, Vec<RustSingleMethod>> = HashMap::new();
+    let mut result = ProcessFunctionsResult::default();
+    let mut default_constructors = Vec::new();
+    let mut copy_constructors = Vec::new();
+    let mut qt_hash_impls: BTreeMap<RustType, TraitImpl> = BTreeMap::new();
+    let mut conversion_impls: BTreeMap<RustType, Vec<TraitImpl>> = BTreeMap::new();
+    let mut conversion_methods = Vec::new();
+    let mut add_impls: BTreeMap<(RustType, RustType), Vec<TraitImpl>> = BTreeMap::new();
+    for method in methods {
+      if &method.cpp_method.name == "qHash" && method.cpp_method.class_membership.is_none() {
+        match self.process_qt_hash_function(method) {
+          Ok(r) => {
+            let has_seed = r.extra ==
+                           Some(TraitImplExtra::QtHashFunction {
+                                  ffi_function_name: method.c_name.clone(),
+                                  has_seed: true,
+                                });
+            // prefer the overload with seed if both are present
+            if has_seed || !qt_hash_impls.contains_key(&r.target_type) {
+              qt_hash_impls.insert(r.target_type.clone(), r);
+            }
+          }
+          Err(msg) => {
+            self.add_skipped_item(method.short_text(),
+                                  format!("Failed to generate Hash implementation: {}", msg));
+          }
+        }
+      }
+      if method.cpp_method.is_destructor() {
+        match self.process_destructor(method, scope) {
+          Ok(r) => result.trait_impls.push(r),
+          Err(msg) => {
+            self.add_skipped_item(method.short_text(),
+                                  format!("Failed to generate destructor: {}", msg));
+          }
+        }
+        continue;
+      }
+      match self.generate_rust_single_method(method, scope, false) {
+        Ok(rust_method) => {
+          if is_stream_write_operator(&method.cpp_method) {
+            if self.input_data.settings.stream_operator_traits() {
+              match self.process_stream_write_operator(&rust_method) {
+                Ok(r) => {
+                  result.trait_impls.push(r);
+                  continue;
+                }
+                Err(msg) => {
+                  self.add_skipped_item(method.short_text(),
+                                        format!("Failed to generate Shl implementation: {}",
+                                                msg));
+                }
+              }
+            } else {
+              match self.process_stream_write_method(&rust_method) {
+                Ok(r) => {
+                  result.stream_write_methods.push(r);
+                  continue;
+                }
+                Err(msg) => {
+                  self.add_skipped_item(method.short_text(),
+                                        format!("Failed to generate write_to method: {}", msg));
+                }
+              }
+            }
+          }
+          if self.input_data.settings.string_from_impls() &&
+             &method.cpp_method.name == "fromUtf8" &&
+             method.cpp_method.arguments.len() == 2 &&
+             method
+               .cpp_method
+               .class_name()
+               .map_or(false, |name| self.input_data.settings.string_types().contains(name)) {
+            match self.process_string_from_utf8(&rust_method) {
+              Ok(mut r) => result.trait_impls.append(&mut r),
+              Err(msg) => {
+                self.add_skipped_item(method.short_text(),
+                                      format!("Failed to generate From<&str> implementation: {}",
+                                              msg));
+              }
+            }
+          }
+          if (&method.cpp_method.name == "static_cast" ||
+              &method.cpp_method.name == "dynamic_cast" ||
+              &method.cpp_method.name == "qobject_cast") &&
+             method.cpp_method.class_membership.is_none() {
+            match self.process_cpp_cast(rust_method) {
+              Ok(mut r) => result.trait_impls.append(&mut r),
+              Err(msg) => {
+                self.add_skipped_item(method.short_text(),
+                                      format!("Failed to generate cast wrapper: {}", msg));
+              }
+            }
+          } else {
+            if method.cpp_method.is_constructor() &&
+               !method.cpp_method.is_protected_constructor() &&
+               method.allocation_place == ReturnValueAllocationPlace::Stack &&
+               rust_method.arguments.arguments.is_empty() {
+              default_constructors.push(method);
+            }
+            if method.cpp_method.is_copy_constructor() &&
+               !method.cpp_method.is_protected_constructor() {
+              copy_constructors.push(method);
+            }
+            if method.cpp_method.is_implicit_conversion_operator() {
+              match self.process_conversion_operator(method, scope) {
+                Ok(r) => {
+                  conversion_impls
+                    .entry(r.target_type.clone())
+                    .or_insert_with(Vec::new)
+                    .push(r)
+                }
+                Err(msg) => {
+                  self.add_skipped_item(method.short_text(),
+                                        format!("Failed to generate From implementation: {}",
+                                                msg));
+                }
+              }
+            }
+            if method.cpp_method.is_explicit_conversion_operator() {
+              match self.process_narrowing_conversion_operator(method, scope) {
+                Ok(r) => {
+                  conversion_impls
+                    .entry(r.target_type.clone())
+                    .or_insert_with(Vec::new)
+                    .push(r)
+                }
+                Err(msg) => {
+                  self.add_skipped_item(method.short_text(),
+                                        format!("Failed to generate TryFrom implementation: {}",
+                                                msg));
+                }
+              }
+            }
+            if method.cpp_method.operator == Some(CppOperator::Addition) {
+              match self.process_add_operator(&rust_method) {
+                Ok(r) => {
+                  add_impls
+                    .entry((r.target_type.clone(), r.trait_type.clone()))
+                    .or_insert_with(Vec::new)
+                    .push(r)
+                }
+                Err(msg) => {
+                  self.add_skipped_item(method.short_text(),
+                                        format!("Failed to generate Add implementation: {}",
+                                                msg));
+                }
+              }
+            }
+            if method.cpp_method.operator == Some(CppOperator::Subscript) {
+              match self.process_index_operator(&rust_method) {
+                Ok(r) => result.trait_impls.push(r),
+                Err(msg) => {
+                  self.add_skipped_item(method.short_text(),
+                                        format!("Failed to generate Index implementation: {}",
+                                                msg));
+                }
+              }
+            }
+            if let Some(CppOperator::Conversion(_)) = method.cpp_method.operator {
+              conversion_methods.push(rust_method);
+              continue;
+            }
+            let name = rust_method.name.last_name()?.clone();
+            add_to_multihash(&mut single_rust_methods, name, rust_method);
+          }
+        }
+        Err(err) => {
+          self.add_skipped_method(method, format!("failed to generate Rust function: {}", err));
+        }
+      }
+    }
+    disambiguate_conversion_operators(&mut conversion_methods)?;
+    for rust_method in conversion_methods {
+      let name = rust_method.name.last_name()?.clone();
+      add_to_multihash(&mut single_rust_methods, name, rust_method);
+    }
+    if default_constructors.len() == 1 {
+      match self.process_default_constructor(default_constructors[0], scope) {
+        Ok(r) => result.trait_impls.push(r),
+        Err(msg) => {
+          self.add_skipped_item(default_constructors[0].short_text(),
+                                format!("Failed to generate Default implementation: {}", msg));
+        }
+      }
+    }
+    result
+      .trait_impls
+      .extend(qt_hash_impls.into_iter().map(|(_, r)| r));
+    // conversions to the same Rust type (e.g. from different
+    // C++ typedefs of the same type) would produce conflicting impls
+    for (_, mut impls) in conversion_impls {
+      if impls.len() == 1 {
+        result.trait_impls.append(&mut impls);
+      }
+    }
+    // the same applies to operators with the same operand types
+    for (_, mut impls) in add_impls {
+      if impls.len() == 1 {
+        result.trait_impls.append(&mut impls);
+      }
+    }
+    if copy_constructors.len() == 1 {
+      match self.process_copy_constructor(copy_constructors[0], scope) {
+        Ok(r) => result.trait_impls.push(r),
+        Err(msg) => {
+          self.add_skipped_item(copy_constructors[0].short_text(),
+                                format!("Failed to generate Clone implementation: {}", msg));
+        }
+      }
+    }
+    let mut single_rust_methods: Vec<_> = single_rust_methods.into_iter().collect();
+    single_rust_methods.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut overloaded_groups = Vec::new();
+    let mut names = Vec::new();
+    for (name, current_methods) in single_rust_methods {
+      assert!(!current_methods.is_empty());
+      if self.input_data.settings.merge_const_getter_pairs() {
+        if let Some(suffixes) = const_getter_pair_suffixes(&current_methods)? {
+          for (name_suffix, method) in suffixes.into_iter().zip(current_methods) {
+            names.push((name.clone(), name_suffix));
+            overloaded_groups.push(vec![method]);
+          }
+          continue;
+        }
+      }
+      for (name_suffix, overloaded_methods) in self.overload_functions(current_methods)? {
+        names.push((name.clone(), name_suffix));
+        overloaded_groups.push(overloaded_methods);
+      }
+    }
+    // lossy name conversions may produce the same final name
+    // for methods from different groups
+    resolve_method_name_collisions(&mut names);
+    for ((_, name_suffix), mut overloaded_methods) in names.into_iter().zip(overloaded_groups) {
+      if overloaded_methods.len() > 1 {
+        // out-parameters are returned in a tuple, so such methods
+        // can't share a parameters trait with other overloads
+        let (skipped, remaining): (Vec<_>, Vec<_>) = overloaded_methods
+          .into_iter()
+          .partition(|m| m.has_out_parameters());
+        for method in skipped {
+          self.add_skipped_item(method.arguments.cpp_method.short_text(),
+                                "method with out-parameters can't be overloaded".to_string());
+        }
+        if remaining.is_empty() {
+          continue;
+        }
+        overloaded_methods = remaining;
+      }
+      let (method, type_declaration) =
+        self
+          .generate_final_method(overloaded_methods, scope, name_suffix)?;
+      if method.variant_docs.is_empty() {
+        return Err(unexpected(format!("docs are empty! {:?}", method)).into());
+      }
+      result.methods.push(method);
+      if let Some(r) = type_declaration {
+        result.overloading_types.push(r);
+      }
+    }
+    result
+      .methods
+      .sort_by(|a, b| {
+                 a.name
+                   .last_name()
+                   .unwrap_or(&String::new())
+                   .cmp(b.name.last_name().unwrap_or(&String::new()))
+               });
+    dedup_trait_impls(&mut result.trait_impls);
+    result
+      .trait_impls
+      .sort_by(|a, b| a.trait_type.cmp(&b.trait_type));
+    Ok(result)
+  }
+
+  /// Checks that all code added by `Config::add_raw_trait_impl`
+  /// targets existing non-template types and refers to their Rust names.
+  fn check_raw_trait_impls(&self) -> Result<()> {
+    for (type_name, codes) in self.input_data.settings.raw_trait_impls() {
+      let info = self
+        .processed_types
+        .iter()
+        .find(|t| &t.cpp_name == type_name && t.cpp_template_arguments.is_none())
+        .chain_err(|| format!("raw trait impl: type not found: {}", type_name))?;
+      let rust_name = info.rust_name.last_name()?;
+      for code in codes {
+        if !code.contains(rust_name.as_str()) {
+          return Err(format!("raw trait impl for {} doesn't refer to {}: {}",
+                             type_name,
+                             rust_name,
+                             code)
+                         .into());
+        }
+      }
+    }
+    Ok(())
+  }
+
+  /// Checks that all types marked with `Config::mark_send` and
+  /// `Config::mark_sync` are existing non-template classes.
+  fn check_thread_safe_types(&self) -> Result<()> {
+    for type_name in self
+          .input_data
+          .settings
+          .send_types()
+          .iter()
+          .chain(self.input_data.settings.sync_types().iter()) {
+      let info = self
+        .processed_types
+        .iter()
+        .find(|t| &t.cpp_name == type_name && t.cpp_template_arguments.is_none())
+        .chain_err(|| format!("thread-safe type not found: {}", type_name))?;
+      if let RustTypeWrapperKind::Enum { .. } = info.kind {
+        return Err(format!("thread-safe type must be a class: {}", type_name).into());
+      }
+    }
+    Ok(())
+  }
+
+  /// Checks that all types passed to `Config::set_type_visibility`
+  /// are existing non-template types.
+  fn check_type_visibility(&self) -> Result<()> {
+    for type_name in self.input_data.settings.type_visibility().keys() {
+      if !self
+            .processed_types
+            .iter()
+            .any(|t| &t.cpp_name == type_name && t.cpp_template_arguments.is_none()) {
+        return Err(format!("type passed to set_type_visibility not found: {}", type_name)
+                     .into());
+      }
+    }
+    Ok(())
+  }
+
+  /// Returns name of the top level module that should
+  /// contain the Rust wrapper of `method`.
+  fn top_module_name_for_method(&self, method: &CppAndFfiMethod) -> Result<String> {
+    let rust_name = if let Some(ref info) = method.cpp_method.class_membership {
+      match self
+              .processed_types
+              .iter()
+              .find(|t| {
+                      t.cpp_name == info.class_type.name &&
+                      t.cpp_template_arguments == info.class_type.template_arguments
+                    }) {
+        Some(t) => t.rust_name.clone(),
+        None => return Err(unexpected("class type of the method is not processed").into()),
+      }
+    } else {
+      self.free_function_rust_name(&method.cpp_method)?
+    };
+    if rust_name.parts.len() < 2 {
+      return Err(unexpected(format!("rust name is too short: {:?}", rust_name)).into());
+    }
+    Ok(rust_name.parts[1].clone())
+  }
+
+  /// Generates a Rust module with specified name from specified
+  /// C++ header. If the module should have nested modules,
+  /// this function calls itself recursively with nested module name
+  /// but the same header data.
+  pub fn generate_module<'a, 'b>(&'a self,
+                                 mut cpp_methods: Vec<&'a CppAndFfiMethod>,
+                                 module_name: &'b RustName)
+                                 -> Result<(Option<RustModule>, Vec<&'a CppAndFfiMethod>)> {
+    let mut direct_submodules = HashSet::new();
+    let cpp_header = if module_name.parts.len() == 2 {
+      self
+        .top_module_names
+        .iter()
+        .find(|&(_k, v)| v == module_name)
+        .and_then(|(k, _v)| Some(k.clone()))
+    } else {
+      None
+    };
+    let mut module = RustModule {
+      name: module_name.last_name()?.clone(),
+      types: Vec::new(),
+      functions: Vec::new(),
+      submodules: Vec::new(),
+      trait_impls: Vec::new(),
+      plugin_code: Vec::new(),
+      doc: if module_name.parts.len() >= 2 && module_name.parts[1] == "slots" {
+        if module_name.parts.len() == 3 && module_name.parts[2] == "raw" {
+          Some(doc_formatter::slots_raw_module_doc())
+        } else if module_name.parts.len() == 2 {
+          Some(doc_formatter::slots_module_doc())
+        } else {
+          return Err(unexpected("unknown slots submodule").into());
+        }
+      } else {
+        cpp_header
+          .as_ref()
+          .map(|h| format!("Entities from `{}` C++ header", h))
+      },
+    };
+    let mut rust_overloading_types = Vec::new();
+    let mut good_methods = Vec::new();
+    {
+      // Checks if the name should be processed.
+      // Returns true if the name is directly in this module.
+      // If the name is in this module's submodule, adds
+      // name of the direct submodule to direct_submodules list.
+      let mut check_name = |rust_name: &RustName| {
+        if module_name.includes(rust_name) {
+          if module_name.includes_directly(rust_name) {
+            return true;
+          } else {
+            let direct_submodule = &rust_name.parts[module_name.parts.len()];
+            if !direct_submodules.contains(direct_submodule) {
+              direct_submodules.insert(direct_submodule.clone());
+            }
+          }
+        }
+        false
+      };
+
+      for type_data in &self.processed_types {
+        if check_name(&type_data.rust_name) {
+          let (mut result, tmp_cpp_methods) = self.generate_type(type_data, cpp_methods)?;
+          cpp_methods = tmp_cpp_methods;
+          for plugin in &self.input_data.settings.type_plugins() {
+            if let Some(code) = plugin(type_data) {
+              module.plugin_code.push(code);
+            }
+          }
+          if type_data.cpp_template_arguments.is_none() {
+            let raw_trait_impls = self.input_data.settings.raw_trait_impls();
+            if let Some(codes) = raw_trait_impls.get(&type_data.cpp_name) {
+              module.plugin_code.extend(codes.iter().cloned());
+            }
+          }
+          if let Some(ref cpp_header) = cpp_header {
+            if &type_data.cpp_name == cpp_header {
+              if let RustTypeDeclarationKind::CppTypeWrapper { ref cpp_doc, .. } =
+                result.main_type.kind {
+                if let Some(ref cpp_doc) = *cpp_doc {
+                  let mut doc = cpp_doc.html.as_str();
+                  if let Some(index) = doc.find("\n") {
+                    doc = &doc[0..index];
+                  }
+                  module.doc = Some(doc.to_string());
+                }
+              }
+            }
+          }
+          doc_formatter::add_special_type_docs(&mut result.main_type)?;
+          module.types.push(result.main_type);
+          rust_overloading_types.append(&mut result.overloading_types);
+        }
+      }
+
+      let mut tmp_cpp_methods = Vec::new();
+      for method in cpp_methods {
+        if method.cpp_method.class_membership.is_none() {
+          let rust_name = self.free_function_rust_name(&method.cpp_method)?;
+
+          if check_name(&rust_name) {
+            good_methods.push(method);
+            continue;
+          }
+        }
+        tmp_cpp_methods.push(method);
+      }
+      cpp_methods = tmp_cpp_methods;
+    }
+    for name in direct_submodules {
+      let mut new_name = module_name.clone();
+      new_name.parts.push(name);
+      let (submodule, tmp_cpp_methods) = self.generate_module(cpp_methods, &new_name)?;
+      cpp_methods = tmp_cpp_methods;
+      if let Some(submodule) = submodule {
+        module.submodules.push(submodule);
+      }
+    }
+    let mut free_functions_result =
+      self
+        .process_all_sibling_functions(good_methods.into_iter(), &RustMethodScope::Free)?;
+    module.trait_impls = free_functions_result.trait_impls;
+    module.functions = free_functions_result.methods;
+    for method in free_functions_result.stream_write_methods {
+      let target_name = match method.scope {
+        RustMethodScope::Impl { target_type: RustType::Common { ref base, .. } } => base.clone(),
+        _ => return Err(unexpected("invalid scope of stream write method").into()),
+      };
+      let type1 = module
+        .types
+        .iter_mut()
+        .find(|t| t.name == target_name)
+        .chain_err(|| unexpected("written type is not in the module"))?;
+      if let RustTypeDeclarationKind::CppTypeWrapper { ref mut methods, .. } = type1.kind {
+        if methods.iter().any(|m| m.name == method.name) {
+          self.add_skipped_item(format!("{}::write_to", target_name.full_name(None)),
+                                "duplicate write_to method".to_string());
+        } else {
+          methods.push(method);
+          methods.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+      } else {
+        return Err(unexpected("written type is not a wrapper").into());
+      }
+    }
+    rust_overloading_types.append(&mut free_functions_result.overloading_types);
+    // overloading traits are declared next to the types, so a trait
+    // must not take the name of a type of this module
+    let type_names: HashSet<_> = module
+      .types
+      .iter()
+      .map_if_ok(|t| t.name.last_name().map(|x| x.clone()))?
+      .into_iter()
+      .collect();
+    for trait_type in &mut rust_overloading_types {
+      let old_name = trait_type.name.last_name()?.clone();
+      if type_names.contains(&old_name) {
+        let mut new_name = format!("{}Overloads", old_name);
+        let mut index = 1;
+        while type_names.contains(&new_name) {
+          index += 1;
+          new_name = format!("{}Overloads{}", old_name, index);
+        }
+        log::llog(log::DebugRustSkips, || {
+          format!("Overloading trait {} is renamed to {} to avoid a name conflict",
+                  old_name,
+                  new_name)
+        });
+        rename_params_trait(&mut module.functions, &old_name, &new_name);
+        for type1 in &mut module.types {
+          if let RustTypeDeclarationKind::CppTypeWrapper { ref mut methods, .. } = type1.kind {
+            rename_params_trait(methods, &old_name, &new_name);
+          }
+        }
+        *trait_type.name.parts.last_mut().unwrap() = new_name;
+      }
+    }
+    module.types.append(&mut rust_overloading_types);
+    if !self.input_data.settings.doc_hooks().is_empty() {
+      apply_doc_hooks(&self.input_data.settings.doc_hooks(), &mut module);
+    }
+    module.types.sort_by(|a, b| a.name.cmp(&b.name));
+    module.submodules.sort_by(|a, b| a.name.cmp(&b.name));
+    if module.types.is_empty() && module.functions.is_empty() && module.submodules.is_empty() {
+      log::llog(log::DebugRustSkips,
+                || format!("Skipping empty module: {}", module.name));
+      return Ok((None, cpp_methods));
+    }
+    Ok((Some(module), cpp_methods))
+  }
+
+
+  /// Generates exact (FFI-compatible) Rust equivalent of `CppAndFfiMethod` object.
+  fn generate_ffi_function(&self, data: &CppAndFfiMethod) -> Result<RustFFIFunction> {
+    let mut args = Vec::new();
+    for arg in &data.c_signature.arguments {
+      let rust_type = ffi_type(&self.types(),
+                               &arg.argument_type.ffi_type)?;
+      args.push(RustFFIArgument {
+                  name: sanitize_rust_identifier(&arg.name),
+                  argument_type: rust_type,
+                });
+    }
+    Ok(RustFFIFunction {
+         return_type: ffi_type(&self.types(),
+                               &data.c_signature.return_type.ffi_type)?,
+         name: data.c_name.clone(),
+         arguments: args,
+         cfg: data
+           .cpp_method
+           .platform_condition(self.input_data.settings.platform_methods())
+           .map(|condition| condition.to_cfg()),
+       })
+  }
+
+  /// Generates Rust representations of all FFI functions
+  pub fn generate_ffi_functions(&self) -> Vec<(String, Vec<RustFFIFunction>)> {
+    log::status("Generating Rust FFI functions");
+    let mut ffi_functions = Vec::new();
+
+    for header in &self.input_data.cpp_ffi_headers {
+      let mut functions = Vec::new();
+      for method in &header.methods {
+        match self.generate_ffi_function(method) {
+          Ok(function) => {
+            functions.push(function);
+          }
+          Err(msg) => {
+            self.add_skipped_item(method.short_text(),
+                                  format!("Can't generate Rust FFI function: {}", msg));
+          }
+        }
+      }
+      ffi_functions.push((header.include_file_base_name.clone(), functions));
+    }
+    ffi_functions
+  }
+
+  /// Generates Rust names and type information for all available C++ types.
+  fn calc_processed_types(&self) -> Result<Vec<RustProcessedTypeInfo>> {
+    let mut result = Vec::new();
+    for type_info in &self.input_data.cpp_data.current.parser.types {
+      if let CppTypeKind::Class { ref template_arguments, .. } = type_info.kind {
+        if template_arguments.is_some() {
+          // only instantiations of templates can be wrapped
+          if !self
+                .input_data
+                .cpp_data
+                .current
+                .processed
+                .template_instantiations
+                .iter()
+                .any(|x| x.class_name == type_info.name) {
+            self.add_skipped_type(type_info.name.clone(),
+                                  "template class has no instantiations".to_string());
+          }
+          continue;
+        }
+      }
+      let rust_name =
+        self
+          .calculate_rust_name(&type_info.name, &type_info.include_file, false, None)?;
+      let rust_type_info = RustProcessedTypeInfo {
+        cpp_name: type_info.name.clone(),
+        cpp_doc: type_info.doc.clone(),
+        cpp_template_arguments: None,
+        kind: match type_info.kind {
+          CppTypeKind::Class { .. } => {
+            match self
+                    .input_data
+                    .cpp_data
+                    .type_allocation_place(&type_info.name) {
+              Err(err) => {
+                self.add_skipped_type(type_info.name.clone(),
+                                      format!("Can't process type: {}", err));
+                continue;
+              }
+              Ok(place) => {
+                RustTypeWrapperKind::Struct {
+                  size_const_name: match place {
+                    CppTypeAllocationPlace::Stack => Some(size_const_name(&rust_name)),
+                    CppTypeAllocationPlace::Heap => None,
+                  },
+                  is_deletable: !self
+                                   .input_data
+                                   .cpp_data
+                                   .current
+                                   .parser
+                                   .has_non_public_destructor(&CppTypeClassBase {
+                                                                 name: type_info.name.clone(),
+                                                                 template_arguments: None,
+                                                               }),
+                  slot_wrapper: None,
+                }
+              }
+            }
+          }
+          CppTypeKind::Enum { ref values } => {
+
+            let mut is_flaggable = false;
+            let template_arg_sample = CppType {
+              is_const: false,
+              is_const2: false,
+              indirection: CppTypeIndirection::None,
+              base: CppTypeBase::Enum { name: type_info.name.clone() },
+            };
+
+            for flag_owner_name in &["QFlags", "QUrlTwoFlags"] {
+              if let Some(instantiations) =
+                self
+                  .input_data
+                  .cpp_data
+                  .current
+                  .processed
+                  .template_instantiations
+                  .iter()
+                  .find(|x| &x.class_name == &flag_owner_name.to_string()) {
+                if instantiations
+                     .instantiations
+                     .iter()
+                     .any(|ins| {
+                            ins
+                              .template_arguments
+                              .iter()
+                              .any(|arg| arg == &template_arg_sample)
+                          }) {
+                  is_flaggable = true;
+                  break;
+                }
+              }
+            }
+            RustTypeWrapperKind::Enum {
+              values: {
+                let blocked_names = self
+                  .input_data
+                  .settings
+                  .blocked_enum_values()
+                  .get(&type_info.name)
+                  .map_or(&[][..], |names| &names[..]);
+                if values.iter().all(|v| blocked_names.contains(&v.name)) {
+                  return Err(format!("all values of enum {} are blocked", type_info.name).into());
+                }
+                prepare_enum_values(values, blocked_names)
+              },
+              is_flaggable: is_flaggable,
+            }
+          }
+        },
+        rust_name: rust_name,
+        is_public: self.input_data.settings.type_visibility().get(&type_info.name) !=
+                   Some(&TypeVisibility::Crate),
+      };
+      result.push(rust_type_info);
+    }
+    let template_final_name =
+      |result: &Vec<RustProcessedTypeInfo>, item: &RustProcessedTypeInfo| -> Result<RustName> {
+        let mut name = item.rust_name.clone();
+        let last_name = name
+          .parts
+          .pop()
+          .chain_err(|| "name.parts can't be empty")?;
+        let mut arg_captions = Vec::new();
+        if let Some(ref args) = item.cpp_template_arguments {
+          for x in args {
+            let rust_type = complete_type(&self.unindexed_types(result),
+                                          &x.to_cpp_ffi_type(CppTypeRole::NotReturnType)?,
+                                          &CppFfiArgumentMeaning::Argument(0),
+                                          true,
+                                          &ReturnValueAllocationPlace::NotApplicable)?;
+            arg_captions.push(rust_type.rust_api_type.caption(&name)?.to_class_case());
+          }
+        } else {
+          return Err("template arguments expected".into());
+        }
+        name.parts.push(last_name + &arg_captions.join(""));
+        Ok(name)
+      };
+    let mut unnamed_items = Vec::new();
+    for template_instantiations in
+      &self
+         .input_data
+         .cpp_data
+         .current
+         .processed
+         .template_instantiations {
+      if template_instantiations.class_name == "QFlags" ||
+         template_instantiations.class_name == "std::optional" {
+        // special processing is implemented for QFlags and std::optional
+        continue;
+      }
+      let type_info = self
+        .input_data
+        .cpp_data
+        .find_type_info(|x| &x.name == &template_instantiations.class_name)
+        .chain_err(|| {
+                     format!("type info not found for {}",
+                             &template_instantiations.class_name)
+                   })?;
+      for ins in &template_instantiations.instantiations {
+        let rust_name = self
+          .calculate_rust_name(&template_instantiations.class_name,
+                               &type_info.include_file,
+                               false,
+                               None)?;
+        unnamed_items.push(RustProcessedTypeInfo {
+                             cpp_name: template_instantiations.class_name.clone(),
+                             cpp_doc: type_info.doc.clone(),
+                             cpp_template_arguments: Some(ins.template_arguments.clone()),
+                             kind: RustTypeWrapperKind::Struct {
+                               size_const_name: None,
+                               is_deletable:
+                                 !self
+                                    .input_data
+                                    .cpp_data
+                                    .current
+                                    .parser
+                                    .has_non_public_destructor(&CppTypeClassBase {
+                                                                  name: template_instantiations
+                                                                    .class_name
+                                                                    .clone(),
+                                                                  template_arguments:
+                                                                    Some(ins
+                                                                           .template_arguments
+                                                                           .clone()),
+                                                                }),
+                               slot_wrapper: None,
+                             },
+                             rust_name: rust_name,
+                             is_public: true,
+                           });
+      }
+    }
+    let mut any_success = true;
+    while !unnamed_items.is_empty() {
+      if !any_success {
+        log::error("Failed to generate Rust names for template types:");
+        for r in unnamed_items {
+          let err = if let Err(err) = template_final_name(&result, &r) {
+            err
+          } else {
+            return Err("template_final_name must return Err at this stage".into());
+          };
+          log::error(format!("  {:?}\n  {}\n\n", r, err));
+          let class_type = CppTypeClassBase {
+            name: r.cpp_name.clone(),
+            template_arguments: r.cpp_template_arguments.clone(),
+          };
+          self.add_skipped_type(class_type.to_cpp_pseudo_code(),
+                                format!("Failed to generate Rust name: {}", err));
+        }
+        break;
+      }
+      any_success = false;
+      let mut unnamed_items_new = Vec::new();
+      for mut r in unnamed_items {
+        match template_final_name(&result, &r) {
+          Ok(name) => {
+            r.rust_name = name.clone();
+            if let RustTypeWrapperKind::Struct { ref mut size_const_name, .. } = r.kind {
+              let class_type = CppTypeClassBase {
+                name: r.cpp_name.clone(),
+                template_arguments: r.cpp_template_arguments.clone(),
+              };
+              match self
+                      .input_data
+                      .cpp_data
+                      .class_type_allocation_place(&class_type) {
+                Err(err) => {
+                  self.add_skipped_type(class_type.to_cpp_pseudo_code(),
+                                        format!("Can't process type: {}", err));
+                  continue;
+                }
+                Ok(place) => {
+                  *size_const_name = match place {
+                    CppTypeAllocationPlace::Stack => Some(self::size_const_name(&name)),
+                    CppTypeAllocationPlace::Heap => None,
+                  };
+                }
+              }
+            } else {
+              unreachable!();
+            }
+            result.push(r);
+            any_success = true;
+          }
+          Err(_) => unnamed_items_new.push(r),
+        }
+
+      }
+      unnamed_items = unnamed_items_new;
+    }
+    for header in &self.input_data.cpp_ffi_headers {
+      for qt_slot_wrapper in &header.qt_slot_wrappers {
+        let incomplete_rust_name = self
+          .calculate_rust_name(&format!("raw_slot"),
+                               &header.include_file_base_name,
+                               false,
+                               None)?;
+        let arg_names = qt_slot_wrapper
+          .arguments
+          .iter()
+          .map_if_ok(|x| -> Result<_> {
+            let rust_type = complete_type(&self.unindexed_types(&result),
+                                          x,
+                                          &CppFfiArgumentMeaning::Argument(0),
+                                          false,
+                                          &ReturnValueAllocationPlace::NotApplicable)?;
+            rust_type.rust_api_type.caption(&incomplete_rust_name)
+          })?;
+        let return_type = complete_type(&self.unindexed_types(&result),
+                                        &qt_slot_wrapper.return_type,
+                                        &CppFfiArgumentMeaning::ReturnValue,
+                                        false,
+                                        &ReturnValueAllocationPlace::NotApplicable)?;
+        let mut args_text = if arg_names.is_empty() {
+          "no_args".to_string()
+        } else {
+          arg_names.join("_")
+        };
+        if return_type.rust_api_type != RustType::EmptyTuple {
+          args_text = format!("{}_returns_{}",
+                              args_text,
+                              return_type
+                                .rust_api_type
+                                .caption(&incomplete_rust_name)?);
+        }
+        let rust_type_info = RustProcessedTypeInfo {
+          cpp_name: qt_slot_wrapper.class_name.clone(),
+          cpp_template_arguments: None,
+          cpp_doc: None, // TODO: do we need doc for this?
+          rust_name: self
+            .calculate_rust_name(&format!("raw_slot_{}", args_text),
+                                 &header.include_file_base_name,
+                                 false,
+                                 None)?,
+          is_public: true,
+          kind: RustTypeWrapperKind::Struct {
+            size_const_name: None,
+            is_deletable: true,
+            slot_wrapper: Some(RustQtSlotWrapper {
+                                 arguments: qt_slot_wrapper
+                                   .arguments
+                                   .iter()
+                                   .map_if_ok(|t| -> Result<_> {
+              let mut t = complete_type(&self.unindexed_types(&result),
+                                        t,
+                                        &CppFfiArgumentMeaning::Argument(0),
+                                        false,
+                                        &ReturnValueAllocationPlace::NotApplicable)?;
+              t.rust_api_type = t.rust_api_type.with_lifetime("static".to_string());
+              Ok(t)
+            })?,
+                                 return_type: return_type,
+                                 receiver_id: qt_slot_wrapper.receiver_id.clone(),
+                                 public_type_name: format!("slot_{}", args_text).to_class_case(),
+                                 callback_name: format!("slot_{}_callback", args_text)
+                                   .to_snake_case(),
+                               }),
+          },
+        };
+        result.push(rust_type_info);
+      }
+    }
+    Ok(result)
+  }
+
+  /// Returns name of the Rust module used for C++ anonymous namespaces.
+  /// `anon` is used unless a named C++ namespace or type would produce
+  /// the same module name.
+  fn anonymous_namespace_module_name(&self) -> String {
+    let cpp_data = self.input_data.cpp_data;
+    let is_used = |candidate: &str| {
+      cpp_data
+        .current
+        .parser
+        .types
+        .iter()
+        .map(|t| &t.name)
+        .chain(cpp_data.current.parser.methods.iter().map(|m| &m.name))
+        .any(|name| {
+               name
+                 .split("::")
+                 .any(|part| {
+                        remove_qt_prefix_and_convert_case(part,
+                                                          Case::Snake,
+                                                          self.input_data.remove_qt_prefix,
+                                                          self.input_data.settings.acronyms()) ==
+                        candidate
+                      })
+             })
+    };
+    let mut candidate = "anon".to_string();
+    if !is_used(&candidate) {
+      return candidate;
+    }
+    candidate = "anonymous_namespace".to_string();
+    let mut index = 1;
+    while is_used(&candidate) {
+      candidate = format!("anonymous_namespace_{}", index);
+      index += 1;
+    }
+    candidate
+  }
+
+  /// Returns an error if C++ types or free functions declared in different
+  /// namespaces get the same Rust name because one of them is declared
+  /// in a namespace flattened with `Config::add_flattened_namespace`.
+  fn check_flattened_namespace_collisions(&self) -> Result<()> {
+    let flattened_namespaces = self.input_data.settings.flattened_namespaces();
+    if flattened_namespaces.is_empty() {
+      return Ok(());
+    }
+    let namespace = |name: &str| name.rsplitn(2, "::").nth(1).unwrap_or("").to_string();
+    let is_flattened = |name: &str| {
+      flattened_namespaces
+        .iter()
+        .any(|ns| name.starts_with(&format!("{}::", ns)))
+    };
+    let parser = &self.input_data.cpp_data.current.parser;
+    let types = parser
+      .types
+      .iter()
+      .filter(|t| match t.kind {
+                CppTypeKind::Class { ref template_arguments, .. } => template_arguments.is_none(),
+                CppTypeKind::Enum { .. } => true,
+              })
+      .map(|t| (&t.name, &t.include_file, false));
+    let functions = parser
+      .methods
+      .iter()
+      .filter(|m| m.class_membership.is_none() && m.operator.is_none())
+      .map(|m| (&m.name, &m.include_file, true));
+    let mut names: HashMap<RustName, &String> = HashMap::new();
+    for (cpp_name, include_file, is_function) in types.chain(functions) {
+      let rust_name = self
+        .calculate_rust_name(cpp_name, include_file, is_function, None)?;
+      if let Some(other_cpp_name) = names.get(&rust_name) {
+        if namespace(other_cpp_name) != namespace(cpp_name) &&
+           (is_flattened(other_cpp_name) || is_flattened(cpp_name)) {
+          return Err(format!("{} and {} have the same Rust name {} because of \
+                              a flattened namespace",
+                             other_cpp_name,
+                             cpp_name,
+                             rust_name.full_name(None))
+                         .into());
+        }
+      }
+      names.entry(rust_name).or_insert(cpp_name);
+    }
+    Ok(())
+  }
+
+  /// Generates `RustName` for specified function or type name,
+  /// including crate name and modules list.
+  fn calculate_rust_name(&self,
+                         name: &str,
+                         include_file: &str,
+                         is_function: bool,
+                         operator: Option<&CppOperator>)
+                         -> Result<RustName> {
+    let mut split_parts: Vec<_> = name.split("::").collect();
+    let original_last_part = split_parts
+      .pop()
+      .chain_err(|| "split_parts can't be empty")?
+      .to_string();
+    let last_part = if let Some(operator) = operator {
+      operator_rust_name(operator)?
+    } else {
+      remove_qt_prefix_and_convert_case(&original_last_part,
+                                        if is_function {
+                                          Case::Snake
+                                        } else {
+                                          Case::Class
+                                        },
+                                        self.input_data.remove_qt_prefix,
+                                        self.input_data.settings.acronyms())
+    };
+
+    let module_name =
+      self
+        .top_module_names
+        .get(include_file)
+        .chain_err(|| format!("no top level module generated for header: {}", include_file))?;
+
+    let mut parts = module_name.parts.clone();
+    if include_file == "slots" {
+      parts.push("raw".to_string());
+    }
+    //    parts.push(config.crate_name.clone());
+    //    parts.push(include_file_to_module_name(include_file, config.remove_qt_prefix));
+    let mut namespace = String::new();
+    for part in split_parts {
+      let part = part.to_string();
+      if !namespace.is_empty() {
+        namespace.push_str("::");
+      }
+      namespace.push_str(&part);
+      if self.input_data.filtered_namespaces.contains(&part) ||
+         self.input_data.settings.flattened_namespaces().contains(&namespace) {
+        continue;
+      }
+      if is_anonymous_namespace(&part) {
+        parts.push(self.anonymous_namespace_module_name());
+        continue;
+      }
+      parts.push(remove_qt_prefix_and_convert_case(&part,
+                                                   Case::Snake,
+                                                   self.input_data.remove_qt_prefix,
+                                                   self.input_data.settings.acronyms()));
+    }
+
+    if parts.len() > 2 && parts[1] == parts[2] {
+      // special case
+      parts.remove(2);
+    }
+    parts.push(last_part);
+    RustName::new(parts)
+  }
+}
+
+
+// ---------------------------------
+#[test]
+fn remove_qt_prefix_and_convert_case_test() {
+  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Class, false, &[]),
+             "OneTwo");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Snake, false, &[]),
+             "one_two");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Class, true, &[]),
+             "OneTwo");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Snake, true, &[]),
+             "one_two");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(),
+                                               Case::Class,
+                                               false,
+                                               &[]),
+             "QDirIterator");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(),
+                                               Case::Snake,
+                                               false,
+                                               &[]),
+             "q_dir_iterator");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Class, true, &[]),
+             "DirIterator");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Snake, true, &[]),
+             "dir_iterator");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Class, false, &[]),
+             "Qt3DWindow");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Snake, false, &[]),
+             "qt_3d_window");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Class, true, &[]),
+             "Qt3DWindow");
+  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Snake, true, &[]),
+             "qt_3d_window");
+  let acronyms = default_acronyms();
+  assert_eq!(remove_qt_prefix_and_convert_case("QURLInfo", Case::Snake, true, &acronyms),
+             "url_info");
+  assert_eq!(remove_qt_prefix_and_convert_case("QURLInfo", Case::Class, true, &acronyms),
+             "URLInfo");
+}
+
+/// Returns a `Config` with settings used by generator tests.
+#[cfg(test)]
+fn test_config() -> ::config::Config {
+  let mut config = ::config::Config::new("output",
+                                         "cache",
+                                         ::config::CrateProperties::new("crate1", "0.0.0"));
+  config.set_target_family(target::Family::Unix);
+  config
+}
+
+/// Returns input data for generator tests using settings of `config`.
+#[cfg(test)]
+fn test_input_data<'a>(cpp_data: &'a CppDataWithDeps<'a>,
+                       config: &'a ::config::Config)
+                       -> RustGeneratorInputData<'a> {
+  RustGeneratorInputData {
+    cpp_ffi_headers: Vec::new(),
+    cpp_data: cpp_data,
+    dependency_types: Vec::new(),
+    crate_name: "crate1".to_string(),
+    remove_qt_prefix: true,
+    filtered_namespaces: Vec::new(),
+    settings: config.rust_generator_settings(),
+  }
+}
+
+/// Creates a generator for tests. `processed_types` are indexed
+/// as if they were produced by `RustGeneratorInputData::run`.
+#[cfg(test)]
+fn test_generator<'a>(input_data: RustGeneratorInputData<'a>,
+                      processed_types: Vec<RustProcessedTypeInfo>)
+                      -> RustGenerator<'a> {
+  RustGenerator {
+    top_module_names: HashMap::new(),
+    type_index: RustTypeIndex::new(&processed_types, &input_data.dependency_types),
+    processed_types: processed_types,
+    skipped: Mutex::new(Vec::new()),
+    input_data: input_data,
+  }
+}
+
+#[cfg(test)]
+fn calculate_rust_name_test_part(name: &'static str,
+                                 include_file: &'static str,
+                                 is_function: bool,
+                                 expected: &[&'static str]) {
+  let header = ::cpp_ffi_data::CppFfiHeaderData {
+    include_file_base_name: include_file.to_string(),
+    methods: Vec::new(),
+    qt_slot_wrappers: Vec::new(),
+  };
+  let cpp_data: CppDataWithDeps = Default::default();
+  let config = test_config();
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: vec![header],
+    crate_name: "qt_core".to_string(),
+    ..test_input_data(&cpp_data, &config)
+  };
+  let mut generator = test_generator(input_data, Vec::new());
+  generator.top_module_names = generator.calc_top_module_names().unwrap();
+
+  assert_eq!(generator
+               .calculate_rust_name(&name.to_string(),
+                                    &include_file.to_string(),
+                                    is_function,
+                                    None)
+               .unwrap(),
+             RustName::new(expected.into_iter().map(|x| x.to_string()).collect()).unwrap());
+}
+
+#[test]
+fn calculate_rust_name_test() {
+  calculate_rust_name_test_part("myFunc1",
+                                "QtGlobal",
+                                true,
+                                &["qt_core", "global", "my_func1"]);
+  calculate_rust_name_test_part("QPointF",
+                                "QPointF",
+                                false,
+                                &["qt_core", "point_f", "PointF"]);
+  calculate_rust_name_test_part("QStringList::Iterator",
+                                "QStringList",
+                                false,
+                                &["qt_core", "string_list", "Iterator"]);
+  calculate_rust_name_test_part("QStringList::Iterator",
+                                "QString",
+                                false,
+                                &["qt_core", "string", "string_list", "Iterator"]);
+  calculate_rust_name_test_part("ns::func1",
+                                "QRect",
+                                true,
+                                &["qt_core", "rect", "ns", "func1"]);
+}
+
+#[test]
+fn ffi_type_specific_numeric_test() {
+  let box_type = default_box_type();
+  let types = RustTypes {
+    processed_types: &[],
+    dependency_types: &[],
+    index: None,
+    box_type: &box_type,
+  };
+  let numeric_type = |name: &str, bits: usize, is_signed: bool| {
+    CppType {
+      base: CppTypeBase::SpecificNumeric(CppSpecificNumericType {
+                                           name: name.to_string(),
+                                           bits: bits,
+                                           kind: CppSpecificNumericTypeKind::Integer {
+                                             is_signed: is_signed,
+                                           },
+                                         }),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let primitive = |name: &str| {
+    RustType::Common {
+      base: RustName::new(vec![name.to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  assert_eq!(ffi_type(&types, &numeric_type("qint32", 32, true)).unwrap(),
+             primitive("i32"));
+  assert_eq!(ffi_type(&types, &numeric_type("quint64", 64, false)).unwrap(),
+             primitive("u64"));
+}
+
+#[test]
+fn complete_type_const_heap_return_test() {
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: None,
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let box_type = default_box_type();
+  let types = RustTypes {
+    processed_types: &processed_types,
+    dependency_types: &[],
+    index: None,
+    box_type: &box_type,
+  };
+  let class_type = |indirection: CppTypeIndirection| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "Class1".to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: indirection,
+      is_const: true,
+      is_const2: false,
+    }
+  };
+  // a const pointer to a new heap-allocated object
+  let ffi_type = CppFfiType {
+    original_type: class_type(CppTypeIndirection::None),
+    ffi_type: class_type(CppTypeIndirection::Ptr),
+    conversion: CppIndirectionChange::ValueToPointer,
+  };
+  let r = complete_type(&types,
+                        &ffi_type,
+                        &CppFfiArgumentMeaning::ReturnValue,
+                        false,
+                        &ReturnValueAllocationPlace::Heap)
+    .unwrap();
+  assert_eq!(r.rust_api_to_c_conversion, RustToCTypeConversion::CppBoxToPtr);
+  assert_eq!(r.rust_api_type,
+             RustType::Common {
+               base: RustName::new(vec!["cpp_utils".to_string(), "CppBox".to_string()]).unwrap(),
+               generic_arguments: Some(vec![RustType::Common {
+                                              base: RustName::new(vec!["crate1".to_string(),
+                                                                       "Class1".to_string()])
+                                                .unwrap(),
+                                              generic_arguments: None,
+                                              indirection: RustTypeIndirection::None,
+                                              is_const: false,
+                                              is_const2: false,
+                                            }]),
+               indirection: RustTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+             });
+}
+
+#[test]
+fn complete_type_custom_box_test() {
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: None,
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let box_type = RustName::new(vec!["my_utils".to_string(), "MyBox".to_string()]).unwrap();
+  assert!(check_box_type(&box_type).is_ok());
+  assert!(check_box_type(&RustName::new(vec!["MyBox".to_string()]).unwrap()).is_err());
+  assert!(check_box_type(&RustName::new(vec!["my_utils".to_string(),
+                                             "MyBox<T>".to_string()])
+                            .unwrap())
+            .is_err());
+  let types = RustTypes {
+    processed_types: &processed_types,
+    dependency_types: &[],
+    index: None,
+    box_type: &box_type,
+  };
+  let class_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let ffi_type = class_type
+    .to_cpp_ffi_type(CppTypeRole::ReturnType)
+    .unwrap();
+  let r = complete_type(&types,
+                        &ffi_type,
+                        &CppFfiArgumentMeaning::ReturnValue,
+                        false,
+                        &ReturnValueAllocationPlace::Heap)
+    .unwrap();
+  assert_eq!(r.rust_api_to_c_conversion, RustToCTypeConversion::CppBoxToPtr);
+  match r.rust_api_type {
+    RustType::Common {
+      ref base,
+      ref generic_arguments,
+      ..
+    } => {
+      assert_eq!(base, &box_type);
+      assert_eq!(generic_arguments.as_ref().map(|args| args.len()), Some(1));
+    }
+    _ => panic!("common type expected"),
+  }
+}
+
+#[test]
+fn complete_type_rvalue_reference_test() {
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: None,
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let box_type = default_box_type();
+  let types = RustTypes {
+    processed_types: &processed_types,
+    dependency_types: &[],
+    index: None,
+    box_type: &box_type,
+  };
+  let cpp_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::RValueRef,
+    is_const: false,
+    is_const2: false,
+  };
+  assert!(cpp_type.to_cpp_ffi_type(CppTypeRole::ReturnType).is_err());
+  let ffi_type = cpp_type
+    .to_cpp_ffi_type(CppTypeRole::NotReturnType)
+    .unwrap();
+  assert_eq!(ffi_type.conversion,
+             CppIndirectionChange::RValueReferenceToPointer);
+  let r = complete_type(&types,
+                        &ffi_type,
+                        &CppFfiArgumentMeaning::Argument(0),
+                        false,
+                        &ReturnValueAllocationPlace::NotApplicable)
+    .unwrap();
+  assert_eq!(r.rust_api_to_c_conversion, RustToCTypeConversion::CppBoxToPtr);
+  assert_eq!(r.rust_api_type,
+             RustType::Common {
+               base: RustName::new(vec!["cpp_utils".to_string(), "CppBox".to_string()]).unwrap(),
+               generic_arguments: Some(vec![RustType::Common {
+                                              base: RustName::new(vec!["crate1".to_string(),
+                                                                       "Class1".to_string()])
+                                                .unwrap(),
+                                              generic_arguments: None,
+                                              indirection: RustTypeIndirection::None,
+                                              is_const: false,
+                                              is_const2: false,
+                                            }]),
+               indirection: RustTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+             });
+}
+
+#[test]
+fn complete_type_optional_test() {
+  let processed_types = Vec::new();
+  let box_type = default_box_type();
+  let types = RustTypes {
+    processed_types: &processed_types,
+    dependency_types: &[],
+    index: None,
+    box_type: &box_type,
+  };
+  let optional_type = |element_base: CppTypeBase| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "std::optional".to_string(),
+                                 template_arguments: Some(vec![CppType {
+                                                                 base: element_base,
+                                                                 indirection:
+                                                                   CppTypeIndirection::None,
+                                                                 is_const: false,
+                                                                 is_const2: false,
+                                                               }]),
+                               }),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let cpp_type = optional_type(CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int));
+  assert!(!cpp_type.needs_allocation_place_variants());
+  let c_int = RustType::Common {
+    base: RustName::new(vec!["libc".to_string(), "c_int".to_string()]).unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let option_c_int = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "option".to_string(), "Option".to_string()])
+      .unwrap(),
+    generic_arguments: Some(vec![c_int]),
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  for role in &[CppTypeRole::NotReturnType, CppTypeRole::ReturnType] {
+    let ffi_type = cpp_type.to_cpp_ffi_type(role.clone()).unwrap();
+    assert_eq!(ffi_type.conversion, CppIndirectionChange::OptionalToPointer);
+    assert_eq!(ffi_type.ffi_type.indirection, CppTypeIndirection::Ptr);
+    assert_eq!(ffi_type.ffi_type.is_const, role == &CppTypeRole::NotReturnType);
+    let meaning = if role == &CppTypeRole::ReturnType {
+      CppFfiArgumentMeaning::ReturnValue
+    } else {
+      CppFfiArgumentMeaning::Argument(0)
+    };
+    let r = complete_type(&types,
+                          &ffi_type,
+                          &meaning,
+                          false,
+                          &ReturnValueAllocationPlace::NotApplicable)
+      .unwrap();
+    assert_eq!(r.rust_api_to_c_conversion, RustToCTypeConversion::OptionToPtr);
+    assert_eq!(r.rust_api_type, option_c_int);
+  }
+  // class element types are not supported
+  let class_optional = optional_type(CppTypeBase::Class(CppTypeClassBase {
+                                                          name: "Class1".to_string(),
+                                                          template_arguments: None,
+                                                        }));
+  assert!(class_optional
+            .to_cpp_ffi_type(CppTypeRole::NotReturnType)
+            .is_err());
+}
+
+#[test]
+fn rust_types_index_test() {
+  let type_info = |cpp_name: &str,
+                   template_arg: Option<CppBuiltInNumericType>,
+                   rust_name: &str| {
+    RustProcessedTypeInfo {
+      cpp_name: cpp_name.to_string(),
+      cpp_doc: None,
+      cpp_template_arguments: template_arg.map(|arg| {
+        vec![CppType {
+               base: CppTypeBase::BuiltInNumeric(arg),
+               indirection: CppTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+             }]
+      }),
+      kind: RustTypeWrapperKind::Enum {
+        values: Vec::new(),
+        is_flaggable: false,
+      },
+      rust_name: RustName::new(vec!["crate1".to_string(), rust_name.to_string()]).unwrap(),
+      is_public: true,
+    }
+  };
+  let processed_types = vec![type_info("A", None, "A1"),
+                             type_info("V", Some(CppBuiltInNumericType::Int), "VInt"),
+                             type_info("V", Some(CppBuiltInNumericType::Double), "VDouble"),
+                             type_info("A", None, "A2")];
+  let dep1 = vec![type_info("B", None, "B1"), type_info("A", None, "A3")];
+  let dep2 = vec![type_info("B", None, "B2"),
+                  type_info("V", Some(CppBuiltInNumericType::Bool), "VBool")];
+  let dependency_types: Vec<&[RustProcessedTypeInfo]> = vec![&dep1, &dep2];
+  let index = RustTypeIndex::new(&processed_types, &dependency_types);
+  let box_type = default_box_type();
+  let indexed = RustTypes {
+    processed_types: &processed_types,
+    dependency_types: &dependency_types,
+    index: Some(&index),
+    box_type: &box_type,
+  };
+  let unindexed = RustTypes {
+    processed_types: &processed_types,
+    dependency_types: &dependency_types,
+    index: None,
+    box_type: &box_type,
+  };
+  let rust_name = |info: Option<&RustProcessedTypeInfo>| {
+    info.map(|x| x.rust_name.last_name().unwrap().clone())
+  };
+  for name in &["A", "B", "V", "C"] {
+    assert_eq!(rust_name(indexed.find_by_cpp_name(name)),
+               rust_name(unindexed.find_by_cpp_name(name)));
+  }
+  assert_eq!(rust_name(indexed.find_by_cpp_name("A")), Some("A1".to_string()));
+  assert_eq!(rust_name(indexed.find_by_cpp_name("B")), Some("B1".to_string()));
+  assert_eq!(rust_name(indexed.find_by_cpp_name("C")), None);
+  let args = [None,
+              Some(CppBuiltInNumericType::Int),
+              Some(CppBuiltInNumericType::Double),
+              Some(CppBuiltInNumericType::Bool),
+              Some(CppBuiltInNumericType::Char)];
+  for name in &["A", "B", "V"] {
+    for arg in &args {
+      let info = type_info(*name, arg.clone(), "X");
+      let class_base = CppTypeClassBase {
+        name: info.cpp_name,
+        template_arguments: info.cpp_template_arguments,
+      };
+      assert_eq!(rust_name(indexed.find_class(&class_base)),
+                 rust_name(unindexed.find_class(&class_base)));
+    }
+  }
+  let class_base = CppTypeClassBase {
+    name: "V".to_string(),
+    template_arguments: type_info("V", Some(CppBuiltInNumericType::Bool), "X")
+      .cpp_template_arguments,
+  };
+  assert_eq!(rust_name(indexed.find_class(&class_base)),
+             Some("VBool".to_string()));
+}
+
+#[cfg(test)]
+fn module_name_hook_test_generator<'a>(config: &'a ::config::Config,
+                                       cpp_data: &'a CppDataWithDeps<'a>)
+                                       -> RustGenerator<'a> {
+  let headers = ["QtGlobal", "QPoint"]
+    .iter()
+    .map(|name| {
+           ::cpp_ffi_data::CppFfiHeaderData {
+             include_file_base_name: name.to_string(),
+             methods: Vec::new(),
+             qt_slot_wrappers: Vec::new(),
+           }
+         })
+    .collect();
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: headers,
+    crate_name: "qt_core".to_string(),
+    remove_qt_prefix: false,
+    ..test_input_data(cpp_data, config)
+  };
+  test_generator(input_data, Vec::new())
+}
+
+#[test]
+fn module_name_hook_test() {
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.set_module_name_hook(|header| if header == "QtGlobal" {
+                                Some("globals".to_string())
+                              } else {
+                                None
+                              });
+  let mut generator = module_name_hook_test_generator(&config, &cpp_data);
+  generator.top_module_names = generator.calc_top_module_names().unwrap();
+  assert_eq!(generator
+               .calculate_rust_name("QFlags", "QtGlobal", false, None)
+               .unwrap(),
+             RustName::new(vec!["qt_core".to_string(),
+                                "globals".to_string(),
+                                "QFlags".to_string()])
+                 .unwrap());
+  assert_eq!(generator
+               .calculate_rust_name("QPoint", "QPoint", false, None)
+               .unwrap(),
+             RustName::new(vec!["qt_core".to_string(),
+                                "q_point".to_string(),
+                                "QPoint".to_string()])
+                 .unwrap());
+}
+
+#[test]
+fn module_name_hook_conflict_test() {
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.set_module_name_hook(|header| if header == "QtGlobal" {
+                                Some("q_point".to_string())
+                              } else {
+                                None
+                              });
+  let generator = module_name_hook_test_generator(&config, &cpp_data);
+  assert!(generator.calc_top_module_names().is_err());
+}
+
+#[test]
+fn calculate_rust_name_anonymous_namespace_test() {
+  calculate_rust_name_test_part("(anonymous namespace)::helper",
+                                "QRect",
+                                true,
+                                &["qt_core", "rect", "anon", "helper"]);
+  calculate_rust_name_test_part("ns::(anonymous namespace)::Helper",
+                                "QRect",
+                                false,
+                                &["qt_core", "rect", "ns", "anon", "Helper"]);
+}
+
+#[test]
+fn calculate_rust_name_anonymous_namespace_conflict_test() {
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(::cpp_data::CppTypeData {
+            name: "anon::Helper".to_string(),
+            include_file: "QRect".to_string(),
+            origin_location: ::cpp_data::CppOriginLocation {
+              include_file_path: "QRect".to_string(),
+              line: 1,
+              column: 1,
+            },
+            kind: CppTypeKind::Enum { values: Vec::new() },
+            doc: None,
+          });
+  let header = ::cpp_ffi_data::CppFfiHeaderData {
+    include_file_base_name: "QRect".to_string(),
+    methods: Vec::new(),
+    qt_slot_wrappers: Vec::new(),
+  };
+  let config = test_config();
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: vec![header],
+    crate_name: "qt_core".to_string(),
+    ..test_input_data(&cpp_data, &config)
+  };
+  let mut generator = test_generator(input_data, Vec::new());
+  generator.top_module_names = generator.calc_top_module_names().unwrap();
+  let name = |cpp_name: &str| {
+    generator
+      .calculate_rust_name(cpp_name, "QRect", false, None)
+      .unwrap()
+      .full_name(None)
+  };
+  assert_eq!(name("anon::Helper"), "::qt_core::rect::anon::Helper");
+  assert_eq!(name("(anonymous namespace)::Helper"),
+             "::qt_core::rect::anonymous_namespace::Helper");
+}
+
+#[test]
+fn calculate_rust_name_flattened_namespace_test() {
+  let type_data = |name: &str| {
+    ::cpp_data::CppTypeData {
+      name: name.to_string(),
+      include_file: "QRect".to_string(),
+      origin_location: ::cpp_data::CppOriginLocation {
+        include_file_path: "QRect".to_string(),
+        line: 1,
+        column: 1,
+      },
+      kind: CppTypeKind::Enum { values: Vec::new() },
+      doc: None,
+    }
+  };
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(type_data("ns::Bar"));
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(type_data("ns::detail::Foo"));
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(type_data("other::detail::Foo"));
+  let mut colliding_cpp_data: CppDataWithDeps = Default::default();
+  colliding_cpp_data.current.parser.types = cpp_data.current.parser.types.clone();
+  colliding_cpp_data
+    .current
+    .parser
+    .types
+    .push(type_data("ns::Foo"));
+
+  let mut config = test_config();
+  config.add_flattened_namespace("ns::detail");
+  let mut input_data = test_input_data(&cpp_data, &config);
+  input_data.cpp_ffi_headers = vec![::cpp_ffi_data::CppFfiHeaderData {
+                                      include_file_base_name: "QRect".to_string(),
+                                      methods: Vec::new(),
+                                      qt_slot_wrappers: Vec::new(),
+                                    }];
+  let mut generator = test_generator(input_data, Vec::new());
+  generator.top_module_names = generator.calc_top_module_names().unwrap();
+  {
+    let name = |cpp_name: &str| {
+      generator
+        .calculate_rust_name(cpp_name, "QRect", false, None)
+        .unwrap()
+        .full_name(None)
+    };
+    assert_eq!(name("ns::detail::Foo"), "::crate1::rect::ns::Foo");
+    assert_eq!(name("ns::Bar"), "::crate1::rect::ns::Bar");
+    assert_eq!(name("other::detail::Foo"), "::crate1::rect::other::detail::Foo");
+  }
+  assert!(generator.check_flattened_namespace_collisions().is_ok());
+
+  // `ns::Foo` and `ns::detail::Foo` can't be placed in the same module
+  generator.input_data.cpp_data = &colliding_cpp_data;
+  assert!(generator.check_flattened_namespace_collisions().is_err());
+}
+
+#[test]
+fn template_instantiation_allocation_place_test() {
+  use cpp_data::{CppTypeData, CppOriginLocation, TemplateArgumentsDeclaration,
+                 CppTemplateInstantiations, CppTemplateInstantiation, template_instantiation_key};
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(CppTypeData {
+            name: "QList".to_string(),
+            include_file: "QList".to_string(),
+            origin_location: CppOriginLocation {
+              include_file_path: "QList".to_string(),
+              line: 1,
+              column: 1,
+            },
+            kind: CppTypeKind::Class {
+              bases: Vec::new(),
+              fields: Vec::new(),
+              static_fields: Vec::new(),
+              template_arguments: Some(TemplateArgumentsDeclaration {
+                                         nested_level: 0,
+                                         names: vec!["T".to_string()],
+                                       }),
+              using_directives: Vec::new(),
+              alignment: None,
+            },
+            doc: None,
+          });
+  let numeric = |t| {
+    CppType {
+      is_const: false,
+      is_const2: false,
+      indirection: CppTypeIndirection::None,
+      base: CppTypeBase::BuiltInNumeric(t),
+    }
+  };
+  cpp_data
+    .current
+    .processed
+    .template_instantiations
+    .push(CppTemplateInstantiations {
+            class_name: "QList".to_string(),
+            instantiations: vec![CppTemplateInstantiation {
+                                   template_arguments: vec![numeric(CppBuiltInNumericType::Int)],
+                                 },
+                                 CppTemplateInstantiation {
+                                   template_arguments:
+                                     vec![numeric(CppBuiltInNumericType::Double)],
+                                 }],
+          });
+  {
+    let places = &mut cpp_data.current.processed.type_allocation_places;
+    places.insert("QList".to_string(), CppTypeAllocationPlace::Heap);
+    places.insert(template_instantiation_key("QList< int >"),
+                  CppTypeAllocationPlace::Stack);
+  }
+  let header = ::cpp_ffi_data::CppFfiHeaderData {
+    include_file_base_name: "QList".to_string(),
+    methods: Vec::new(),
+    qt_slot_wrappers: Vec::new(),
+  };
+  let config = test_config();
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: vec![header],
+    crate_name: "qt_core".to_string(),
+    ..test_input_data(&cpp_data, &config)
+  };
+  let mut generator = test_generator(input_data, Vec::new());
+  generator.top_module_names = generator.calc_top_module_names().unwrap();
+  let types = generator.calc_processed_types().unwrap();
+  let has_size_const = |arg: CppBuiltInNumericType| {
+    let type1 = types
+      .iter()
+      .find(|t| t.cpp_template_arguments == Some(vec![numeric(arg.clone())]))
+      .unwrap();
+    if let RustTypeWrapperKind::Struct { ref size_const_name, .. } = type1.kind {
+      size_const_name.is_some()
+    } else {
+      panic!("struct expected");
+    }
+  };
+  assert!(has_size_const(CppBuiltInNumericType::Int));
+  assert!(!has_size_const(CppBuiltInNumericType::Double));
+}
+
+#[test]
+fn type_manifest_test() {
+  use cpp_data::{CppTypeData, CppOriginLocation};
+  let type_data = |name: &str| {
+    CppTypeData {
+      name: name.to_string(),
+      include_file: name.to_string(),
+      origin_location: CppOriginLocation {
+        include_file_path: name.to_string(),
+        line: 1,
+        column: 1,
+      },
+      kind: CppTypeKind::Class {
+        bases: Vec::new(),
+        fields: Vec::new(),
+        static_fields: Vec::new(),
+        template_arguments: None,
+        using_directives: Vec::new(),
+        alignment: None,
+      },
+      doc: None,
+    }
+  };
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  let mut template_data = type_data("Template1");
+  if let CppTypeKind::Class { ref mut template_arguments, .. } = template_data.kind {
+    *template_arguments = Some(::cpp_data::TemplateArgumentsDeclaration {
+                                 nested_level: 0,
+                                 names: vec!["T".to_string()],
+                               });
+  }
+  cpp_data.current.parser.types = vec![type_data("Class2"), type_data("Class1"), template_data];
+  // `Class2` has no allocation place
+  cpp_data
+    .current
+    .processed
+    .type_allocation_places
+    .insert("Class1".to_string(), CppTypeAllocationPlace::Heap);
+  let header = |name: &str| {
+    ::cpp_ffi_data::CppFfiHeaderData {
+      include_file_base_name: name.to_string(),
+      methods: Vec::new(),
+      qt_slot_wrappers: Vec::new(),
+    }
+  };
+  let config = test_config();
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: vec![header("Class1"), header("Class2")],
+    ..test_input_data(&cpp_data, &config)
+  };
+  let mut generator = test_generator(input_data, Vec::new());
+  generator.top_module_names = generator.calc_top_module_names().unwrap();
+  generator.processed_types = generator.calc_processed_types().unwrap();
+  let manifest = generator.type_manifest();
+  assert_eq!(manifest.len(), 3);
+  assert_eq!(manifest[0].cpp_type, "Class1");
+  assert_eq!(manifest[0].status,
+             TypeManifestStatus::Generated(generator.processed_types[0].rust_name.clone()));
+  assert_eq!(manifest[1].cpp_type, "Class2");
+  match manifest[1].status {
+    TypeManifestStatus::Skipped(ref reason) => {
+      assert!(reason.contains("no type allocation place information for Class2"));
+    }
+    TypeManifestStatus::Generated(..) => panic!("Class2 must be skipped"),
+  }
+  assert_eq!(manifest[2].cpp_type, "Template1");
+  assert_eq!(manifest[2].status,
+             TypeManifestStatus::Skipped("template class has no instantiations".to_string()));
+}
+
+#[cfg(test)]
+fn overload_suffixes_test_run(suffixes: &[&str]) -> Result<Vec<Option<String>>> {
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.set_overload_suffixes("foo", suffixes.iter().cloned());
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  // methods without arguments can't be overloaded with each other
+  let method = |c_name: &str| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "foo".to_string();
+    RustSingleMethod {
+      scope: RustMethodScope::Free,
+      is_unsafe: false,
+      name: RustName::new(vec!["crate1".to_string(), "foo".to_string()]).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: Vec::new(),
+        cpp_method: CppAndFfiMethod {
+          cpp_method: cpp_method,
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: Vec::new(),
+            return_type: CppFfiType::void(),
+          },
+          c_name: c_name.to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: CompleteType {
+          cpp_type: CppType::void(),
+          cpp_ffi_type: CppType::void(),
+          cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+          rust_ffi_type: RustType::EmptyTuple,
+          rust_api_type: RustType::EmptyTuple,
+          rust_api_to_c_conversion: RustToCTypeConversion::None,
+        },
+      },
+      doc: None,
+    }
+  };
+  let result = generator
+    .overload_functions(vec![method("crate1_foo"), method("crate1_foo1")])?;
+  Ok(result.into_iter().map(|(suffix, _)| suffix).collect())
+}
+
+#[test]
+fn overload_suffixes_test() {
+  assert_eq!(overload_suffixes_test_run(&["", "other"]).unwrap(),
+             vec![None, Some("other".to_string())]);
+  assert!(overload_suffixes_test_run(&["one"]).is_err());
+  assert!(overload_suffixes_test_run(&["one", "two", "three"]).is_err());
+  assert!(overload_suffixes_test_run(&["one", "one"]).is_err());
+}
+
+#[test]
+fn skipped_items_test() {
+  use cpp_method::CppMethodArgument;
+
+  let cpp_data = Default::default();
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  // the argument type is not known to the generator
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "foo".to_string();
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "arg1".to_string(),
+                              argument_type: CppType {
+                                base: CppTypeBase::Class(CppTypeClassBase {
+                                                           name: "Unknown".to_string(),
+                                                           template_arguments: None,
+                                                         }),
+                                indirection: CppTypeIndirection::Ptr,
+                                is_const: false,
+                                is_const2: false,
+                              },
+                              has_default_value: false,
+                            });
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::NotApplicable)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_name: "crate1_foo".to_string(),
+  };
+  let result = generator
+    .process_all_sibling_functions(vec![&method].into_iter(), &RustMethodScope::Free)
+    .unwrap();
+  assert!(result.methods.is_empty());
+  let skipped = generator.skipped.lock().unwrap();
+  assert_eq!(skipped.len(), 1);
+  assert_eq!(skipped[0].kind, SkippedItemKind::Method);
+  assert_eq!(skipped[0].cpp_item, method.short_text());
+  assert!(skipped[0]
+            .reason
+            .starts_with("failed to generate Rust function"));
+}
+
+#[test]
+fn out_parameter_test() {
+  use cpp_method::CppMethodArgument;
+
+  let mut top_module_names = HashMap::new();
+  top_module_names.insert("module1".to_string(),
+                          RustName::new(vec!["crate1".to_string(), "module1".to_string()])
+                            .unwrap());
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.add_out_parameter("parse", "outCode");
+  let generator = RustGenerator {
+    top_module_names: top_module_names,
+    ..test_generator(test_input_data(&cpp_data, &config), Vec::new())
+  };
+  // `bool parse(int* outCode)`
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "parse".to_string();
+  cpp_method.include_file = "module1".to_string();
+  cpp_method.return_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "outCode".to_string(),
+                              argument_type: CppType {
+                                base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                                indirection: CppTypeIndirection::Ptr,
+                                is_const: false,
+                                is_const2: false,
+                              },
+                              has_default_value: false,
+                            });
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::NotApplicable)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_name: "crate1_parse".to_string(),
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&method, &RustMethodScope::Free, false)
+    .unwrap();
+  // the out-parameter is no longer a raw pointer, so the method is safe
+  assert!(!rust_method.is_unsafe);
+  assert_eq!(rust_method.arguments.arguments.len(), 1);
+  let arg = &rust_method.arguments.arguments[0];
+  assert_eq!(arg.argument_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::OutValueToPtr);
+  let primitive = |name: Vec<&str>| {
+    RustType::Common {
+      base: RustName::new(name.into_iter().map(|x| x.to_string()).collect()).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  assert_eq!(arg.argument_type.rust_api_type, primitive(vec!["libc", "c_int"]));
+  assert_eq!(rust_method.arguments.return_type.rust_api_type,
+             RustType::Tuple(vec![primitive(vec!["bool"]), primitive(vec!["libc", "c_int"])]));
+
+  // `bool parse(int* outCode, bool strict)` can't be an overload of the other method,
+  // so both methods are skipped without failing the generation
+  let mut overload = method.clone();
+  overload
+    .cpp_method
+    .arguments
+    .push(CppMethodArgument {
+            name: "strict".to_string(),
+            argument_type: CppType {
+              base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+              indirection: CppTypeIndirection::None,
+              is_const: false,
+              is_const2: false,
+            },
+            has_default_value: false,
+          });
+  overload.c_signature = overload
+    .cpp_method
+    .c_signature(ReturnValueAllocationPlace::NotApplicable)
+    .unwrap();
+  overload.c_name = "crate1_parse1".to_string();
+  let result = generator
+    .process_all_sibling_functions(vec![&method, &overload].into_iter(), &RustMethodScope::Free)
+    .unwrap();
+  assert!(result.methods.is_empty());
+  let skipped = generator.skipped.lock().unwrap();
+  assert_eq!(skipped.len(), 2);
+  assert!(skipped
+            .iter()
+            .all(|item| item.reason == "method with out-parameters can't be overloaded"));
+}
+
+#[test]
+fn argument_rename_test() {
+  use cpp_method::CppMethodArgument;
+
+  let mut top_module_names = HashMap::new();
+  top_module_names.insert("module1".to_string(),
+                          RustName::new(vec!["crate1".to_string(), "module1".to_string()])
+                            .unwrap());
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.add_argument_rename("move", 1, "distance");
+  config.add_argument_rename("resize", 2, "height");
+  config.add_argument_rename("scale", 0, "b");
+  let generator = RustGenerator {
+    top_module_names: top_module_names,
+    ..test_generator(test_input_data(&cpp_data, &config), Vec::new())
+  };
+  // `void move(int a, int b)`, `void resize(int a, int b)` and `void scale(int a, int b)`
+  let method = |name: &str| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = name.to_string();
+    cpp_method.include_file = "module1".to_string();
+    for arg_name in &["a", "b"] {
+      cpp_method.arguments.push(CppMethodArgument {
+                                  name: arg_name.to_string(),
+                                  argument_type: CppType {
+                                    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                                    indirection: CppTypeIndirection::None,
+                                    is_const: false,
+                                    is_const2: false,
+                                  },
+                                  has_default_value: false,
+                                });
+    }
+    CppAndFfiMethod {
+      c_signature: cpp_method
+        .c_signature(ReturnValueAllocationPlace::NotApplicable)
+        .unwrap(),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: ReturnValueAllocationPlace::NotApplicable,
+      c_name: format!("crate1_{}", name),
+    }
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&method("move"), &RustMethodScope::Free, false)
+    .unwrap();
+  let names: Vec<_> = rust_method
+    .arguments
+    .arguments
+    .iter()
+    .map(|arg| arg.name.as_str())
+    .collect();
+  assert_eq!(names, vec!["a", "distance"]);
+  // the index is out of range
+  assert!(generator
+            .generate_rust_single_method(&method("resize"), &RustMethodScope::Free, false)
+            .is_err());
+  // the new name conflicts with the other argument
+  assert!(generator
+            .generate_rust_single_method(&method("scale"), &RustMethodScope::Free, false)
+            .is_err());
+
+  // `void move(int a)` variant of `void move(int a, int b = 0)` doesn't have
+  // the renamed argument, but the index is valid for the full signature
+  let mut omitted = method("move");
+  let all_arguments = omitted.cpp_method.arguments.clone();
+  omitted.cpp_method.arguments.pop();
+  omitted.c_signature = omitted
+    .cpp_method
+    .c_signature(ReturnValueAllocationPlace::NotApplicable)
+    .unwrap();
+  omitted.kind = CppFfiMethodKind::RealWithOmittedArguments {
+    arguments_before_omitting: Some(all_arguments),
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&omitted, &RustMethodScope::Free, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.arguments.len(), 1);
+  assert_eq!(rust_method.arguments.arguments[0].name, "a");
+}
+
+#[test]
+fn index_operator_impls_test() {
+  use cpp_method::CppMethodArgument;
+  use common::file_utils::{file_to_string, PathBufWithAdded};
+
+  let cpp_data = Default::default();
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("CLASS1".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  // `const int& operator[](int) const` or `int& operator[](int)`
+  let index_operator = |is_const: bool| {
+    let int_type = |indirection: CppTypeIndirection| {
+      CppType {
+        base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+        indirection: indirection,
+        is_const: is_const,
+        is_const2: false,
+      }
+    };
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "operator[]".to_string();
+    cpp_method.operator = Some(CppOperator::Subscript);
+    cpp_method.return_type = int_type(CppTypeIndirection::Ref);
+    cpp_method.arguments.push(CppMethodArgument {
+                                name: "i".to_string(),
+                                argument_type: int_type(CppTypeIndirection::None),
+                                has_default_value: false,
+                              });
+    let mut membership = ::tests::cpp_method::empty_membership("Class1");
+    membership.is_const = is_const;
+    cpp_method.class_membership = Some(membership);
+    let method = CppAndFfiMethod {
+      c_signature: cpp_method
+        .c_signature(ReturnValueAllocationPlace::NotApplicable)
+        .unwrap(),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: ReturnValueAllocationPlace::NotApplicable,
+      c_name: format!("crate1_Class1_operator_index{}", if is_const { "" } else { "1" }),
+    };
+    let rust_method = generator
+      .generate_rust_single_method(&method, &scope, false)
+      .unwrap();
+    generator.process_index_operator(&rust_method).unwrap()
+  };
+  let const_impl = index_operator(true);
+  let mut_impl = index_operator(false);
+  assert_eq!(const_impl.associated_types.len(), 1);
+  assert_eq!(const_impl.methods[0].name.last_name().unwrap(), "index");
+  assert_eq!(mut_impl.methods[0].name.last_name().unwrap(), "index_mut");
+
+  let dir = ::tests::TempTestDir::new("test_index_operator_impls");
+  let mut module = ::tests::rust_code_generator::empty_module("module1");
+  module.trait_impls = vec![const_impl.clone(), mut_impl.clone()];
+  let output_path = ::tests::rust_code_generator::run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("fn index(&self, i: ::libc::c_int) -> &::libc::c_int {"));
+  assert!(module_code.contains("fn index_mut(&mut self, i: ::libc::c_int) -> \
+                                &mut ::libc::c_int {"));
+  assert!(!module_code.contains("'l0"));
+
+  // the same `Index` impl may be produced by multiple overloads
+  let mut trait_impls = vec![mut_impl.clone(), const_impl.clone(), const_impl.clone()];
+  dedup_trait_impls(&mut trait_impls);
+  let count = |name: &str| {
+    trait_impls
+      .iter()
+      .filter(|x| match x.trait_type {
+                RustType::Common { ref base, .. } => base.last_name().unwrap() == name,
+                _ => false,
+              })
+      .count()
+  };
+  assert_eq!(count("Index"), 1);
+  assert_eq!(count("IndexMut"), 1);
+
+  // `IndexMut` requires a matching `Index` implementation
+  let mut trait_impls = vec![mut_impl];
+  dedup_trait_impls(&mut trait_impls);
+  assert!(trait_impls.is_empty());
+}
+
+#[test]
+fn fallible_constructor_test() {
+  let cpp_data = Default::default();
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("CLASS1".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let ffi_method = |cpp_method: CppMethod, allocation_place: ReturnValueAllocationPlace| {
+    CppAndFfiMethod {
+      c_signature: cpp_method.c_signature(allocation_place.clone()).unwrap(),
+      c_name: format!("crate1_Class1_{}", cpp_method.name),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: allocation_place,
+    }
+  };
+  // `bool isValid() const`
+  let mut is_valid = ::tests::cpp_method::empty_regular_method();
+  is_valid.name = "isValid".to_string();
+  is_valid.return_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  is_valid.class_membership = Some({
+                                     let mut info = ::tests::cpp_method::empty_membership("Class1");
+                                     info.is_const = true;
+                                     info
+                                   });
+  let header = ::cpp_ffi_data::CppFfiHeaderData {
+    include_file_base_name: "Class1".to_string(),
+    methods: vec![ffi_method(is_valid, ReturnValueAllocationPlace::NotApplicable)],
+    qt_slot_wrappers: Vec::new(),
+  };
+  let mut config = test_config();
+  config.add_fallible_constructor("Class1", "isValid");
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: vec![header],
+    ..test_input_data(&cpp_data, &config)
+  };
+  let generator = test_generator(input_data, processed_types);
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  // `Class1()`
+  let mut constructor = ::tests::cpp_method::empty_regular_method();
+  constructor.name = "Class1".to_string();
+  constructor.class_membership = Some({
+                                        let mut info =
+                                          ::tests::cpp_method::empty_membership("Class1");
+                                        info.kind = ::cpp_method::CppMethodKind::Constructor;
+                                        info
+                                      });
+  let method = ffi_method(constructor, ReturnValueAllocationPlace::Stack);
+  let rust_method = generator
+    .generate_rust_single_method(&method, &scope, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.validity_check_function,
+             Some("crate1_Class1_isValid".to_string()));
+  match rust_method.arguments.return_type.rust_api_type {
+    RustType::Common {
+      ref base,
+      generic_arguments: Some(ref args),
+      ..
+    } => {
+      assert_eq!(base.last_name().unwrap(), "Result");
+      if let RustMethodScope::Impl { ref target_type } = scope {
+        assert_eq!(&args[0], target_type);
+      }
+      assert_eq!(args[1].caption(&base).unwrap(), "cpp_utils_invalid_object_error");
+    }
+    _ => panic!("Result type expected"),
+  }
+  // `Default` can't be implemented for a fallible constructor
+  assert!(generator
+            .process_default_constructor(&method, &scope)
+            .is_err());
+}
+
+#[test]
+fn template_destructor_test() {
+  let int_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let cpp_data = Default::default();
+  // `QList<int>` was made stack-allocated in `calc_processed_types`
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "QList".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: Some(vec![int_type.clone()]),
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("QLIST_INT".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "ListInt".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "ListInt".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  // `~QList()` with the allocation place of the type
+  let mut destructor = ::tests::cpp_method::empty_regular_method();
+  destructor.name = "~QList".to_string();
+  destructor.class_membership = Some({
+                                       let mut info =
+                                         ::tests::cpp_method::empty_membership("QList");
+                                       info.kind = ::cpp_method::CppMethodKind::Destructor;
+                                       info.class_type.template_arguments =
+                                         Some(vec![int_type.clone()]);
+                                       info
+                                     });
+  let ffi_method = |allocation_place: ReturnValueAllocationPlace, c_name: &str| {
+    CppAndFfiMethod {
+      c_signature: destructor.c_signature(allocation_place.clone()).unwrap(),
+      c_name: c_name.to_string(),
+      cpp_method: destructor.clone(),
+      kind: CppFfiMethodKind::Real,
+      allocation_place: allocation_place,
+    }
+  };
+  let method = ffi_method(ReturnValueAllocationPlace::Stack, "crate1_QList_int_destructor");
+  let trait_impl = generator.process_destructor(&method, &scope).unwrap();
+  assert_eq!(trait_impl.trait_type,
+             RustType::Common {
+               base: RustName::new(vec!["Drop".to_string()]).unwrap(),
+               generic_arguments: None,
+               indirection: RustTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+             });
+  assert_eq!(trait_impl.extra, None);
+  assert_eq!(trait_impl.methods.len(), 1);
+
+  // the FFI function would call `delete` on a stack-allocated value
+  let method = ffi_method(ReturnValueAllocationPlace::Heap, "crate1_QList_int_delete");
+  assert!(generator.process_destructor(&method, &scope).is_err());
+}
+
+#[test]
+fn parented_qobject_constructor_test() {
+  use cpp_data::{CppTypeData, CppOriginLocation, CppBaseSpecifier};
+  use cpp_method::CppMethodArgument;
+
+  let class_type = |name: &str, indirection: CppTypeIndirection| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: name.to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let type_data = |name: &str, bases: Vec<CppBaseSpecifier>| {
+    CppTypeData {
+      name: name.to_string(),
+      include_file: name.to_string(),
+      origin_location: CppOriginLocation {
+        include_file_path: name.to_string(),
+        line: 1,
+        column: 1,
+      },
+      kind: CppTypeKind::Class {
+        bases: bases,
+        fields: Vec::new(),
+        static_fields: Vec::new(),
+        template_arguments: None,
+        using_directives: Vec::new(),
+        alignment: None,
+      },
+      doc: None,
+    }
+  };
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data.current.parser.types =
+    vec![type_data("QObject", Vec::new()),
+         type_data("QWidget",
+                   vec![CppBaseSpecifier {
+                          base_type: class_type("QObject", CppTypeIndirection::None),
+                          is_virtual: false,
+                          visibility: CppVisibility::Public,
+                        }])];
+  let processed_type = |name: &str| {
+    RustProcessedTypeInfo {
+      cpp_name: name.to_string(),
+      cpp_doc: None,
+      cpp_template_arguments: None,
+      kind: RustTypeWrapperKind::Struct {
+        size_const_name: None,
+        is_deletable: true,
+        slot_wrapper: None,
+      },
+      rust_name: RustName::new(vec!["crate1".to_string(), name.to_string()]).unwrap(),
+      is_public: true,
+    }
+  };
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "QWidget".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  let constructor = |enabled: bool, arguments: Vec<CppMethodArgument>| {
+    let mut config = test_config();
+    config.set_parented_qobject_constructors(enabled);
+    let processed_types = vec![processed_type("QObject"), processed_type("QWidget")];
+    let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "QWidget".to_string();
+    cpp_method.arguments = arguments;
+    cpp_method.class_membership = Some({
+                                         let mut info =
+                                           ::tests::cpp_method::empty_membership("QWidget");
+                                         info.kind = ::cpp_method::CppMethodKind::Constructor;
+                                         info
+                                       });
+    let allocation_place = ReturnValueAllocationPlace::Heap;
+    let method = CppAndFfiMethod {
+      c_signature: cpp_method.c_signature(allocation_place.clone()).unwrap(),
+      c_name: format!("crate1_QWidget_new{}", cpp_method.arguments.len()),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: allocation_place,
+    };
+    generator
+      .generate_rust_single_method(&method, &scope, false)
+      .unwrap()
+      .arguments
+      .return_type
+  };
+
+  let parent_argument = |name: &str| {
+    CppMethodArgument {
+      name: name.to_string(),
+      argument_type: class_type("QWidget", CppTypeIndirection::Ptr),
+      has_default_value: false,
+    }
+  };
+
+  // `QWidget()`: the caller owns the object
+  let return_type = constructor(true, Vec::new());
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::CppBoxToPtr);
+
+  // `QWidget(QWidget* parent)`: the object is owned by the parent if it's not null
+  let return_type = constructor(true, vec![parent_argument("parent")]);
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::MaybeOwnedToPtr);
+  assert_eq!(return_type.rust_api_type,
+             RustType::Common {
+               base: RustName::new(vec!["cpp_utils".to_string(), "MaybeOwned".to_string()])
+                 .unwrap(),
+               generic_arguments: Some(vec![RustType::Common {
+                                              base: RustName::new(vec!["crate1".to_string(),
+                                                                       "QWidget".to_string()])
+                                                .unwrap(),
+                                              generic_arguments: None,
+                                              indirection: RustTypeIndirection::None,
+                                              is_const: false,
+                                              is_const2: false,
+                                            }]),
+               indirection: RustTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+             });
+
+  // the conversion is opt-in
+  let return_type = constructor(false, vec![parent_argument("parent")]);
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::CppBoxToPtr);
+
+  // other pointer arguments don't affect the ownership
+  let return_type = constructor(true, vec![parent_argument("other")]);
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::CppBoxToPtr);
+}
+
+#[test]
+fn is_owned_upcast_allowed_test() {
+  use cpp_data::{CppTypeData, CppOriginLocation, CppBaseSpecifier};
+
+  let class_base = |name: &str| {
+    CppTypeClassBase {
+      name: name.to_string(),
+      template_arguments: None,
+    }
+  };
+  let type_data = |name: &str, bases: Vec<&str>| {
+    CppTypeData {
+      name: name.to_string(),
+      include_file: name.to_string(),
+      origin_location: CppOriginLocation {
+        include_file_path: name.to_string(),
+        line: 1,
+        column: 1,
+      },
+      kind: CppTypeKind::Class {
+        bases: bases
+          .into_iter()
+          .map(|base| {
+                 CppBaseSpecifier {
+                   base_type: CppType {
+                     base: CppTypeBase::Class(class_base(base)),
+                     indirection: CppTypeIndirection::None,
+                     is_const: false,
+                     is_const2: false,
+                   },
+                   is_virtual: false,
+                   visibility: CppVisibility::Public,
+                 }
+               })
+          .collect(),
+        fields: Vec::new(),
+        static_fields: Vec::new(),
+        template_arguments: None,
+        using_directives: Vec::new(),
+        alignment: None,
+      },
+      doc: None,
+    }
+  };
+  let destructor = |class_name: &'static str, is_virtual: bool, visibility: CppVisibility| {
+    let mut method = ::tests::cpp_method::empty_regular_method();
+    method.name = format!("~{}", class_name);
+    method.class_membership = Some({
+                                     let mut info =
+                                       ::tests::cpp_method::empty_membership(class_name);
+                                     info.kind = ::cpp_method::CppMethodKind::Destructor;
+                                     info.is_virtual = is_virtual;
+                                     info.visibility = visibility;
+                                     info
+                                   });
+    method
+  };
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data.current.parser.types = vec![type_data("Base1", Vec::new()),
+                                       type_data("Base2", Vec::new()),
+                                       type_data("Derived1", vec!["Base1"]),
+                                       type_data("Derived2", vec!["Derived1"]),
+                                       type_data("Derived3", vec!["Base1", "Base2"]),
+                                       type_data("Derived4", vec!["Base2"]),
+                                       type_data("Derived5", vec!["Base1"]),
+                                       type_data("Derived6", vec!["Base1"])];
+  cpp_data.current.parser.methods = vec![destructor("Base1", true, CppVisibility::Public),
+                                         destructor("Base2", false, CppVisibility::Public),
+                                         destructor("Derived5",
+                                                    true,
+                                                    CppVisibility::Protected)];
+  for name in &["Base1", "Base2", "Derived1", "Derived2", "Derived3", "Derived4", "Derived5"] {
+    cpp_data
+      .current
+      .processed
+      .type_allocation_places
+      .insert(name.to_string(), CppTypeAllocationPlace::Heap);
+  }
+  cpp_data
+    .current
+    .processed
+    .type_allocation_places
+    .insert("Derived6".to_string(), CppTypeAllocationPlace::Stack);
+  let processed_types = Vec::new();
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let allowed = |derived: &str, base: &str| {
+    generator
+      .is_owned_upcast_allowed(&class_base(derived), &class_base(base))
+      .unwrap()
+  };
+  assert!(allowed("Derived1", "Base1"));
+  // the virtual destructor is inherited from `Base1`
+  assert!(allowed("Derived2", "Derived1"));
+  // multiple inheritance
+  assert!(!allowed("Derived3", "Base1"));
+  // non-virtual destructor of the base
+  assert!(!allowed("Derived4", "Base2"));
+  // non-public destructor of the derived class
+  assert!(!allowed("Derived5", "Base1"));
+  // stack-allocated derived class
+  assert!(!allowed("Derived6", "Base1"));
+}
+
+#[test]
+fn narrowing_conversion_operator_test() {
+  use cpp_data::{CppTypeData, CppOriginLocation, CppClassField};
+
+  let numeric_type = |name: &str, bits: usize| {
+    CppType {
+      base: CppTypeBase::SpecificNumeric(CppSpecificNumericType {
+                                           name: name.to_string(),
+                                           bits: bits,
+                                           kind: CppSpecificNumericTypeKind::Integer {
+                                             is_signed: true,
+                                           },
+                                         }),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(CppTypeData {
+            name: "Class1".to_string(),
+            include_file: "Class1".to_string(),
+            origin_location: CppOriginLocation {
+              include_file_path: "Class1".to_string(),
+              line: 1,
+              column: 1,
+            },
+            kind: CppTypeKind::Class {
+              bases: Vec::new(),
+              fields: vec![CppClassField {
+                             name: "value".to_string(),
+                             field_type: numeric_type("qint64", 64),
+                             visibility: CppVisibility::Private,
+                             size: Some(8),
+                           }],
+              static_fields: Vec::new(),
+              template_arguments: None,
+              using_directives: Vec::new(),
+              alignment: None,
+            },
+            doc: None,
+          });
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("CLASS1".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  let conversion_method = |target_type: CppType, declaration_code: &str| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = format!("operator {}", target_type.to_cpp_pseudo_code());
+    cpp_method.operator = Some(CppOperator::Conversion(target_type.clone()));
+    cpp_method.return_type = target_type;
+    cpp_method.declaration_code = Some(declaration_code.to_string());
+    cpp_method.class_membership = Some({
+                                         let mut info =
+                                           ::tests::cpp_method::empty_membership("Class1");
+                                         info.is_const = true;
+                                         info
+                                       });
+    CppAndFfiMethod {
+      c_signature: cpp_method
+        .c_signature(ReturnValueAllocationPlace::NotApplicable)
+        .unwrap(),
+      c_name: "crate1_Class1_convert".to_string(),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    }
+  };
+
+  // `explicit operator qint32() const` narrows `qint64` value
+  let narrowing = conversion_method(numeric_type("qint32", 32),
+                                    "explicit operator qint32() const");
+  let r = generator
+    .process_narrowing_conversion_operator(&narrowing, &scope)
+    .unwrap();
+  match r.trait_type {
+    RustType::Common { ref base, .. } => assert_eq!(base.last_name().unwrap(), "TryFrom"),
+    _ => panic!("RustType::Common expected"),
+  }
+  assert_eq!(r.associated_types.len(), 1);
+  assert_eq!(r.associated_types[0].name, "Error");
+  assert_eq!(r.target_type, RustType::Common {
+    base: RustName::new(vec!["i32".to_string()]).unwrap(),
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    generic_arguments: None,
+  });
+  assert_eq!(r.extra,
+             Some(TraitImplExtra::NarrowingConversionTryFrom {
+                    ffi_function_name: "crate1_Class1_convert".to_string(),
+                  }));
+
+  // widening conversion
+  let widening = conversion_method(numeric_type("qint64", 64),
+                                   "explicit operator qint64() const");
+  assert!(generator
+            .process_narrowing_conversion_operator(&widening, &scope)
+            .is_err());
+
+  // implicit conversion is handled by `From`
+  let implicit = conversion_method(numeric_type("qint32", 32), "operator qint32() const");
+  assert!(generator
+            .process_narrowing_conversion_operator(&implicit, &scope)
+            .is_err());
+}
+
+#[test]
+fn add_operator_test() {
+  use cpp_method::CppMethodArgument;
+
+  let cpp_data = Default::default();
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Point".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("POINT".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Point".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let point_type = |indirection: CppTypeIndirection, is_const: bool| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "Point".to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: indirection,
+      is_const: is_const,
+      is_const2: false,
+    }
+  };
+  // `Point Point::operator+(rhs) const`
+  let add_operator = |rhs_type: CppType, c_name: &str| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "operator+".to_string();
+    cpp_method.operator = Some(CppOperator::Addition);
+    cpp_method.return_type = point_type(CppTypeIndirection::None, false);
+    cpp_method.arguments.push(CppMethodArgument {
+                                name: "other".to_string(),
+                                argument_type: rhs_type,
+                                has_default_value: false,
+                              });
+    cpp_method.class_membership = Some({
+                                         let mut info =
+                                           ::tests::cpp_method::empty_membership("Point");
+                                         info.is_const = true;
+                                         info
+                                       });
+    CppAndFfiMethod {
+      c_signature: cpp_method
+        .c_signature(ReturnValueAllocationPlace::Stack)
+        .unwrap(),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: ReturnValueAllocationPlace::Stack,
+      c_name: c_name.to_string(),
+    }
+  };
+  let add_point = add_operator(point_type(CppTypeIndirection::Ref, true),
+                               "crate1_Point_operator_add_point");
+  let add_int = add_operator(CppType {
+                               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                               indirection: CppTypeIndirection::None,
+                               is_const: false,
+                               is_const2: false,
+                             },
+                             "crate1_Point_operator_add_int");
+  let point_rust_type = RustType::Common {
+    base: RustName::new(vec!["crate1".to_string(), "Point".to_string()]).unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let scope = RustMethodScope::Impl { target_type: point_rust_type.clone() };
+  let result = generator
+    .process_all_sibling_functions(vec![&add_point, &add_int].into_iter(), &scope)
+    .unwrap();
+  let add_impls: Vec<_> = result
+    .trait_impls
+    .iter()
+    .filter(|r| match r.trait_type {
+              RustType::Common { ref base, .. } => base.last_name().unwrap() == "Add",
+              _ => false,
+            })
+    .collect();
+  assert_eq!(add_impls.len(), 2);
+  let rhs_types: Vec<_> = add_impls
+    .iter()
+    .map(|r| match r.trait_type {
+           RustType::Common { generic_arguments: Some(ref args), .. } => args[0].clone(),
+           _ => panic!("generic argument expected"),
+         })
+    .collect();
+  let point_ref_type = RustType::Common {
+    base: RustName::new(vec!["crate1".to_string(), "Point".to_string()]).unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::Ref { lifetime: Some("a".to_string()) },
+    is_const: true,
+    is_const2: false,
+  };
+  assert!(rhs_types.contains(&point_ref_type));
+  assert!(rhs_types.contains(&RustType::Common {
+                               base: RustName::new(vec!["libc".to_string(),
+                                                        "c_int".to_string()])
+                                 .unwrap(),
+                               generic_arguments: None,
+                               indirection: RustTypeIndirection::None,
+                               is_const: false,
+                               is_const2: false,
+                             }));
+  for r in add_impls {
+    assert_eq!(r.target_type, point_ref_type);
+    assert_eq!(r.associated_types[0].name, "Output");
+    assert_eq!(r.associated_types[0].value, point_rust_type);
+    assert_eq!(r.methods.len(), 1);
+    assert_eq!(r.methods[0].name.last_name().unwrap(), "add");
+  }
+}
+
+#[test]
+fn cast_error_type_test() {
+  use cpp_method::CppMethodArgument;
+
+  let cpp_data = Default::default();
+  let class_info = |name: &str| {
+    RustProcessedTypeInfo {
+      cpp_name: name.to_string(),
+      cpp_doc: None,
+      cpp_template_arguments: None,
+      kind: RustTypeWrapperKind::Struct {
+        size_const_name: None,
+        is_deletable: true,
+        slot_wrapper: None,
+      },
+      rust_name: RustName::new(vec!["crate1".to_string(), name.to_string()]).unwrap(),
+      is_public: true,
+    }
+  };
+  let processed_types = vec![class_info("Base"), class_info("Derived")];
+  let error_name = RustName::new(vec!["crate1".to_string(), "CastFailed".to_string()]).unwrap();
+  let mut config = test_config();
+  config.set_cast_error_type(error_name.clone());
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let class_ptr = |name: &str| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: name.to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: CppTypeIndirection::Ptr,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  // `Derived* dynamic_cast<Derived*>(Base* ptr)`
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "dynamic_cast".to_string();
+  cpp_method.return_type = class_ptr("Derived");
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "ptr".to_string(),
+                              argument_type: class_ptr("Base"),
+                              has_default_value: false,
+                            });
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::NotApplicable)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Cast(CppCast::Dynamic),
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_name: "crate1_dynamic_cast_Base_Derived".to_string(),
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&method, &RustMethodScope::Free, false)
+    .unwrap();
+  let impls = generator.process_cpp_cast(rust_method).unwrap();
+  let error_type = RustType::Common {
+    base: error_name,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    generic_arguments: None,
+  };
+  let cast_impl = impls
+    .iter()
+    .find(|r| match r.trait_type {
+            RustType::Common { ref base, .. } => base.last_name().unwrap() == "DynamicCastResult",
+            _ => false,
+          })
+    .unwrap();
+  assert_eq!(cast_impl.associated_types[0].name, "Error");
+  assert_eq!(cast_impl.associated_types[0].value, error_type);
+  assert_eq!(cast_impl.methods.len(), 2);
+  for method in &cast_impl.methods {
+    if let RustMethodArguments::SingleVariant(ref variant) = method.arguments {
+      assert_eq!(variant.return_type.rust_api_to_c_conversion,
+                 RustToCTypeConversion::ResultRefToPtr);
+      match variant.return_type.rust_api_type {
+        RustType::Common {
+          ref base,
+          generic_arguments: Some(ref args),
+          ..
+        } => {
+          assert_eq!(base.last_name().unwrap(), "Result");
+          assert_eq!(args.len(), 2);
+          assert_eq!(&args[1], &error_type);
+        }
+        _ => panic!("Result type expected"),
       }
+    } else {
+      panic!("single variant expected");
     }
-    Ok(result)
   }
+  let try_from_impl = impls
+    .iter()
+    .find(|r| r.extra == Some(TraitImplExtra::DynamicCastTryFrom { uses_cast_result: true }))
+    .unwrap();
+  assert_eq!(try_from_impl.associated_types[0].value, error_type);
+}
 
-  /// Generates `RustName` for specified function or type name,
-  /// including crate name and modules list.
-  fn calculate_rust_name(&self,
-                         name: &str,
-                         include_file: &str,
-                         is_function: bool,
-                         operator: Option<&CppOperator>)
-                         -> Result<RustName> {
-    let mut split_parts: Vec<_> = name.split("::").collect();
-    let original_last_part = split_parts
-      .pop()
-      .chain_err(|| "split_parts can't be empty")?
-      .to_string();
-    let last_part = if let Some(operator) = operator {
-      operator_rust_name(operator)?
+#[test]
+fn overloaded_ref_getter_lifetime_test() {
+  let cpp_data = Default::default();
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  let ref_type = |name: &str, lifetime: &str| {
+    RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), name.to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ref { lifetime: Some(lifetime.to_string()) },
+      is_const: true,
+      is_const2: false,
+    }
+  };
+  let complete_type = |rust_type: RustType| {
+    CompleteType {
+      cpp_type: CppType::void(),
+      cpp_ffi_type: CppType::void(),
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: rust_type.clone(),
+      rust_api_type: rust_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::RefToPtr,
+    }
+  };
+  let method = |scope: &RustMethodScope,
+                c_name: &str,
+                arguments: Vec<(&str, RustType)>,
+                return_type: RustType| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "get".to_string();
+    RustSingleMethod {
+      scope: scope.clone(),
+      is_unsafe: false,
+      name: RustName::new(vec!["crate1".to_string(), "get".to_string()]).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: arguments
+          .into_iter()
+          .enumerate()
+          .map(|(index, (name, rust_type))| {
+                 RustMethodArgument {
+                   argument_type: complete_type(rust_type),
+                   name: name.to_string(),
+                   ffi_index: index,
+                 }
+               })
+          .collect(),
+        cpp_method: CppAndFfiMethod {
+          cpp_method: cpp_method,
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: Vec::new(),
+            return_type: CppFfiType::void(),
+          },
+          c_name: c_name.to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: complete_type(return_type),
+      },
+      doc: None,
+    }
+  };
+  let lifetimes = |methods: Vec<RustSingleMethod>, scope: &RustMethodScope| {
+    let (rust_method, _) = generator
+      .generate_final_method(methods, scope, None)
+      .unwrap();
+    if let RustMethodArguments::MultipleVariants {
+             params_trait_lifetime,
+             common_return_type,
+             shared_arguments,
+             ..
+           } = rust_method.arguments {
+      (params_trait_lifetime,
+       common_return_type.and_then(|t| t.lifetime().cloned()),
+       shared_arguments
+         .into_iter()
+         .map(|arg| arg.argument_type.rust_api_type.lifetime().cloned())
+         .collect::<Vec<_>>())
     } else {
-      remove_qt_prefix_and_convert_case(&original_last_part,
-                                        if is_function {
-                                          Case::Snake
-                                        } else {
-                                          Case::Class
-                                        },
-                                        self.input_data.remove_qt_prefix)
-    };
+      panic!("MultipleVariants expected");
+    }
+  };
+  let largs = Some("largs".to_string());
 
-    let module_name =
-      self
-        .top_module_names
-        .get(include_file)
-        .chain_err(|| format!("no top level module generated for header: {}", include_file))?;
+  // the returned reference borrows from `self`
+  let impl_scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap(),
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+      generic_arguments: None,
+    },
+  };
+  let methods = vec![method(&impl_scope,
+                            "crate1_Class1_get",
+                            vec![("self", ref_type("Class1", "l0"))],
+                            ref_type("Class2", "l0")),
+                     method(&impl_scope,
+                            "crate1_Class1_get1",
+                            vec![("self", ref_type("Class1", "l0")),
+                                 ("key", ref_type("Class3", "l1"))],
+                            ref_type("Class2", "l0"))];
+  assert_eq!(lifetimes(methods, &impl_scope),
+             (largs.clone(), largs.clone(), vec![largs.clone()]));
 
-    let mut parts = module_name.parts.clone();
-    if include_file == "slots" {
-      parts.push("raw".to_string());
-    }
-    //    parts.push(config.crate_name.clone());
-    //    parts.push(include_file_to_module_name(include_file, config.remove_qt_prefix));
-    for part in split_parts {
-      let part = part.to_string();
-      if self.input_data.filtered_namespaces.contains(&part) {
-        continue;
-      }
-      parts.push(remove_qt_prefix_and_convert_case(&part,
-                                                   Case::Snake,
-                                                   self.input_data.remove_qt_prefix));
+  // variants returning references with different lifetimes
+  let free_scope = RustMethodScope::Free;
+  let methods = vec![method(&free_scope,
+                            "crate1_get",
+                            vec![("key", ref_type("Class3", "l0"))],
+                            ref_type("Class2", "l0")),
+                     method(&free_scope, "crate1_get1", Vec::new(), ref_type("Class2", "static"))];
+  assert_eq!(lifetimes(methods, &free_scope),
+             (largs.clone(), largs.clone(), Vec::new()));
+}
+
+#[test]
+fn chainable_setter_test() {
+  use cpp_method::CppMethodArgument;
+
+  let cpp_data = Default::default();
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("CLASS1".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "Class1".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let mut config = test_config();
+  config.set_chainable_setters(true);
+  let generator = test_generator(test_input_data(&cpp_data, &config),
+                                 processed_types.clone());
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  // `Class1& setValue(int value)`
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "setValue".to_string();
+  cpp_method.return_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::Ref,
+    is_const: false,
+    is_const2: false,
+  };
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "value".to_string(),
+                              argument_type: CppType {
+                                base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                                indirection: CppTypeIndirection::None,
+                                is_const: false,
+                                is_const2: false,
+                              },
+                              has_default_value: false,
+                            });
+  cpp_method.class_membership = Some(::tests::cpp_method::empty_membership("Class1"));
+  assert!(cpp_method.is_chainable_setter());
+  let mut const_method = cpp_method.clone();
+  const_method.class_membership.as_mut().unwrap().is_const = true;
+  assert!(!const_method.is_chainable_setter());
+  let mut append_method = cpp_method.clone();
+  append_method.name = "append".to_string();
+  assert!(append_method.returns_self_reference());
+  assert!(!append_method.is_chainable_setter());
+  let mut settle_method = cpp_method.clone();
+  settle_method.name = "settle".to_string();
+  assert!(!settle_method.is_chainable_setter());
+  let ffi_method = |cpp_method: CppMethod, c_name: &str| {
+    CppAndFfiMethod {
+      c_signature: cpp_method
+        .c_signature(ReturnValueAllocationPlace::NotApplicable)
+        .unwrap(),
+      cpp_method: cpp_method,
+      kind: CppFfiMethodKind::Real,
+      allocation_place: ReturnValueAllocationPlace::NotApplicable,
+      c_name: c_name.to_string(),
     }
+  };
+  let method = ffi_method(cpp_method, "crate1_Class1_setValue");
+  let append_method = ffi_method(append_method, "crate1_Class1_append");
+  let rust_method = generator
+    .generate_rust_single_method(&append_method, &scope, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::RefToPtr);
+  let rust_method = generator
+    .generate_rust_single_method(&method, &scope, false)
+    .unwrap();
+  let return_type = &rust_method.arguments.return_type;
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::RefToSelf);
+  assert!(!return_type.rust_api_type.is_const().unwrap());
+  let self_arg = &rust_method.arguments.arguments[0];
+  assert_eq!(self_arg.name, "self");
+  assert!(!self_arg.argument_type.rust_api_type.is_const().unwrap());
+  assert!(return_type.rust_api_type.lifetime().is_some());
+  assert_eq!(return_type.rust_api_type.lifetime(),
+             self_arg.argument_type.rust_api_type.lifetime());
 
-    if parts.len() > 2 && parts[1] == parts[2] {
-      // special case
-      parts.remove(2);
+  // other names must be listed explicitly
+  let mut listed_config = test_config();
+  listed_config.set_chainable_setters(true);
+  listed_config.add_chainable_setter("Class1::append");
+  let generator = test_generator(test_input_data(&cpp_data, &listed_config),
+                                 processed_types.clone());
+  let rust_method = generator
+    .generate_rust_single_method(&append_method, &scope, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::RefToSelf);
+
+  let default_config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &default_config),
+                                 processed_types);
+  let rust_method = generator
+    .generate_rust_single_method(&method, &scope, false)
+    .unwrap();
+  assert_eq!(rust_method.arguments.return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::RefToPtr);
+}
+
+#[test]
+fn const_getter_pair_suffixes_test() {
+  let ref_type = |name: &str, is_const: bool| {
+    let rust_type = RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), name.to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ref { lifetime: None },
+      is_const: is_const,
+      is_const2: false,
+    };
+    CompleteType {
+      cpp_type: CppType::void(),
+      cpp_ffi_type: CppType::void(),
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: rust_type.clone(),
+      rust_api_type: rust_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::RefToPtr,
     }
-    parts.push(last_part);
-    RustName::new(parts)
+  };
+  let data_method = |is_const: bool, return_type: CompleteType| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "data".to_string();
+    RustSingleMethod {
+      scope: RustMethodScope::Impl { target_type: ref_type("Class1", false).rust_api_type },
+      is_unsafe: false,
+      name: RustName::new(vec!["data".to_string()]).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: vec![RustMethodArgument {
+                          argument_type: ref_type("Class1", is_const),
+                          name: "self".to_string(),
+                          ffi_index: 0,
+                        }],
+        cpp_method: CppAndFfiMethod {
+          cpp_method: cpp_method,
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: Vec::new(),
+            return_type: CppFfiType::void(),
+          },
+          c_name: "crate1_Class1_data".to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: return_type,
+      },
+      doc: None,
+    }
+  };
+
+  let pair = vec![data_method(false, ref_type("Data", false)),
+                  data_method(true, ref_type("Data", true))];
+  let suffixes = const_getter_pair_suffixes(&pair).unwrap().unwrap();
+  let names: Vec<_> = suffixes
+    .iter()
+    .map(|suffix| final_method_last_name("data", suffix.as_ref()))
+    .collect();
+  assert_eq!(names, vec!["data_mut".to_string(), "data".to_string()]);
+
+  // methods returning values are not getter pairs
+  let mut value_type = ref_type("Data", true);
+  if let RustType::Common { ref mut indirection, .. } = value_type.rust_api_type {
+    *indirection = RustTypeIndirection::None;
   }
+  let values = vec![data_method(false, value_type.clone()), data_method(true, value_type)];
+  assert!(const_getter_pair_suffixes(&values).unwrap().is_none());
+
+  // two const methods are not a pair
+  let both_const = vec![data_method(true, ref_type("Data", true)),
+                        data_method(true, ref_type("Data", true))];
+  assert!(const_getter_pair_suffixes(&both_const).unwrap().is_none());
 }
 
+#[test]
+fn disambiguate_conversion_operators_test() {
+  let value_type = |parts: Vec<&str>| {
+    let rust_type = RustType::Common {
+      base: RustName::new(parts.into_iter().map(|x| x.to_string()).collect()).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    };
+    CompleteType {
+      cpp_type: CppType::void(),
+      cpp_ffi_type: CppType::void(),
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: rust_type.clone(),
+      rust_api_type: rust_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::None,
+    }
+  };
+  let self_type = || {
+    let rust_type = RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ref { lifetime: None },
+      is_const: true,
+      is_const2: false,
+    };
+    CompleteType {
+      cpp_type: CppType::void(),
+      cpp_ffi_type: CppType::void(),
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: rust_type.clone(),
+      rust_api_type: rust_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::RefToPtr,
+    }
+  };
+  let conversion_method = |name: &str, return_type: CompleteType| {
+    RustSingleMethod {
+      scope: RustMethodScope::Impl { target_type: self_type().rust_api_type },
+      is_unsafe: false,
+      name: RustName::new(vec![name.to_string()]).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: vec![RustMethodArgument {
+                          argument_type: self_type(),
+                          name: "self".to_string(),
+                          ffi_index: 0,
+                        }],
+        cpp_method: CppAndFfiMethod {
+          cpp_method: ::tests::cpp_method::empty_regular_method(),
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: Vec::new(),
+            return_type: CppFfiType::void(),
+          },
+          c_name: "crate1_Class1_operator_conversion".to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: return_type,
+      },
+      doc: None,
+    }
+  };
+  let final_names = |methods: &[RustSingleMethod]| -> Vec<String> {
+    methods
+      .iter()
+      .map(|m| m.name.last_name().unwrap().clone())
+      .collect()
+  };
+
+  // e.g. `operator int()` and `operator qint64()` with a custom `int` typedef
+  let mut methods = vec![conversion_method("as_int", value_type(vec!["libc", "c_int"])),
+                         conversion_method("as_int", value_type(vec!["i64"])),
+                         conversion_method("as_float", value_type(vec!["f32"]))];
+  disambiguate_conversion_operators(&mut methods).unwrap();
+  assert_eq!(final_names(&methods), vec!["as_int_c_int", "as_int_i64", "as_float"]);
+
+  // return type captions are identical as well
+  let mut methods = vec![conversion_method("as_int", value_type(vec!["i64"])),
+                         conversion_method("as_int", value_type(vec!["i64"])),
+                         conversion_method("as_int_1", value_type(vec!["i32"]))];
+  disambiguate_conversion_operators(&mut methods).unwrap();
+  assert_eq!(final_names(&methods), vec!["as_int_2", "as_int_3", "as_int_1"]);
+}
 
-// ---------------------------------
 #[test]
-fn remove_qt_prefix_and_convert_case_test() {
-  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Class, false),
-             "OneTwo");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Snake, false),
-             "one_two");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Class, true),
-             "OneTwo");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"OneTwo".to_string(), Case::Snake, true),
-             "one_two");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Class, false),
-             "QDirIterator");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Snake, false),
-             "q_dir_iterator");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Class, true),
-             "DirIterator");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"QDirIterator".to_string(), Case::Snake, true),
-             "dir_iterator");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Class, false),
-             "Qt3DWindow");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Snake, false),
-             "qt_3d_window");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Class, true),
-             "Qt3DWindow");
-  assert_eq!(remove_qt_prefix_and_convert_case(&"Qt3DWindow".to_string(), Case::Snake, true),
-             "qt_3d_window");
+fn base_as_ref_impl_test() {
+  let ptr_type = |name: &str| {
+    CompleteType {
+      cpp_type: CppType::void(),
+      cpp_ffi_type: CppType::void(),
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: RustType::EmptyTuple,
+      rust_api_type: RustType::Common {
+        base: RustName::new(vec!["crate1".to_string(), name.to_string()]).unwrap(),
+        indirection: RustTypeIndirection::Ptr,
+        is_const: true,
+        is_const2: false,
+        generic_arguments: None,
+      },
+      rust_api_to_c_conversion: RustToCTypeConversion::None,
+    }
+  };
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "static_cast".to_string();
+  let cast_method = RustSingleMethod {
+    scope: RustMethodScope::TraitImpl,
+    is_unsafe: false,
+    name: RustName::new(vec!["static_cast".to_string()]).unwrap(),
+    arguments: RustMethodArgumentsVariant {
+      arguments: vec![RustMethodArgument {
+                        argument_type: ptr_type("Derived").ptr_to_ref(true).unwrap(),
+                        name: "self".to_string(),
+                        ffi_index: 0,
+                      }],
+      cpp_method: CppAndFfiMethod {
+        cpp_method: cpp_method,
+        kind: CppFfiMethodKind::Real,
+        allocation_place: ReturnValueAllocationPlace::NotApplicable,
+        c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+          arguments: Vec::new(),
+          return_type: CppFfiType::void(),
+        },
+        c_name: "crate1_static_cast_Base_ptr_Derived_ptr".to_string(),
+      },
+      return_type_ffi_index: None,
+      validity_check_function: None,
+      return_type: ptr_type("Base").ptr_to_ref(true).unwrap(),
+    },
+    doc: None,
+  };
+  let r = base_as_ref_impl(&cast_method, true, &ptr_type("Derived"), &ptr_type("Base"))
+    .unwrap()
+    .unwrap();
+  assert_eq!(r.target_type, ptr_type("Derived").ptr_to_value().unwrap().rust_api_type);
+  if let RustType::Common {
+           ref base,
+           ref generic_arguments,
+           ..
+         } = r.trait_type {
+    assert_eq!(base.full_name(None), "::std::convert::AsRef");
+    assert_eq!(generic_arguments,
+               &Some(vec![ptr_type("Base").ptr_to_value().unwrap().rust_api_type]));
+  } else {
+    panic!("RustType::Common expected");
+  }
+  assert_eq!(r.methods.len(), 1);
+  assert_eq!(r.methods[0].name.last_name().unwrap(), "as_ref");
+  if let RustMethodArguments::SingleVariant(ref variant) = r.methods[0].arguments {
+    assert_eq!(variant.cpp_method.c_name, "crate1_static_cast_Base_ptr_Derived_ptr");
+  } else {
+    panic!("single variant expected");
+  }
+
+  let r_mut = base_as_ref_impl(&cast_method, false, &ptr_type("Derived"), &ptr_type("Base"))
+    .unwrap()
+    .unwrap();
+  assert_eq!(r_mut.methods[0].name.last_name().unwrap(), "as_mut");
+
+  // no identity `AsRef<Self>` implementation
+  assert!(base_as_ref_impl(&cast_method, true, &ptr_type("Base"), &ptr_type("Base"))
+            .unwrap()
+            .is_none());
 }
 
-#[cfg(test)]
-fn calculate_rust_name_test_part(name: &'static str,
-                                 include_file: &'static str,
-                                 is_function: bool,
-                                 expected: &[&'static str]) {
-  let header = ::cpp_ffi_data::CppFfiHeaderData {
-    include_file_base_name: include_file.to_string(),
-    methods: Vec::new(),
-    qt_slot_wrappers: Vec::new(),
+#[test]
+fn overloading_trait_name_test() {
+  let cpp_data = Default::default();
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  let method = |name: Vec<&str>, scope: &RustMethodScope, c_name: &str| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "foo".to_string();
+    RustSingleMethod {
+      scope: scope.clone(),
+      is_unsafe: false,
+      name: RustName::new(name.into_iter().map(|x| x.to_string()).collect()).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: Vec::new(),
+        cpp_method: CppAndFfiMethod {
+          cpp_method: cpp_method,
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: Vec::new(),
+            return_type: CppFfiType::void(),
+          },
+          c_name: c_name.to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: CompleteType {
+          cpp_type: CppType::void(),
+          cpp_ffi_type: CppType::void(),
+          cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+          rust_ffi_type: RustType::EmptyTuple,
+          rust_api_type: RustType::EmptyTuple,
+          rust_api_to_c_conversion: RustToCTypeConversion::None,
+        },
+      },
+      doc: None,
+    }
   };
-  let mut generator = RustGenerator {
-    top_module_names: HashMap::new(),
-    processed_types: Vec::new(),
-    input_data: RustGeneratorInputData {
-      cpp_ffi_headers: vec![header],
-      cpp_data: &Default::default(),
-      dependency_types: Vec::new(),
-      crate_name: "qt_core".to_string(),
-      remove_qt_prefix: true,
-      filtered_namespaces: Vec::new(),
+  let trait_name = |name: Vec<&str>, scope: RustMethodScope| {
+    let (_, declaration) = generator
+      .generate_final_method(vec![method(name.clone(), &scope, "crate1_foo"),
+                                  method(name, &scope, "crate1_foo1")],
+                             &scope,
+                             None)
+      .unwrap();
+    declaration.unwrap().name.full_name(None)
+  };
+
+  assert_eq!(trait_name(vec!["crate1", "module1", "foo"], RustMethodScope::Free),
+             "::crate1::module1::FooArgs");
+  let impl_scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(),
+                               "module1".to_string(),
+                               "Type1".to_string()])
+        .unwrap(),
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+      generic_arguments: None,
     },
   };
-  generator.top_module_names = generator.calc_top_module_names().unwrap();
+  assert_eq!(trait_name(vec!["foo"], impl_scope),
+             "::crate1::module1::Type1FooArgs");
+}
 
-  assert_eq!(generator
-               .calculate_rust_name(&name.to_string(),
-                                    &include_file.to_string(),
-                                    is_function,
-                                    None)
-               .unwrap(),
-             RustName::new(expected.into_iter().map(|x| x.to_string()).collect()).unwrap());
+#[test]
+fn platform_method_cfg_test() {
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.add_platform_method("foo", target::Condition::OS(target::OS::Windows));
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  let method = |name: Vec<&str>, scope: &RustMethodScope, c_name: &str| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = if c_name == "crate1_bar" { "bar" } else { "foo" }.to_string();
+    RustSingleMethod {
+      scope: scope.clone(),
+      is_unsafe: false,
+      name: RustName::new(name.into_iter().map(|x| x.to_string()).collect()).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: Vec::new(),
+        cpp_method: CppAndFfiMethod {
+          cpp_method: cpp_method,
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: Vec::new(),
+            return_type: CppFfiType::void(),
+          },
+          c_name: c_name.to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: CompleteType {
+          cpp_type: CppType::void(),
+          cpp_ffi_type: CppType::void(),
+          cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+          rust_ffi_type: RustType::EmptyTuple,
+          rust_api_type: RustType::EmptyTuple,
+          rust_api_to_c_conversion: RustToCTypeConversion::None,
+        },
+      },
+      doc: None,
+    }
+  };
+  let scope = RustMethodScope::Free;
+  let (rust_method, _) = generator
+    .generate_final_method(vec![method(vec!["crate1", "foo"], &scope, "crate1_foo")],
+                           &scope,
+                           None)
+    .unwrap();
+  assert_eq!(rust_method.cfg, Some("target_os = \"windows\"".to_string()));
+
+  // platform-specific methods are not overloaded with other methods
+  let buckets = generator
+    .overload_functions(vec![method(vec!["crate1", "foo"], &scope, "crate1_foo"),
+                             method(vec!["crate1", "foo"], &scope, "crate1_bar")])
+    .unwrap();
+  assert_eq!(buckets.len(), 2);
+  assert!(buckets.iter().all(|&(_, ref methods)| methods.len() == 1));
+  let (_, bar_method) = buckets
+    .into_iter()
+    .find(|&(_, ref methods)| methods[0].arguments.cpp_method.c_name == "crate1_bar")
+    .unwrap();
+  let (rust_method, _) = generator
+    .generate_final_method(bar_method, &scope, None)
+    .unwrap();
+  assert_eq!(rust_method.cfg, None);
 }
 
 #[test]
-fn calculate_rust_name_test() {
-  calculate_rust_name_test_part("myFunc1",
-                                "QtGlobal",
-                                true,
-                                &["qt_core", "global", "my_func1"]);
-  calculate_rust_name_test_part("QPointF",
-                                "QPointF",
-                                false,
-                                &["qt_core", "point_f", "PointF"]);
-  calculate_rust_name_test_part("QStringList::Iterator",
-                                "QStringList",
-                                false,
-                                &["qt_core", "string_list", "Iterator"]);
-  calculate_rust_name_test_part("QStringList::Iterator",
-                                "QString",
-                                false,
-                                &["qt_core", "string", "string_list", "Iterator"]);
-  calculate_rust_name_test_part("ns::func1",
-                                "QRect",
-                                true,
-                                &["qt_core", "rect", "ns", "func1"]);
+fn resolve_method_name_collisions_test() {
+  // "qFooBar" and "foo_bar_1" don't collide in C++, but Qt prefix removal
+  // and index suffixes make their Rust names identical
+  let name1 = remove_qt_prefix_and_convert_case("qFooBar", Case::Snake, true, &[]);
+  let name2 = remove_qt_prefix_and_convert_case("fooBar", Case::Snake, true, &[]);
+  assert_eq!(name1, name2);
+  let mut names = vec![(name1, Some("1".to_string())),
+                       (name2, None),
+                       ("foo_bar_1".to_string(), None),
+                       ("type".to_string(), None)];
+  resolve_method_name_collisions(&mut names);
+  assert_eq!(names,
+             vec![("foo_bar".to_string(), Some("1".to_string())),
+                  ("foo_bar".to_string(), None),
+                  ("foo_bar_1".to_string(), Some("1".to_string())),
+                  ("type".to_string(), None)]);
+  let final_names: Vec<_> = names
+    .iter()
+    .map(|&(ref name, ref suffix)| final_method_last_name(name, suffix.as_ref()))
+    .collect();
+  assert_eq!(final_names, vec!["foo_bar_1", "foo_bar", "foo_bar_1_1", "type_"]);
+}
+
+#[test]
+fn bool_getter_rust_name_test() {
+  assert_eq!(bool_getter_rust_name("visible"), Some("is_visible".to_string()));
+  assert_eq!(bool_getter_rust_name("enabled"), Some("is_enabled".to_string()));
+  assert_eq!(bool_getter_rust_name("empty"), Some("is_empty".to_string()));
+  assert_eq!(bool_getter_rust_name(&"isEmpty".to_snake_case()), None);
+  assert_eq!(bool_getter_rust_name("has_focus"), None);
+  assert_eq!(bool_getter_rust_name("can_undo"), None);
+  assert_eq!(bool_getter_rust_name("should_close"), None);
+  assert_eq!(bool_getter_rust_name("flush"), None);
+  assert_eq!(bool_getter_rust_name("auto_repeat"), None);
 }
 
 #[test]
@@ -2127,7 +8838,8 @@ fn prepare_enum_values_test_simple() {
                                   name: "other_var2".to_string(),
                                   value: 2,
                                   doc: None,
-                                }]);
+                                }],
+                              &[]);
   assert_eq!(r.len(), 2);
   assert_eq!(r[0].name, "Var1");
   assert_eq!(r[0].value, 1);
@@ -2138,48 +8850,102 @@ fn prepare_enum_values_test_simple() {
 #[test]
 fn prepare_enum_values_test_duplicates() {
   let r = prepare_enum_values(&[CppEnumValue {
-                                  name: "var1".to_string(),
+                                  name: "var1".to_string(),
+                                  value: 1,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "other_var2".to_string(),
+                                  value: 2,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "other_var_dup".to_string(),
+                                  value: 2,
+                                  doc: None,
+                                }],
+                              &[]);
+  assert_eq!(r.len(), 2);
+  assert_eq!(r[0].name, "Var1");
+  assert_eq!(r[0].value, 1);
+  assert_eq!(r[1].name, "OtherVar2");
+  assert_eq!(r[1].value, 2);
+}
+
+#[test]
+fn prepare_enum_values_test_prefix() {
+  let r = prepare_enum_values(&[CppEnumValue {
+                                  name: "OptionGood".to_string(),
+                                  value: 1,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "OptionBad".to_string(),
+                                  value: 2,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "OptionNecessaryEvil".to_string(),
+                                  value: 3,
+                                  doc: None,
+                                }],
+                              &[]);
+  assert_eq!(r.len(), 3);
+  assert_eq!(r[0].name, "Good");
+  assert_eq!(r[1].name, "Bad");
+  assert_eq!(r[2].name, "NecessaryEvil");
+}
+
+#[test]
+fn prepare_enum_values_test_blocked() {
+  let r = prepare_enum_values(&[CppEnumValue {
+                                  name: "OptionGood".to_string(),
                                   value: 1,
                                   doc: None,
                                 },
                                 CppEnumValue {
-                                  name: "other_var2".to_string(),
+                                  name: "OtherEvil".to_string(),
                                   value: 2,
                                   doc: None,
                                 },
                                 CppEnumValue {
-                                  name: "other_var_dup".to_string(),
-                                  value: 2,
+                                  name: "OptionBad".to_string(),
+                                  value: 3,
                                   doc: None,
-                                }]);
-  assert_eq!(r.len(), 2);
-  assert_eq!(r[0].name, "Var1");
+                                }],
+                              &["OtherEvil".to_string()]);
+  assert_eq!(r.len(), 3);
+  assert_eq!(r[0].name, "Good");
   assert_eq!(r[0].value, 1);
-  assert_eq!(r[1].name, "OtherVar2");
+  assert!(!r[0].is_hidden);
+  // the value is kept because C++ may still return it
+  assert_eq!(r[1].name, "_OtherEvil");
   assert_eq!(r[1].value, 2);
-}
+  assert!(r[1].is_hidden);
+  assert_eq!(r[2].name, "Bad");
+  assert_eq!(r[2].value, 3);
+  assert!(!r[2].is_hidden);
+  assert!(r.iter().all(|v| !v.is_dummy));
 
-#[test]
-fn prepare_enum_values_test_prefix() {
+  // a blocked variant sharing its value with another variant is not needed
   let r = prepare_enum_values(&[CppEnumValue {
-                                  name: "OptionGood".to_string(),
+                                  name: "Good".to_string(),
                                   value: 1,
                                   doc: None,
                                 },
                                 CppEnumValue {
-                                  name: "OptionBad".to_string(),
-                                  value: 2,
+                                  name: "Evil".to_string(),
+                                  value: 1,
                                   doc: None,
                                 },
                                 CppEnumValue {
-                                  name: "OptionNecessaryEvil".to_string(),
-                                  value: 3,
+                                  name: "Bad".to_string(),
+                                  value: 2,
                                   doc: None,
-                                }]);
-  assert_eq!(r.len(), 3);
-  assert_eq!(r[0].name, "Good");
-  assert_eq!(r[1].name, "Bad");
-  assert_eq!(r[2].name, "NecessaryEvil");
+                                }],
+                              &["Evil".to_string()]);
+  assert_eq!(r.len(), 2);
+  assert!(r.iter().all(|v| !v.is_hidden));
 }
 
 #[test]
@@ -2198,7 +8964,8 @@ fn prepare_enum_values_test_suffix() {
                                   name: "NoFriend".to_string(),
                                   value: 3,
                                   doc: None,
-                                }]);
+                                }],
+                              &[]);
   assert_eq!(r.len(), 3);
   assert_eq!(r[0].name, "Best");
   assert_eq!(r[1].name, "Good");
@@ -2216,12 +8983,37 @@ fn prepare_enum_values_test_prefix_digits() {
                                   name: "Base64".to_string(),
                                   value: 2,
                                   doc: None,
-                                }]);
+                                }],
+                              &[]);
   assert_eq!(r.len(), 2);
   assert_eq!(r[0].name, "Base32");
   assert_eq!(r[1].name, "Base64");
 }
 
+#[test]
+fn prepare_enum_values_test_prefix_numeric() {
+  let r = prepare_enum_values(&[CppEnumValue {
+                                  name: "Mode1".to_string(),
+                                  value: 1,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "Mode2".to_string(),
+                                  value: 2,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "ModeAuto".to_string(),
+                                  value: 3,
+                                  doc: None,
+                                }],
+                              &[]);
+  assert_eq!(r.len(), 3);
+  assert_eq!(r[0].name, "Mode1");
+  assert_eq!(r[1].name, "Mode2");
+  assert_eq!(r[2].name, "Auto");
+}
+
 #[test]
 fn prepare_enum_values_test_suffix_empty() {
   let r = prepare_enum_values(&[CppEnumValue {
@@ -2233,7 +9025,8 @@ fn prepare_enum_values_test_suffix_empty() {
                                   name: "Recursive".to_string(),
                                   value: 2,
                                   doc: None,
-                                }]);
+                                }],
+                              &[]);
   assert_eq!(r.len(), 2);
   assert_eq!(r[0].name, "NonRecursive");
   assert_eq!(r[1].name, "Recursive");
@@ -2250,14 +9043,68 @@ fn prepare_enum_values_test_suffix_partial() {
                                   name: "CoarseTimer".to_string(),
                                   value: 2,
                                   doc: None,
-                                }]);
+                                }],
+                              &[]);
   assert_eq!(r.len(), 2);
   assert_eq!(r[0].name, "Precise");
   assert_eq!(r[1].name, "Coarse");
 }
 
+#[test]
+fn enum_repr_type_test() {
+  let r = prepare_enum_values(&[CppEnumValue {
+                                  name: "Small".to_string(),
+                                  value: -1,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "Large".to_string(),
+                                  value: 1 << 31,
+                                  doc: None,
+                                }],
+                              &[]);
+  assert_eq!(r[0].value, -1);
+  assert_eq!(r[1].value, 1 << 31);
+  assert_eq!(enum_repr_type(&r), "i64");
+
+  let r = prepare_enum_values(&[CppEnumValue {
+                                  name: "Small".to_string(),
+                                  value: -1,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "Large".to_string(),
+                                  value: 5,
+                                  doc: None,
+                                }],
+                              &[]);
+  assert_eq!(enum_repr_type(&r), "i32");
+
+  let r = prepare_enum_values(&[CppEnumValue {
+                                  name: "Small".to_string(),
+                                  value: 0,
+                                  doc: None,
+                                },
+                                CppEnumValue {
+                                  name: "Large".to_string(),
+                                  value: 0xffffffff,
+                                  doc: None,
+                                }],
+                              &[]);
+  assert_eq!(enum_repr_type(&r), "u32");
+}
 
 impl RustSingleMethod {
+  /// Returns true if any argument of this method is an out-parameter
+  /// (see `Config::add_out_parameter`).
+  fn has_out_parameters(&self) -> bool {
+    self
+      .arguments
+      .arguments
+      .iter()
+      .any(|arg| arg.argument_type.rust_api_to_c_conversion == RustToCTypeConversion::OutValueToPtr)
+  }
+
   /// Converts this method to a final Rust method
   /// without overloading.
   fn to_rust_method(&self) -> RustMethod {
@@ -2270,6 +9117,10 @@ impl RustSingleMethod {
         Vec::new()
       },
       common_doc: None,
+      doc_override: None,
+      cfg: None,
+      deprecated: None,
+      cpp_doc_url: None,
       is_unsafe: self.is_unsafe,
       scope: self.scope.clone(),
     }
@@ -2427,3 +9278,958 @@ impl RustSingleMethod {
     Ok(result)
   }
 }
+
+#[test]
+fn convert_string_argument_test() {
+  let string_type = |is_const: bool| {
+    let cpp_type = CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "QString".to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: CppTypeIndirection::Ref,
+      is_const: is_const,
+      is_const2: false,
+    };
+    let rust_ffi_type = RustType::Common {
+      base: RustName::new(vec!["qt_core".to_string(), "string".to_string(), "String".to_string()])
+        .unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ptr,
+      is_const: is_const,
+      is_const2: false,
+    };
+    let mut rust_api_type = rust_ffi_type.clone();
+    if let RustType::Common { ref mut indirection, .. } = rust_api_type {
+      *indirection = RustTypeIndirection::Ref { lifetime: None };
+    }
+    CompleteType {
+      cpp_ffi_type: CppType {
+        indirection: CppTypeIndirection::Ptr,
+        ..cpp_type.clone()
+      },
+      cpp_type: cpp_type,
+      cpp_to_ffi_conversion: CppIndirectionChange::ReferenceToPointer,
+      rust_ffi_type: rust_ffi_type,
+      rust_api_type: rust_api_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::RefToPtr,
+    }
+  };
+  let string_types = vec!["QString".to_string()];
+
+  let mut type1 = string_type(true);
+  convert_string_argument(&mut type1, &string_types).unwrap();
+  assert_eq!(type1.rust_api_to_c_conversion,
+             RustToCTypeConversion::ToCppRefToPtr);
+  assert_eq!(type1.rust_api_type,
+             RustType::Common {
+               base: RustName::new(vec!["cpp_utils".to_string(), "ToCppRef".to_string()])
+                 .unwrap(),
+               generic_arguments: Some(vec![RustType::Common {
+                                              base: RustName::new(vec!["qt_core".to_string(),
+                                                                       "string".to_string(),
+                                                                       "String".to_string()])
+                                                .unwrap(),
+                                              generic_arguments: None,
+                                              indirection: RustTypeIndirection::None,
+                                              is_const: false,
+                                              is_const2: false,
+                                            }]),
+               indirection: RustTypeIndirection::None,
+               is_const: false,
+               is_const2: false,
+             });
+
+  // non-const references are not converted
+  let mut type2 = string_type(false);
+  convert_string_argument(&mut type2, &string_types).unwrap();
+  assert_eq!(type2, string_type(false));
+
+  let mut type3 = string_type(true);
+  convert_string_argument(&mut type3, &[]).unwrap();
+  assert_eq!(type3, string_type(true));
+
+  // arguments of overloaded methods are not converted because
+  // generic types can't be used in implementations of parameters traits
+  let cpp_data = Default::default();
+  let mut config = test_config();
+  config.set_string_types(vec!["QString"]);
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  let int_type = CompleteType {
+    cpp_type: CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+    cpp_ffi_type: CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+    cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+    rust_ffi_type: RustType::Common {
+      base: RustName::new(vec!["libc".to_string(), "c_int".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+    rust_api_type: RustType::Common {
+      base: RustName::new(vec!["libc".to_string(), "c_int".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+    rust_api_to_c_conversion: RustToCTypeConversion::None,
+  };
+  let method = |c_name: &str, arg_type: CompleteType| {
+    let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+    cpp_method.name = "foo".to_string();
+    RustSingleMethod {
+      scope: RustMethodScope::Free,
+      is_unsafe: false,
+      name: RustName::new(vec!["crate1".to_string(), "foo".to_string()]).unwrap(),
+      arguments: RustMethodArgumentsVariant {
+        arguments: vec![RustMethodArgument {
+                          argument_type: arg_type.clone(),
+                          name: "arg".to_string(),
+                          ffi_index: 0,
+                        }],
+        cpp_method: CppAndFfiMethod {
+          cpp_method: cpp_method,
+          kind: CppFfiMethodKind::Real,
+          allocation_place: ReturnValueAllocationPlace::NotApplicable,
+          c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+            arguments: vec![::cpp_ffi_data::CppFfiMethodArgument {
+                              name: "arg".to_string(),
+                              argument_type: CppFfiType {
+                                original_type: arg_type.cpp_type.clone(),
+                                ffi_type: arg_type.cpp_ffi_type.clone(),
+                                conversion: arg_type.cpp_to_ffi_conversion.clone(),
+                              },
+                              meaning: CppFfiArgumentMeaning::Argument(0),
+                            }],
+            return_type: CppFfiType::void(),
+          },
+          c_name: c_name.to_string(),
+        },
+        return_type_ffi_index: None,
+        validity_check_function: None,
+        return_type: CompleteType {
+          cpp_type: CppType::void(),
+          cpp_ffi_type: CppType::void(),
+          cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+          rust_ffi_type: RustType::EmptyTuple,
+          rust_api_type: RustType::EmptyTuple,
+          rust_api_to_c_conversion: RustToCTypeConversion::None,
+        },
+      },
+      doc: None,
+    }
+  };
+  let (rust_method, _) = generator
+    .generate_final_method(vec![method("crate1_foo", string_type(true))],
+                           &RustMethodScope::Free,
+                           None)
+    .unwrap();
+  match rust_method.arguments {
+    RustMethodArguments::SingleVariant(ref variant) => {
+      assert_eq!(variant.arguments[0].argument_type.rust_api_to_c_conversion,
+                 RustToCTypeConversion::ToCppRefToPtr);
+    }
+    _ => panic!("single variant expected"),
+  }
+  let (_, declaration) = generator
+    .generate_final_method(vec![method("crate1_foo", string_type(true)),
+                                method("crate1_foo1", int_type)],
+                           &RustMethodScope::Free,
+                           None)
+    .unwrap();
+  match declaration.unwrap().kind {
+    RustTypeDeclarationKind::MethodParametersTrait { ref impls, .. } => {
+      let string_variant = impls
+        .iter()
+        .find(|v| v.cpp_method.c_name == "crate1_foo")
+        .unwrap();
+      assert_eq!(string_variant.arguments[0].argument_type.rust_api_to_c_conversion,
+                 RustToCTypeConversion::RefToPtr);
+    }
+    _ => panic!("parameters trait expected"),
+  }
+}
+
+#[test]
+fn convert_qstring_return_test() {
+  let qstring_type = |indirection: CppTypeIndirection, is_const: bool| {
+    let cpp_type = CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "QString".to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: indirection.clone(),
+      is_const: is_const,
+      is_const2: false,
+    };
+    let rust_ffi_type = RustType::Common {
+      base: RustName::new(vec!["qt_core".to_string(), "string".to_string(), "String".to_string()])
+        .unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ptr,
+      is_const: is_const,
+      is_const2: false,
+    };
+    let mut rust_api_type = rust_ffi_type.clone();
+    if let RustType::Common { indirection: ref mut api_indirection, .. } = rust_api_type {
+      *api_indirection = if indirection == CppTypeIndirection::Ref {
+        RustTypeIndirection::Ref { lifetime: None }
+      } else {
+        RustTypeIndirection::None
+      };
+    }
+    CompleteType {
+      cpp_ffi_type: CppType {
+        indirection: CppTypeIndirection::Ptr,
+        ..cpp_type.clone()
+      },
+      cpp_type: cpp_type,
+      cpp_to_ffi_conversion: if indirection == CppTypeIndirection::Ref {
+        CppIndirectionChange::ReferenceToPointer
+      } else {
+        CppIndirectionChange::ValueToPointer
+      },
+      rust_ffi_type: rust_ffi_type,
+      rust_api_type: rust_api_type,
+      rust_api_to_c_conversion: if indirection == CppTypeIndirection::Ref {
+        RustToCTypeConversion::RefToPtr
+      } else {
+        RustToCTypeConversion::ValueToPtr
+      },
+    }
+  };
+  let std_string = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "string".to_string(), "String".to_string()])
+      .unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+
+  let mut type1 = qstring_type(CppTypeIndirection::None, false);
+  convert_qstring_return(&mut type1).unwrap();
+  assert_eq!(type1.rust_api_to_c_conversion,
+             RustToCTypeConversion::QStringToString);
+  assert_eq!(type1.rust_api_type, std_string);
+  // the FFI type is not changed
+  assert_eq!(type1.rust_ffi_type,
+             qstring_type(CppTypeIndirection::None, false).rust_ffi_type);
+
+  let mut type2 = qstring_type(CppTypeIndirection::Ref, true);
+  convert_qstring_return(&mut type2).unwrap();
+  assert_eq!(type2.rust_api_to_c_conversion,
+             RustToCTypeConversion::QStringToString);
+  assert_eq!(type2.rust_api_type, std_string);
+
+  // non-const references are not converted
+  let mut type3 = qstring_type(CppTypeIndirection::Ref, false);
+  convert_qstring_return(&mut type3).unwrap();
+  assert_eq!(type3, qstring_type(CppTypeIndirection::Ref, false));
+}
+
+#[test]
+fn convert_char_ptr_test() {
+  let char_ptr_type = |is_const: bool| {
+    let cpp_type = CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+      indirection: CppTypeIndirection::Ptr,
+      is_const: is_const,
+      is_const2: false,
+    };
+    let rust_type = RustType::Common {
+      base: RustName::new(vec!["libc".to_string(), "c_char".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ptr,
+      is_const: is_const,
+      is_const2: false,
+    };
+    CompleteType {
+      cpp_ffi_type: cpp_type.clone(),
+      cpp_type: cpp_type,
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: rust_type.clone(),
+      rust_api_type: rust_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::None,
+    }
+  };
+  let mut type1 = char_ptr_type(true);
+  convert_char_ptr(&mut type1).unwrap();
+  assert_eq!(type1.rust_api_to_c_conversion,
+             RustToCTypeConversion::CharPtrToCStr);
+  assert_eq!(type1.rust_api_type,
+             RustType::Common {
+               base: RustName::new(vec!["std".to_string(), "ffi".to_string(), "CStr".to_string()])
+                 .unwrap(),
+               generic_arguments: None,
+               indirection: RustTypeIndirection::Ref { lifetime: None },
+               is_const: true,
+               is_const2: false,
+             });
+  assert_eq!(type1.rust_ffi_type, char_ptr_type(true).rust_ffi_type);
+
+  // mutable buffers are not converted
+  let mut type2 = char_ptr_type(false);
+  convert_char_ptr(&mut type2).unwrap();
+  assert_eq!(type2, char_ptr_type(false));
+}
+
+#[test]
+fn convert_wchar_ptr_test() {
+  let wchar_ptr_type = || {
+    let cpp_type = CppType {
+      base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::WChar),
+      indirection: CppTypeIndirection::Ptr,
+      is_const: true,
+      is_const2: false,
+    };
+    let rust_type = RustType::Common {
+      base: RustName::new(vec!["libc".to_string(), "wchar_t".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ptr,
+      is_const: true,
+      is_const2: false,
+    };
+    CompleteType {
+      cpp_ffi_type: cpp_type.clone(),
+      cpp_type: cpp_type,
+      cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+      rust_ffi_type: rust_type.clone(),
+      rust_api_type: rust_type,
+      rust_api_to_c_conversion: RustToCTypeConversion::None,
+    }
+  };
+  let std_ffi_type = |name: &str, indirection: RustTypeIndirection| {
+    RustType::Common {
+      base: RustName::new(vec!["std".to_string(), "ffi".to_string(), name.to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: indirection,
+      is_const: true,
+      is_const2: false,
+    }
+  };
+
+  let mut arg_type = wchar_ptr_type();
+  convert_wchar_ptr(&mut arg_type, false, target::Family::Windows).unwrap();
+  assert_eq!(arg_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::WCharPtrToOsString);
+  assert_eq!(arg_type.rust_api_type,
+             std_ffi_type("OsStr", RustTypeIndirection::Ref { lifetime: None }));
+  assert_eq!(arg_type.rust_ffi_type, wchar_ptr_type().rust_ffi_type);
+
+  let mut return_type = wchar_ptr_type();
+  convert_wchar_ptr(&mut return_type, true, target::Family::Windows).unwrap();
+  assert_eq!(return_type.rust_api_to_c_conversion,
+             RustToCTypeConversion::WCharPtrToOsString);
+  if let RustType::Common { ref base, .. } = return_type.rust_api_type {
+    assert_eq!(base.full_name(None), "::std::ffi::OsString");
+  } else {
+    panic!("RustType::Common expected");
+  }
+  assert_eq!(return_type.rust_ffi_type, wchar_ptr_type().rust_ffi_type);
+
+  // wchar_t is not UTF-16 on Unix
+  for &is_return_type in &[false, true] {
+    let mut type1 = wchar_ptr_type();
+    convert_wchar_ptr(&mut type1, is_return_type, target::Family::Unix).unwrap();
+    assert_eq!(type1, wchar_ptr_type());
+  }
+}
+
+#[test]
+fn add_return_lifetime_test() {
+  let ref_type = |name: &str, lifetime: Option<&str>| {
+    RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), name.to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ref { lifetime: lifetime.map(|x| x.to_string()) },
+      is_const: true,
+      is_const2: false,
+    }
+  };
+  let argument = |name: &str, rust_type: RustType| {
+    RustMethodArgument {
+      argument_type: CompleteType {
+        cpp_type: CppType::void(),
+        cpp_ffi_type: CppType::void(),
+        cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+        rust_ffi_type: rust_type.clone(),
+        rust_api_type: rust_type,
+        rust_api_to_c_conversion: RustToCTypeConversion::RefToPtr,
+      },
+      name: name.to_string(),
+      ffi_index: 0,
+    }
+  };
+
+  // getter with only `this` argument
+  let mut args = vec![argument("self", ref_type("Class1", None))];
+  let mut return_type = ref_type("Class2", None);
+  add_return_lifetime(&mut args, &mut return_type, "");
+  assert_eq!(return_type, ref_type("Class2", Some("l0")));
+  assert_eq!(args[0].argument_type.rust_api_type,
+             ref_type("Class1", Some("l0")));
+
+  // `self` is preferred over other arguments with lifetimes
+  let mut args = vec![argument("self", ref_type("Class1", None)),
+                      argument("arg1", ref_type("Class3", Some("a")))];
+  let mut return_type = ref_type("Class2", None);
+  add_return_lifetime(&mut args, &mut return_type, "");
+  assert_eq!(return_type, ref_type("Class2", Some("l0")));
+
+  // no candidates
+  let mut args = Vec::new();
+  let mut return_type = ref_type("Class2", None);
+  add_return_lifetime(&mut args, &mut return_type, "");
+  assert_eq!(return_type, ref_type("Class2", Some("static")));
+}
+
+#[test]
+fn stream_write_operator_test() {
+  use cpp_method::CppMethodArgument;
+
+  let cpp_data = Default::default();
+  let class_info = |cpp_name: &str, rust_name: &str| {
+    RustProcessedTypeInfo {
+      cpp_name: cpp_name.to_string(),
+      cpp_doc: None,
+      cpp_template_arguments: None,
+      kind: RustTypeWrapperKind::Struct {
+        size_const_name: None,
+        is_deletable: true,
+        slot_wrapper: None,
+      },
+      rust_name: RustName::new(vec!["crate1".to_string(),
+                                    "module1".to_string(),
+                                    rust_name.to_string()])
+        .unwrap(),
+      is_public: true,
+    }
+  };
+  let processed_types = vec![class_info("QDataStream", "DataStream"),
+                             class_info("QPoint", "Point")];
+  let mut top_module_names = HashMap::new();
+  top_module_names.insert("module1".to_string(),
+                          RustName::new(vec!["crate1".to_string(), "module1".to_string()])
+                            .unwrap());
+  let config = test_config();
+  let generator = RustGenerator {
+    top_module_names: top_module_names,
+    ..test_generator(test_input_data(&cpp_data, &config), processed_types)
+  };
+  let class_type = |name: &str, is_const: bool| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: name.to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: CppTypeIndirection::Ref,
+      is_const: is_const,
+      is_const2: false,
+    }
+  };
+  // `QDataStream& operator<<(QDataStream& stream, const QPoint& point)`
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "operator<<".to_string();
+  cpp_method.include_file = "module1".to_string();
+  cpp_method.operator = Some(CppOperator::BitwiseLeftShift);
+  cpp_method.return_type = class_type("QDataStream", false);
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "stream".to_string(),
+                              argument_type: class_type("QDataStream", false),
+                              has_default_value: false,
+                            });
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "point".to_string(),
+                              argument_type: class_type("QPoint", true),
+                              has_default_value: false,
+                            });
+  assert!(is_stream_write_operator(&cpp_method));
+  let method = CppAndFfiMethod {
+    c_signature: cpp_method
+      .c_signature(ReturnValueAllocationPlace::NotApplicable)
+      .unwrap(),
+    cpp_method: cpp_method,
+    kind: CppFfiMethodKind::Real,
+    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+    c_name: "crate1_operator_shl".to_string(),
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&method, &RustMethodScope::Free, false)
+    .unwrap();
+
+  let write_method = generator
+    .process_stream_write_method(&rust_method)
+    .unwrap();
+  assert_eq!(write_method.name, RustName::new(vec!["write_to".to_string()]).unwrap());
+  match write_method.scope {
+    RustMethodScope::Impl { target_type: RustType::Common { ref base, ref indirection, .. } } => {
+      assert_eq!(base.last_name().unwrap(), "Point");
+      assert_eq!(indirection, &RustTypeIndirection::None);
+    }
+    _ => panic!("invalid scope"),
+  }
+  if let RustMethodArguments::SingleVariant(ref variant) = write_method.arguments {
+    assert_eq!(variant.arguments[0].name, "self");
+    assert_eq!(variant.arguments[0].ffi_index, 1);
+    assert_eq!(variant.arguments[1].name, "stream");
+  } else {
+    panic!("single variant expected");
+  }
+
+  let trait_impl = generator
+    .process_stream_write_operator(&rust_method)
+    .unwrap();
+  match trait_impl.trait_type {
+    RustType::Common { ref base, .. } => assert_eq!(base.last_name().unwrap(), "Shl"),
+    _ => panic!("invalid trait type"),
+  }
+  assert_eq!(trait_impl.target_type.lifetime(), Some(&"a".to_string()));
+  assert_eq!(trait_impl.associated_types[0].value, trait_impl.target_type);
+}
+
+#[test]
+fn string_from_utf8_test() {
+  use cpp_method::CppMethodArgument;
+
+  let cpp_data = Default::default();
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "QString".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("QSTRING_SIZE".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: RustName::new(vec!["crate1".to_string(),
+                                                             "string".to_string(),
+                                                             "String".to_string()])
+                                 .unwrap(),
+                               is_public: true,
+                             }];
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), processed_types);
+  let string_type = RustType::Common {
+    base: RustName::new(vec!["crate1".to_string(), "string".to_string(), "String".to_string()])
+      .unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let scope = RustMethodScope::Impl { target_type: string_type.clone() };
+  // `static QString QString::fromUtf8(const char* str, int size)`
+  let mut cpp_method = ::tests::cpp_method::empty_regular_method();
+  cpp_method.name = "fromUtf8".to_string();
+  cpp_method.class_membership = Some({
+                                       let mut info =
+                                         ::tests::cpp_method::empty_membership("QString");
+                                       info.is_static = true;
+                                       info
+                                     });
+  cpp_method.return_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "QString".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "str".to_string(),
+                              argument_type: CppType {
+                                base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+                                indirection: CppTypeIndirection::Ptr,
+                                is_const: true,
+                                is_const2: false,
+                              },
+                              has_default_value: false,
+                            });
+  cpp_method.arguments.push(CppMethodArgument {
+                              name: "size".to_string(),
+                              argument_type: CppType {
+                                base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                                indirection: CppTypeIndirection::None,
+                                is_const: false,
+                                is_const2: false,
+                              },
+                              has_default_value: false,
+                            });
+  let method = |allocation_place: ReturnValueAllocationPlace| {
+    CppAndFfiMethod {
+      c_signature: cpp_method.c_signature(allocation_place.clone()).unwrap(),
+      cpp_method: cpp_method.clone(),
+      kind: CppFfiMethodKind::Real,
+      allocation_place: allocation_place,
+      c_name: "crate1_QString_fromUtf8".to_string(),
+    }
+  };
+  let rust_method = generator
+    .generate_rust_single_method(&method(ReturnValueAllocationPlace::Stack), &scope, false)
+    .unwrap();
+  let impls = generator.process_string_from_utf8(&rust_method).unwrap();
+  assert_eq!(impls.len(), 2);
+  assert_eq!(impls[0].target_type, string_type);
+  assert_eq!(impls[1].target_type, impls[0].target_type);
+  assert_eq!(impls[0].extra,
+             Some(TraitImplExtra::StringFromUtf8 {
+                    ffi_function_name: "crate1_QString_fromUtf8".to_string(),
+                  }));
+  assert_eq!(impls[1].extra, Some(TraitImplExtra::StringFromStdString));
+  match impls[0].trait_type {
+    RustType::Common { ref base, ref generic_arguments, .. } => {
+      assert_eq!(base.last_name().unwrap(), "From");
+      let args = generic_arguments.as_ref().unwrap();
+      assert_eq!(args[0].lifetime(), Some(&"a".to_string()));
+    }
+    _ => panic!("invalid trait type"),
+  }
+
+  // `From` can't be implemented for `CppBox`
+  let heap_method = generator
+    .generate_rust_single_method(&method(ReturnValueAllocationPlace::Heap), &scope, false)
+    .unwrap();
+  assert!(generator.process_string_from_utf8(&heap_method).is_err());
+}
+
+#[test]
+fn list_element_type_test() {
+  let cpp_data = Default::default();
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  // `QList<int>`
+  let info = RustProcessedTypeInfo {
+    cpp_name: "QList".to_string(),
+    cpp_doc: None,
+    cpp_template_arguments: Some(vec![CppType {
+                                        base:
+                                          CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                                        indirection: CppTypeIndirection::None,
+                                        is_const: false,
+                                        is_const2: false,
+                                      }]),
+    kind: RustTypeWrapperKind::Struct {
+      size_const_name: None,
+      is_deletable: true,
+      slot_wrapper: None,
+    },
+    rust_name: RustName::new(vec!["crate1".to_string(),
+                                  "list".to_string(),
+                                  "ListCInt".to_string()])
+      .unwrap(),
+    is_public: true,
+  };
+  assert_eq!(generator.list_element_type(&info),
+             Some(RustType::Common {
+                    base: RustName::new(vec!["libc".to_string(), "c_int".to_string()]).unwrap(),
+                    generic_arguments: None,
+                    indirection: RustTypeIndirection::None,
+                    is_const: false,
+                    is_const2: false,
+                  }));
+  assert_eq!(generator.vector_info(&info), None);
+
+  // iterator adapter of an iterable type also provides `iter()`
+  let mut iterable_config = test_config();
+  iterable_config.add_iterable_type("QList");
+  let generator = test_generator(test_input_data(&cpp_data, &iterable_config), Vec::new());
+  assert_eq!(generator.list_element_type(&info), None);
+}
+
+#[test]
+fn vector_info_test() {
+  use cpp_method::{CppMethodArgument, CppMethodKind};
+
+  let class1 = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let class1_rust_name = RustName::new(vec!["crate1".to_string(), "Class1".to_string()]).unwrap();
+  let processed_types = vec![RustProcessedTypeInfo {
+                               cpp_name: "Class1".to_string(),
+                               cpp_doc: None,
+                               cpp_template_arguments: None,
+                               kind: RustTypeWrapperKind::Struct {
+                                 size_const_name: Some("CLASS1".to_string()),
+                                 is_deletable: true,
+                                 slot_wrapper: None,
+                               },
+                               rust_name: class1_rust_name.clone(),
+                               is_public: true,
+                             }];
+  // `std::vector<Class1>`
+  let info = RustProcessedTypeInfo {
+    cpp_name: "std::vector".to_string(),
+    cpp_doc: None,
+    cpp_template_arguments: Some(vec![class1.clone()]),
+    kind: RustTypeWrapperKind::Struct {
+      size_const_name: None,
+      is_deletable: true,
+      slot_wrapper: None,
+    },
+    rust_name: RustName::new(vec!["crate1".to_string(), "VectorClass1".to_string()]).unwrap(),
+    is_public: true,
+  };
+  // `push_back(T&&)` only
+  let mut push_back = ::tests::cpp_method::empty_regular_method();
+  push_back.name = "push_back".to_string();
+  let mut membership = ::tests::cpp_method::empty_membership("std::vector");
+  membership.class_type.template_arguments = Some(vec![class1.clone()]);
+  push_back.class_membership = Some(membership);
+  push_back.arguments.push(CppMethodArgument {
+                             name: "value".to_string(),
+                             argument_type: CppType {
+                               indirection: CppTypeIndirection::RValueRef,
+                               ..class1.clone()
+                             },
+                             has_default_value: false,
+                           });
+  let header = ::cpp_ffi_data::CppFfiHeaderData {
+    include_file_base_name: "vector".to_string(),
+    methods: vec![CppAndFfiMethod {
+                    cpp_method: push_back,
+                    kind: CppFfiMethodKind::Real,
+                    allocation_place: ReturnValueAllocationPlace::NotApplicable,
+                    c_signature: ::cpp_ffi_data::CppFfiMethodSignature {
+                      arguments: Vec::new(),
+                      return_type: CppFfiType::void(),
+                    },
+                    c_name: "crate1_vector_push_back".to_string(),
+                  }],
+    qt_slot_wrappers: Vec::new(),
+  };
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  let config = test_config();
+  {
+    let input_data = RustGeneratorInputData {
+      cpp_ffi_headers: vec![header.clone()],
+      ..test_input_data(&cpp_data, &config)
+    };
+    let generator = test_generator(input_data, processed_types.clone());
+    let vector_info = generator.vector_info(&info).unwrap();
+    match vector_info.element_type {
+      RustType::Common { ref base, ref indirection, .. } => {
+        assert_eq!(base, &class1_rust_name);
+        assert_eq!(indirection, &RustTypeIndirection::None);
+      }
+      _ => panic!("class type expected"),
+    }
+    // `Class1` has no copy constructor
+    assert!(!vector_info.element_is_clone);
+    assert!(!vector_info.has_copy_push_back);
+    assert!(vector_info.has_move_push_back);
+  }
+
+  let mut copy_constructor = ::tests::cpp_method::empty_regular_method();
+  copy_constructor.name = "Class1".to_string();
+  let mut membership = ::tests::cpp_method::empty_membership("Class1");
+  membership.kind = CppMethodKind::Constructor;
+  copy_constructor.class_membership = Some(membership);
+  copy_constructor.arguments.push(CppMethodArgument {
+                                    name: "other".to_string(),
+                                    argument_type: CppType {
+                                      indirection: CppTypeIndirection::Ref,
+                                      is_const: true,
+                                      ..class1.clone()
+                                    },
+                                    has_default_value: false,
+                                  });
+  cpp_data.current.parser.methods = vec![copy_constructor];
+  let input_data = RustGeneratorInputData {
+    cpp_ffi_headers: vec![header],
+    ..test_input_data(&cpp_data, &config)
+  };
+  let generator = test_generator(input_data, processed_types);
+  assert!(generator.vector_info(&info).unwrap().element_is_clone);
+}
+
+#[test]
+fn module_tree_hook_test() {
+  use cpp_data::{CppTypeData, CppOriginLocation};
+  use common::file_utils::{file_to_string, PathBufWithAdded};
+
+  let mut cpp_data: CppDataWithDeps = Default::default();
+  cpp_data
+    .current
+    .parser
+    .types
+    .push(CppTypeData {
+            name: "Enum1".to_string(),
+            include_file: "module1.h".to_string(),
+            origin_location: CppOriginLocation {
+              include_file_path: "module1.h".to_string(),
+              line: 1,
+              column: 1,
+            },
+            kind: CppTypeKind::Enum {
+              values: vec![CppEnumValue {
+                             name: "Value1".to_string(),
+                             value: 1,
+                             doc: None,
+                           }],
+            },
+            doc: None,
+          });
+  let config = test_config();
+  let output = test_input_data(&cpp_data, &config).run().unwrap();
+  assert_eq!(output.modules.len(), 1);
+  assert_eq!(output.modules[0].name, "module1");
+
+  let mut config = test_config();
+  config.add_module_tree_hook(|modules: &mut Vec<RustModule>| {
+                                for module in modules {
+                                  module.doc = Some("Custom module doc".to_string());
+                                }
+                                Ok(())
+                              });
+  let output = test_input_data(&cpp_data, &config).run().unwrap();
+  let dir = ::tests::TempTestDir::new("test_module_tree_hook");
+  let output_path = ::tests::rust_code_generator::run_code_generator(&dir,
+                                                                     output.modules,
+                                                                     |_| {});
+  let lib_code = file_to_string(output_path.with_added("src").with_added("lib.rs")).unwrap();
+  assert!(lib_code.contains("/// Custom module doc\npub mod module1;\n"));
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub enum Enum1 {"));
+
+  // other items refer to the type by its full name, so the module can't be renamed
+  let mut config = test_config();
+  config.add_module_tree_hook(|modules: &mut Vec<RustModule>| {
+                                for module in modules {
+                                  if module.name == "module1" {
+                                    module.name = "renamed".to_string();
+                                  }
+                                }
+                                Ok(())
+                              });
+  assert!(test_input_data(&cpp_data, &config).run().is_err());
+
+  // removed types are reported as skipped
+  let mut config = test_config();
+  config.add_module_tree_hook(|modules: &mut Vec<RustModule>| {
+                                modules.clear();
+                                Ok(())
+                              });
+  let output = test_input_data(&cpp_data, &config).run().unwrap();
+  assert!(output.processed_types.is_empty());
+  assert_eq!(output.type_manifest,
+             vec![TypeManifestItem {
+                    cpp_type: "Enum1".to_string(),
+                    status: TypeManifestStatus::Skipped("type is not declared in any module"
+                                                          .to_string()),
+                  }]);
+}
+
+#[test]
+fn is_padding_free_layout_test() {
+  let field = |base: CppBuiltInNumericType, size: usize| {
+    CppClassField {
+      name: "field1".to_string(),
+      field_type: CppType {
+        base: CppTypeBase::BuiltInNumeric(base),
+        indirection: CppTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      },
+      visibility: CppVisibility::Private,
+      size: Some(size),
+    }
+  };
+  assert!(is_padding_free_layout(&[]));
+  assert!(is_padding_free_layout(&[field(CppBuiltInNumericType::Int, 4),
+                                   field(CppBuiltInNumericType::Int, 4)]));
+  assert!(is_padding_free_layout(&[field(CppBuiltInNumericType::Short, 2),
+                                   field(CppBuiltInNumericType::Short, 2),
+                                   field(CppBuiltInNumericType::Int, 4)]));
+  // padding after the field
+  assert!(!is_padding_free_layout(&[field(CppBuiltInNumericType::Int, 4),
+                                    field(CppBuiltInNumericType::Char, 1)]));
+  // padding between the fields
+  assert!(!is_padding_free_layout(&[field(CppBuiltInNumericType::Char, 1),
+                                    field(CppBuiltInNumericType::Int, 4)]));
+  assert!(!is_padding_free_layout(&[field(CppBuiltInNumericType::Double, 8)]));
+  let mut unknown_size = field(CppBuiltInNumericType::Int, 4);
+  unknown_size.size = None;
+  assert!(!is_padding_free_layout(&[unknown_size]));
+}
+
+#[test]
+fn remove_pod_trait_impls_test() {
+  let trait_impl = |name: &str| {
+    TraitImpl {
+      target_type: RustType::Common {
+        base: RustName::new(vec!["crate1".to_string(), "Point1".to_string()]).unwrap(),
+        generic_arguments: None,
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      },
+      associated_types: Vec::new(),
+      trait_type: RustType::Common {
+        base: RustName::new(name.split("::").map(|x| x.to_string()).collect()).unwrap(),
+        generic_arguments: None,
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      },
+      extra: None,
+      methods: Vec::new(),
+    }
+  };
+  let mut trait_impls = vec![trait_impl("Clone"),
+                             trait_impl("Drop"),
+                             trait_impl("std::fmt::Debug")];
+  remove_pod_trait_impls(&mut trait_impls).unwrap();
+  assert_eq!(trait_impls, vec![trait_impl("std::fmt::Debug")]);
+}
+
+#[test]
+fn associated_constant_test() {
+  let cpp_data = Default::default();
+  let config = test_config();
+  let generator = test_generator(test_input_data(&cpp_data, &config), Vec::new());
+  let constant_value = |numeric: CppBuiltInNumericType, value: i64| -> String {
+    let field = CppClassStaticField {
+      name: "value".to_string(),
+      field_type: CppType {
+        base: CppTypeBase::BuiltInNumeric(numeric),
+        indirection: CppTypeIndirection::None,
+        is_const: true,
+        is_const2: false,
+      },
+      visibility: CppVisibility::Public,
+      value: Some(value),
+    };
+    generator.associated_constant(&field, value).unwrap().value
+  };
+  assert_eq!(constant_value(CppBuiltInNumericType::Int, -1), "-1");
+  assert_eq!(constant_value(CppBuiltInNumericType::UInt, 16), "16");
+  // negative literals of unsigned types are converted as in C++
+  assert_eq!(constant_value(CppBuiltInNumericType::UInt, -1), "-1i64 as _");
+  assert_eq!(constant_value(CppBuiltInNumericType::UChar, 300), "300i64 as _");
+  // signedness of `char` depends on the target
+  assert_eq!(constant_value(CppBuiltInNumericType::Char, -1), "-1i64 as _");
+  assert_eq!(constant_value(CppBuiltInNumericType::Char, 1), "1");
+  assert_eq!(constant_value(CppBuiltInNumericType::LongLong, ::std::i64::MIN),
+             ::std::i64::MIN.to_string());
+  assert_eq!(constant_value(CppBuiltInNumericType::Bool, 1), "true");
+}
@@ -21,6 +21,11 @@ pub struct RustEnumValue {
   /// True if this variant was added because enums with
   /// one variant are not supported
   pub is_dummy: bool,
+  /// True if this variant corresponds to a C++ variant blocked with
+  /// `Config::add_cpp_parser_blocked_enum_value`. It's hidden from
+  /// documentation but kept because C++ code may still produce its value.
+  #[serde(default)]
+  pub is_hidden: bool,
 }
 
 
@@ -40,6 +45,8 @@ pub struct CppEnumValueDocItem {
 pub struct RustQtSlotWrapper {
   /// Argument types of the slot
   pub arguments: Vec<CompleteType>,
+  /// Return type of the slot
+  pub return_type: CompleteType,
   /// Identifier of the slot for `QObject::connect`
   pub receiver_id: String,
   /// Name of the public Rust struct of this wrapper
@@ -68,6 +75,8 @@ pub enum RustTypeWrapperKind {
     /// crate compile time.
     /// If `None`, this struct can only be used as pointer, like an
     /// empty enum.
+    /// Alignment of the struct is set by `type_sizes.rs` as well
+    /// (see `align_type_name`).
     size_const_name: Option<String>,
     /// True if `CppDeletable` trait is implemented
     /// for this type, i.e. if this C++ type has public destructor
@@ -172,6 +181,10 @@ pub struct RustMethodArgumentsVariant {
   pub return_type_ffi_index: Option<usize>,
   /// C++ and Rust return types at all levels.
   pub return_type: CompleteType,
+  /// Name of the FFI function of the method checking validity of the object
+  /// returned by a fallible constructor (see `Config::add_fallible_constructor`).
+  /// If set, the Rust API return type is wrapped in `Result`.
+  pub validity_check_function: Option<String>,
 }
 
 /// Arguments of a Rust method
@@ -215,6 +228,18 @@ pub struct RustMethod {
   pub variant_docs: Vec<RustMethodDocItem>,
   /// Rustdoc content that will appear before documentation for variants.
   pub common_doc: Option<String>,
+  /// Documentation returned by functions added with `Config::add_doc_hook`.
+  /// If set, it's used instead of the automatically generated documentation.
+  pub doc_override: Option<String>,
+  /// Configuration predicate (e.g. `target_os = "windows"`) the method
+  /// is conditionally compiled with (see `Config::add_platform_method`).
+  pub cfg: Option<String>,
+  /// Deprecation note set with `Config::add_deprecated_method`
+  /// if the method is deprecated.
+  pub deprecated: Option<String>,
+  /// URL of the documentation of the corresponding C++ method
+  /// (see `Config::set_cpp_doc_base_url`).
+  pub cpp_doc_url: Option<String>,
 }
 
 /// Information about type of `self` argument of the method.
@@ -236,6 +261,53 @@ pub enum TraitImplExtra {
   /// For `CppDeletable` trait implementation,
   /// `deleter_name` contains name of the FFI function used as deleter.
   CppDeletable { deleter_name: String },
+  /// For `std::hash::Hash` trait implementation based on
+  /// a `qHash` function, `ffi_function_name` contains name of
+  /// the FFI function wrapping `qHash`. If `has_seed` is true,
+  /// the function has second `seed` argument.
+  QtHashFunction {
+    ffi_function_name: String,
+    has_seed: bool,
+  },
+  /// For `std::fmt::Debug` trait implementation, `to_string_call`
+  /// contains Rust code of the call of the `to_string` method
+  /// (without `self.`), or `None` if the method is not available.
+  Debug { to_string_call: Option<String> },
+  /// For `std::fmt::Display` trait implementation, `call`
+  /// contains Rust code of the call of the method returning
+  /// displayed value (without `self.`).
+  Display { call: String },
+  /// For `std::convert::TryFrom` implementation converting a reference
+  /// to a base class into a reference to a derived class using `DynamicCast`,
+  /// or `DynamicCastResult` if `uses_cast_result` is true
+  /// (see `Config::set_cast_error_type`).
+  DynamicCastTryFrom { uses_cast_result: bool },
+  /// For `From<E>` implementation for `qt_core::flags::Flags<E>`,
+  /// where `E` is a flaggable enum.
+  FlagsFromEnum,
+  /// For `std::cmp::Ord` implementation of a type marked with
+  /// `Config::mark_total_order` that delegates to `PartialOrd`.
+  OrdFromPartialOrd,
+  /// For `std::convert::TryFrom` implementation based on an `explicit`
+  /// narrowing conversion operator, `ffi_function_name` contains name of
+  /// the FFI function wrapping the operator.
+  NarrowingConversionTryFrom { ffi_function_name: String },
+  /// For `std::ops::Shl` implementation for a mutable reference to
+  /// a stream based on a free `operator<<` function, `ffi_function_name`
+  /// contains name of the FFI function wrapping the operator.
+  StreamWriteOperator { ffi_function_name: String },
+  /// For `From<&str>` implementation for a string class
+  /// (see `Config::set_string_from_impls`), `ffi_function_name` contains
+  /// name of the FFI function wrapping `fromUtf8(const char*, int)`.
+  /// The function writes the created object to its last argument.
+  StringFromUtf8 { ffi_function_name: String },
+  /// For `From<String>` implementation for a string class
+  /// that delegates to its `From<&str>` implementation.
+  StringFromStdString,
+  /// For `cpp_utils::StaticUpcastOwned` implementation that allows
+  /// to convert `CppBox` of a derived class into `CppBox` of its base class.
+  /// The implementation is generated as `unsafe impl` without any items.
+  StaticUpcastOwned,
 }
 
 /// Information about an associated type value
@@ -289,6 +361,66 @@ pub struct RustQtReceiverDeclaration {
   pub arguments: Vec<RustType>,
 }
 
+/// Traits implemented for a type marked as POD with `Config::set_pod_type`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustPodTraits {
+  /// True if `PartialEq` is implemented using byte comparison.
+  pub partial_eq: bool,
+}
+
+/// Key and value types of a `QHash` instantiation.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustQtHashTypes {
+  /// Rust type of the keys.
+  pub key_type: RustType,
+  /// Rust type of the values.
+  pub value_type: RustType,
+}
+
+/// Element type and available operations of a `std::vector` instantiation.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustVectorInfo {
+  /// Rust type of the elements.
+  pub element_type: RustType,
+  /// True if the elements can be cloned.
+  pub element_is_clone: bool,
+  /// True if `push_back(const T&)` is available.
+  pub has_copy_push_back: bool,
+  /// True if `push_back(T&&)` is available.
+  pub has_move_push_back: bool,
+}
+
+/// Information required to generate an iterator adapter
+/// for a container type with `begin()` and `end()` methods.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustIteratorAdapter {
+  /// Name of the adapter struct.
+  pub name: String,
+  /// Rust type of the wrapper of the C++ iterator class.
+  pub iterator_type: RustType,
+  /// True if iterators returned by `begin()` and `end()` are wrapped in `CppBox`.
+  pub iterator_is_boxed: bool,
+  /// Rust type of the items produced by the adapter.
+  pub item_type: RustType,
+  /// Name of the FFI function of the iterator's `operator++`.
+  pub increment_function: String,
+  /// Name of the FFI function of the iterator's `operator*`.
+  pub indirection_function: String,
+  /// Name of the FFI function of the iterator's `operator!=`.
+  pub not_equal_function: String,
+}
+
+/// Associated constant generated from a C++ static const data member.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustAssociatedConstant {
+  /// Name of the constant.
+  pub name: String,
+  /// Rust type of the constant.
+  pub value_type: RustType,
+  /// Rust code of the value.
+  pub value: String,
+}
+
 /// Part of the information about a Rust type declaration.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub enum RustTypeDeclarationKind {
@@ -305,11 +437,36 @@ pub enum RustTypeDeclarationKind {
     cpp_doc: Option<CppTypeDoc>,
     /// Methods in direct `impl` for this type.
     methods: Vec<RustMethod>,
+    /// Associated constants in direct `impl` for this type.
+    constants: Vec<RustAssociatedConstant>,
     /// Trait implementations for this type.
     trait_impls: Vec<TraitImpl>,
     /// List of Qt receiver types for signals and slots of
     /// this C++ type.
     qt_receivers: Vec<RustQtReceiverDeclaration>,
+    /// Key and value types if this type is a `QHash` instantiation
+    /// that supports conversions to and from Rust collections.
+    qt_hash_types: Option<RustQtHashTypes>,
+    /// Element type and available operations if this type is a `std::vector`
+    /// instantiation that supports conversions to and from Rust collections.
+    vector_info: Option<RustVectorInfo>,
+    /// Rust type of the elements if this type is a `QList` or `QVector`
+    /// instantiation that supports index-based iteration.
+    list_element_type: Option<RustType>,
+    /// Traits implemented for this type if it was marked as POD.
+    pod_traits: Option<RustPodTraits>,
+    /// Rust type wrapped by this type if it was marked
+    /// with `Config::set_transparent_type`.
+    transparent_type: Option<RustType>,
+    /// Iterator adapter information if this type was marked
+    /// with `Config::add_iterable_type`.
+    iterator_adapter: Option<RustIteratorAdapter>,
+    /// Whether `Send` should be implemented for this type
+    /// (see `Config::mark_send`).
+    is_send: bool,
+    /// Whether `Sync` should be implemented for this type
+    /// (see `Config::mark_sync`).
+    is_sync: bool,
   },
   /// Information about a Rust trait created for overloading emulation.
   MethodParametersTrait {
@@ -346,6 +503,9 @@ pub struct RustTypeDeclaration {
   /// Additional documentation content that will appear before C++ documentation or any other
   /// automatically generated content.
   pub rust_doc: Option<String>,
+  /// Documentation returned by functions added with `Config::add_doc_hook`.
+  /// If set, it's used instead of the automatically generated documentation.
+  pub doc_override: Option<String>,
 }
 
 /// Information about a Rust module.
@@ -365,6 +525,25 @@ pub struct RustModule {
   pub doc: Option<String>,
   /// Submodules of this module.
   pub submodules: Vec<RustModule>,
+  /// Code produced by type plugins for types of this module
+  /// and code added by `Config::add_raw_trait_impl`.
+  pub plugin_code: Vec<String>,
+}
+
+/// Machine-readable list of public items of a generated Rust module.
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Serialize, Deserialize)]
+pub struct RustModuleInventory {
+  /// Full name of the module.
+  pub name: String,
+  /// Full names of public types.
+  pub types: Vec<String>,
+  /// Full names of public methods and free functions.
+  pub methods: Vec<String>,
+  /// Trait implementations in `impl Trait for Type` form.
+  pub trait_impls: Vec<String>,
+  /// Inventories of submodules.
+  pub submodules: Vec<RustModuleInventory>,
 }
 
 /// Information about a loaded dependency.
@@ -411,7 +590,8 @@ impl RustMethodCaptionStrategy {
 
 impl RustProcessedTypeInfo {
   /// Implements sanity check of the data.
-  /// Returns true if this type was properly declared within any of the modules.
+  /// Returns true if this type was properly declared within any of the modules
+  /// under its `rust_name`.
   pub fn is_declared_in(&self, modules: &[RustModule]) -> bool {
     for module in modules {
       if module
@@ -424,7 +604,8 @@ impl RustProcessedTypeInfo {
                     ..
                   } => {
                     cpp_type_name == &self.cpp_name &&
-                    cpp_template_arguments == &self.cpp_template_arguments
+                    cpp_template_arguments == &self.cpp_template_arguments &&
+                    t.name == self.rust_name
                   }
                   _ => false,
                 }) {
@@ -458,4 +639,7 @@ pub struct RustFFIFunction {
   pub name: String,
   /// Arguments of the function.
   pub arguments: Vec<RustFFIArgument>,
+  /// Configuration predicate the function is conditionally compiled with
+  /// if the wrapped C++ method is platform-specific.
+  pub cfg: Option<String>,
 }
@@ -30,12 +30,47 @@ pub enum RustToCTypeConversion {
   RefToPtr,
   /// `Option<&T>` to `*const T` (or similar mutable types)
   OptionRefToPtr,
+  /// `Result<&T, E>` to `*const T` (or similar mutable types).
+  /// Only used for return types. Null pointer is converted to
+  /// `Err(E::default())` (see `Config::set_cast_error_type`).
+  ResultRefToPtr,
   /// `T` to `*const T` (or similar mutable type)
   ValueToPtr,
   /// `CppBox<T>` to `*const T` (or similar mutable type)
   CppBoxToPtr,
+  /// `cpp_utils::MaybeOwned<T>` from `*mut T` returned by a constructor
+  /// with a `parent` argument (see `Config::set_parented_qobject_constructors`).
+  /// Only used for return types. The object is owned by the caller
+  /// if the passed parent is null.
+  MaybeOwnedToPtr,
   /// `qt_core::flags::Flags<T>` to `libc::c_uint`
   QFlagsToUInt,
+  /// `Option<T>` to `*const T` for `std::optional<T>` arguments
+  /// (`None` is passed as a null pointer). For return types,
+  /// a local variable is passed as the output argument, and
+  /// the returned `bool` indicates whether it was written.
+  OptionToPtr,
+  /// `Option<&CStr>` to `*const c_char`. `None` corresponds to a null pointer.
+  CharPtrToCStr,
+  /// `impl cpp_utils::ToCppRef<T>` to `*const T`, where `T` is a string class.
+  /// A temporary object is created if a Rust string is passed.
+  ToCppRefToPtr,
+  /// `Option<&OsStr>` to `*const wchar_t` (for arguments) or
+  /// `Option<OsString>` to `*const wchar_t` (for return types).
+  /// `None` corresponds to a null pointer.
+  /// Only used on Windows where `wchar_t` is UTF-16.
+  WCharPtrToOsString,
+  /// `T` to `*mut T` for out-parameters (see `Config::add_out_parameter`).
+  /// The argument is not present in the Rust API. A local variable
+  /// is passed instead, and its value is added to the return value.
+  OutValueToPtr,
+  /// `&mut T` to `*mut T` for return types of chainable setters
+  /// (see `Config::set_chainable_setters`). The returned pointer
+  /// is ignored and the `self` argument is returned instead.
+  RefToSelf,
+  /// `std::string::String` from a returned `QString` (by value or by const reference)
+  /// (see `Config::set_qstring_return_conversion`). Only used for return types.
+  QStringToString,
 }
 
 /// Information about a completely processed type
@@ -70,6 +105,9 @@ pub enum RustTypeIndirection {
   Ref { lifetime: Option<String> },
   /// Raw pointer to raw pointer
   PtrPtr,
+  /// Raw pointer to raw pointer to raw pointer.
+  /// The outermost pointer is const if `is_const3` is true.
+  PtrPtrPtr { is_const3: bool },
   /// Raw pointer to reference
   PtrRef { lifetime: Option<String> },
 }
@@ -107,6 +145,9 @@ pub enum RustType {
     /// Argument types of the function.
     arguments: Vec<RustType>,
   },
+  /// A tuple of at least two types, used as the return type
+  /// of methods with out-parameters (see `Config::add_out_parameter`).
+  Tuple(Vec<RustType>),
 }
 
 
@@ -142,10 +183,19 @@ impl RustName {
   /// If `current_crate` is `None`, it's assumed that the formatted name
   /// will be used outside of the crate it belongs to.
   pub fn full_name(&self, current_crate: Option<&str>) -> String {
+    self.full_name_with_local_prefix(current_crate, "::")
+  }
+
+  /// Same as `full_name`, but uses `local_prefix` (e.g. `"crate::"`)
+  /// instead of `"::"` if this name belongs to `current_crate`.
+  pub fn full_name_with_local_prefix(&self,
+                                     current_crate: Option<&str>,
+                                     local_prefix: &str)
+                                     -> String {
     if let Some(current_crate) = current_crate {
       if let Some(self_crate) = self.crate_name() {
         if self_crate == current_crate {
-          return format!("::{}", self.parts[1..].join("::"));
+          return format!("{}{}", local_prefix, self.parts[1..].join("::"));
         }
       }
     }
@@ -232,6 +282,11 @@ impl RustType {
           let mut_text2 = if *is_const2 { "" } else { "_mut" };
           name = format!("{}{}_ptr{}_ptr", name, mut_text, mut_text2);
         }
+        RustTypeIndirection::PtrPtrPtr { ref is_const3 } => {
+          let mut_text2 = if *is_const2 { "" } else { "_mut" };
+          let mut_text3 = if *is_const3 { "" } else { "_mut" };
+          name = format!("{}{}_ptr{}_ptr{}_ptr", name, mut_text, mut_text2, mut_text3);
+        }
         RustTypeIndirection::PtrRef { .. } => {
           let mut_text2 = if *is_const2 { "" } else { "_mut" };
           name = format!("{}{}_ptr{}_ref", name, mut_text, mut_text2);
@@ -240,13 +295,41 @@ impl RustType {
       name
     }
          RustType::FunctionPointer { .. } => "fn".to_string(),
+         RustType::Tuple(ref items) => {
+           items
+             .iter()
+             .map_if_ok(|item| item.caption(context))?
+             .join("_")
+         }
        })
   }
 
-  /// Returns true if this type is a reference.
-  #[allow(dead_code)]
-  pub fn is_ref(&self) -> bool {
+  /// Returns true if this type is `std::option::Option`.
+  pub fn is_option(&self) -> bool {
     match *self {
+      RustType::Common { ref base, ref indirection, ref generic_arguments, .. } => {
+        *indirection == RustTypeIndirection::None &&
+        base.parts == vec!["std".to_string(), "option".to_string(), "Option".to_string()] &&
+        generic_arguments.as_ref().map_or(false, |args| args.len() == 1)
+      }
+      _ => false,
+    }
+  }
+
+  /// Returns the type that holds the reference of this type:
+  /// the type itself or the argument of `Option`.
+  fn referenced_part(&self) -> &RustType {
+    if self.is_option() {
+      if let RustType::Common { generic_arguments: Some(ref args), .. } = *self {
+        return &args[0];
+      }
+    }
+    self
+  }
+
+  /// Returns true if this type is a reference or an `Option` of a reference.
+  pub fn is_ref(&self) -> bool {
+    match *self.referenced_part() {
       RustType::Common { ref indirection, .. } => {
         match *indirection {
           RustTypeIndirection::Ref { .. } |
@@ -255,27 +338,47 @@ impl RustType {
         }
       }
       RustType::EmptyTuple |
-      RustType::FunctionPointer { .. } => false,
+      RustType::FunctionPointer { .. } |
+      RustType::Tuple(..) => false,
     }
   }
 
-  /// Returns a copy of this type with `new_lifetime` added, if possible.
-  pub fn with_lifetime(&self, new_lifetime: String) -> RustType {
-    let mut r = self.clone();
-    if let RustType::Common { ref mut indirection, .. } = r {
+  /// Replaces lifetime of the reference of this type
+  /// (or of the `Option` argument) with `new_lifetime`.
+  fn set_lifetime(&mut self, new_lifetime: Option<String>) {
+    if self.is_option() {
+      if let RustType::Common { generic_arguments: Some(ref mut args), .. } = *self {
+        args[0].set_lifetime(new_lifetime);
+      }
+      return;
+    }
+    if let RustType::Common { ref mut indirection, .. } = *self {
       match *indirection {
         RustTypeIndirection::Ref { ref mut lifetime } |
-        RustTypeIndirection::PtrRef { ref mut lifetime } => *lifetime = Some(new_lifetime),
+        RustTypeIndirection::PtrRef { ref mut lifetime } => *lifetime = new_lifetime,
         _ => {}
       }
     }
+  }
+
+  /// Returns a copy of this type with `new_lifetime` added, if possible.
+  pub fn with_lifetime(&self, new_lifetime: String) -> RustType {
+    let mut r = self.clone();
+    r.set_lifetime(Some(new_lifetime));
     r
   }
 
-  /// Returns name of the lifetime of this type,
+  /// Returns a copy of this type with its lifetime removed, if any.
+  pub fn without_lifetime(&self) -> RustType {
+    let mut r = self.clone();
+    r.set_lifetime(None);
+    r
+  }
+
+  /// Returns name of the lifetime of this type (or of the `Option` argument),
   /// or `None` if there isn't any lifetime in this type.
   pub fn lifetime(&self) -> Option<&String> {
-    match *self {
+    match *self.referenced_part() {
       RustType::Common { ref indirection, .. } => {
         match *indirection {
           RustTypeIndirection::Ref { ref lifetime } |
@@ -297,6 +400,7 @@ impl RustType {
       match *indirection {
         RustTypeIndirection::PtrPtr { .. } |
         RustTypeIndirection::PtrRef { .. } => Ok(*is_const2),
+        RustTypeIndirection::PtrPtrPtr { ref is_const3 } => Ok(*is_const3),
         _ => Ok(*is_const),
       }
     } else {
@@ -339,6 +443,7 @@ impl RustType {
           RustTypeIndirection::Ref { .. } => {}
           RustTypeIndirection::Ptr |
           RustTypeIndirection::PtrPtr |
+          RustTypeIndirection::PtrPtrPtr { .. } |
           RustTypeIndirection::PtrRef { .. } => {
             return true;
           }
@@ -356,6 +461,7 @@ impl RustType {
       }
       RustType::EmptyTuple => false,
       RustType::FunctionPointer { .. } => true,
+      RustType::Tuple(ref items) => items.iter().any(|item| item.is_unsafe_argument()),
     }
   }
 }
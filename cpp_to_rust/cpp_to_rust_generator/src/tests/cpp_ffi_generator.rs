@@ -0,0 +1,163 @@
+use cpp_ffi_generator;
+use cpp_data::{CppDataWithDeps, CppTypeData, CppTypeKind, CppBaseSpecifier, CppOriginLocation,
+               CppVisibility, CppTypeAllocationPlace};
+use cpp_method::{CppMethod, CppMethodKind};
+use cpp_type::{CppType, CppTypeBase, CppTypeClassBase, CppTypeIndirection};
+use tests::cpp_method::{empty_regular_method, empty_membership};
+
+fn class_data(name: &str, include_file: &str, bases: Vec<CppBaseSpecifier>) -> CppTypeData {
+  CppTypeData {
+    name: name.to_string(),
+    include_file: include_file.to_string(),
+    origin_location: CppOriginLocation {
+      include_file_path: include_file.to_string(),
+      line: 0,
+      column: 0,
+    },
+    kind: CppTypeKind::Class {
+      bases: bases,
+      fields: Vec::new(),
+      static_fields: Vec::new(),
+      template_arguments: None,
+      using_directives: Vec::new(),
+      alignment: None,
+    },
+    doc: None,
+  }
+}
+
+fn class_method(class_name: &'static str, name: &str, include_file: &str) -> CppMethod {
+  let mut method = empty_regular_method();
+  method.name = name.to_string();
+  method.class_membership = Some(empty_membership(class_name));
+  method.include_file = include_file.to_string();
+  method
+}
+
+#[test]
+fn inline_inherited_methods() {
+  let mut data = CppDataWithDeps::default();
+  let base = CppBaseSpecifier {
+    base_type: CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "Base".to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: CppTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+    is_virtual: false,
+    visibility: CppVisibility::Public,
+  };
+  data
+    .current
+    .parser
+    .types
+    .push(class_data("Base", "base.h", Vec::new()));
+  data
+    .current
+    .parser
+    .types
+    .push(class_data("Derived", "derived.h", vec![base.clone()]));
+  for name in &["Base", "Derived"] {
+    data
+      .current
+      .processed
+      .type_allocation_places
+      .insert(name.to_string(), CppTypeAllocationPlace::Heap);
+  }
+  data
+    .current
+    .parser
+    .methods
+    .push(class_method("Base", "foo", "base.h"));
+  data
+    .current
+    .parser
+    .methods
+    .push(class_method("Base", "bar", "base.h"));
+  // `bar` is overridden, so only `foo` is inherited
+  data
+    .current
+    .parser
+    .methods
+    .push(class_method("Derived", "bar", "derived.h"));
+  let mut inherited_foo = class_method("Derived", "foo", "derived.h");
+  inherited_foo.inheritance_chain.push(base);
+  data.current.processed.inherited_methods.push(inherited_foo);
+
+  let derived_methods = |inline_inherited_methods: &[String]| -> Vec<String> {
+    let headers = cpp_ffi_generator::run(&data,
+                                         "crate1_c".to_string(),
+                                         Vec::new(),
+                                         &[],
+                                         inline_inherited_methods)
+      .unwrap();
+    let mut names: Vec<_> = headers
+      .iter()
+      .flat_map(|header| header.methods.iter())
+      .filter(|method| method.cpp_method.class_name() == Some(&"Derived".to_string()))
+      .filter(|method| !method.cpp_method.is_destructor())
+      .map(|method| method.cpp_method.name.clone())
+      .collect();
+    names.sort();
+    names
+  };
+  assert_eq!(derived_methods(&[]), vec!["bar".to_string()]);
+  assert_eq!(derived_methods(&["Derived".to_string()]),
+             vec!["bar".to_string(), "foo".to_string()]);
+}
+
+#[test]
+fn protected_constructors_require_virtual_destructor() {
+  let class_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let mut data = CppDataWithDeps::default();
+  data
+    .current
+    .parser
+    .types
+    .push(class_data("Class1", "class1.h", Vec::new()));
+  data
+    .current
+    .processed
+    .type_allocation_places
+    .insert("Class1".to_string(), CppTypeAllocationPlace::Heap);
+  let mut constructor = class_method("Class1", "Class1", "class1.h");
+  constructor.return_type = class_type;
+  if let Some(ref mut membership) = constructor.class_membership {
+    membership.kind = CppMethodKind::Constructor;
+    membership.visibility = CppVisibility::Protected;
+  }
+  data.current.parser.methods.push(constructor);
+
+  let has_constructor = |data: &CppDataWithDeps| -> bool {
+    cpp_ffi_generator::run(data,
+                           "crate1_c".to_string(),
+                           Vec::new(),
+                           &["Class1".to_string()],
+                           &[])
+      .unwrap()
+      .iter()
+      .flat_map(|header| header.methods.iter())
+      .any(|method| method.cpp_method.is_constructor())
+  };
+  // deleting the helper subclass through `Class1*` would be undefined behavior
+  assert!(!has_constructor(&data));
+
+  let mut destructor = class_method("Class1", "~Class1", "class1.h");
+  if let Some(ref mut membership) = destructor.class_membership {
+    membership.kind = CppMethodKind::Destructor;
+    membership.is_virtual = true;
+  }
+  data.current.parser.methods.push(destructor);
+  assert!(has_constructor(&data));
+}
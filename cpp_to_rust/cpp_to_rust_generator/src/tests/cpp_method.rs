@@ -3,6 +3,7 @@ use cpp_data::CppVisibility;
 use cpp_type::*;
 use cpp_ffi_data::CppFfiArgumentMeaning;
 use cpp_ffi_data::CppIndirectionChange;
+use cpp_operator::CppOperator;
 
 #[test]
 fn cpp_method_kind() {
@@ -28,6 +29,7 @@ pub fn empty_membership(class_name: &'static str) -> CppMethodClassMembership {
     is_static: false,
     visibility: CppVisibility::Public,
     is_signal: false,
+    is_private_signal: false,
     is_slot: false,
     class_type: CppTypeClassBase {
       name: class_name.to_string(),
@@ -246,6 +248,41 @@ fn argument_types_equal8() {
   assert!(method2.argument_types_equal(&method1));
 }
 
+#[test]
+fn blocked_overload() {
+  use config::{Config, CrateProperties};
+
+  let foo = |base: CppTypeBase| {
+    let mut method = empty_regular_method();
+    method.name = "foo".to_string();
+    method.class_membership = Some(empty_membership("MyClass"));
+    method.arguments.push(CppMethodArgument {
+                            name: "arg1".to_string(),
+                            argument_type: CppType {
+                              indirection: CppTypeIndirection::None,
+                              is_const: false,
+                              is_const2: false,
+                              base: base,
+                            },
+                            has_default_value: false,
+                          });
+    method
+  };
+  let foo_int = foo(CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int));
+  let foo_double = foo(CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Double));
+  assert!(foo_int.matches_overload("foo", &["int".to_string()]));
+  assert!(foo_int.matches_overload("MyClass::foo", &["int".to_string()]));
+  assert!(!foo_int.matches_overload("bar", &["int".to_string()]));
+  assert!(!foo_int.matches_overload("foo", &[]));
+
+  let mut config = Config::new("output", "cache", CrateProperties::new("crate1", "0.0.0"));
+  config.add_blocked_overload("MyClass::foo", vec!["int"]);
+  let filters = config.cpp_ffi_generator_filters();
+  assert_eq!(filters.len(), 1);
+  assert!(!filters[0](&foo_int).unwrap());
+  assert!(filters[0](&foo_double).unwrap());
+}
+
 #[test]
 fn c_signature_empty() {
   let mut method1 = empty_regular_method();
@@ -707,6 +744,7 @@ fn short_text1() {
                              is_static: false,
                              visibility: CppVisibility::Protected,
                              is_signal: false,
+                             is_private_signal: false,
                              is_slot: false,
                              class_type: CppTypeClassBase {
                                name: "Class1".to_string(),
@@ -753,3 +791,71 @@ fn short_text1() {
   assert_eq!(method.short_text(),
              "protected int Class1::method1(int arg1, double arg2 = ?) const");
 }
+
+#[test]
+fn copy_constructor() {
+  let mut method1 = empty_regular_method();
+  method1.class_membership = Some({
+                                    let mut info = empty_membership("Point");
+                                    info.kind = CppMethodKind::Constructor;
+                                    info
+                                  });
+  assert!(!method1.is_copy_constructor());
+  let mut arg = CppMethodArgument {
+    argument_type: CppType {
+      indirection: CppTypeIndirection::Ref,
+      is_const: true,
+      is_const2: false,
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "Point".to_string(),
+                                 template_arguments: None,
+                               }),
+    },
+    name: "other".to_string(),
+    has_default_value: false,
+  };
+  method1.arguments.push(arg.clone());
+  assert!(method1.is_copy_constructor());
+
+  let mut method2 = method1.clone();
+  method2.arguments[0].argument_type.is_const = false;
+  assert!(!method2.is_copy_constructor());
+
+  let mut method3 = method1.clone();
+  method3.class_membership = Some(empty_membership("Point"));
+  assert!(!method3.is_copy_constructor());
+
+  arg.argument_type.base = CppTypeBase::Class(CppTypeClassBase {
+                                                name: "Rect".to_string(),
+                                                template_arguments: None,
+                                              });
+  let mut method4 = method1.clone();
+  method4.arguments = vec![arg];
+  assert!(!method4.is_copy_constructor());
+}
+
+#[test]
+fn implicit_conversion_operator() {
+  let mut method1 = empty_regular_method();
+  method1.class_membership = Some(empty_membership("QVariant"));
+  method1.name = "operator bool".to_string();
+  method1.return_type = CppType {
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Bool),
+  };
+  method1.declaration_code = Some("operator bool() const".to_string());
+  assert!(!method1.is_implicit_conversion_operator());
+
+  method1.operator = Some(CppOperator::Conversion(method1.return_type.clone()));
+  assert!(method1.is_implicit_conversion_operator());
+
+  let mut method2 = method1.clone();
+  method2.declaration_code = Some("explicit operator bool() const".to_string());
+  assert!(!method2.is_implicit_conversion_operator());
+
+  let mut method3 = method1.clone();
+  method3.declaration_code = None;
+  assert!(!method3.is_implicit_conversion_operator());
+}
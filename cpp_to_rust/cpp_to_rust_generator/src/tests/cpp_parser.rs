@@ -10,6 +10,10 @@ use common::file_utils::{create_dir, create_file, PathBufWithAdded};
 use std::path::PathBuf;
 
 fn run_parser(code: &'static str) -> ParserCppData {
+  run_parser_with_allowlist(code, Vec::new())
+}
+
+fn run_parser_with_allowlist(code: &'static str, name_allowlist: Vec<String>) -> ParserCppData {
   let dir = tempdir::TempDir::new("test_cpp_parser_run").unwrap();
   let include_dir = dir.path().with_added("include");
   create_dir(&include_dir).unwrap();
@@ -26,6 +30,7 @@ fn run_parser(code: &'static str) -> ParserCppData {
                                      target_include_paths: Vec::new(),
                                      tmp_cpp_path: dir.path().with_added("1.cpp"),
                                      name_blacklist: Vec::new(),
+                                     name_allowlist: name_allowlist,
                                      framework_paths: Vec::new(),
                                      clang_arguments: Vec::new(),
                                    },
@@ -131,11 +136,15 @@ fn functions_with_class_arg() {
   if let CppTypeKind::Class {
            ref bases,
            ref fields,
+           ref static_fields,
            ref template_arguments,
            ref using_directives,
+           ref alignment,
          } = data.types[0].kind {
     assert!(template_arguments.is_none());
     assert!(using_directives.is_empty());
+    assert!(static_fields.is_empty());
+    assert_eq!(alignment, &Some(4));
     assert!(bases.is_empty());
     assert_eq!(fields.len(), 2);
     assert_eq!(fields[0].name, "a");
@@ -455,6 +464,7 @@ fn simple_class_method() {
                                         is_static: false,
                                         visibility: CppVisibility::Public,
                                         is_signal: false,
+                                        is_private_signal: false,
                                         is_slot: false,
                                       }),
                operator: None,
@@ -666,6 +676,7 @@ fn template_class_method() {
                                         is_static: false,
                                         visibility: CppVisibility::Public,
                                         is_signal: false,
+                                        is_private_signal: false,
                                         is_slot: false,
                                       }),
                operator: None,
@@ -1094,6 +1105,48 @@ fn class_with_use() {
   }
 }
 
+#[test]
+fn class_with_static_fields() {
+  let data = run_parser("class A {
+  public:
+    static const int MaxSize = 0x10;
+    static const bool IsEnabled = true;
+    static int counter;
+  private:
+    static const int Offset = -3;
+  };");
+  assert!(data.types.len() == 1);
+  if let CppTypeKind::Class {
+           ref fields,
+           ref static_fields,
+           ..
+         } = data.types[0].kind {
+    assert!(fields.is_empty());
+    assert_eq!(static_fields.len(), 4);
+    assert_eq!(static_fields[0],
+               CppClassStaticField {
+                 name: "MaxSize".to_string(),
+                 field_type: CppType {
+                   indirection: CppTypeIndirection::None,
+                   is_const: true,
+                   is_const2: false,
+                   base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+                 },
+                 visibility: CppVisibility::Public,
+                 value: Some(16),
+               });
+    assert_eq!(static_fields[1].name, "IsEnabled");
+    assert_eq!(static_fields[1].value, Some(1));
+    assert_eq!(static_fields[2].name, "counter");
+    assert_eq!(static_fields[2].value, None);
+    assert_eq!(static_fields[3].name, "Offset");
+    assert_eq!(static_fields[3].visibility, CppVisibility::Private);
+    assert_eq!(static_fields[3].value, Some(-3));
+  } else {
+    panic!("invalid type kind");
+  }
+}
+
 #[test]
 fn complex_const_types() {
   let data = run_parser("
@@ -1182,6 +1235,35 @@ fn complex_const_types() {
               });
 }
 
+#[test]
+fn char_ptr_ptr_ptr() {
+  let data = run_parser("void func1(char*** x);
+    void func2(const char* const** x);
+    void func3(char** const* x);");
+  assert_eq!(data.methods.len(), 3);
+  assert_eq!(data.methods[0].arguments[0].argument_type,
+             CppType {
+               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+               indirection: CppTypeIndirection::PtrPtrPtr { is_const3: false },
+               is_const: false,
+               is_const2: false,
+             });
+  assert_eq!(data.methods[1].arguments[0].argument_type,
+             CppType {
+               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+               indirection: CppTypeIndirection::PtrPtrPtr { is_const3: false },
+               is_const: true,
+               is_const2: true,
+             });
+  assert_eq!(data.methods[2].arguments[0].argument_type,
+             CppType {
+               base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+               indirection: CppTypeIndirection::PtrPtrPtr { is_const3: true },
+               is_const: false,
+               is_const2: false,
+             });
+}
+
 #[test]
 fn anon_enum() {
   let data = run_parser("class X {
@@ -1289,3 +1371,52 @@ fn template_class_with_base() {
     panic!("invalid type kind");
   }
 }
+
+#[test]
+fn allowlist() {
+  let data = run_parser_with_allowlist("class Used1 { public: ~Used1(); int f(); };
+    class Used2 {};
+    class Derived : public Used2 { public: void g(); };
+    class Unused { public: void h(); };
+    namespace ns1 { Used1 func1(Derived* d); void func2(); }
+    void func3();",
+                                       vec!["ns1::func1".to_string()]);
+  let method_names: Vec<_> = data.methods.iter().map(|m| m.doc_id()).collect();
+  assert_eq!(method_names, vec!["ns1::func1", "Used1::~Used1"]);
+  let mut type_names: Vec<_> = data.types.iter().map(|t| t.name.clone()).collect();
+  type_names.sort();
+  assert_eq!(type_names, vec!["Derived", "Used1", "Used2"]);
+}
+
+#[test]
+fn include_directives_order() {
+  use config::{Config, CrateProperties};
+  let mut config = Config::new("output", "cache", CrateProperties::new("crate1", "0.0.0"));
+  config.add_include_directive("b.h");
+  config.add_include_directive("c.h");
+  config.add_include_directive("b.h");
+  config.add_include_directive_front("a.h");
+  config.add_include_directive_front("c.h");
+  assert_eq!(config.include_directives(),
+             &[PathBuf::from("c.h"),
+               PathBuf::from("a.h"),
+               PathBuf::from("b.h"),
+               PathBuf::from("c.h"),
+               PathBuf::from("b.h")]);
+  config.dedup_include_directives();
+  assert_eq!(config.include_directives(),
+             &[PathBuf::from("c.h"), PathBuf::from("a.h"), PathBuf::from("b.h")]);
+  let parser_config = cpp_parser::CppParserConfig {
+    include_paths: Vec::new(),
+    include_directives: Vec::from(config.include_directives()),
+    target_include_paths: Vec::new(),
+    tmp_cpp_path: PathBuf::from("1.cpp"),
+    name_blacklist: Vec::new(),
+    name_allowlist: Vec::new(),
+    framework_paths: Vec::new(),
+    clang_arguments: Vec::new(),
+  };
+  assert_eq!(cpp_parser::parser_input_code(&parser_config, Some("int x;\n".to_string()))
+               .unwrap(),
+             "#include \"c.h\"\n#include \"a.h\"\n#include \"b.h\"\nint x;\n");
+}
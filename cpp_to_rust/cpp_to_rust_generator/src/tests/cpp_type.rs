@@ -131,6 +131,39 @@ fn char_ptr_ptr() {
   assert!(!type1.needs_allocation_place_variants());
 }
 
+#[test]
+fn char_ptr_ptr_ptr() {
+  let mut type1 = CppType {
+    indirection: CppTypeIndirection::PtrPtrPtr { is_const3: false },
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Char),
+  };
+  assert_eq!(type1.to_cpp_code(None).unwrap(), "char***");
+  assert_eq!(type1.caption(TypeCaptionStrategy::Full).unwrap(),
+             "char_ptr_ptr_ptr");
+  assert_type_to_ffi_unchanged(&type1);
+  assert!(!type1.needs_allocation_place_variants());
+  type1.is_const = true;
+  type1.is_const2 = true;
+  assert_eq!(type1.to_cpp_code(None).unwrap(), "const char* const **");
+  assert_eq!(type1.caption(TypeCaptionStrategy::Full).unwrap(),
+             "const_char_ptr_const_ptr_ptr");
+  assert_type_to_ffi_unchanged(&type1);
+  type1.indirection = CppTypeIndirection::PtrPtrPtr { is_const3: true };
+  assert_eq!(type1.to_cpp_code(None).unwrap(), "const char* const * const *");
+  assert_eq!(type1.caption(TypeCaptionStrategy::Full).unwrap(),
+             "const_char_ptr_const_ptr_const_ptr");
+  assert_type_to_ffi_unchanged(&type1);
+  assert_eq!(CppTypeIndirection::combine(&CppTypeIndirection::PtrPtr,
+                                         &CppTypeIndirection::Ptr)
+               .unwrap(),
+             CppTypeIndirection::PtrPtrPtr { is_const3: false });
+  assert!(CppTypeIndirection::combine(&CppTypeIndirection::PtrPtrPtr { is_const3: false },
+                                      &CppTypeIndirection::Ptr)
+            .is_err());
+}
+
 #[test]
 fn qint64() {
   let type1 = CppType {
@@ -671,3 +704,46 @@ fn instantiate1() {
   assert_eq!(r.is_const, false);
   assert_eq!(r.is_const2, true);
 }
+
+#[test]
+fn class_rvalue_ref() {
+  let type1 = CppType {
+    indirection: CppTypeIndirection::RValueRef,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "QRectF".to_string(),
+                               template_arguments: None,
+                             }),
+  };
+  assert_eq!(type1.to_cpp_code(None).unwrap(), "QRectF&&");
+  assert!(type1.to_cpp_ffi_type(CppTypeRole::ReturnType).is_err());
+  let ffi1 = type1
+    .to_cpp_ffi_type(CppTypeRole::NotReturnType)
+    .unwrap();
+  assert_eq!(&ffi1.original_type, &type1);
+  assert_eq!(&ffi1.ffi_type.to_cpp_code(None).unwrap(), "QRectF*");
+  assert_eq!(ffi1.conversion,
+             CppIndirectionChange::RValueReferenceToPointer);
+
+  let type2 = CppType {
+    indirection: CppTypeIndirection::RValueRef,
+    is_const: false,
+    is_const2: false,
+    base: CppTypeBase::BuiltInNumeric(CppBuiltInNumericType::Int),
+  };
+  assert!(type2.to_cpp_ffi_type(CppTypeRole::NotReturnType).is_err());
+
+  assert_eq!(CppTypeIndirection::combine(&CppTypeIndirection::Ref,
+                                         &CppTypeIndirection::RValueRef)
+               .unwrap(),
+             CppTypeIndirection::Ref);
+  assert_eq!(CppTypeIndirection::combine(&CppTypeIndirection::RValueRef,
+                                         &CppTypeIndirection::Ref)
+               .unwrap(),
+             CppTypeIndirection::Ref);
+  assert_eq!(CppTypeIndirection::combine(&CppTypeIndirection::RValueRef,
+                                         &CppTypeIndirection::RValueRef)
+               .unwrap(),
+             CppTypeIndirection::RValueRef);
+}
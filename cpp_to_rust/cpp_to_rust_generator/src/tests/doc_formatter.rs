@@ -0,0 +1,54 @@
+use doc_formatter::enum_value_doc;
+use rust_info::{RustEnumValue, CppEnumValueDocItem};
+
+fn doc_item(name: &str, doc: Option<&str>) -> CppEnumValueDocItem {
+  CppEnumValueDocItem {
+    variant_name: name.to_string(),
+    doc: doc.map(|x| x.to_string()),
+  }
+}
+
+#[test]
+fn enum_value_doc_single() {
+  let value = RustEnumValue {
+    name: "Foo".to_string(),
+    value: 1,
+    cpp_docs: vec![doc_item("Foo", Some("Foo text"))],
+    is_dummy: false,
+    is_hidden: false,
+  };
+  let doc = enum_value_doc(&value);
+  assert!(doc.starts_with("Foo text (C++ enum variant: "));
+  assert!(!doc.contains("Also known as"));
+}
+
+#[test]
+fn enum_value_doc_aliases() {
+  let value = RustEnumValue {
+    name: "Foo".to_string(),
+    value: 1,
+    cpp_docs: vec![doc_item("Foo", Some("Foo text")),
+                   doc_item("Bar", None),
+                   doc_item("Baz", Some("Baz text"))],
+    is_dummy: false,
+    is_hidden: false,
+  };
+  let doc = enum_value_doc(&value);
+  assert!(doc.contains("```Foo = 1```</span>: Foo text\n"));
+  assert!(doc.contains("```Bar = 1```</span>\n"));
+  assert!(doc.ends_with("\nAlso known as: `Bar`, `Baz`."));
+}
+
+#[test]
+fn enum_value_doc_dummy() {
+  let value = RustEnumValue {
+    name: "_Invalid".to_string(),
+    value: 2,
+    cpp_docs: Vec::new(),
+    is_dummy: true,
+    is_hidden: false,
+  };
+  let doc = enum_value_doc(&value);
+  assert!(doc.starts_with("This variant is added in Rust"));
+  assert!(!doc.contains("Also known as"));
+}
@@ -0,0 +1,25 @@
+use dry_run::{DryRunReport, DryRunModule, report_path};
+use rust_generator::{SkippedItem, SkippedItemKind};
+use common::file_utils::save_json;
+use tests::TempTestDir;
+
+#[test]
+fn report_save_and_load() {
+  let dir = TempTestDir::new("test_dry_run_report");
+  let report = DryRunReport {
+    crate_name: "crate1".to_string(),
+    modules: vec![DryRunModule {
+                    name: "crate1::module1".to_string(),
+                    types_count: 2,
+                    methods_count: 5,
+                  }],
+    skipped_methods: vec![SkippedItem {
+                            kind: SkippedItemKind::Method,
+                            cpp_item: "void Class1::f(int*)".to_string(),
+                            reason: "class type is not available in Rust".to_string(),
+                          }],
+  };
+  save_json(report_path(dir.path()), &report).unwrap();
+  assert!(dir.path().join("dry_run_report.json").exists());
+  assert_eq!(DryRunReport::load(dir.path()).unwrap(), report);
+}
@@ -1,8 +1,9 @@
-use common::file_utils::{PathBufWithAdded, create_dir};
+use common::file_utils::{PathBufWithAdded, create_dir, file_to_string};
 use common::utils::{run_command, add_env_path_item};
 use common::cpp_lib_builder::{CppLibBuilder, BuildType};
 use common::errors::fancy_unwrap;
 use config::{Config, CrateProperties, CacheUsage};
+use cpp_data::CppTypeAllocationPlace;
 use common::cpp_build_config::CppBuildConfigData;
 use common::target;
 use std::process::Command;
@@ -90,10 +91,23 @@ fn full_run() {
   } else {
     config.add_cpp_parser_argument("-std=gnu++11");
   }
+  config.add_type_plugin(|info| if info.cpp_name == "Class1" {
+                           Some("impl Class1 {\n  pub fn plugin_value() -> i32 { 42 }\n}"
+                                  .to_string())
+                         } else {
+                           None
+                         });
+  config.set_type_allocation_place(CppTypeAllocationPlace::Stack, "Point1");
+  config.set_pod_type("Point1", true);
+  config.add_raw_trait_impl("Class1", "unsafe impl Send for Class1 {}");
+  config.add_iterable_type("IntContainer1");
+  config.set_generate_layout_tests(true);
   config.set_crate_template_path(&crate_template_path);
   config.set_cache_usage(CacheUsage::None);
   fancy_unwrap(config.exec());
   assert!(crate_dir.exists());
+  let class1_code = file_to_string(crate_dir.with_added("src").with_added("class1.rs")).unwrap();
+  assert!(class1_code.contains("unsafe impl Send for Class1 {}"));
 
   for cargo_cmd in &["update", "build", "test", "doc"] {
     let mut command = Command::new("cargo");
@@ -1,9 +1,13 @@
 mod cpp_type;
-mod cpp_method;
+pub mod cpp_method;
 mod cpp_ffi_data;
+mod cpp_ffi_generator;
 mod cpp_operator;
 mod cpp_parser;
+mod doc_formatter;
+mod dry_run;
 mod full_run;
+pub mod rust_code_generator;
 
 use std::path::{Path, PathBuf};
 use common::file_utils::{create_dir_all, PathBufWithAdded};
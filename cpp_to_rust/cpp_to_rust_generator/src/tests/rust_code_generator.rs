@@ -0,0 +1,1848 @@
+use rust_code_generator::{module_inventory, run, default_built_in_modules, rust_type_to_code,
+                          RustCodeGeneratorConfig};
+use rust_generator::{RustGeneratorOutput, RustItem, RustItemKind, flags_from_enum_impl,
+                     total_order_impls, apply_doc_hooks, default_box_type, used_ffi_functions};
+use config::{Config, CrateProperties, RustEdition, DocHookFn, LinkLibrary};
+use common::cpp_build_config::LinkKind;
+use common::target;
+use common::toml;
+use common::file_utils::{create_dir_all, file_to_string, PathBufWithAdded};
+use tests::TempTestDir;
+use std::path::PathBuf;
+use rust_info::*;
+use rust_type::*;
+use cpp_ffi_data::*;
+use cpp_type::{CppType, CppTypeBase, CppTypeClassBase, CppTypeIndirection};
+use cpp_method::ReturnValueAllocationPlace;
+use tests::cpp_method::empty_regular_method;
+use doc_formatter;
+
+fn empty_rust_method(name: Vec<&str>, scope: RustMethodScope) -> RustMethod {
+  RustMethod {
+    scope: scope,
+    is_unsafe: false,
+    name: RustName::new(name.into_iter().map(|x| x.to_string()).collect()).unwrap(),
+    arguments: RustMethodArguments::SingleVariant(RustMethodArgumentsVariant {
+      arguments: Vec::new(),
+      cpp_method: CppAndFfiMethod {
+        cpp_method: empty_regular_method(),
+        kind: CppFfiMethodKind::Real,
+        allocation_place: ReturnValueAllocationPlace::NotApplicable,
+        c_signature: CppFfiMethodSignature {
+          arguments: Vec::new(),
+          return_type: CppFfiType::void(),
+        },
+        c_name: String::new(),
+      },
+      return_type_ffi_index: None,
+      validity_check_function: None,
+      return_type: CompleteType {
+        cpp_type: CppType::void(),
+        cpp_ffi_type: CppType::void(),
+        cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+        rust_ffi_type: RustType::EmptyTuple,
+        rust_api_type: RustType::EmptyTuple,
+        rust_api_to_c_conversion: RustToCTypeConversion::None,
+      },
+    }),
+    variant_docs: Vec::new(),
+    common_doc: None,
+    doc_override: None,
+    cfg: None,
+    deprecated: None,
+    cpp_doc_url: None,
+  }
+}
+
+#[test]
+fn module_inventory_methods() {
+  let type_name = RustName::new(vec!["crate1".to_string(),
+                                     "module1".to_string(),
+                                     "Class1".to_string()])
+    .unwrap();
+  let type_rust_type = RustType::Common {
+    base: type_name.clone(),
+    generic_arguments: None,
+    is_const: false,
+    is_const2: false,
+    indirection: RustTypeIndirection::None,
+  };
+  let module = RustModule {
+    name: "module1".to_string(),
+    types: vec![RustTypeDeclaration {
+                  is_public: true,
+                  name: type_name.clone(),
+                  kind: RustTypeDeclarationKind::CppTypeWrapper {
+                    kind: RustTypeWrapperKind::Struct {
+                      size_const_name: None,
+                      is_deletable: false,
+                      slot_wrapper: None,
+                    },
+                    cpp_type_name: "Class1".to_string(),
+                    cpp_template_arguments: None,
+                    cpp_doc: None,
+                    methods: vec![empty_rust_method(vec!["method1"],
+                                                    RustMethodScope::Impl {
+                                                      target_type: type_rust_type.clone(),
+                                                    }),
+                                  empty_rust_method(vec!["method2"],
+                                                    RustMethodScope::Impl {
+                                                      target_type: type_rust_type.clone(),
+                                                    })],
+                    constants: Vec::new(),
+                    trait_impls: vec![TraitImpl {
+                                        target_type: type_rust_type.clone(),
+                                        trait_type: RustType::Common {
+                                          base: RustName::new(vec!["Default".to_string()])
+                                            .unwrap(),
+                                          generic_arguments: None,
+                                          is_const: false,
+                                          is_const2: false,
+                                          indirection: RustTypeIndirection::None,
+                                        },
+                                        associated_types: Vec::new(),
+                                        extra: None,
+                                        methods: Vec::new(),
+                                      }],
+                    qt_receivers: Vec::new(),
+                    qt_hash_types: None,
+                    vector_info: None,
+                    list_element_type: None,
+                    pod_traits: None,
+                    transparent_type: None,
+                    iterator_adapter: None,
+                    is_send: false,
+                    is_sync: false,
+                  },
+                  rust_doc: None,
+                  doc_override: None,
+                }],
+    functions: vec![empty_rust_method(vec!["crate1", "module1", "func1"],
+                                      RustMethodScope::Free)],
+    trait_impls: Vec::new(),
+    doc: None,
+    submodules: Vec::new(),
+    plugin_code: Vec::new(),
+  };
+  let inventory = module_inventory(&module, "crate1");
+  assert_eq!(inventory.name, "crate1::module1");
+  assert_eq!(inventory.types, vec!["crate1::module1::Class1"]);
+  assert_eq!(inventory.methods,
+             vec!["crate1::module1::Class1::method1",
+                  "crate1::module1::Class1::method2",
+                  "crate1::module1::func1"]);
+  assert_eq!(inventory.trait_impls,
+             vec!["impl Default for ::crate1::module1::Class1"]);
+  assert!(inventory.submodules.is_empty());
+}
+
+pub fn empty_module(name: &str) -> RustModule {
+  RustModule {
+    name: name.to_string(),
+    types: Vec::new(),
+    functions: Vec::new(),
+    trait_impls: Vec::new(),
+    doc: None,
+    submodules: Vec::new(),
+    plugin_code: Vec::new(),
+  }
+}
+
+/// Runs the code generator on `modules` and returns path to
+/// the generated crate within `dir`. `configure` may change
+/// the default generator configuration.
+pub fn run_code_generator<F>(dir: &TempTestDir, modules: Vec<RustModule>, configure: F) -> PathBuf
+  where F: FnOnce(&mut Config)
+{
+  let data = RustGeneratorOutput {
+    used_ffi_functions: used_ffi_functions(&modules),
+    modules: modules,
+    ffi_functions: Vec::new(),
+    processed_types: Vec::new(),
+    skipped: Vec::new(),
+    type_manifest: Vec::new(),
+  };
+  run_code_generator_with_data(dir, &data, configure)
+}
+
+/// Same as `run_code_generator`, but uses complete output data
+/// of the Rust generator.
+fn run_code_generator_with_data<F>(dir: &TempTestDir,
+                                   data: &RustGeneratorOutput,
+                                   configure: F)
+                                   -> PathBuf
+  where F: FnOnce(&mut Config)
+{
+  let output_path = dir.path().with_added("crate1");
+  create_dir_all(&output_path).unwrap();
+  let mut config = Config::new(output_path.clone(),
+                               dir.path().with_added("cache"),
+                               CrateProperties::new("crate1", "0.0.0"));
+  config.set_write_dependencies_local_paths(false);
+  config.set_format_generated_code(false);
+  configure(&mut config);
+  let generator_config = RustCodeGeneratorConfig {
+    crate_properties: config.crate_properties().clone(),
+    output_path: output_path.clone(),
+    crate_template_path: None,
+    cpp_ffi_lib_name: "crate1_c".to_string(),
+    cpp_lib_version: None,
+    generator_dependencies: &[],
+    write_dependencies_local_paths: config.write_dependencies_local_paths(),
+    settings: config.rust_generator_settings(),
+    built_in_modules: default_built_in_modules(),
+  };
+  run(generator_config, data).unwrap();
+  output_path
+}
+
+#[test]
+fn ptr_ptr_ptr_type_code() {
+  let mut rust_type = RustType::Common {
+    base: RustName::new(vec!["libc".to_string(), "c_char".to_string()]).unwrap(),
+    generic_arguments: None,
+    is_const: true,
+    is_const2: false,
+    indirection: RustTypeIndirection::PtrPtrPtr { is_const3: false },
+  };
+  assert_eq!(rust_type_to_code(&rust_type, "crate1"),
+             "*mut *mut *const ::libc::c_char");
+  if let RustType::Common { ref mut indirection, .. } = rust_type {
+    *indirection = RustTypeIndirection::PtrPtrPtr { is_const3: true };
+  }
+  assert_eq!(rust_type_to_code(&rust_type, "crate1"),
+             "*const *mut *const ::libc::c_char");
+  assert_eq!(rust_type.last_is_const().unwrap(), true);
+}
+
+#[test]
+fn generator_output_items() {
+  let mut submodule = empty_module("module1");
+  submodule.types.push(stack_struct());
+  let mut module = empty_module("module0");
+  module.submodules.push(submodule);
+  let data = RustGeneratorOutput {
+    modules: vec![module],
+    ffi_functions: Vec::new(),
+    used_ffi_functions: Default::default(),
+    processed_types: Vec::new(),
+    skipped: Vec::new(),
+    type_manifest: Vec::new(),
+  };
+  assert_eq!(data.items("crate1"),
+             vec![RustItem {
+                    module_path: "crate1::module0::module1".to_string(),
+                    name: "crate1::module1::Class1".to_string(),
+                    kind: RustItemKind::Type,
+                  }]);
+}
+
+/// Returns declaration of a stack-allocated struct `Class1`.
+fn stack_struct() -> RustTypeDeclaration {
+  RustTypeDeclaration {
+    is_public: true,
+    name: RustName::new(vec!["crate1".to_string(), "module1".to_string(), "Class1".to_string()])
+      .unwrap(),
+    kind: RustTypeDeclarationKind::CppTypeWrapper {
+      kind: RustTypeWrapperKind::Struct {
+        size_const_name: Some("Class1".to_string()),
+        is_deletable: false,
+        slot_wrapper: None,
+      },
+      cpp_type_name: "Class1".to_string(),
+      cpp_template_arguments: None,
+      cpp_doc: None,
+      methods: Vec::new(),
+      constants: Vec::new(),
+      trait_impls: Vec::new(),
+      qt_receivers: Vec::new(),
+      qt_hash_types: None,
+      vector_info: None,
+      list_element_type: None,
+      pod_traits: None,
+      transparent_type: None,
+      iterator_adapter: None,
+      is_send: false,
+      is_sync: false,
+    },
+    rust_doc: None,
+    doc_override: None,
+  }
+}
+
+/// Runs the code generator on a module containing a stack-allocated
+/// struct and returns contents of `lib.rs`, `module1.rs` and `Cargo.toml`.
+fn generate_crate_files<F>(dir_name: &str, configure: F) -> (String, String, String)
+  where F: FnOnce(&mut Config)
+{
+  let dir = TempTestDir::new(dir_name);
+  let mut module = empty_module("module1");
+  module.types.push(stack_struct());
+  let output_path = run_code_generator(&dir, vec![module], configure);
+  let src_path = output_path.with_added("src");
+  (file_to_string(src_path.with_added("lib.rs")).unwrap(),
+   file_to_string(src_path.with_added("module1.rs")).unwrap(),
+   file_to_string(output_path.with_added("Cargo.toml")).unwrap())
+}
+
+#[test]
+fn rust_edition_2015() {
+  let (lib_code, module_code, cargo_toml) =
+    generate_crate_files("test_rust_edition_2015", |_| {});
+  assert!(lib_code.contains("pub extern crate libc;"));
+  assert!(lib_code.contains("pub extern crate cpp_utils;"));
+  assert!(module_code.contains("[u8; ::type_sizes::Class1]"));
+  assert!(!cargo_toml.contains("edition"));
+  assert!(!module_code.contains("layout_tests"));
+}
+
+#[test]
+fn struct_alignment() {
+  let dir = TempTestDir::new("test_struct_alignment");
+  let mut module = empty_module("module1");
+  module.types.push(stack_struct());
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  // alignment is determined by the build script on the target platform
+  assert!(module_code.contains("#[repr(C)]\npub struct Class1([u8; ::type_sizes::Class1],\n  \
+                                [::type_sizes::Class1_ALIGN; 0]);"));
+  assert!(module_code.contains("Class1(::std::mem::uninitialized(), [])"));
+}
+
+#[test]
+fn rust_edition_2018() {
+  let (lib_code, module_code, cargo_toml) =
+    generate_crate_files("test_rust_edition_2018", |config| {
+      config.set_rust_edition(RustEdition::Edition2018);
+    });
+  assert!(!lib_code.contains("extern crate"));
+  assert!(lib_code.contains("pub use libc;"));
+  assert!(lib_code.contains("pub use cpp_utils;"));
+  assert!(module_code.contains("[u8; crate::type_sizes::Class1]"));
+  assert!(cargo_toml.contains("edition = \"2018\""));
+}
+
+/// Runs the code generator with `link_libraries`
+/// and returns contents of `ffi.in.rs`.
+fn generate_ffi_file_with_links(dir_name: &str, link_libraries: Vec<LinkLibrary>) -> String {
+  let dir = TempTestDir::new(dir_name);
+  let output_path = run_code_generator(&dir, vec![empty_module("module1")], |config| {
+    for library in link_libraries {
+      config.add_link_library(library.name, library.kind, library.target_filter);
+    }
+  });
+  file_to_string(output_path.with_added("src").with_added("ffi.in.rs")).unwrap()
+}
+
+#[test]
+fn link_library_dylib() {
+  let code = generate_ffi_file_with_links("test_link_library_dylib",
+                                          vec![LinkLibrary {
+                                                 name: "Qt5Core".to_string(),
+                                                 kind: LinkKind::Dylib,
+                                                 target_filter: None,
+                                               }]);
+  assert!(code.starts_with("#[link(name = \"Qt5Core\", kind = \"dylib\")]\nextern \"C\" {\n"));
+}
+
+#[test]
+fn link_library_macos_framework() {
+  let macos = target::Condition::OS(target::OS::MacOS);
+  let code = generate_ffi_file_with_links("test_link_library_macos_framework",
+                                          vec![LinkLibrary {
+                                                 name: "QtCore".to_string(),
+                                                 kind: LinkKind::Framework,
+                                                 target_filter: Some(macos.clone()),
+                                               },
+                                               LinkLibrary {
+                                                 name: "Qt5Core".to_string(),
+                                                 kind: LinkKind::Dylib,
+                                                 target_filter: Some(macos.negate()),
+                                               }]);
+  assert!(code.contains("#[cfg_attr(target_os = \"macos\", \
+                         link(name = \"QtCore\", kind = \"framework\"))]\n"));
+  assert!(code.contains("#[cfg_attr(not(target_os = \"macos\"), \
+                         link(name = \"Qt5Core\", kind = \"dylib\"))]\n"));
+}
+
+#[test]
+fn prune_ffi_functions() {
+  let mut method = empty_rust_method(vec!["crate1", "module1", "used"], RustMethodScope::Free);
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_used".to_string();
+  }
+  let mut module = empty_module("module1");
+  module.functions.push(method);
+  let ffi_function = |name: &str| {
+    RustFFIFunction {
+      return_type: RustType::EmptyTuple,
+      name: name.to_string(),
+      arguments: Vec::new(),
+      cfg: None,
+    }
+  };
+  let modules = vec![module];
+  let data = RustGeneratorOutput {
+    used_ffi_functions: used_ffi_functions(&modules),
+    modules: modules,
+    // `crate1_skipped` belongs to a method skipped by the Rust generator
+    ffi_functions: vec![("module1".to_string(),
+                         vec![ffi_function("crate1_used"), ffi_function("crate1_skipped")])],
+    processed_types: Vec::new(),
+    skipped: Vec::new(),
+    type_manifest: Vec::new(),
+  };
+  assert_eq!(data.used_ffi_functions.iter().collect::<Vec<_>>(), vec!["crate1_used"]);
+
+  let dir = TempTestDir::new("test_prune_ffi_functions");
+  let output_path = run_code_generator_with_data(&dir, &data, |_| {});
+  let code = file_to_string(output_path.with_added("src").with_added("ffi.in.rs")).unwrap();
+  assert!(code.contains("  pub fn crate1_used();\n"));
+  assert!(code.contains("  pub fn crate1_skipped();\n"));
+
+  let dir = TempTestDir::new("test_prune_ffi_functions_enabled");
+  let output_path = run_code_generator_with_data(&dir, &data, |config| {
+    config.set_prune_ffi_functions(true);
+  });
+  let code = file_to_string(output_path.with_added("src").with_added("ffi.in.rs")).unwrap();
+  assert!(code.contains("  pub fn crate1_used();\n"));
+  assert!(!code.contains("crate1_skipped"));
+}
+
+#[test]
+fn ffi_functions_stable_order() {
+  let ffi_function = |name: &str| {
+    RustFFIFunction {
+      return_type: RustType::EmptyTuple,
+      name: name.to_string(),
+      arguments: Vec::new(),
+      cfg: None,
+    }
+  };
+  let generate = |dir_name: &str, ffi_functions: Vec<(String, Vec<RustFFIFunction>)>| {
+    let data = RustGeneratorOutput {
+      modules: vec![empty_module("module1")],
+      ffi_functions: ffi_functions,
+      processed_types: Vec::new(),
+      skipped: Vec::new(),
+      type_manifest: Vec::new(),
+      used_ffi_functions: Default::default(),
+    };
+    let dir = TempTestDir::new(dir_name);
+    let output_path = run_code_generator_with_data(&dir, &data, |_| {});
+    file_to_string(output_path.with_added("src").with_added("ffi.in.rs")).unwrap()
+  };
+  let code1 = generate("test_ffi_functions_stable_order1",
+                       vec![("module2".to_string(),
+                             vec![ffi_function("crate1_b"), ffi_function("crate1_a")]),
+                            ("module1".to_string(), vec![ffi_function("crate1_c")])]);
+  let code2 = generate("test_ffi_functions_stable_order2",
+                       vec![("module1".to_string(), vec![ffi_function("crate1_c")]),
+                            ("module2".to_string(),
+                             vec![ffi_function("crate1_a"), ffi_function("crate1_b")])]);
+  assert_eq!(code1, code2);
+  let position = |text: &str| code1.find(text).unwrap();
+  assert!(position("// Header: module1") < position("// Header: module2"));
+  assert!(position("crate1_a") < position("crate1_b"));
+}
+
+#[test]
+fn platform_ffi_function() {
+  let data = RustGeneratorOutput {
+    modules: vec![empty_module("module1")],
+    ffi_functions: vec![("module1".to_string(),
+                         vec![RustFFIFunction {
+                                return_type: RustType::EmptyTuple,
+                                name: "crate1_native".to_string(),
+                                arguments: Vec::new(),
+                                cfg: Some("target_os = \"windows\"".to_string()),
+                              }])],
+    processed_types: Vec::new(),
+    skipped: Vec::new(),
+    type_manifest: Vec::new(),
+    used_ffi_functions: Default::default(),
+  };
+  let dir = TempTestDir::new("test_platform_ffi_function");
+  let output_path = run_code_generator_with_data(&dir, &data, |_| {});
+  let code = file_to_string(output_path.with_added("src").with_added("ffi.in.rs")).unwrap();
+  assert!(code.contains("  #[cfg(target_os = \"windows\")]\n  pub fn crate1_native();\n"));
+}
+
+/// Returns an implementation of trait `trait_name` for `crate1::module1::Class1`.
+fn class1_trait_impl(trait_name: Vec<&str>) -> TraitImpl {
+  let rust_type = |name: Vec<&str>| {
+    RustType::Common {
+      base: RustName::new(name.into_iter().map(|x| x.to_string()).collect()).unwrap(),
+      generic_arguments: None,
+      is_const: false,
+      is_const2: false,
+      indirection: RustTypeIndirection::None,
+    }
+  };
+  TraitImpl {
+    target_type: rust_type(vec!["crate1", "module1", "Class1"]),
+    trait_type: rust_type(trait_name),
+    associated_types: Vec::new(),
+    extra: None,
+    methods: Vec::new(),
+  }
+}
+
+#[test]
+fn lib_file_prelude() {
+  let dir = TempTestDir::new("test_lib_file_prelude");
+  let mut module1 = empty_module("module1");
+  module1.trait_impls = vec![class1_trait_impl(vec!["cpp_utils", "StaticCast"]),
+                             class1_trait_impl(vec!["std", "ops", "Deref"])];
+  let mut submodule1 = empty_module("submodule1");
+  submodule1.trait_impls = vec![class1_trait_impl(vec!["cpp_utils", "DynamicCast"]),
+                                class1_trait_impl(vec!["cpp_utils", "StaticCast"])];
+  module1.submodules.push(submodule1);
+  let output_path = run_code_generator(&dir, vec![module1], |_| {});
+  let lib_code = file_to_string(output_path.with_added("src").with_added("lib.rs")).unwrap();
+  assert!(lib_code.contains("pub mod prelude {\n\
+                             pub use ::cpp_utils::DynamicCast;\n\
+                             pub use ::cpp_utils::StaticCast;\n\
+                             }\n"));
+  assert!(lib_code.contains("use crate1::prelude::*;"));
+  assert!(!lib_code.contains("Deref"));
+}
+
+#[test]
+fn lib_file_nested_modules() {
+  let dir = TempTestDir::new("test_lib_file_nested_modules");
+  let mut module1 = empty_module("module1");
+  let mut submodule1 = empty_module("submodule1");
+  submodule1.submodules.push(empty_module("submodule2"));
+  module1.submodules.push(submodule1);
+  let output_path = run_code_generator(&dir, vec![module1], |_| {});
+  let src_path = output_path.with_added("src");
+  let lib_code = file_to_string(src_path.with_added("lib.rs")).unwrap();
+  assert!(lib_code.contains("pub mod module1;"));
+  assert!(!lib_code.contains("submodule"));
+  assert!(lib_code.contains("#[allow(dead_code)]\nmod ffi {"));
+  assert!(lib_code.contains("mod type_sizes {"));
+  let module1_code = file_to_string(src_path.with_added("module1.rs")).unwrap();
+  let submodule1_pos = module1_code.find("pub mod submodule1 {").unwrap();
+  let submodule2_pos = module1_code.find("pub mod submodule2 {").unwrap();
+  assert!(submodule1_pos < submodule2_pos);
+}
+
+#[test]
+fn flatten_single_type_modules() {
+  let dir = TempTestDir::new("test_flatten_single_type_modules");
+  let mut module1 = empty_module("module1");
+  module1.types.push(stack_struct());
+  let mut module2 = empty_module("module2");
+  module2.types.push(stack_struct());
+  module2.types.push(stack_struct());
+  module2.types[1].name = RustName::new(vec!["crate1".to_string(),
+                                             "module2".to_string(),
+                                             "Class2".to_string()])
+    .unwrap();
+  let mut submodule1 = empty_module("submodule1");
+  submodule1.types.push(stack_struct());
+  module2.submodules.push(submodule1);
+  let output_path = run_code_generator(&dir, vec![module1, module2], |config| {
+    config.set_flatten_single_type_modules(true);
+  });
+  let src_path = output_path.with_added("src");
+  let lib_code = file_to_string(src_path.with_added("lib.rs")).unwrap();
+  assert!(lib_code.contains("pub use self::module1::Class1;"));
+  assert!(!lib_code.contains("pub use self::module2"));
+  let module2_code = file_to_string(src_path.with_added("module2.rs")).unwrap();
+  assert!(module2_code.contains("pub use self::submodule1::Class1;"));
+}
+
+#[test]
+fn transparent_struct() {
+  let dir = TempTestDir::new("test_transparent_struct");
+  let mut type1 = stack_struct();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut transparent_type, .. } = type1.kind {
+    *transparent_type = Some(RustType::Common {
+                               base: RustName::new(vec!["libc".to_string(),
+                                                        "c_int".to_string()])
+                                 .unwrap(),
+                               generic_arguments: None,
+                               indirection: RustTypeIndirection::None,
+                               is_const: false,
+                               is_const2: false,
+                             });
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("#[repr(transparent)]\npub struct Class1(pub ::libc::c_int);"));
+  assert!(module_code.contains("const Class1_SIZE_CHECK: [(); ::type_sizes::Class1] =\n  \
+                                [(); ::std::mem::size_of::<::libc::c_int>()];"));
+  assert!(!module_code.contains("[u8;"));
+}
+
+#[test]
+fn crate_authors_and_license() {
+  let (_, _, cargo_toml) = generate_crate_files("test_crate_authors_and_license", |config| {
+    config
+      .crate_properties_mut()
+      .set_authors(vec!["Author 1 <author1@example.com>", ""]);
+    config.crate_properties_mut().set_license("");
+  });
+  assert!(cargo_toml.contains("authors = [\"Author 1 <author1@example.com>\"]"));
+  assert!(!cargo_toml.contains("license"));
+
+  let (_, _, cargo_toml) = generate_crate_files("test_crate_authors_and_license2", |config| {
+    config.crate_properties_mut().set_authors(vec!["Author 1"]);
+    config.crate_properties_mut().set_license("MIT");
+    let mut package = toml::Table::new();
+    package.insert("authors".to_string(),
+                   toml::Value::Array(vec![toml::Value::String("Author 2".to_string())]));
+    package.insert("license".to_string(),
+                   toml::Value::String("Apache-2.0".to_string()));
+    let mut custom_fields = toml::Table::new();
+    custom_fields.insert("package".to_string(), toml::Value::Table(package));
+    config.crate_properties_mut().set_custom_fields(custom_fields);
+  });
+  assert!(cargo_toml.contains("authors = [\"Author 2\"]"));
+  assert!(cargo_toml.contains("license = \"Apache-2.0\""));
+}
+
+#[test]
+fn errors_module() {
+  let dir = TempTestDir::new("test_errors_module");
+  let mut try_from_impl = class1_trait_impl(vec!["std", "convert", "TryFrom"]);
+  if let RustType::Common { ref mut generic_arguments, .. } = try_from_impl.trait_type {
+    *generic_arguments = Some(vec![RustType::Common {
+                                     base: RustName::new(vec!["crate1".to_string(),
+                                                              "module1".to_string(),
+                                                              "Class2".to_string()])
+                                       .unwrap(),
+                                     generic_arguments: None,
+                                     is_const: true,
+                                     is_const2: false,
+                                     indirection: RustTypeIndirection::Ref {
+                                       lifetime: Some("a".to_string()),
+                                     },
+                                   }]);
+  }
+  try_from_impl.associated_types = vec![TraitAssociatedType {
+                                          name: "Error".to_string(),
+                                          value: RustType::Common {
+                                            base: RustName::new(vec!["crate1".to_string(),
+                                                                     "errors".to_string(),
+                                                                     "CastError".to_string()])
+                                              .unwrap(),
+                                            generic_arguments: None,
+                                            is_const: false,
+                                            is_const2: false,
+                                            indirection: RustTypeIndirection::None,
+                                          },
+                                        }];
+  try_from_impl.extra = Some(TraitImplExtra::DynamicCastTryFrom { uses_cast_result: false });
+  let mut module1 = empty_module("module1");
+  module1.trait_impls.push(try_from_impl);
+  let output_path = run_code_generator(&dir, vec![module1], |config| {
+    config.set_errors_module(true);
+  });
+  let src_path = output_path.with_added("src");
+  let lib_code = file_to_string(src_path.with_added("lib.rs")).unwrap();
+  assert!(lib_code.contains("pub mod errors;"));
+  let errors_code = file_to_string(src_path.with_added("errors.rs")).unwrap();
+  assert!(errors_code.contains("pub use ::cpp_utils::{CastError, InvalidObjectError, \
+                                UnknownEnumValue};"));
+  assert!(errors_code.contains("pub enum ConversionError {"));
+  let module_code = file_to_string(src_path.with_added("module1.rs")).unwrap();
+  assert!(module_code.contains("type Error = ::errors::CastError;"));
+  assert!(module_code.contains(".ok_or(::errors::CastError)"));
+  assert!(!module_code.contains("::cpp_utils::CastError"));
+
+  // the module is not generated if none of the error types is used
+  let dir2 = TempTestDir::new("test_errors_module2");
+  let output_path2 = run_code_generator(&dir2, vec![empty_module("module1")], |config| {
+    config.set_errors_module(true);
+  });
+  let src_path2 = output_path2.with_added("src");
+  let lib_code2 = file_to_string(src_path2.with_added("lib.rs")).unwrap();
+  assert!(!lib_code2.contains("pub mod errors;"));
+  assert!(!src_path2.with_added("errors.rs").exists());
+}
+
+#[test]
+fn modules_behind_features() {
+  let dir = TempTestDir::new("test_modules_behind_features");
+  let mut module1 = empty_module("module1");
+  module1.types.push(stack_struct());
+  let mut module2 = empty_module("module2");
+  module2
+    .trait_impls
+    .push(class1_trait_impl(vec!["std", "marker", "Copy"]));
+  let mut module3 = empty_module("module3");
+  module3
+    .plugin_code
+    .push("pub fn f(_: &::module1::Class1) {}".to_string());
+  let ffi_function = |name: &str, arguments: Vec<RustFFIArgument>| {
+    RustFFIFunction {
+      return_type: RustType::EmptyTuple,
+      name: name.to_string(),
+      arguments: arguments,
+      cfg: None,
+    }
+  };
+  let class1_ptr = RustFFIArgument {
+    name: "this_ptr".to_string(),
+    argument_type: RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "module1".to_string(), "Class1".to_string()])
+        .unwrap(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::Ptr,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  let modules = vec![module1, module2, module3];
+  let data = RustGeneratorOutput {
+    used_ffi_functions: used_ffi_functions(&modules),
+    modules: modules,
+    // neither function is called by the modules
+    ffi_functions: vec![("module1".to_string(),
+                         vec![ffi_function("crate1_Class1_unused", vec![class1_ptr]),
+                              ffi_function("crate1_free_unused", Vec::new())])],
+    processed_types: Vec::new(),
+    skipped: Vec::new(),
+    type_manifest: Vec::new(),
+  };
+  let output_path = run_code_generator_with_data(&dir, &data, |config| {
+    config.set_modules_behind_features(true);
+  });
+  let lib_code = file_to_string(output_path.with_added("src").with_added("lib.rs")).unwrap();
+  assert!(lib_code.contains("#[cfg(feature = \"module1\")]\npub mod module1;"));
+  assert!(lib_code.contains("#[cfg(feature = \"module2\")]\npub mod module2;"));
+  let cargo_toml = file_to_string(output_path.with_added("Cargo.toml")).unwrap();
+  assert!(cargo_toml.contains("[features]"));
+  assert!(cargo_toml.contains("default = [\"module1\", \"module2\", \"module3\"]"));
+  assert!(cargo_toml.contains("module1 = []"));
+  assert!(cargo_toml.contains("module2 = [\"module1\"]"));
+  // dependencies of plugin code are detected too
+  assert!(cargo_toml.contains("module3 = [\"module1\"]"));
+  // FFI functions are gated by the modules of their types even if they are not used
+  let ffi_code = file_to_string(output_path.with_added("src").with_added("ffi.in.rs")).unwrap();
+  assert!(ffi_code.contains("  #[cfg(feature = \"module1\")]\n  pub fn crate1_Class1_unused("));
+  assert!(ffi_code.contains("\n  pub fn crate1_free_unused();\n"));
+}
+
+#[test]
+fn total_order_ord_impl() {
+  let dir = TempTestDir::new("test_total_order_ord_impl");
+  let mut type1 = stack_struct();
+  let partial_impls = vec![class1_trait_impl(vec!["std", "cmp", "PartialEq"]),
+                           class1_trait_impl(vec!["std", "cmp", "PartialOrd"])];
+  assert!(total_order_impls(&type1.name, &partial_impls[..1], &[], None).is_err());
+  let raw_impl = "impl PartialOrd for Class1 {}".to_string();
+  assert!(total_order_impls(&type1.name, &partial_impls[..1], &[raw_impl], None).is_ok());
+  let mut impls = total_order_impls(&type1.name, &partial_impls, &[], None).unwrap();
+  assert_eq!(impls.len(), 2);
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut trait_impls, .. } = type1.kind {
+    trait_impls.append(&mut impls);
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("impl ::std::cmp::Eq for ::module1::Class1 {"));
+  assert!(module_code.contains("impl ::std::cmp::Ord for ::module1::Class1 {\n\
+                                fn cmp(&self, other: &Self) -> ::std::cmp::Ordering {\n\
+                                ::std::cmp::PartialOrd::partial_cmp(self, other).unwrap()\n\
+                                }\n}"));
+}
+
+#[test]
+fn string_from_impls() {
+  let dir = TempTestDir::new("test_string_from_impls");
+  let mut type1 = stack_struct();
+  let from_impl = |argument_type: RustType, extra: TraitImplExtra| {
+    let mut r = class1_trait_impl(vec!["std", "convert", "From"]);
+    if let RustType::Common { ref mut generic_arguments, .. } = r.trait_type {
+      *generic_arguments = Some(vec![argument_type]);
+    }
+    r.extra = Some(extra);
+    r
+  };
+  let str_type = RustType::Common {
+    base: RustName::new(vec!["str".to_string()]).unwrap(),
+    generic_arguments: None,
+    is_const: true,
+    is_const2: false,
+    indirection: RustTypeIndirection::Ref { lifetime: Some("a".to_string()) },
+  };
+  let string_type = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "string".to_string(), "String".to_string()])
+      .unwrap(),
+    generic_arguments: None,
+    is_const: false,
+    is_const2: false,
+    indirection: RustTypeIndirection::None,
+  };
+  let mut impls =
+    vec![from_impl(str_type,
+                   TraitImplExtra::StringFromUtf8 {
+                     ffi_function_name: "crate1_Class1_fromUtf8".to_string(),
+                   }),
+         from_impl(string_type, TraitImplExtra::StringFromStdString)];
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut trait_impls, .. } = type1.kind {
+    trait_impls.append(&mut impls);
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("impl<'a> ::std::convert::From<&'a str> for ::module1::Class1 {\n\
+                                fn from(value: &'a str) -> Self {\n"));
+  assert!(module_code.contains("let len = ::std::convert::TryFrom::try_from(value.len())\
+                                .expect(\"string is too long\");\n"));
+  assert!(module_code.contains("::ffi::crate1_Class1_fromUtf8(value.as_ptr() as *const _, \
+                                len, &mut object)"));
+  assert!(module_code.contains("impl ::std::convert::From<::std::string::String> \
+                                for ::module1::Class1 {\n\
+                                fn from(value: ::std::string::String) -> Self {\n\
+                                Self::from(value.as_str())\n\
+                                }\n}"));
+}
+
+#[test]
+fn static_upcast_owned_impl() {
+  let dir = TempTestDir::new("test_static_upcast_owned_impl");
+  let mut trait1 = class1_trait_impl(vec!["cpp_utils", "StaticUpcastOwned"]);
+  if let RustType::Common { ref mut generic_arguments, .. } = trait1.trait_type {
+    *generic_arguments = Some(vec![RustType::Common {
+                                     base: RustName::new(vec!["crate1".to_string(),
+                                                              "module1".to_string(),
+                                                              "Base1".to_string()])
+                                       .unwrap(),
+                                     generic_arguments: None,
+                                     is_const: false,
+                                     is_const2: false,
+                                     indirection: RustTypeIndirection::None,
+                                   }]);
+  }
+  trait1.extra = Some(TraitImplExtra::StaticUpcastOwned);
+  let mut module = empty_module("module1");
+  module.trait_impls = vec![trait1];
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("unsafe impl ::cpp_utils::StaticUpcastOwned<::module1::Base1> \
+                                for ::module1::Class1 {\n}"));
+}
+
+#[test]
+fn thread_safety_markers() {
+  let dir = TempTestDir::new("test_thread_safety_markers");
+  let mut type1 = stack_struct();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut is_send, ref mut is_sync, .. } =
+    type1.kind {
+    *is_send = true;
+    *is_sync = true;
+  }
+  let mut type2 = stack_struct();
+  type2.name = RustName::new(vec!["crate1".to_string(),
+                                  "module1".to_string(),
+                                  "Class2".to_string()])
+    .unwrap();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut is_send, .. } = type2.kind {
+    *is_send = true;
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  module.types.push(type2);
+  module.types.push(stack_struct());
+  module.types[2].name = RustName::new(vec!["crate1".to_string(),
+                                            "module1".to_string(),
+                                            "Class3".to_string()])
+    .unwrap();
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("unsafe impl Send for Class1 {}"));
+  assert!(module_code.contains("unsafe impl Sync for Class1 {}"));
+  assert!(module_code.contains("unsafe impl Send for Class2 {}"));
+  assert_eq!(module_code.matches("unsafe impl Send").count(), 2);
+  assert_eq!(module_code.matches("unsafe impl Sync").count(), 1);
+}
+
+#[test]
+fn associated_constants() {
+  let dir = TempTestDir::new("test_associated_constants");
+  let mut type1 = stack_struct();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut constants, .. } = type1.kind {
+    constants.push(RustAssociatedConstant {
+                     name: "MAX_SIZE".to_string(),
+                     value_type: RustType::Common {
+                       base: RustName::new(vec!["libc".to_string(), "c_int".to_string()])
+                         .unwrap(),
+                       generic_arguments: None,
+                       is_const: false,
+                       is_const2: false,
+                       indirection: RustTypeIndirection::None,
+                     },
+                     value: "16".to_string(),
+                   });
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("impl Class1 {\npub const MAX_SIZE: ::libc::c_int = 16;\n}"));
+}
+
+#[test]
+fn qstring_return_value() {
+  let dir = TempTestDir::new("test_qstring_return_value");
+  let qstring_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "QString".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let qstring_ptr_type = CppType {
+    indirection: CppTypeIndirection::Ptr,
+    ..qstring_type.clone()
+  };
+  let mut method = empty_rust_method(vec!["crate1", "module1", "func1"], RustMethodScope::Free);
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_func1".to_string();
+    variant.cpp_method.allocation_place = ReturnValueAllocationPlace::Stack;
+    variant.cpp_method.c_signature.arguments.push(CppFfiMethodArgument {
+                                                     name: "output".to_string(),
+                                                     argument_type: CppFfiType {
+                                                       original_type: qstring_type.clone(),
+                                                       ffi_type: qstring_ptr_type.clone(),
+                                                       conversion:
+                                                         CppIndirectionChange::ValueToPointer,
+                                                     },
+                                                     meaning: CppFfiArgumentMeaning::ReturnValue,
+                                                   });
+    variant.return_type_ffi_index = Some(0);
+    variant.return_type = CompleteType {
+      cpp_type: qstring_type,
+      cpp_ffi_type: qstring_ptr_type,
+      cpp_to_ffi_conversion: CppIndirectionChange::ValueToPointer,
+      rust_ffi_type: RustType::Common {
+        base: RustName::new(vec!["qt_core".to_string(),
+                                 "string".to_string(),
+                                 "String".to_string()])
+          .unwrap(),
+        generic_arguments: None,
+        indirection: RustTypeIndirection::Ptr,
+        is_const: false,
+        is_const2: false,
+      },
+      rust_api_type: RustType::Common {
+        base: RustName::new(vec!["std".to_string(), "string".to_string(), "String".to_string()])
+          .unwrap(),
+        generic_arguments: None,
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      },
+      rust_api_to_c_conversion: RustToCTypeConversion::QStringToString,
+    };
+  }
+  let mut module = empty_module("module1");
+  module.functions.push(method);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub fn func1() -> ::std::string::String {"));
+  assert!(module_code.contains("let mut object: ::qt_core::string::String = "));
+  assert!(module_code.contains("::ffi::crate1_func1(&mut object);"));
+  assert!(module_code.contains("::std::string::String::from(&object)\n}"));
+}
+
+#[test]
+fn fallible_constructor() {
+  let dir = TempTestDir::new("test_fallible_constructor");
+  let class_type = CppType {
+    base: CppTypeBase::Class(CppTypeClassBase {
+                               name: "Class1".to_string(),
+                               template_arguments: None,
+                             }),
+    indirection: CppTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let class_ptr_type = CppType {
+    indirection: CppTypeIndirection::Ptr,
+    ..class_type.clone()
+  };
+  let class_rust_type = RustType::Common {
+    base: RustName::new(vec!["crate1".to_string(), "module1".to_string(), "Class1".to_string()])
+      .unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let box_type = RustType::Common {
+    base: default_box_type(),
+    generic_arguments: Some(vec![class_rust_type.clone()]),
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let error_type = RustType::Common {
+    base: RustName::new(vec!["cpp_utils".to_string(), "InvalidObjectError".to_string()])
+      .unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  let mut method = empty_rust_method(vec!["new"],
+                                     RustMethodScope::Impl {
+                                       target_type: class_rust_type.clone(),
+                                     });
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_Class1_new".to_string();
+    variant.cpp_method.allocation_place = ReturnValueAllocationPlace::Heap;
+    variant.validity_check_function = Some("crate1_Class1_isValid".to_string());
+    variant.return_type = CompleteType {
+      cpp_type: class_type,
+      cpp_ffi_type: class_ptr_type,
+      cpp_to_ffi_conversion: CppIndirectionChange::ValueToPointer,
+      rust_ffi_type: RustType::Common {
+        indirection: RustTypeIndirection::Ptr,
+        ..class_rust_type
+      },
+      rust_api_type: RustType::Common {
+        base: RustName::new(vec!["std".to_string(), "result".to_string(), "Result".to_string()])
+          .unwrap(),
+        generic_arguments: Some(vec![box_type, error_type]),
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      },
+      rust_api_to_c_conversion: RustToCTypeConversion::CppBoxToPtr,
+    };
+  }
+  let mut type1 = stack_struct();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut methods, .. } = type1.kind {
+    methods.push(method);
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub fn new() -> \
+                                ::std::result::Result<::cpp_utils::CppBox<::module1::Class1>, \
+                                ::cpp_utils::InvalidObjectError> {"));
+  assert!(module_code.contains("let object = {\n\
+                                let ffi_result = unsafe { ::ffi::crate1_Class1_new() };\n\
+                                unsafe { ::cpp_utils::CppBox::new(ffi_result) }\n\
+                                };\n"));
+  assert!(module_code.contains("if unsafe { ::ffi::crate1_Class1_isValid(object.as_ptr()) } {\n\
+                                Ok(object)\n\
+                                } else {\n\
+                                Err(::cpp_utils::InvalidObjectError)\n\
+                                }"));
+}
+
+#[test]
+fn parented_constructor() {
+  let dir = TempTestDir::new("test_parented_constructor");
+  let class_type = |indirection: CppTypeIndirection| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: "Class1".to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let class_rust_type = |indirection: RustTypeIndirection| {
+    RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "module1".to_string(), "Class1".to_string()])
+        .unwrap(),
+      generic_arguments: None,
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let mut method = empty_rust_method(vec!["new"],
+                                     RustMethodScope::Impl {
+                                       target_type: class_rust_type(RustTypeIndirection::None),
+                                     });
+  method.is_unsafe = true;
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_Class1_new".to_string();
+    variant.cpp_method.allocation_place = ReturnValueAllocationPlace::Heap;
+    variant
+      .cpp_method
+      .cpp_method
+      .arguments
+      .push(::cpp_method::CppMethodArgument {
+              name: "parent".to_string(),
+              argument_type: class_type(CppTypeIndirection::Ptr),
+              has_default_value: false,
+            });
+    let parent_type = CppFfiType {
+      original_type: class_type(CppTypeIndirection::Ptr),
+      ffi_type: class_type(CppTypeIndirection::Ptr),
+      conversion: CppIndirectionChange::NoChange,
+    };
+    variant
+      .cpp_method
+      .c_signature
+      .arguments
+      .push(CppFfiMethodArgument {
+              name: "parent".to_string(),
+              argument_type: parent_type.clone(),
+              meaning: CppFfiArgumentMeaning::Argument(0),
+            });
+    variant
+      .arguments
+      .push(RustMethodArgument {
+              argument_type: CompleteType {
+                cpp_type: parent_type.original_type,
+                cpp_ffi_type: parent_type.ffi_type,
+                cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+                rust_ffi_type: class_rust_type(RustTypeIndirection::Ptr),
+                rust_api_type: class_rust_type(RustTypeIndirection::Ptr),
+                rust_api_to_c_conversion: RustToCTypeConversion::None,
+              },
+              name: "parent".to_string(),
+              ffi_index: 0,
+            });
+    variant.return_type = CompleteType {
+      cpp_type: class_type(CppTypeIndirection::None),
+      cpp_ffi_type: class_type(CppTypeIndirection::Ptr),
+      cpp_to_ffi_conversion: CppIndirectionChange::ValueToPointer,
+      rust_ffi_type: class_rust_type(RustTypeIndirection::Ptr),
+      rust_api_type: RustType::Common {
+        base: RustName::new(vec!["cpp_utils".to_string(), "MaybeOwned".to_string()]).unwrap(),
+        generic_arguments: Some(vec![class_rust_type(RustTypeIndirection::None)]),
+        indirection: RustTypeIndirection::None,
+        is_const: false,
+        is_const2: false,
+      },
+      rust_api_to_c_conversion: RustToCTypeConversion::MaybeOwnedToPtr,
+    };
+  }
+  let mut type1 = stack_struct();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut methods, .. } = type1.kind {
+    methods.push(method);
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub unsafe fn new(parent: *mut ::module1::Class1) -> \
+                                ::cpp_utils::MaybeOwned<::module1::Class1> {"));
+  // the object is only owned by the caller if there is no parent
+  assert!(module_code.contains("let parent_ptr = parent;\n\
+                                ::cpp_utils::MaybeOwned::new(\
+                                ::ffi::crate1_Class1_new(parent_ptr), parent_ptr.is_null())"));
+}
+
+#[test]
+fn debug_size_assertions() {
+  let dir = TempTestDir::new("test_debug_size_assertions");
+  let class_rust_type = |name: &str, indirection: RustTypeIndirection| {
+    RustType::Common {
+      base: RustName::new(vec!["crate1".to_string(), "module1".to_string(), name.to_string()])
+        .unwrap(),
+      generic_arguments: None,
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let class_type = |name: &str, indirection: CppTypeIndirection| {
+    CppType {
+      base: CppTypeBase::Class(CppTypeClassBase {
+                                 name: name.to_string(),
+                                 template_arguments: None,
+                               }),
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let constructor = |name: &str, allocation_place: ReturnValueAllocationPlace| {
+    let mut method = empty_rust_method(vec!["new"],
+                                       RustMethodScope::Impl {
+                                         target_type: class_rust_type(name,
+                                                                      RustTypeIndirection::None),
+                                       });
+    if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+      variant.cpp_method.c_name = format!("crate1_{}_new", name);
+      variant.cpp_method.cpp_method.class_membership = Some({
+        let mut info = ::tests::cpp_method::empty_membership("Class1");
+        info.kind = ::cpp_method::CppMethodKind::Constructor;
+        info
+      });
+      let ffi_type = CppFfiType {
+        original_type: class_type(name, CppTypeIndirection::None),
+        ffi_type: class_type(name, CppTypeIndirection::Ptr),
+        conversion: CppIndirectionChange::ValueToPointer,
+      };
+      let (rust_api_type, conversion) = if allocation_place == ReturnValueAllocationPlace::Stack {
+        variant
+          .cpp_method
+          .c_signature
+          .arguments
+          .push(CppFfiMethodArgument {
+                  name: "output".to_string(),
+                  argument_type: ffi_type.clone(),
+                  meaning: CppFfiArgumentMeaning::ReturnValue,
+                });
+        variant.return_type_ffi_index = Some(0);
+        (class_rust_type(name, RustTypeIndirection::None), RustToCTypeConversion::ValueToPtr)
+      } else {
+        (RustType::Common {
+           base: default_box_type(),
+           generic_arguments: Some(vec![class_rust_type(name, RustTypeIndirection::None)]),
+           indirection: RustTypeIndirection::None,
+           is_const: false,
+           is_const2: false,
+         },
+         RustToCTypeConversion::CppBoxToPtr)
+      };
+      variant.cpp_method.allocation_place = allocation_place;
+      variant.return_type = CompleteType {
+        cpp_type: ffi_type.original_type,
+        cpp_ffi_type: ffi_type.ffi_type,
+        cpp_to_ffi_conversion: CppIndirectionChange::ValueToPointer,
+        rust_ffi_type: class_rust_type(name, RustTypeIndirection::Ptr),
+        rust_api_type: rust_api_type,
+        rust_api_to_c_conversion: conversion,
+      };
+    }
+    method
+  };
+  let mut stack_type = stack_struct();
+  if let RustTypeDeclarationKind::CppTypeWrapper { ref mut methods, .. } = stack_type.kind {
+    methods.push(constructor("Class1", ReturnValueAllocationPlace::Stack));
+  }
+  let mut heap_type = stack_struct();
+  heap_type.name = RustName::new(vec!["crate1".to_string(),
+                                      "module1".to_string(),
+                                      "Class2".to_string()])
+    .unwrap();
+  if let RustTypeDeclarationKind::CppTypeWrapper {
+           ref mut kind,
+           ref mut methods,
+           ..
+         } = heap_type.kind {
+    *kind = RustTypeWrapperKind::Struct {
+      size_const_name: None,
+      is_deletable: true,
+      slot_wrapper: None,
+    };
+    methods.push(constructor("Class2", ReturnValueAllocationPlace::Heap));
+  }
+  let mut module = empty_module("module1");
+  module.types.push(stack_type);
+  module.types.push(heap_type);
+  let output_path = run_code_generator(&dir, vec![module], |config| {
+    config.set_debug_size_assertions(true);
+  });
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("debug_assert_eq!(::std::mem::size_of::<::module1::Class1>(), \
+                                ::type_sizes::CRATE1_MODULE1_CLASS1);"));
+  assert!(!module_code.contains("size_of::<::module1::Class2>()"));
+  assert!(module_code.contains("::ffi::crate1_Class2_new()"));
+}
+
+#[test]
+fn layout_tests() {
+  let (_, module_code, _) = generate_crate_files("test_layout_tests", |config| {
+    config.set_generate_layout_tests(true);
+  });
+  assert!(module_code.contains("#[cfg(test)]\nmod layout_tests {\n#[test]\nfn class1_size() {\n  \
+                                assert_eq!(::std::mem::size_of::<super::Class1>(), \
+                                ::type_sizes::Class1);\n}"));
+}
+
+#[test]
+fn inline_wrappers() {
+  let dir = TempTestDir::new("test_inline_wrappers");
+  let mut module = empty_module("module1");
+  module.functions.push(empty_rust_method(vec!["crate1", "module1", "func1"],
+                                          RustMethodScope::Free));
+  let output_path = run_code_generator(&dir, vec![module], |config| {
+    config.set_inline_wrappers(true);
+  });
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("#[inline]\npub fn func1()"));
+}
+
+#[test]
+fn platform_method_cfg() {
+  let dir = TempTestDir::new("test_platform_method_cfg");
+  let mut module = empty_module("module1");
+  let mut method = empty_rust_method(vec!["crate1", "module1", "func1"], RustMethodScope::Free);
+  method.cfg = Some("target_os = \"windows\"".to_string());
+  module.functions.push(method);
+  module.functions.push(empty_rust_method(vec!["crate1", "module1", "func2"],
+                                          RustMethodScope::Free));
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("#[cfg(target_os = \"windows\")]\npub fn func1()"));
+  assert_eq!(module_code.matches("#[cfg(").count(), 1);
+}
+
+#[test]
+fn out_parameter_tuple() {
+  let dir = TempTestDir::new("test_out_parameter_tuple");
+  let int_type = |indirection: CppTypeIndirection| {
+    CppType {
+      base: CppTypeBase::BuiltInNumeric(::cpp_type::CppBuiltInNumericType::Int),
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let rust_int_type = |indirection: RustTypeIndirection| {
+    RustType::Common {
+      base: RustName::new(vec!["libc".to_string(), "c_int".to_string()]).unwrap(),
+      generic_arguments: None,
+      indirection: indirection,
+      is_const: false,
+      is_const2: false,
+    }
+  };
+  let bool_type = RustType::Common {
+    base: RustName::new(vec!["bool".to_string()]).unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+  // `bool parse(int* outCode)`
+  let mut method = empty_rust_method(vec!["crate1", "module1", "parse"], RustMethodScope::Free);
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_parse".to_string();
+    variant.cpp_method.c_signature.arguments.push(CppFfiMethodArgument {
+                                                     name: "outCode".to_string(),
+                                                     argument_type: CppFfiType {
+                                                       original_type:
+                                                         int_type(CppTypeIndirection::Ptr),
+                                                       ffi_type: int_type(CppTypeIndirection::Ptr),
+                                                       conversion: CppIndirectionChange::NoChange,
+                                                     },
+                                                     meaning: CppFfiArgumentMeaning::Argument(0),
+                                                   });
+    variant.arguments.push(RustMethodArgument {
+                             argument_type: CompleteType {
+                               cpp_type: int_type(CppTypeIndirection::Ptr),
+                               cpp_ffi_type: int_type(CppTypeIndirection::Ptr),
+                               cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+                               rust_ffi_type: rust_int_type(RustTypeIndirection::Ptr),
+                               rust_api_type: rust_int_type(RustTypeIndirection::None),
+                               rust_api_to_c_conversion: RustToCTypeConversion::OutValueToPtr,
+                             },
+                             name: "out_code".to_string(),
+                             ffi_index: 0,
+                           });
+    variant.return_type.rust_ffi_type = bool_type.clone();
+    variant.return_type.rust_api_type =
+      RustType::Tuple(vec![bool_type, rust_int_type(RustTypeIndirection::None)]);
+  }
+  let mut module = empty_module("module1");
+  module.functions.push(method);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub fn parse() -> (bool, ::libc::c_int) {\n\
+                                let mut out_code: ::libc::c_int = Default::default();\n\
+                                let ffi_result = unsafe { ::ffi::crate1_parse(&mut out_code) };\n\
+                                (ffi_result, out_code)"));
+}
+
+#[test]
+fn nullable_char_ptr_argument() {
+  let dir = TempTestDir::new("test_nullable_char_ptr_argument");
+  let char_ptr_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(::cpp_type::CppBuiltInNumericType::Char),
+    indirection: CppTypeIndirection::Ptr,
+    is_const: true,
+    is_const2: false,
+  };
+  let cstr_type = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "ffi".to_string(), "CStr".to_string()]).unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::Ref { lifetime: None },
+    is_const: true,
+    is_const2: false,
+  };
+  // `void print(const char* text)`
+  let mut method = empty_rust_method(vec!["crate1", "module1", "print"], RustMethodScope::Free);
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_print".to_string();
+    variant.cpp_method.c_signature.arguments.push(CppFfiMethodArgument {
+                                                     name: "text".to_string(),
+                                                     argument_type: CppFfiType {
+                                                       original_type: char_ptr_type.clone(),
+                                                       ffi_type: char_ptr_type.clone(),
+                                                       conversion: CppIndirectionChange::NoChange,
+                                                     },
+                                                     meaning: CppFfiArgumentMeaning::Argument(0),
+                                                   });
+    variant.arguments.push(RustMethodArgument {
+                             argument_type: CompleteType {
+                               cpp_type: char_ptr_type.clone(),
+                               cpp_ffi_type: char_ptr_type,
+                               cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+                               rust_ffi_type: RustType::Common {
+                                 base: RustName::new(vec!["libc".to_string(),
+                                                          "c_char".to_string()])
+                                   .unwrap(),
+                                 generic_arguments: None,
+                                 indirection: RustTypeIndirection::Ptr,
+                                 is_const: true,
+                                 is_const2: false,
+                               },
+                               rust_api_type: RustType::Common {
+                                 base: RustName::new(vec!["std".to_string(),
+                                                          "option".to_string(),
+                                                          "Option".to_string()])
+                                   .unwrap(),
+                                 generic_arguments: Some(vec![cstr_type]),
+                                 indirection: RustTypeIndirection::None,
+                                 is_const: false,
+                                 is_const2: false,
+                               },
+                               rust_api_to_c_conversion: RustToCTypeConversion::CharPtrToCStr,
+                             },
+                             name: "text".to_string(),
+                             ffi_index: 0,
+                           });
+  }
+  let mut module = empty_module("module1");
+  module.functions.push(method);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub fn print(text: ::std::option::Option<&::std::ffi::CStr>)"));
+  assert!(module_code.contains("::ffi::crate1_print(text.map_or(::std::ptr::null(), \
+                                |s| s.as_ptr()))"));
+}
+
+#[test]
+fn nullable_wchar_ptr_argument() {
+  let dir = TempTestDir::new("test_nullable_wchar_ptr_argument");
+  let wchar_ptr_type = CppType {
+    base: CppTypeBase::BuiltInNumeric(::cpp_type::CppBuiltInNumericType::WChar),
+    indirection: CppTypeIndirection::Ptr,
+    is_const: true,
+    is_const2: false,
+  };
+  let os_str_type = RustType::Common {
+    base: RustName::new(vec!["std".to_string(), "ffi".to_string(), "OsStr".to_string()]).unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::Ref { lifetime: None },
+    is_const: true,
+    is_const2: false,
+  };
+  // `void print(const wchar_t* text)`
+  let mut method = empty_rust_method(vec!["crate1", "module1", "print"], RustMethodScope::Free);
+  if let RustMethodArguments::SingleVariant(ref mut variant) = method.arguments {
+    variant.cpp_method.c_name = "crate1_print".to_string();
+    variant.cpp_method.c_signature.arguments.push(CppFfiMethodArgument {
+                                                     name: "text".to_string(),
+                                                     argument_type: CppFfiType {
+                                                       original_type: wchar_ptr_type.clone(),
+                                                       ffi_type: wchar_ptr_type.clone(),
+                                                       conversion: CppIndirectionChange::NoChange,
+                                                     },
+                                                     meaning: CppFfiArgumentMeaning::Argument(0),
+                                                   });
+    variant.arguments.push(RustMethodArgument {
+                             argument_type: CompleteType {
+                               cpp_type: wchar_ptr_type.clone(),
+                               cpp_ffi_type: wchar_ptr_type,
+                               cpp_to_ffi_conversion: CppIndirectionChange::NoChange,
+                               rust_ffi_type: RustType::Common {
+                                 base: RustName::new(vec!["libc".to_string(),
+                                                          "wchar_t".to_string()])
+                                   .unwrap(),
+                                 generic_arguments: None,
+                                 indirection: RustTypeIndirection::Ptr,
+                                 is_const: true,
+                                 is_const2: false,
+                               },
+                               rust_api_type: RustType::Common {
+                                 base: RustName::new(vec!["std".to_string(),
+                                                          "option".to_string(),
+                                                          "Option".to_string()])
+                                   .unwrap(),
+                                 generic_arguments: Some(vec![os_str_type]),
+                                 indirection: RustTypeIndirection::None,
+                                 is_const: false,
+                                 is_const2: false,
+                               },
+                               rust_api_to_c_conversion:
+                                 RustToCTypeConversion::WCharPtrToOsString,
+                             },
+                             name: "text".to_string(),
+                             ffi_index: 0,
+                           });
+  }
+  let mut module = empty_module("module1");
+  module.functions.push(method);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub fn print(text: ::std::option::Option<&::std::ffi::OsStr>)"));
+  assert!(module_code.contains("::ffi::crate1_print(text.map(|s| \
+                                ::std::os::windows::ffi::OsStrExt::encode_wide(s)\
+                                .chain(::std::iter::once(0))\
+                                .collect::<Vec<u16>>())\
+                                .as_ref()\
+                                .map_or(::std::ptr::null(), |v| v.as_ptr()))"));
+}
+
+#[test]
+fn flaggable_enum_from_impl() {
+  let dir = TempTestDir::new("test_flaggable_enum_from_impl");
+  let enum_name = RustName::new(vec!["crate1".to_string(),
+                                     "module1".to_string(),
+                                     "Enum1".to_string()])
+    .unwrap();
+  let mut module = empty_module("module1");
+  module.types.push(RustTypeDeclaration {
+                      is_public: true,
+                      name: enum_name.clone(),
+                      kind: RustTypeDeclarationKind::CppTypeWrapper {
+                        kind: RustTypeWrapperKind::Enum {
+                          values: vec![RustEnumValue {
+                                         name: "Value1".to_string(),
+                                         value: 1,
+                                         cpp_docs: Vec::new(),
+                                         is_dummy: false,
+                                         is_hidden: false,
+                                       },
+                                       RustEnumValue {
+                                         name: "Value2".to_string(),
+                                         value: 2,
+                                         cpp_docs: Vec::new(),
+                                         is_dummy: false,
+                                         is_hidden: false,
+                                       }],
+                          is_flaggable: true,
+                        },
+                        cpp_type_name: "Enum1".to_string(),
+                        cpp_template_arguments: None,
+                        cpp_doc: None,
+                        methods: Vec::new(),
+                        constants: Vec::new(),
+                        trait_impls: vec![flags_from_enum_impl(&enum_name).unwrap()],
+                        qt_receivers: Vec::new(),
+                        qt_hash_types: None,
+                        vector_info: None,
+                        list_element_type: None,
+                        pod_traits: None,
+                        transparent_type: None,
+                        iterator_adapter: None,
+                        is_send: false,
+                        is_sync: false,
+                      },
+                      rust_doc: None,
+                      doc_override: None,
+                    });
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("impl ::std::convert::From<::module1::Enum1> for \
+                                ::qt_core::flags::Flags<::module1::Enum1> {\n\
+                                fn from(value: ::module1::Enum1) -> Self {\n\
+                                Self::from_enum(value)\n}\n}"));
+}
+
+#[test]
+fn enum_try_from_impl() {
+  let dir = TempTestDir::new("test_enum_try_from_impl");
+  let mut module = empty_module("module1");
+  module.types.push(RustTypeDeclaration {
+                      is_public: true,
+                      name: RustName::new(vec!["crate1".to_string(),
+                                               "module1".to_string(),
+                                               "Enum1".to_string()])
+                        .unwrap(),
+                      kind: RustTypeDeclarationKind::CppTypeWrapper {
+                        kind: RustTypeWrapperKind::Enum {
+                          values: vec![RustEnumValue {
+                                         name: "Value1".to_string(),
+                                         value: -1,
+                                         cpp_docs: Vec::new(),
+                                         is_dummy: false,
+                                         is_hidden: false,
+                                       },
+                                       RustEnumValue {
+                                         name: "_Blocked".to_string(),
+                                         value: 2,
+                                         cpp_docs: Vec::new(),
+                                         is_dummy: false,
+                                         is_hidden: true,
+                                       },
+                                       RustEnumValue {
+                                         name: "_Invalid".to_string(),
+                                         value: 3,
+                                         cpp_docs: Vec::new(),
+                                         is_dummy: true,
+                                         is_hidden: false,
+                                       }],
+                          is_flaggable: false,
+                        },
+                        cpp_type_name: "Enum1".to_string(),
+                        cpp_template_arguments: None,
+                        cpp_doc: None,
+                        methods: Vec::new(),
+                        constants: Vec::new(),
+                        trait_impls: Vec::new(),
+                        qt_receivers: Vec::new(),
+                        qt_hash_types: None,
+                        vector_info: None,
+                        list_element_type: None,
+                        pod_traits: None,
+                        transparent_type: None,
+                        iterator_adapter: None,
+                        is_send: false,
+                        is_sync: false,
+                      },
+                      rust_doc: None,
+                      doc_override: None,
+                    });
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("impl ::std::convert::TryFrom<i32> for Enum1 {"));
+  assert!(module_code.contains("type Error = ::cpp_utils::UnknownEnumValue<i32>;"));
+  assert!(module_code.contains("-1 => Ok(Enum1::Value1),"));
+  // blocked C++ variants are hidden, but their values are still valid
+  assert!(module_code.contains("#[doc(hidden)]\n  _Blocked = 2"));
+  assert!(module_code.contains("2 => Ok(Enum1::_Blocked),"));
+  assert!(!module_code.contains("Ok(Enum1::_Invalid)"));
+  assert!(module_code.contains("_ => Err(::cpp_utils::UnknownEnumValue(value)),"));
+}
+
+#[test]
+fn doc_hook_appends_text() {
+  let mut module = empty_module("module1");
+  module.functions.push(empty_rust_method(vec!["crate1", "module1", "foo"],
+                                          RustMethodScope::Free));
+  module.functions.push(empty_rust_method(vec!["crate1", "module1", "bar"],
+                                          RustMethodScope::Free));
+  let auto_doc = doc_formatter::method_doc(&module.functions[0]);
+  let hook: Box<DocHookFn> = Box::new(|name: &str, doc: String| {
+    if name == "crate1::module1::foo" {
+      doc + "\n\nExample: `foo()`"
+    } else {
+      doc
+    }
+  });
+  apply_doc_hooks(&[&hook], &mut module);
+  assert_eq!(module.functions[0].doc_override,
+             Some(format!("{}\n\nExample: `foo()`", auto_doc)));
+  assert_eq!(doc_formatter::method_doc(&module.functions[0]),
+             format!("{}\n\nExample: `foo()`", auto_doc));
+  // returning the input unchanged doesn't override the documentation
+  assert_eq!(module.functions[1].doc_override, None);
+}
+
+#[test]
+fn cpp_doc_links() {
+  assert_eq!(doc_formatter::cpp_doc_url("https://doc.qt.io/qt-5/", "QString", Some("arg")),
+             "https://doc.qt.io/qt-5/qstring.html#arg");
+  assert_eq!(doc_formatter::cpp_doc_url("https://doc.qt.io/qt-5", "QTextBlock::iterator", None),
+             "https://doc.qt.io/qt-5/qtextblock-iterator.html");
+
+  let mut method = empty_rust_method(vec!["crate1", "module1", "foo"], RustMethodScope::Free);
+  method.variant_docs.push(RustMethodDocItem {
+                             doc: None,
+                             rust_fns: Vec::new(),
+                             cpp_fn: "int QString::foo()".to_string(),
+                           });
+  assert!(doc_formatter::method_doc(&method).starts_with("C++ method: "));
+  method.cpp_doc_url = Some("https://doc.qt.io/qt-5/qstring.html#foo".to_string());
+  assert!(doc_formatter::method_doc(&method)
+            .starts_with("<a href=\"https://doc.qt.io/qt-5/qstring.html#foo\">C++ method:</a> "));
+}
+
+#[test]
+fn crate_private_type() {
+  let dir = TempTestDir::new("test_crate_private_type");
+  let mut type1 = stack_struct();
+  type1.is_public = false;
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("pub(crate) struct Class1("));
+  assert!(!module_code.contains("\npub struct Class1("));
+}
+
+#[test]
+fn list_iterator() {
+  let dir = TempTestDir::new("test_list_iterator");
+  let mut type1 = stack_struct();
+  let type_rust_type = RustType::Common {
+    base: type1.name.clone(),
+    generic_arguments: None,
+    is_const: false,
+    is_const2: false,
+    indirection: RustTypeIndirection::None,
+  };
+  if let RustTypeDeclarationKind::CppTypeWrapper {
+           ref mut methods,
+           ref mut list_element_type,
+           ..
+         } = type1.kind {
+    for name in &["at", "size"] {
+      methods.push(empty_rust_method(vec![*name],
+                                     RustMethodScope::Impl {
+                                       target_type: type_rust_type.clone(),
+                                     }));
+    }
+    *list_element_type = Some(RustType::Common {
+                                base: RustName::new(vec!["libc".to_string(),
+                                                         "c_int".to_string()])
+                                  .unwrap(),
+                                generic_arguments: None,
+                                is_const: false,
+                                is_const2: false,
+                                indirection: RustTypeIndirection::None,
+                              });
+  }
+  let mut module = empty_module("module1");
+  module.types.push(type1);
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code.contains("impl<'a> Iterator for Class1Iter<'a> {"));
+  assert!(module_code.contains("type Item = &'a ::libc::c_int;"));
+  assert!(module_code.contains("let item = self.list.at(self.index);"));
+  assert!(module_code.contains("size: self.size(),"));
+}
+
+#[test]
+fn deprecated_method() {
+  let dir = TempTestDir::new("test_deprecated_method");
+  let mut module = empty_module("module1");
+  let mut method = empty_rust_method(vec!["crate1", "module1", "func1"], RustMethodScope::Free);
+  method.deprecated = Some("use \"func2\" instead".to_string());
+  module.functions.push(method);
+  module.functions.push(empty_rust_method(vec!["crate1", "module1", "func2"],
+                                          RustMethodScope::Free));
+  let output_path = run_code_generator(&dir, vec![module], |_| {});
+  let module_code = file_to_string(output_path.with_added("src").with_added("module1.rs"))
+    .unwrap();
+  assert!(module_code
+            .contains("#[deprecated(note = \"use \\\"func2\\\" instead\")]\npub fn func1()"));
+  assert_eq!(module_code.matches("#[deprecated").count(), 1);
+}
+
+#[test]
+fn vector_impls() {
+  let type_name = RustName::new(vec!["crate1".to_string(),
+                                     "module1".to_string(),
+                                     "VectorClass2".to_string()])
+    .unwrap();
+  let scope = RustMethodScope::Impl {
+    target_type: RustType::Common {
+      base: type_name.clone(),
+      generic_arguments: None,
+      indirection: RustTypeIndirection::None,
+      is_const: false,
+      is_const2: false,
+    },
+  };
+  let generate = |dir_name: &str, info: RustVectorInfo| {
+    let mut module = empty_module("module1");
+    module.types.push(RustTypeDeclaration {
+                        is_public: true,
+                        name: type_name.clone(),
+                        kind: RustTypeDeclarationKind::CppTypeWrapper {
+                          kind: RustTypeWrapperKind::Struct {
+                            size_const_name: None,
+                            is_deletable: true,
+                            slot_wrapper: None,
+                          },
+                          cpp_type_name: "std::vector".to_string(),
+                          cpp_template_arguments: None,
+                          cpp_doc: None,
+                          methods: ["new", "size", "at", "push_back"]
+                            .iter()
+                            .map(|name| empty_rust_method(vec![name], scope.clone()))
+                            .collect(),
+                          constants: Vec::new(),
+                          trait_impls: Vec::new(),
+                          qt_receivers: Vec::new(),
+                          qt_hash_types: None,
+                          vector_info: Some(info),
+                          list_element_type: None,
+                          pod_traits: None,
+                          transparent_type: None,
+                          iterator_adapter: None,
+                          is_send: false,
+                          is_sync: false,
+                        },
+                        rust_doc: None,
+                        doc_override: None,
+                      });
+    let dir = TempTestDir::new(dir_name);
+    let output_path = run_code_generator(&dir, vec![module], |_| {});
+    file_to_string(output_path.with_added("src").with_added("module1.rs")).unwrap()
+  };
+  let element_type = RustType::Common {
+    base: RustName::new(vec!["crate1".to_string(), "module1".to_string(), "Class2".to_string()])
+      .unwrap(),
+    generic_arguments: None,
+    indirection: RustTypeIndirection::None,
+    is_const: false,
+    is_const2: false,
+  };
+
+  let code = generate("test_vector_impls_copy",
+                      RustVectorInfo {
+                        element_type: element_type.clone(),
+                        element_is_clone: true,
+                        has_copy_push_back: true,
+                        has_move_push_back: false,
+                      });
+  assert!(code.contains("pub fn to_vec(&self) -> Vec<::module1::Class2> {"));
+  assert!(code.contains("pub fn from_slice(slice: &[::module1::Class2]) -> \
+                         ::cpp_utils::CppBox<VectorClass2> {"));
+  assert!(!code.contains("from_boxes"));
+
+  // move-only elements can't be cloned or copied into the vector
+  let code = generate("test_vector_impls_move",
+                      RustVectorInfo {
+                        element_type: element_type,
+                        element_is_clone: false,
+                        has_copy_push_back: false,
+                        has_move_push_back: true,
+                      });
+  assert!(!code.contains("to_vec"));
+  assert!(!code.contains("from_slice"));
+  assert!(code.contains("pub fn from_boxes<I: IntoIterator<Item = \
+                         ::cpp_utils::CppBox<::module1::Class2>>>(iter: I) -> \
+                         ::cpp_utils::CppBox<VectorClass2> {"));
+  assert!(code.contains("vector.push_back(item);"));
+}
@@ -10,10 +10,10 @@ public:
   }}
 
 public slots:
-  void custom_slot({method_args}) {{
+  {return_type} custom_slot({method_args}) {{
     if (m_func) {{
-      m_func({func_args});
-    }}
+      {maybe_return}m_func({func_args});
+    }}{default_return}
   }}
 
 private:
@@ -1,2 +1,5 @@
 #include "ctrt1/utils.h"
 #include "ctrt1/class1.h"
+#include "ctrt1/point.h"
+#include "ctrt1/shapes.h"
+#include "ctrt1/containers.h"
@@ -1,5 +1,6 @@
 #include "ctrt1/exports.h"
 #include <cstdint>
+#include <cstring>
 
 class CTRT1_EXPORT Class1_F {
 public:
@@ -44,6 +45,12 @@ public:
   void ov5(int a) {}
   void ov5(uint16_t b) {}
 
+  operator bool() const { return m_x != 0; }
+  explicit operator double() const { return m_x; }
+
+  const char* name() const { return "Class1"; }
+  const char* null_name() const { return 0; }
+  static int name_length(const char* name) { return strlen(name); }
 
 private:
   int m_x;
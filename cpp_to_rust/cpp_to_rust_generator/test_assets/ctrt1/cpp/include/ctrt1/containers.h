@@ -0,0 +1,31 @@
+#ifndef CTRT1_CONTAINERS_H
+#define CTRT1_CONTAINERS_H
+
+#include "ctrt1/exports.h"
+
+class CTRT1_EXPORT IntIterator1 {
+public:
+  IntIterator1(const int* ptr) : m_ptr(ptr) {}
+  const int& operator*() const { return *m_ptr; }
+  IntIterator1& operator++() { ++m_ptr; return *this; }
+  bool operator!=(const IntIterator1& other) const { return m_ptr != other.m_ptr; }
+
+private:
+  const int* m_ptr;
+};
+
+class CTRT1_EXPORT IntContainer1 {
+public:
+  IntContainer1() {
+    for(int i = 0; i < 3; i++) {
+      m_data[i] = (i + 1) * 10;
+    }
+  }
+  IntIterator1 begin() const { return IntIterator1(m_data); }
+  IntIterator1 end() const { return IntIterator1(m_data + 3); }
+
+private:
+  int m_data[3];
+};
+
+#endif // CTRT1_CONTAINERS_H
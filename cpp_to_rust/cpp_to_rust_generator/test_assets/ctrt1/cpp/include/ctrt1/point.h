@@ -0,0 +1,18 @@
+#ifndef CTRT1_POINT_H
+#define CTRT1_POINT_H
+
+#include "ctrt1/exports.h"
+
+class CTRT1_EXPORT Point1 {
+public:
+  Point1(int x, int y) : m_x(x), m_y(y) {}
+  int x() const { return m_x; }
+  int y() const { return m_y; }
+  void set_x(int x) { m_x = x; }
+
+private:
+  int m_x;
+  int m_y;
+};
+
+#endif // CTRT1_POINT_H
@@ -0,0 +1,22 @@
+#ifndef CTRT1_SHAPES_H
+#define CTRT1_SHAPES_H
+
+#include "ctrt1/exports.h"
+
+class CTRT1_EXPORT Shape1 {
+public:
+  virtual ~Shape1() {}
+  virtual int sides() const { return 0; }
+};
+
+class CTRT1_EXPORT Triangle1 : public Shape1 {
+public:
+  int sides() const { return 3; }
+};
+
+class CTRT1_EXPORT Square1 : public Shape1 {
+public:
+  int sides() const { return 4; }
+};
+
+#endif // CTRT1_SHAPES_H
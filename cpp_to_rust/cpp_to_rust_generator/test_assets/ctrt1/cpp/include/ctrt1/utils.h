@@ -7,4 +7,6 @@ CTRT1_EXPORT const char* ctrt1_version();
 
 CTRT1_EXPORT int ctrt1_abs(int x);
 
+CTRT1_EXPORT int ctrt1_add(int a, int b = 0);
+
 #endif // CTRT1_UTILS_H
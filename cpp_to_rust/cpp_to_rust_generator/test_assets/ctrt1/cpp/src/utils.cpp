@@ -8,6 +8,10 @@ int ctrt1_abs(int x) {
   }
 }
 
+int ctrt1_add(int a, int b) {
+  return a + b;
+}
+
 const char* ctrt1_version() {
   return "0.0.1";
 }
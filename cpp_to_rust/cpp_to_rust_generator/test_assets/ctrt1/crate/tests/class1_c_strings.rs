@@ -0,0 +1,11 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::class1::Class1;
+use std::ffi::CString;
+
+#[test]
+fn class1_c_strings() {
+  let v = Class1::new(1);
+  assert_eq!(v.name().unwrap().to_str().unwrap(), "Class1");
+  assert!(v.null_name().is_none());
+  assert_eq!(Class1::name_length(&CString::new("text").unwrap()), 4);
+}
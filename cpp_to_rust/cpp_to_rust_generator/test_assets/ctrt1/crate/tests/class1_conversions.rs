@@ -0,0 +1,12 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::class1::Class1;
+
+#[test]
+fn class1_to_bool() {
+  assert!(Class1::new(1).to_bool());
+  assert!(!Class1::new(0).to_bool());
+
+  let v = Class1::new(2);
+  assert!(bool::from(&v));
+  assert_eq!(v.as_double(), 2.0);
+}
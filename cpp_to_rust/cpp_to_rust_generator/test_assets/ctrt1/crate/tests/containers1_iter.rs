@@ -0,0 +1,16 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::containers::IntContainer1;
+
+#[test]
+fn int_container1_iter() {
+  let container = IntContainer1::new();
+  let values: Vec<i32> = container.iter().cloned().collect();
+  assert_eq!(values, vec![10, 20, 30]);
+}
+
+#[test]
+fn int_container1_iter_sum() {
+  let container = IntContainer1::new();
+  assert_eq!(container.iter().sum::<i32>(), 60);
+  assert_eq!(container.iter().count(), 3);
+}
@@ -0,0 +1,21 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::point::Point1;
+
+#[test]
+fn point1_copy() {
+  let mut p1 = Point1::new(1, 2);
+  let p2 = p1;
+  p1.set_x(3);
+  assert_eq!(p1.x(), 3);
+  assert_eq!(p2.x(), 1);
+  assert_eq!(p2.y(), 2);
+}
+
+#[test]
+fn point1_partial_eq() {
+  let p1 = Point1::new(1, 2);
+  let mut p2 = p1.clone();
+  assert!(p1 == p2);
+  p2.set_x(5);
+  assert!(p1 != p2);
+}
@@ -0,0 +1,9 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::class1::Class1;
+
+fn assert_send<T: Send>() {}
+
+#[test]
+fn raw_trait_impl() {
+  assert_send::<Class1>();
+}
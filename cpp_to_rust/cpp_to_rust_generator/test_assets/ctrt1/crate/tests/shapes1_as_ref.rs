@@ -0,0 +1,19 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::shapes::{Shape1, Triangle1};
+
+fn sides_of<T: AsRef<Shape1>>(shape: &T) -> i32 {
+  shape.as_ref().sides()
+}
+
+#[test]
+fn shape1_as_ref() {
+  let triangle = Triangle1::new();
+  assert_eq!(sides_of(&triangle), 3);
+}
+
+#[test]
+fn shape1_as_mut() {
+  let mut triangle = Triangle1::new();
+  let shape: &mut Shape1 = triangle.as_mut();
+  assert_eq!(shape.sides(), 3);
+}
@@ -0,0 +1,21 @@
+extern crate rust_ctrt1;
+extern crate cpp_utils;
+use std::convert::TryFrom;
+use rust_ctrt1::shapes::{Shape1, Square1, Triangle1};
+use cpp_utils::{CastError, StaticCast};
+
+#[test]
+fn shape1_try_from_success() {
+  let triangle = Triangle1::new();
+  let shape: &Shape1 = triangle.static_cast();
+  let triangle2 = <&Triangle1>::try_from(shape).unwrap();
+  assert_eq!(triangle2.sides(), 3);
+}
+
+#[test]
+fn shape1_try_from_failure() {
+  let square = Square1::new();
+  let shape: &Shape1 = square.static_cast();
+  assert_eq!(<&Triangle1>::try_from(shape).err(), Some(CastError));
+  assert_eq!(<&Square1>::try_from(shape).unwrap().sides(), 4);
+}
@@ -0,0 +1,7 @@
+extern crate rust_ctrt1;
+use rust_ctrt1::class1::Class1;
+
+#[test]
+fn type_plugin() {
+  assert_eq!(Class1::plugin_value(), 42);
+}
@@ -7,3 +7,11 @@ fn utils1() {
   assert_eq!(ctrt1_abs(0), 0);
   assert_eq!(ctrt1_abs(-2), 2);
 }
+
+#[test]
+fn utils1_default_arguments() {
+  use rust_ctrt1::utils::ctrt1_add;
+  assert_eq!(ctrt1_add(1), 1);
+  assert_eq!(ctrt1_add((1, 2)), 3);
+  assert_eq!(ctrt1_add((-1, 0)), -1);
+}
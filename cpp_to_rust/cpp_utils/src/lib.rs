@@ -4,7 +4,8 @@
 mod tests {
   use std::rc::Rc;
   use std::cell::RefCell;
-  use {CppDeletable, Deleter, CppBox};
+  use {CppDeletable, CppClone, Deleter, CppBox, MaybeOwned, StaticCast, StaticUpcastOwned,
+       ToCppRef};
 
   struct Struct1 {
     value: Rc<RefCell<i32>>,
@@ -30,6 +31,188 @@ mod tests {
     }
     assert!(value1.borrow().clone() == 42);
   }
+
+  #[test]
+  fn align_types() {
+    use std::mem::{align_of, size_of};
+    use align::{Align1, Align2, Align4, Align8, Align16, Align32, Align64};
+
+    assert_eq!(align_of::<Align1>(), 1);
+    assert_eq!(align_of::<Align2>(), 2);
+    assert_eq!(align_of::<Align4>(), 4);
+    assert_eq!(align_of::<Align8>(), 8);
+    assert_eq!(align_of::<Align16>(), 16);
+    assert_eq!(align_of::<Align32>(), 32);
+    assert_eq!(align_of::<Align64>(), 64);
+    // a struct with a buffer and a zero-length array of an alignment type
+    // has size of the buffer and alignment of the type
+    #[allow(dead_code)]
+    struct Buffer([u8; 16], [Align8; 0]);
+    assert_eq!(size_of::<Buffer>(), 16);
+    assert_eq!(align_of::<Buffer>(), 8);
+  }
+
+  #[test]
+  fn maybe_owned() {
+    let value1 = Rc::new(RefCell::new(10));
+    let mut object1 = Struct1 { value: value1.clone() };
+    let ptr = &mut object1 as *mut Struct1;
+    let unowned = unsafe { MaybeOwned::new(ptr, false) };
+    assert!(!unowned.is_owned());
+    assert_eq!(unowned.as_ptr(), ptr as *const Struct1);
+    drop(unowned);
+    assert!(value1.borrow().clone() == 10);
+
+    let owned = unsafe { MaybeOwned::new(ptr, true) };
+    assert!(owned.is_owned());
+    assert_eq!(*owned.value.borrow(), 10);
+    drop(owned);
+    assert!(value1.borrow().clone() == 42);
+  }
+
+  struct Struct2 {
+    value: i32,
+  }
+
+  unsafe extern "C" fn struct2_delete(this_ptr: *mut Struct2) {
+    drop(Box::from_raw(this_ptr));
+  }
+
+  impl CppDeletable for Struct2 {
+    fn deleter() -> Deleter<Self> {
+      struct2_delete
+    }
+  }
+
+  impl CppClone for Struct2 {
+    fn cpp_clone(&self) -> CppBox<Self> {
+      unsafe { CppBox::new(Box::into_raw(Box::new(Struct2 { value: self.value }))) }
+    }
+  }
+
+  #[test]
+  fn test_clone_box() {
+    let box1 = unsafe { CppBox::new(Box::into_raw(Box::new(Struct2 { value: 10 }))) };
+    let mut box2 = box1.clone();
+    assert!(box2.as_ptr() != box1.as_ptr());
+    assert_eq!(box2.value, 10);
+    box2.value = 20;
+    assert_eq!(box1.value, 10);
+
+    let null_box: CppBox<Struct2> = CppBox::default();
+    assert!(null_box.clone().is_null());
+  }
+
+  struct Base1 {
+    deleted_count: Rc<RefCell<i32>>,
+  }
+
+  unsafe extern "C" fn base1_delete(this_ptr: *mut Base1) {
+    *(*this_ptr).deleted_count.borrow_mut() += 1;
+  }
+
+  impl CppDeletable for Base1 {
+    fn deleter() -> Deleter<Self> {
+      base1_delete
+    }
+  }
+
+  struct Derived1 {
+    base: Base1,
+  }
+
+  unsafe extern "C" fn derived1_delete(this_ptr: *mut Derived1) {
+    *(*this_ptr).base.deleted_count.borrow_mut() += 100;
+  }
+
+  impl CppDeletable for Derived1 {
+    fn deleter() -> Deleter<Self> {
+      derived1_delete
+    }
+  }
+
+  impl StaticCast<Base1> for Derived1 {
+    fn static_cast(&self) -> &Base1 {
+      &self.base
+    }
+    fn static_cast_mut(&mut self) -> &mut Base1 {
+      &mut self.base
+    }
+  }
+
+  unsafe impl StaticUpcastOwned<Base1> for Derived1 {}
+
+  #[test]
+  fn test_upcast_box() {
+    let deleted_count = Rc::new(RefCell::new(0));
+    let mut object1 = Derived1 { base: Base1 { deleted_count: deleted_count.clone() } };
+    let base_ptr = &mut object1.base as *mut Base1;
+    let box1 = unsafe { CppBox::new(&mut object1 as *mut Derived1) };
+    let box2: CppBox<Base1> = box1.upcast();
+    assert_eq!(*deleted_count.borrow(), 0);
+    assert_eq!(box2.as_mut_ptr(), base_ptr);
+    drop(box2);
+    // the object is deleted exactly once using the base class deleter
+    assert_eq!(*deleted_count.borrow(), 1);
+
+    let null_box: CppBox<Derived1> = CppBox::default();
+    assert!(null_box.upcast::<Base1>().is_null());
+  }
+
+  #[derive(Debug, PartialEq, Eq)]
+  enum Enum1 {
+    Value1 = 1,
+  }
+
+  // same as the code generated for C++ enums
+  impl ::std::convert::TryFrom<i32> for Enum1 {
+    type Error = ::UnknownEnumValue<i32>;
+    fn try_from(value: i32) -> ::std::result::Result<Self, Self::Error> {
+      match value {
+        1 => Ok(Enum1::Value1),
+        _ => Err(::UnknownEnumValue(value)),
+      }
+    }
+  }
+
+  #[derive(Debug, PartialEq, Eq)]
+  struct String1(String);
+
+  impl<'a> From<&'a str> for String1 {
+    fn from(value: &'a str) -> String1 {
+      String1(value.to_string())
+    }
+  }
+
+  impl From<String> for String1 {
+    fn from(value: String) -> String1 {
+      String1(value)
+    }
+  }
+
+  // same as the code generated for string class arguments
+  fn string1_len(value: impl ToCppRef<String1>) -> usize {
+    let ptr = &*value.to_cpp_ref() as *const String1;
+    let object: &String1 = unsafe { &*ptr };
+    object.0.len()
+  }
+
+  #[test]
+  fn to_cpp_ref() {
+    let string1 = String1("abc".to_string());
+    assert_eq!(string1_len(&string1), 3);
+    assert_eq!(string1_len("ab"), 2);
+    assert_eq!(string1_len("a".to_string()), 1);
+  }
+
+  #[test]
+  fn enum_try_from() {
+    use std::convert::TryFrom;
+    assert_eq!(Enum1::try_from(1), Ok(Enum1::Value1));
+    assert_eq!(Enum1::try_from(5), Err(::UnknownEnumValue(5)));
+    assert_eq!(Enum1::try_from(5).unwrap_err().to_string(),
+               "unknown enum value: 5");
+  }
 }
 
 /// Deleter function type.
@@ -54,6 +237,15 @@ pub trait CppDeletable: Sized {
   fn deleter() -> Deleter<Self>;
 }
 
+/// Indicates that a copy of the object can be created in C++ heap,
+/// usually by calling its copy constructor.
+///
+/// `CppBox<T>` implements `Clone` if `T` implements this trait.
+pub trait CppClone: CppDeletable {
+  /// Creates a copy of `self` owned by a new `CppBox`.
+  fn cpp_clone(&self) -> CppBox<Self>;
+}
+
 /// A C++ pointer wrapper to manage deletion of objects.
 ///
 /// Objects of CppBox should be created by calling into_box() for
@@ -86,6 +278,19 @@ impl<T: CppDeletable> CppBox<T> {
   pub fn is_null(&self) -> bool {
     self.ptr.is_null()
   }
+
+  /// Converts the box into a box of the base class, transferring
+  /// ownership of the object. The pointer is converted using `static_cast_mut`.
+  /// The object will be deleted using the deleter of the base class,
+  /// which is valid because `StaticUpcastOwned` is only implemented
+  /// if the base class has a virtual destructor.
+  /// If the box contains a null pointer, the resulting box contains a null pointer.
+  pub fn upcast<U: CppDeletable>(self) -> CppBox<U>
+    where T: StaticUpcastOwned<U>
+  {
+    let ptr = static_cast_mut(self.into_raw());
+    unsafe { CppBox::new(ptr) }
+  }
 }
 
 impl<T: CppDeletable> CppBox<T> {
@@ -165,6 +370,94 @@ impl<T: CppDeletable> Default for CppBox<T> {
   }
 }
 
+impl<T: CppClone> Clone for CppBox<T> {
+  /// Creates a copy of the object in the box. Cloning a box containing
+  /// a null pointer results in another box containing a null pointer.
+  fn clone(&self) -> CppBox<T> {
+    if self.is_null() {
+      CppBox::default()
+    } else {
+      self.as_ref().cpp_clone()
+    }
+  }
+}
+
+/// A pointer to an object created by a C++ constructor that is owned
+/// either by the caller or by another C++ object.
+///
+/// `cpp_to_rust` returns this type from constructors of `QObject`-based classes
+/// that take a `parent` argument (see `Config::set_parented_qobject_constructors`).
+/// If the passed parent is null, the caller owns the object, and it will be deleted
+/// when `MaybeOwned` is dropped. Otherwise, the object will be deleted by its parent,
+/// so `MaybeOwned` doesn't delete it.
+///
+/// Attempting to dereference a null pointer using `deref` or `deref_mut`
+/// will result in a panic.
+pub enum MaybeOwned<T: CppDeletable> {
+  /// The object is owned by the caller.
+  Owned(CppBox<T>),
+  /// The object is owned by another C++ object.
+  Unowned(*mut T),
+}
+
+impl<T: CppDeletable> MaybeOwned<T> {
+  /// Wraps `ptr` into `CppBox` if `is_owned` is true.
+  ///
+  /// This function has the same requirements as `CppBox::new`
+  /// if `is_owned` is true.
+  pub unsafe fn new(ptr: *mut T, is_owned: bool) -> MaybeOwned<T> {
+    if is_owned {
+      MaybeOwned::Owned(CppBox::new(ptr))
+    } else {
+      MaybeOwned::Unowned(ptr)
+    }
+  }
+
+  /// Returns true if the object is owned by the caller.
+  pub fn is_owned(&self) -> bool {
+    match *self {
+      MaybeOwned::Owned(..) => true,
+      MaybeOwned::Unowned(..) => false,
+    }
+  }
+
+  /// Returns constant raw pointer to the object.
+  pub fn as_ptr(&self) -> *const T {
+    self.as_mut_ptr()
+  }
+
+  /// Returns mutable raw pointer to the object.
+  pub fn as_mut_ptr(&self) -> *mut T {
+    match *self {
+      MaybeOwned::Owned(ref b) => b.as_mut_ptr(),
+      MaybeOwned::Unowned(ptr) => ptr,
+    }
+  }
+
+  /// Returns the pointer to the object. If the object was owned by the caller,
+  /// the caller of the function becomes responsible for deleting it,
+  /// as with `CppBox::into_raw`.
+  pub fn into_raw(self) -> *mut T {
+    match self {
+      MaybeOwned::Owned(b) => b.into_raw(),
+      MaybeOwned::Unowned(ptr) => ptr,
+    }
+  }
+}
+
+impl<T: CppDeletable> std::ops::Deref for MaybeOwned<T> {
+  type Target = T;
+  fn deref(&self) -> &T {
+    unsafe { self.as_ptr().as_ref().unwrap() }
+  }
+}
+
+impl<T: CppDeletable> std::ops::DerefMut for MaybeOwned<T> {
+  fn deref_mut(&mut self) -> &mut T {
+    unsafe { self.as_mut_ptr().as_mut().unwrap() }
+  }
+}
+
 /// This module contains `NewUninitialized` trait.
 /// It's an implementation detail of `cpp_to_rust` and should not be used directly.
 pub mod new_uninitialized {
@@ -179,6 +472,48 @@ pub mod new_uninitialized {
   }
 }
 
+/// This module contains zero-sized types with fixed alignment.
+/// `type_sizes.rs` generated by `cpp_to_rust` build script selects one of them
+/// for each stack-allocated struct according to alignment of the C++ type
+/// on the target platform. It's an implementation detail of `cpp_to_rust`
+/// and should not be used directly.
+pub mod align {
+  /// Zero-sized type with alignment of 1 byte.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(1))]
+  pub struct Align1;
+
+  /// Zero-sized type with alignment of 2 bytes.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(2))]
+  pub struct Align2;
+
+  /// Zero-sized type with alignment of 4 bytes.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(4))]
+  pub struct Align4;
+
+  /// Zero-sized type with alignment of 8 bytes.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(8))]
+  pub struct Align8;
+
+  /// Zero-sized type with alignment of 16 bytes.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(16))]
+  pub struct Align16;
+
+  /// Zero-sized type with alignment of 32 bytes.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(32))]
+  pub struct Align32;
+
+  /// Zero-sized type with alignment of 64 bytes.
+  #[derive(Debug, Clone, Copy)]
+  #[repr(C, align(64))]
+  pub struct Align64;
+}
+
 /// Provides access to C++ `static_cast` conversion from derived class to base class.
 ///
 /// This trait is automatically implemented by `cpp_to_rust`.
@@ -221,6 +556,21 @@ pub fn static_cast_mut<R, T: StaticCast<R>>(ptr: *mut T) -> *mut R {
     .unwrap_or(std::ptr::null_mut())
 }
 
+/// Allows to convert `CppBox<T1>` into `CppBox<T2>` using `CppBox::upcast`
+/// if `T1` class is derived (in C++) from `T2` class.
+///
+/// This trait is automatically implemented by `cpp_to_rust` for classes
+/// with a single base if both classes are heap-allocated and the base class
+/// has a virtual destructor. `From` can't be used for this conversion because
+/// `CppBox` is defined outside of the generated crates.
+///
+/// # Safety
+///
+/// This trait is unsafe to implement because deleting an object
+/// through a pointer to a base class without a virtual destructor
+/// results in undefined behavior.
+pub unsafe trait StaticUpcastOwned<T: CppDeletable>: StaticCast<T> + CppDeletable {}
+
 /// Provides access to C++ `static_cast` conversion from base class to derived class.
 ///
 /// This trait is automatically implemented by `cpp_to_rust`.
@@ -321,3 +671,116 @@ pub unsafe fn dynamic_cast_mut<R, T: DynamicCast<R>>(ptr: *mut T) -> *mut R {
     .map(|x| x as *mut R)
     .unwrap_or(std::ptr::null_mut())
 }
+
+/// Variant of `DynamicCast` that returns `Result` instead of `Option`.
+/// The generator implements this trait instead of `DynamicCast` if
+/// an error type was configured with `Config::set_cast_error_type`.
+/// Conversion returns `Err(Self::Error::default())` if the object is actually
+/// not an instance of the target type.
+pub trait DynamicCastResult<T> {
+  /// Error returned if the conversion fails.
+  type Error;
+  /// Convert type of a const reference.
+  /// Returns `Err` if `self` is not an instance of `T`.
+  fn dynamic_cast(&self) -> Result<&T, Self::Error>;
+  /// Convert type of a mutable reference.
+  /// Returns `Err` if `self` is not an instance of `T`.
+  fn dynamic_cast_mut(&mut self) -> Result<&mut T, Self::Error>;
+}
+
+/// Error returned by `std::convert::TryFrom` implementations based on `DynamicCast`
+/// if the object is actually not an instance of the target type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CastError;
+
+impl std::fmt::Display for CastError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "dynamic_cast failed: object is not an instance of the target type")
+  }
+}
+
+impl std::error::Error for CastError {
+  fn description(&self) -> &str {
+    "dynamic_cast failed"
+  }
+}
+
+/// Error returned by `std::convert::TryFrom` implementations of enums
+/// if the integer value doesn't correspond to any of the variants.
+/// Contains the value that couldn't be converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnknownEnumValue<T>(pub T);
+
+impl<T: std::fmt::Display> std::fmt::Display for UnknownEnumValue<T> {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "unknown enum value: {}", self.0)
+  }
+}
+
+impl<T: std::fmt::Debug + std::fmt::Display> std::error::Error for UnknownEnumValue<T> {
+  fn description(&self) -> &str {
+    "unknown enum value"
+  }
+}
+
+/// Error returned by fallible constructors if the created object
+/// didn't pass the validity check (e.g. its `isValid()` method returned `false`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InvalidObjectError;
+
+impl std::fmt::Display for InvalidObjectError {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    write!(f, "constructed object is invalid")
+  }
+}
+
+impl std::error::Error for InvalidObjectError {
+  fn description(&self) -> &str {
+    "constructed object is invalid"
+  }
+}
+
+/// Conversion of a value passed to a C++ method taking a string class `T`
+/// (e.g. `QString`) by value or by const reference.
+///
+/// This trait is implemented for references to `T`, which are passed
+/// without copying, and for Rust strings if `T` can be created from them.
+/// In the latter case, a temporary object is created for the duration of the call.
+/// The generator uses this trait for classes listed in `Config::set_string_types`.
+pub trait ToCppRef<T> {
+  /// Type holding the reference or the temporary object.
+  type Holder: std::ops::Deref<Target = T>;
+  /// Returns the holder of the object to pass to C++.
+  fn to_cpp_ref(self) -> Self::Holder;
+}
+
+/// A temporary C++ object created by `ToCppRef` implementations.
+pub struct CppTemporary<T>(T);
+
+impl<T> std::ops::Deref for CppTemporary<T> {
+  type Target = T;
+  fn deref(&self) -> &T {
+    &self.0
+  }
+}
+
+impl<'a, T> ToCppRef<T> for &'a T {
+  type Holder = &'a T;
+  fn to_cpp_ref(self) -> &'a T {
+    self
+  }
+}
+
+impl<'a, T: From<&'a str>> ToCppRef<T> for &'a str {
+  type Holder = CppTemporary<T>;
+  fn to_cpp_ref(self) -> CppTemporary<T> {
+    CppTemporary(T::from(self))
+  }
+}
+
+impl<T: From<String>> ToCppRef<T> for String {
+  type Holder = CppTemporary<T>;
+  fn to_cpp_ref(self) -> CppTemporary<T> {
+    CppTemporary(T::from(self))
+  }
+}
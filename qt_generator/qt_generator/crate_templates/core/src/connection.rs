@@ -1,6 +1,6 @@
 //! Types and functions for connecting Qt signals and slots.
 
-use libc::c_char;
+use std::ffi::CStr;
 
 /// Argument types compatible for signal connection.
 ///
@@ -40,6 +40,31 @@ pub trait Receiver {
 /// A Qt signal.
 pub trait Signal: Receiver {
   /// Connects this signal to another signal or slot with compatible arguments.
+  ///
+  /// Compatibility of arguments is checked at compile time
+  /// (see `ArgumentsCompatible`):
+  ///
+  /// ```no_run
+  /// use qt_core::connection::Signal;
+  /// use qt_core::slots::SlotNoArgs;
+  /// use qt_core::timer::Timer;
+  ///
+  /// let timer = Timer::new();
+  /// let slot = SlotNoArgs::new(|| println!("timeout"));
+  /// timer.signals().timeout().connect(&slot);
+  /// ```
+  ///
+  /// A signal can't be connected to a receiver that expects more arguments:
+  ///
+  /// ```compile_fail
+  /// use qt_core::connection::Signal;
+  /// use qt_core::slots::SlotVariantRef;
+  /// use qt_core::timer::Timer;
+  ///
+  /// let timer = Timer::new();
+  /// let slot = SlotVariantRef::new(|_| {});
+  /// timer.signals().timeout().connect(&slot);
+  /// ```
   fn connect<A, R: Receiver<Arguments = A>>(&self, receiver: &R) -> ::meta_object::Connection
     where Self::Arguments: ArgumentsCompatible<A>
   {
@@ -47,9 +72,9 @@ pub trait Signal: Receiver {
     // TODO: meta_object::Connection should have operator bool()
     unsafe {
       ::object::Object::connect_static((self.object() as *const ::object::Object,
-                                        Self::receiver_id().as_ptr() as *const c_char,
+                                        CStr::from_bytes_with_nul_unchecked(Self::receiver_id()),
                                         receiver.object() as *const ::object::Object,
-                                        R::receiver_id().as_ptr() as *const c_char))
+                                        CStr::from_bytes_with_nul_unchecked(R::receiver_id())))
     }
   }
 }
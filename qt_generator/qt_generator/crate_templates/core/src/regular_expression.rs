@@ -0,0 +1,26 @@
+include_generated!();
+
+// These helpers are template code, not generated bindings. They are written
+// on top of the generated `match_` method and `QRegularExpressionMatch` wrapper,
+// so they don't depend on generator support and must be kept in sync with
+// the generated API manually.
+impl ::regular_expression::RegularExpression {
+  /// Returns `true` if the regular expression matches `text`.
+  pub fn is_match(&self, text: &str) -> bool {
+    self.match_(&::string::String::from(text)).has_match()
+  }
+
+  /// Matches the regular expression against `text` and returns
+  /// the captured strings. The first item is the whole match,
+  /// the following items correspond to capturing groups.
+  /// Returns an empty vector if there is no match.
+  pub fn captures(&self, text: &str) -> Vec<::std::string::String> {
+    let match_result = self.match_(&::string::String::from(text));
+    if !match_result.has_match() {
+      return Vec::new();
+    }
+    (0..match_result.last_captured_index() + 1)
+      .map(|i| match_result.captured(i).to_std_string())
+      .collect()
+  }
+}
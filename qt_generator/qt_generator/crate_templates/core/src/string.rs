@@ -17,6 +17,20 @@ impl<'a> From<&'a ::string::String> for ::std::string::String {
   }
 }
 
+/// Formats Qt strings the same way as `std` strings
+impl std::fmt::Debug for ::string::String {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    std::fmt::Debug::fmt(&self.to_std_string(), f)
+  }
+}
+
+/// Allows to use Qt strings in `format!` and similar macros
+impl std::fmt::Display for ::string::String {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    std::fmt::Display::fmt(&self.to_std_string(), f)
+  }
+}
+
 impl ::string::String {
   /// Creates Qt string from an `std` string.
   pub fn from_std_str<S: AsRef<str>>(s: S) -> ::string::String {
@@ -0,0 +1,19 @@
+extern crate qt_core;
+use qt_core::string::String;
+use qt_core::regular_expression::RegularExpression;
+
+#[test]
+fn clone_value_type() {
+  let string1 = String::from("text1");
+  let string2 = string1.clone();
+  assert_eq!(string1.to_std_string(), "text1");
+  assert_eq!(string2.to_std_string(), "text1");
+}
+
+#[test]
+fn clone_boxed_type() {
+  let regexp1 = RegularExpression::new(&String::from("a+"));
+  let regexp2 = regexp1.clone();
+  assert!(regexp1.as_ptr() != regexp2.as_ptr());
+  assert_eq!(regexp2.pattern().to_std_string(), "a+");
+}
@@ -0,0 +1,14 @@
+extern crate qt_core;
+use qt_core::date::Date;
+use qt_core::string::String;
+
+#[test]
+fn string_debug() {
+  assert_eq!(format!("{:?}", String::from("text")), "\"text\"");
+}
+
+#[test]
+fn date_debug() {
+  let date = Date::new((2017, 5, 20));
+  assert!(format!("{:?}", date).contains("2017"));
+}
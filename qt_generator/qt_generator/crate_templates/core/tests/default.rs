@@ -0,0 +1,10 @@
+extern crate qt_core;
+use qt_core::point::Point;
+
+#[test]
+fn point_default() {
+  let point = Point::default();
+  assert_eq!(point.x(), 0);
+  assert_eq!(point.y(), 0);
+  assert!(point.is_null());
+}
@@ -0,0 +1,14 @@
+extern crate qt_core;
+use qt_core::date::Date;
+use qt_core::string::String;
+
+#[test]
+fn string_display() {
+  assert_eq!(format!("{}", String::from("text")), "text");
+}
+
+#[test]
+fn date_display() {
+  let date = Date::new((2017, 5, 20));
+  assert_eq!(format!("{}", date), date.to_string(()).to_std_string());
+}
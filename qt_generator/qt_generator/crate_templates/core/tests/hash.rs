@@ -0,0 +1,39 @@
+extern crate qt_core;
+use qt_core::hash::HashCIntCInt;
+use qt_core::string::String;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[test]
+fn hash_round_trip() {
+  let mut map = HashMap::new();
+  map.insert(1, 10);
+  map.insert(2, 20);
+  map.insert(3, 30);
+  let hash = HashCIntCInt::from(&map);
+  assert_eq!(hash.to_hashmap(), map);
+
+  let mut keys = hash.keys_vec();
+  keys.sort();
+  assert_eq!(keys, vec![1, 2, 3]);
+  let mut values = hash.values_vec();
+  values.sort();
+  assert_eq!(values, vec![10, 20, 30]);
+  let mut pairs: Vec<_> = hash.iter().collect();
+  pairs.sort();
+  assert_eq!(pairs, vec![(1, 10), (2, 20), (3, 30)]);
+}
+
+fn hash_value<T: Hash>(value: &T) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  value.hash(&mut hasher);
+  hasher.finish()
+}
+
+#[test]
+fn q_hash_function() {
+  assert_eq!(hash_value(&String::from("text1")),
+             hash_value(&String::from("text1")));
+  assert!(hash_value(&String::from("text1")) != hash_value(&String::from("text2")));
+}
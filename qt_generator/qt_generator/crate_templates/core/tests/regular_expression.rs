@@ -0,0 +1,13 @@
+extern crate qt_core;
+use qt_core::regular_expression::RegularExpression;
+use qt_core::string::String;
+
+#[test]
+fn regular_expression_match() {
+  let regexp = RegularExpression::new(&String::from(r"(\w+)@(\w+)\.com"));
+  assert!(regexp.is_match("mail: user@example.com"));
+  assert!(!regexp.is_match("no mail here"));
+  assert_eq!(regexp.captures("mail: user@example.com"),
+             vec!["user@example.com", "user", "example"]);
+  assert!(regexp.captures("no mail here").is_empty());
+}
@@ -0,0 +1,18 @@
+extern crate qt_core;
+use qt_core::variant_animation::VariantAnimation;
+use qt_core::connection::Signal;
+use qt_core::slots::SlotCInt;
+use std::cell::Cell;
+
+#[test]
+fn signal_emit() {
+  let value = Cell::new(0);
+  let slot1 = SlotCInt::new(|x| value.set(x));
+  let mut animation = VariantAnimation::new();
+  animation
+    .signals()
+    .current_loop_changed()
+    .connect(&slot1);
+  animation.emit_current_loop_changed(3);
+  assert_eq!(value.get(), 3);
+}
@@ -0,0 +1,22 @@
+extern crate qt_core;
+use qt_core::core_application::CoreApplication;
+use qt_core::timer::Timer;
+use qt_core::connection::Signal;
+use qt_core::slots::SlotNoArgs;
+use std::cell::Cell;
+
+#[test]
+fn signal_no_args() {
+  CoreApplication::create_and_exit(|app| {
+    let count = Cell::new(0);
+    let slot1 = SlotNoArgs::new(|| count.set(count.get() + 1));
+    let mut timer = Timer::new();
+    timer.set_single_shot(true);
+    timer.signals().timeout().connect(&slot1);
+    timer.signals().timeout().connect(&app.slots().quit());
+    timer.start(0);
+    let r = CoreApplication::exec();
+    assert_eq!(count.get(), 1);
+    r
+  })
+}
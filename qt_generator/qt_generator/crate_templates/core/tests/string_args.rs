@@ -0,0 +1,24 @@
+extern crate qt_core;
+use qt_core::regular_expression::RegularExpression;
+use qt_core::string::String;
+
+#[test]
+fn string_ref_argument() {
+  let mut regexp = RegularExpression::new(&String::from("a+"));
+  regexp.set_pattern(&String::from("b+"));
+  assert_eq!(regexp.pattern().to_std_string(), "b+");
+}
+
+#[test]
+fn str_argument() {
+  let mut regexp = RegularExpression::new(&String::from("a+"));
+  regexp.set_pattern("c+");
+  assert_eq!(regexp.pattern().to_std_string(), "c+");
+}
+
+#[test]
+fn std_string_argument() {
+  let mut regexp = RegularExpression::new(&String::from("a+"));
+  regexp.set_pattern("d+".to_string());
+  assert_eq!(regexp.pattern().to_std_string(), "d+");
+}
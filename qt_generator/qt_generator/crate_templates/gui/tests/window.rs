@@ -10,6 +10,9 @@ fn window1() {
     let mut a = Window::new();
     let mut b = unsafe { Window::new_unsafe(a.as_mut_ptr()) };
     let mut c = unsafe { Window::new_unsafe(b.as_mut_ptr()) };
+    // child windows are deleted by their parents
+    assert!(!b.is_owned());
+    assert!(!c.is_owned());
     a.set_geometry((10, 10, 300, 300));
     b.set_geometry((20, 20, 200, 200));
     c.set_geometry((40, 40, 100, 100));
@@ -38,7 +38,8 @@ impl<'a> Form<'a> {
       layout.add_widget(button.static_cast_mut() as *mut _);
     }
     let button = button.into_raw();
-    layout.into_raw();
+    // the layout is owned by `widget`, so it's not deleted here
+    drop(layout);
     widget.show();
 
     let button1 = button;
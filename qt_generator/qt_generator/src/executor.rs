@@ -27,6 +27,7 @@ pub struct ExecConfig {
   pub write_cache: bool,
   pub debug_logging_config: DebugLoggingConfig,
   pub quiet_mode: bool,
+  pub format_generated_code: bool,
 }
 
 /// Executes generator for `libs` with given configuration.
@@ -146,6 +147,7 @@ fn make_config(sublib_name: &str,
   config.set_write_dependencies_local_paths(exec_config.write_dependencies_local_paths);
   config.set_write_cache(exec_config.write_cache);
   config.set_quiet_mode(exec_config.quiet_mode);
+  config.set_format_generated_code(exec_config.format_generated_code);
   config.set_debug_logging_config(exec_config.debug_logging_config.clone());
   config.set_cpp_lib_version(installation_data.qt_version.as_str());
   if exec_config.write_dependencies_local_paths {
@@ -190,6 +192,9 @@ fn make_config(sublib_name: &str,
     config.add_cpp_parser_argument("-std=gnu++11");
   }
   config.add_cpp_parser_blocked_name("qt_check_for_QGADGET_macro");
+  config.add_display_method_name("toString");
+  config.set_string_types(vec!["QString"]);
+  config.set_parented_qobject_constructors(true);
   let sublib_name_clone = sublib_name.to_string();
   let docs_path = installation_data.docs_path.clone();
 
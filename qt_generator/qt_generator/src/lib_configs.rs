@@ -1,6 +1,7 @@
 //! Generator configurations specific for each Qt module.
 
 use cpp_to_rust_generator::common::errors::{Result, ChainErr};
+use cpp_to_rust_generator::common::target;
 use cpp_to_rust_generator::config::{Config, CppTypeAllocationPlace};
 use cpp_to_rust_generator::cpp_type::{CppType, CppTypeBase, CppBuiltInNumericType,
                                       CppTypeIndirection};
@@ -139,12 +140,13 @@ pub fn core(config: &mut Config) -> Result<()> {
   // TODO: the following items should be conditionally available on Windows;
   config.add_cpp_parser_blocked_names(vec!["QWinEventNotifier",
                                            "QProcess::CreateProcessArguments",
-                                           "QProcess::nativeArguments",
-                                           "QProcess::setNativeArguments",
                                            "QProcess::createProcessArgumentsModifier",
                                            "QProcess::setCreateProcessArgumentsModifier",
                                            "QAbstractEventDispatcher::registerEventNotifier",
                                            "QAbstractEventDispatcher::unregisterEventNotifier"]);
+  let windows = target::Condition::OS(target::OS::Windows);
+  config.add_platform_method("QProcess::nativeArguments", windows.clone());
+  config.add_platform_method("QProcess::setNativeArguments", windows);
 
   // QProcess::pid returns different types on different platforms,
   // but this method is obsolete anyway
@@ -158,6 +160,7 @@ pub fn core(config: &mut Config) -> Result<()> {
                                     vec!["QAssociativeIterable",
                                          "QByteArray",
                                          "QChar",
+                                         "QHash",
                                          "QItemSelection",
                                          "QJsonArray",
                                          "QJsonObject",
@@ -173,6 +176,8 @@ pub fn core(config: &mut Config) -> Result<()> {
                                          "QSequentialIterable",
                                          "QString"]);
 
+  config.add_debug_impl_types(vec!["QDate", "QDateTime", "QTime", "QUrl"]);
+
   config.add_cpp_ffi_generator_filter(|method| {
     if let Some(ref info) = method.class_membership {
       if info.class_type.to_cpp_pseudo_code() == "QFuture<void>" {
@@ -56,6 +56,7 @@ fn run(matches: clap::ArgMatches) -> Result<()> {
     },
     write_cache: !matches.is_present("dont-write-cache"),
     quiet_mode: matches.is_present("quiet"),
+    format_generated_code: !matches.is_present("no-rustfmt"),
     debug_logging_config: match matches
             .value_of("debug-logging")
             .chain_err(|| "mising value of debug-logging")? {
@@ -93,6 +94,7 @@ fn main() {
     const QUIET_HELP: &'static str = "Don't output status messages to stderr";
     const DONT_WRITE_CACHE_HELP: &'static str = "Don't write files for dependency processing";
     const NO_LOCAL_PATHS_HELP: &'static str = "Don't write local paths to output Cargo.toml file";
+    const NO_RUSTFMT_HELP: &'static str = "Don't format generated Rust code with rustfmt";
 
 
     run(App::new("cpp_to_rust")
@@ -152,6 +154,9 @@ fn main() {
           .arg(Arg::with_name("no-local-paths")
                  .long("no-local-paths")
                  .help(NO_LOCAL_PATHS_HELP))
+          .arg(Arg::with_name("no-rustfmt")
+                 .long("no-rustfmt")
+                 .help(NO_RUSTFMT_HELP))
           .get_matches())
   };
   if let Err(err) = result {